@@ -166,8 +166,92 @@ pub fn keep_denormals() {
     }
 }
 
+/// Compares values elementwise with an absolute tolerance. Used by the `assert_close*`
+/// test macros and by [nn_traits::gradcheck()].
+pub trait AssertClose {
+    type Elem: std::fmt::Display + std::fmt::Debug + Copy;
+    const DEFAULT_TOLERANCE: Self::Elem;
+    fn get_default_tol(&self) -> Self::Elem {
+        Self::DEFAULT_TOLERANCE
+    }
+    /// Returns the first pair of elements that differ by more than `tolerance`.
+    fn get_far_pair(&self, rhs: &Self, tolerance: Self::Elem) -> Option<(Self::Elem, Self::Elem)>;
+    fn assert_close(&self, rhs: &Self, tolerance: Self::Elem)
+    where
+        Self: std::fmt::Debug,
+    {
+        if let Some((l, r)) = self.get_far_pair(rhs, tolerance) {
+            panic!("lhs != rhs | {l} != {r}\n\n{self:?}\n\n{rhs:?}");
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl<F: Copy + std::fmt::Debug + std::fmt::Display + AssertClose> AssertClose
+    for crate::dtypes::AMP<F>
+{
+    type Elem = crate::dtypes::AMP<F::Elem>;
+    const DEFAULT_TOLERANCE: Self::Elem = crate::dtypes::AMP(F::DEFAULT_TOLERANCE);
+    fn get_far_pair(&self, rhs: &Self, tolerance: Self::Elem) -> Option<(Self::Elem, Self::Elem)> {
+        self.0
+            .get_far_pair(&rhs.0, tolerance.0)
+            .map(|(l, r)| (crate::dtypes::AMP(l), crate::dtypes::AMP(r)))
+    }
+}
+
+#[cfg(feature = "f16")]
+impl AssertClose for half::f16 {
+    type Elem = Self;
+    const DEFAULT_TOLERANCE: Self::Elem = half::f16::from_f32_const(1e-2);
+    fn get_far_pair(&self, rhs: &Self, tolerance: Self) -> Option<(Self, Self)> {
+        if num_traits::Float::abs(self - rhs) > tolerance {
+            Some((*self, *rhs))
+        } else {
+            None
+        }
+    }
+}
+
+impl AssertClose for f32 {
+    type Elem = f32;
+    const DEFAULT_TOLERANCE: Self::Elem = 1e-6;
+    fn get_far_pair(&self, rhs: &Self, tolerance: f32) -> Option<(f32, f32)> {
+        if (self - rhs).abs() > tolerance {
+            Some((*self, *rhs))
+        } else {
+            None
+        }
+    }
+}
+
+impl AssertClose for f64 {
+    type Elem = f64;
+    const DEFAULT_TOLERANCE: Self::Elem = 1e-6;
+    fn get_far_pair(&self, rhs: &Self, tolerance: f64) -> Option<(f64, f64)> {
+        if (self - rhs).abs() > tolerance {
+            Some((*self, *rhs))
+        } else {
+            None
+        }
+    }
+}
+
+impl<T: AssertClose, const M: usize> AssertClose for [T; M] {
+    type Elem = T::Elem;
+    const DEFAULT_TOLERANCE: Self::Elem = T::DEFAULT_TOLERANCE;
+    fn get_far_pair(&self, rhs: &Self, tolerance: Self::Elem) -> Option<(Self::Elem, Self::Elem)> {
+        for (l, r) in self.iter().zip(rhs.iter()) {
+            if let Some(pair) = l.get_far_pair(r, tolerance) {
+                return Some(pair);
+            }
+        }
+        None
+    }
+}
+
 #[cfg(test)]
 pub(crate) mod tests {
+    pub use crate::AssertClose;
     pub use num_traits::{Float, NumCast, Zero};
 
     #[cfg(not(feature = "cuda"))]
@@ -195,97 +279,6 @@ pub(crate) mod tests {
     #[cfg(feature = "test-amp-f16")]
     pub type TestDtype = crate::dtypes::AMP<half::f16>;
 
-    pub trait AssertClose {
-        type Elem: std::fmt::Display + std::fmt::Debug + Copy;
-        const DEFAULT_TOLERANCE: Self::Elem;
-        fn get_default_tol(&self) -> Self::Elem {
-            Self::DEFAULT_TOLERANCE
-        }
-        fn get_far_pair(
-            &self,
-            rhs: &Self,
-            tolerance: Self::Elem,
-        ) -> Option<(Self::Elem, Self::Elem)>;
-        fn assert_close(&self, rhs: &Self, tolerance: Self::Elem)
-        where
-            Self: std::fmt::Debug,
-        {
-            if let Some((l, r)) = self.get_far_pair(rhs, tolerance) {
-                panic!("lhs != rhs | {l} != {r}\n\n{self:?}\n\n{rhs:?}");
-            }
-        }
-    }
-
-    impl<F: Copy + std::fmt::Debug + std::fmt::Display + AssertClose> AssertClose
-        for crate::dtypes::AMP<F>
-    {
-        type Elem = crate::dtypes::AMP<F::Elem>;
-        const DEFAULT_TOLERANCE: Self::Elem = crate::dtypes::AMP(F::DEFAULT_TOLERANCE);
-        fn get_far_pair(
-            &self,
-            rhs: &Self,
-            tolerance: Self::Elem,
-        ) -> Option<(Self::Elem, Self::Elem)> {
-            self.0
-                .get_far_pair(&rhs.0, tolerance.0)
-                .map(|(l, r)| (crate::dtypes::AMP(l), crate::dtypes::AMP(r)))
-        }
-    }
-
-    #[cfg(feature = "f16")]
-    impl AssertClose for half::f16 {
-        type Elem = Self;
-        const DEFAULT_TOLERANCE: Self::Elem = half::f16::from_f32_const(1e-2);
-        fn get_far_pair(&self, rhs: &Self, tolerance: Self) -> Option<(Self, Self)> {
-            if num_traits::Float::abs(self - rhs) > tolerance {
-                Some((*self, *rhs))
-            } else {
-                None
-            }
-        }
-    }
-
-    impl AssertClose for f32 {
-        type Elem = f32;
-        const DEFAULT_TOLERANCE: Self::Elem = 1e-6;
-        fn get_far_pair(&self, rhs: &Self, tolerance: f32) -> Option<(f32, f32)> {
-            if (self - rhs).abs() > tolerance {
-                Some((*self, *rhs))
-            } else {
-                None
-            }
-        }
-    }
-
-    impl AssertClose for f64 {
-        type Elem = f64;
-        const DEFAULT_TOLERANCE: Self::Elem = 1e-6;
-        fn get_far_pair(&self, rhs: &Self, tolerance: f64) -> Option<(f64, f64)> {
-            if (self - rhs).abs() > tolerance {
-                Some((*self, *rhs))
-            } else {
-                None
-            }
-        }
-    }
-
-    impl<T: AssertClose, const M: usize> AssertClose for [T; M] {
-        type Elem = T::Elem;
-        const DEFAULT_TOLERANCE: Self::Elem = T::DEFAULT_TOLERANCE;
-        fn get_far_pair(
-            &self,
-            rhs: &Self,
-            tolerance: Self::Elem,
-        ) -> Option<(Self::Elem, Self::Elem)> {
-            for (l, r) in self.iter().zip(rhs.iter()) {
-                if let Some(pair) = l.get_far_pair(r, tolerance) {
-                    return Some(pair);
-                }
-            }
-            None
-        }
-    }
-
     pub trait NdMap {
         type Elem;
         type Mapped<O>;
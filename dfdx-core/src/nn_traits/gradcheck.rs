@@ -0,0 +1,394 @@
+use crate::{
+    dtypes::Dtype,
+    shapes::{Rank0, Shape},
+    tensor::{Error, Gradients, OwnedTape, SplitTape, Tensor, Trace, UniqueId},
+    tensor_ops::{Backward, Device},
+    AssertClose,
+};
+
+use super::{Optimizer, UpdateParams, ZeroGrads};
+
+use std::vec::Vec;
+
+/// Configuration for [gradcheck()].
+#[derive(Debug, Clone, Copy)]
+pub struct GradCheckConfig {
+    /// The step size used for central finite differences.
+    pub eps: f64,
+    /// Absolute tolerance between the numerical & autodiff gradients.
+    pub atol: f64,
+    /// Tolerance relative to the magnitude of the numerical gradient, which is added to `atol`.
+    pub rtol: f64,
+    /// The maximum number of mismatches to keep in [GradCheckReport::mismatches].
+    pub max_mismatches: usize,
+}
+
+impl Default for GradCheckConfig {
+    /// - `eps`: 1e-3
+    /// - `atol`: 1e-3
+    /// - `rtol`: 1e-2
+    /// - `max_mismatches`: 10
+    fn default() -> Self {
+        Self {
+            eps: 1e-3,
+            atol: 1e-3,
+            rtol: 1e-2,
+            max_mismatches: 10,
+        }
+    }
+}
+
+/// An element whose autodiff gradient does not match its numerical gradient.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GradMismatch<E> {
+    /// The id of the parameter or input this element is in.
+    pub id: UniqueId,
+    /// The index of the element, in row major order.
+    pub index: usize,
+    pub numerical: E,
+    pub autodiff: E,
+}
+
+/// The result of [gradcheck()].
+#[derive(Debug, Clone)]
+pub struct GradCheckReport<E> {
+    /// The number of elements that were checked.
+    pub num_checked: usize,
+    /// The number of elements whose gradients did not match.
+    pub num_mismatched: usize,
+    /// The worst mismatches, largest absolute difference first.
+    pub mismatches: Vec<GradMismatch<E>>,
+}
+
+impl<E: std::fmt::Display> GradCheckReport<E> {
+    /// Whether every checked gradient was within tolerance.
+    pub fn is_ok(&self) -> bool {
+        self.num_mismatched == 0
+    }
+
+    /// **Panics** with the worst mismatches if any gradient was not within tolerance.
+    pub fn assert_ok(&self) {
+        if !self.is_ok() {
+            panic!("{self}");
+        }
+    }
+}
+
+impl<E: std::fmt::Display> std::fmt::Display for GradCheckReport<E> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} of {} gradients did not match",
+            self.num_mismatched, self.num_checked
+        )?;
+        for m in self.mismatches.iter() {
+            write!(
+                f,
+                "\n  tensor #{} [{}]: numerical {} != autodiff {}",
+                m.id, m.index, m.numerical, m.autodiff
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Compares the gradients computed by backprop through `f` against central finite
+/// differences, for every element of every parameter of `model` and of the input `x`.
+///
+/// `f` computes a scalar loss from the model and the input. It is run twice per element
+/// checked, so this is only meant for small models and inputs (e.g. in tests of new
+/// layers or [crate::tensor_ops::custom_op()]s). Parameters are restored to their original
+/// values afterwards. Elements are compared with [AssertClose], with a tolerance of
+/// `atol + rtol * |numerical|`.
+///
+/// Use a higher precision dtype like `f64` for tight tolerances.
+///
+/// ```rust
+/// # use dfdx_core::{prelude::*, nn_traits::*};
+/// # let dev: Cpu = Default::default();
+/// let mut model: (Tensor<Rank2<3, 2>, f64, _>, Tensor<Rank1<2>, f64, _>) =
+///     (dev.sample_normal(), dev.sample_normal());
+/// let x: Tensor<Rank1<3>, f64, _> = dev.sample_normal();
+/// let report = gradcheck(
+///     &mut model,
+///     &x,
+///     |(w, b), x| x.try_matmul(w.clone())?.try_add(b.clone())?.try_tanh()?.try_sum(),
+///     Default::default(),
+/// );
+/// assert_eq!(report.num_checked, 11);
+/// report.assert_ok();
+/// ```
+pub fn gradcheck<M, S: Shape, E, D: Device<E>, F>(
+    model: &mut M,
+    x: &Tensor<S, E, D>,
+    f: F,
+    cfg: GradCheckConfig,
+) -> GradCheckReport<E>
+where
+    M: UpdateParams<E, D> + ZeroGrads<E, D>,
+    E: Dtype + AssertClose<Elem = E>,
+    F: FnMut(
+        &M,
+        Tensor<S, E, D, OwnedTape<E, D>>,
+    ) -> Result<Tensor<Rank0, E, D, OwnedTape<E, D>>, Error>,
+{
+    try_gradcheck(model, x, f, cfg).unwrap()
+}
+
+/// Fallible version of [gradcheck()].
+pub fn try_gradcheck<M, S: Shape, E, D: Device<E>, F>(
+    model: &mut M,
+    x: &Tensor<S, E, D>,
+    mut f: F,
+    cfg: GradCheckConfig,
+) -> Result<GradCheckReport<E>, Error>
+where
+    M: UpdateParams<E, D> + ZeroGrads<E, D>,
+    E: Dtype + AssertClose<Elem = E>,
+    F: FnMut(
+        &M,
+        Tensor<S, E, D, OwnedTape<E, D>>,
+    ) -> Result<Tensor<Rank0, E, D, OwnedTape<E, D>>, Error>,
+{
+    let mut grads = Gradients::leaky();
+    model.try_zero_grads(&mut grads)?;
+    let grads = f(model, x.clone().traced(grads))?.try_backward()?;
+
+    let mut loss = |model: &M, x: Tensor<S, E, D>| -> Result<f64, Error> {
+        let (y, _) = f(model, x.traced(Gradients::leaky()))?.split_tape();
+        Ok(y.as_vec()[0].to_f64().unwrap())
+    };
+
+    let mut report = GradCheckReport {
+        num_checked: 0,
+        num_mismatched: 0,
+        mismatches: Vec::new(),
+    };
+
+    let mut params = ReadParams(Vec::new());
+    model.try_update_params::<(), _>(&mut params, &grads, &mut Vec::new())?;
+    for (id, values, autodiff) in params.0 {
+        for (i, &a) in autodiff.iter().enumerate() {
+            let numerical = central_difference(values[i], cfg.eps, |v| {
+                let mut values = values.clone();
+                values[i] = v;
+                model.try_update_params::<(), _>(
+                    &mut WriteParam { id, values },
+                    &grads,
+                    &mut Vec::new(),
+                )?;
+                loss(model, x.clone())
+            })?;
+            report.check(id, i, numerical, a, &cfg);
+        }
+        model.try_update_params::<(), _>(
+            &mut WriteParam { id, values },
+            &grads,
+            &mut Vec::new(),
+        )?;
+    }
+
+    let values = x.as_vec();
+    let autodiff = grad_or_zeros(&grads, x);
+    for (i, &a) in autodiff.iter().enumerate() {
+        let numerical = central_difference(values[i], cfg.eps, |v| {
+            let mut values = values.clone();
+            values[i] = v;
+            loss(model, x.device.try_tensor_from_vec(values, x.shape)?)
+        })?;
+        report.check(x.id, i, numerical, a, &cfg);
+    }
+
+    report
+        .mismatches
+        .sort_by(|a, b| abs_diff(b).total_cmp(&abs_diff(a)));
+    report.mismatches.truncate(cfg.max_mismatches);
+    Ok(report)
+}
+
+impl<E: Dtype + AssertClose<Elem = E>> GradCheckReport<E> {
+    fn check(
+        &mut self,
+        id: UniqueId,
+        index: usize,
+        numerical: f64,
+        autodiff: E,
+        cfg: &GradCheckConfig,
+    ) {
+        self.num_checked += 1;
+        let numerical = E::from_f64(numerical).unwrap();
+        let tolerance = cfg.atol + cfg.rtol * numerical.to_f64().unwrap().abs();
+        let far = numerical.get_far_pair(&autodiff, E::from_f64(tolerance).unwrap());
+        let m = GradMismatch {
+            id,
+            index,
+            numerical,
+            autodiff,
+        };
+        // NaNs are never "far" from anything, but are always wrong
+        if far.is_some() || abs_diff(&m).is_nan() {
+            self.num_mismatched += 1;
+            self.mismatches.push(m);
+        }
+    }
+}
+
+fn abs_diff<E: Dtype>(m: &GradMismatch<E>) -> f64 {
+    (m.numerical.to_f64().unwrap() - m.autodiff.to_f64().unwrap()).abs()
+}
+
+/// Estimates the derivative of `f` at `v` with `(f(v + eps) - f(v - eps)) / 2eps`, where
+/// the step is measured after rounding `v +- eps` to `E`.
+fn central_difference<E: Dtype, F: FnMut(E) -> Result<f64, Error>>(
+    v: E,
+    eps: f64,
+    mut f: F,
+) -> Result<f64, Error> {
+    let eps = E::from_f64(eps).unwrap();
+    let (plus, minus) = (v + eps, v - eps);
+    let step = plus.to_f64().unwrap() - minus.to_f64().unwrap();
+    Ok((f(plus)? - f(minus)?) / step)
+}
+
+fn grad_or_zeros<S: Shape, E: Dtype, D: Device<E>>(
+    grads: &Gradients<E, D>,
+    t: &Tensor<S, E, D>,
+) -> Vec<E> {
+    match grads.get_ref_checked(t) {
+        Some(_) => grads.get(t).as_vec(),
+        None => std::vec![E::default(); t.shape.num_elements()],
+    }
+}
+
+/// Collects the id, values, and gradient of each parameter, in row major order.
+struct ReadParams<E>(Vec<(UniqueId, Vec<E>, Vec<E>)>);
+
+impl<M, E: Dtype, D: Device<E>> Optimizer<M, E, D> for ReadParams<E> {
+    fn update_tensor<S: Shape>(
+        &mut self,
+        t: &mut Tensor<S, E, D>,
+        gradients: &Gradients<E, D>,
+        _: &mut Vec<UniqueId>,
+    ) -> Result<(), Error> {
+        self.0.push((t.id, t.as_vec(), grad_or_zeros(gradients, t)));
+        Ok(())
+    }
+}
+
+/// Replaces the values of the parameter with id `id`, keeping its id.
+struct WriteParam<E> {
+    id: UniqueId,
+    values: Vec<E>,
+}
+
+impl<M, E: Dtype, D: Device<E>> Optimizer<M, E, D> for WriteParam<E> {
+    fn update_tensor<S: Shape>(
+        &mut self,
+        t: &mut Tensor<S, E, D>,
+        _: &Gradients<E, D>,
+        _: &mut Vec<UniqueId>,
+    ) -> Result<(), Error> {
+        if t.id == self.id {
+            let mut new = t.device.try_tensor_from_vec(self.values.clone(), t.shape)?;
+            new.id = t.id;
+            *t = new;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{shapes::*, tensor::*, tensor_ops::*, tests::*};
+
+    type Model = (
+        Tensor<Rank2<3, 2>, TestDtype, TestDevice>,
+        Tensor<Rank1<2>, TestDtype, TestDevice>,
+    );
+
+    #[test]
+    fn test_gradcheck_passes() {
+        let dev: TestDevice = Default::default();
+        let mut model: Model = (dev.sample_normal(), dev.sample_normal());
+        let x: Tensor<Rank2<4, 3>, TestDtype, _> = dev.sample_normal();
+        let original = model.clone();
+        let report = gradcheck(
+            &mut model,
+            &x,
+            |(w, b), x| {
+                let b = b.clone().try_broadcast::<Rank2<4, 2>, Axis<0>>()?;
+                x.try_matmul(w.clone())?.try_add(b)?.try_sin()?.try_mean()
+            },
+            Default::default(),
+        );
+        report.assert_ok();
+        assert_eq!(report.num_checked, 6 + 2 + 12);
+        assert!(report.mismatches.is_empty());
+
+        // parameters are restored
+        assert_eq!(model.0.array(), original.0.array());
+        assert_eq!(model.1.array(), original.1.array());
+        assert_eq!(model.0.id, original.0.id);
+    }
+
+    #[test]
+    fn test_gradcheck_finds_wrong_input_grad() {
+        let dev: TestDevice = Default::default();
+        let mut model: Tensor<Rank1<3>, TestDtype, _> = dev.tensor([1.0, 2.0, 3.0]);
+        let x: Tensor<Rank1<3>, TestDtype, _> = dev.tensor([0.5, -1.0, 2.0]);
+        let report = gradcheck(
+            &mut model,
+            &x,
+            |w, x| {
+                // backward is wrong by a factor of 2 for x
+                let y = custom_binary_op(
+                    x,
+                    w.clone(),
+                    |x, w| x.clone().try_mul(w.clone()),
+                    |x, w, _, g| {
+                        Ok((
+                            w.clone().try_mul(g.clone())?.try_mul(2.0)?,
+                            x.clone().try_mul(g)?,
+                        ))
+                    },
+                );
+                y.try_sum()
+            },
+            Default::default(),
+        );
+        assert!(!report.is_ok());
+        assert_eq!(report.num_checked, 6);
+        assert_eq!(report.num_mismatched, 3);
+        // worst mismatch first
+        let indices: std::vec::Vec<_> = report.mismatches.iter().map(|m| m.index).collect();
+        assert_eq!(indices, [2, 1, 0]);
+        assert!(report.mismatches.iter().all(|m| m.id == x.id));
+        let m = report.mismatches[0];
+        assert_close!(m.numerical, NumCast::from(3.0).unwrap(), 1e-2);
+        assert_close!(m.autodiff, NumCast::from(6.0).unwrap());
+    }
+
+    #[test]
+    fn test_gradcheck_finds_wrong_param_grad() {
+        let dev: TestDevice = Default::default();
+        let mut model: Model = (dev.sample_normal(), dev.sample_normal());
+        let x: Tensor<Rank1<3>, TestDtype, _> = dev.sample_normal();
+        let report = gradcheck(
+            &mut model,
+            &x,
+            |(w, b), x| {
+                // the gradient of b is dropped
+                let b = custom_op(
+                    b.leaky_trace(),
+                    |b| Ok(b.clone()),
+                    |b, _, _| Ok(b.device.zeros()),
+                );
+                x.try_matmul(w.clone())?.try_add(b)?.try_sum()
+            },
+            Default::default(),
+        );
+        assert_eq!(report.num_mismatched, 2);
+        assert!(report.mismatches.iter().all(|m| m.id == model.1.id));
+    }
+}
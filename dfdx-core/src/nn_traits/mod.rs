@@ -1,9 +1,15 @@
+mod gradcheck;
 mod tuples;
 mod vecs;
 
+pub use gradcheck::{gradcheck, try_gradcheck, GradCheckConfig, GradCheckReport, GradMismatch};
+
+use num_traits::Float;
 use std::vec::Vec;
 
-use crate::prelude::{Device, Dtype, Error, Gradients, Shape, Tensor, UniqueId};
+use crate::prelude::{
+    Device, Dtype, Error, Gradients, Rank0, Shape, Storage, SumTo, Tensor, TryAdd, TryMul, UniqueId,
+};
 
 /// Mutable & Immutable forward of `Input` that produces [Module::Output].
 pub trait Module<X> {
@@ -82,6 +88,16 @@ pub trait UpdateParams<E: Dtype, D: Device<E>> {
         gradients: &Gradients<E, D>,
         missing_tensors: &mut Vec<UniqueId>,
     ) -> Result<(), crate::tensor::Error>;
+
+    /// Calls `f` with the path, shape and a flattened view of every tensor that
+    /// [UpdateParams::try_update_params()] updates. The flattened view has the same id as the
+    /// parameter.
+    ///
+    /// Paths are the names of fields (or indices of tuples & vecs) joined by `.`, e.g.
+    /// `"0.weight"`, and are prefixed with `location`, which is either empty or ends with `.`.
+    fn visit_params<F>(&self, location: &str, f: &mut F)
+    where
+        F: FnMut(&str, &[usize], &Tensor<(usize,), E, D>);
 }
 
 impl<S: Shape, E: Dtype, D: Device<E>> UpdateParams<E, D> for Tensor<S, E, D> {
@@ -93,6 +109,26 @@ impl<S: Shape, E: Dtype, D: Device<E>> UpdateParams<E, D> for Tensor<S, E, D> {
     ) -> Result<(), crate::tensor::Error> {
         optimizer.update_tensor(self, gradients, missing_tensors)
     }
+
+    fn visit_params<F>(&self, location: &str, f: &mut F)
+    where
+        F: FnMut(&str, &[usize], &Tensor<(usize,), E, D>),
+    {
+        let shape: Vec<usize> = self.shape.concrete().into();
+        let flat = Tensor {
+            id: self.id,
+            shape: (self.shape.num_elements(),),
+            strides: [1],
+            data: self.data.clone(),
+            device: self.device.clone(),
+            tape: Default::default(),
+        };
+        f(
+            location.strip_suffix('.').unwrap_or(location),
+            &shape,
+            &flat,
+        )
+    }
 }
 
 /// Something that can allocate a [Gradients] object or zero out the [Gradients] object.
@@ -113,6 +149,171 @@ pub trait ZeroGrads<E: Dtype, D: Device<E>> {
     }
 }
 
+impl<S: Shape, E: Dtype, D: Device<E>> ZeroGrads<E, D> for Tensor<S, E, D> {
+    fn try_zero_grads(&self, grads: &mut Gradients<E, D>) -> Result<(), crate::tensor::Error> {
+        self.device
+            .try_fill_with_zeros(grads.get_or_alloc_mut(self)?)
+    }
+}
+
+/// Something that can view or modify the [Gradients] of its parameters, e.g. for gradient clipping.
+///
+/// Parameters that don't have a gradient in the [Gradients] object are skipped.
+pub trait WithGrads<E: Dtype, D: Device<E>> {
+    /// Calls `f` with the gradient of each parameter, flattened into a 1d tensor.
+    ///
+    /// This takes `&mut` [Gradients] so each gradient can be lent to `f` without copying it.
+    fn try_grads_view<F>(&self, grads: &mut Gradients<E, D>, f: &mut F) -> Result<(), Error>
+    where
+        F: FnMut(&Tensor<(usize,), E, D>) -> Result<(), Error>;
+
+    /// Replaces the gradient of each parameter with the result of `f`. `f` receives the
+    /// gradient flattened into a 1d tensor, and must return a tensor of the same length.
+    fn try_grads_map<F>(&self, grads: &mut Gradients<E, D>, f: &mut F) -> Result<(), Error>
+    where
+        F: FnMut(Tensor<(usize,), E, D>) -> Result<Tensor<(usize,), E, D>, Error>;
+
+    /// Registers `hook` on the gradient of each parameter with [Gradients::register_hook()].
+    /// `hook` receives the id of the parameter and its gradient flattened into a 1d tensor,
+    /// and must return a tensor of the same length.
+    fn register_grad_hooks<F>(&self, grads: &mut Gradients<E, D>, hook: &F)
+    where
+        F: 'static
+            + Clone
+            + Send
+            + Sync
+            + Fn(UniqueId, Tensor<(usize,), E, D>) -> Result<Tensor<(usize,), E, D>, Error>;
+
+    /// Computes the sum of all squared gradient values.
+    ///
+    /// The squared norm of each gradient is summed on the device, so this only
+    /// synchronizes with the device once.
+    fn grads_norm_squared(&self, grads: &mut Gradients<E, D>) -> E {
+        self.try_grads_norm_squared(grads).unwrap()
+    }
+    fn try_grads_norm_squared(&self, grads: &mut Gradients<E, D>) -> Result<E, Error> {
+        let mut total: Option<Tensor<Rank0, E, D>> = None;
+        self.try_grads_view(grads, &mut |g| {
+            let norm_squared = g.clone().try_square()?.try_sum::<Rank0, _>()?;
+            total = Some(match total.take() {
+                Some(total) => total.try_add(norm_squared)?,
+                None => norm_squared,
+            });
+            Ok(())
+        })?;
+        Ok(total.map_or(E::default(), |total| total.as_vec()[0]))
+    }
+
+    /// Scales all gradients so that their global L2 norm is at most `max_norm`.
+    /// Returns the global norm *before* clipping.
+    ///
+    /// Gradients are scaled in place, without copying them.
+    ///
+    /// This is the same as pytorch's `torch.nn.utils.clip_grad_norm_`.
+    fn grads_clip_norm(&self, grads: &mut Gradients<E, D>, max_norm: E) -> E {
+        self.try_grads_clip_norm(grads, max_norm).unwrap()
+    }
+    fn try_grads_clip_norm(&self, grads: &mut Gradients<E, D>, max_norm: E) -> Result<E, Error> {
+        let norm = Float::sqrt(self.try_grads_norm_squared(grads)?.to_f64().unwrap());
+        let scale = max_norm.to_f64().unwrap() / (norm + 1e-6);
+        if scale < 1.0 {
+            self.try_grads_map(grads, &mut |g| g.try_mul(scale))?;
+        }
+        Ok(E::from_f64(norm).unwrap())
+    }
+
+    /// Clamps all gradient values to `[-clip, clip]`.
+    ///
+    /// This is the same as pytorch's `torch.nn.utils.clip_grad_value_`.
+    fn grads_clip_value(&self, grads: &mut Gradients<E, D>, clip: E) {
+        self.try_grads_clip_value(grads, clip).unwrap()
+    }
+    fn try_grads_clip_value(&self, grads: &mut Gradients<E, D>, clip: E) -> Result<(), Error> {
+        let clip = clip.to_f64().unwrap();
+        self.try_grads_map(grads, &mut |g| g.try_clamp(-clip, clip))
+    }
+}
+
+impl<S: Shape, E: Dtype, D: Device<E>> WithGrads<E, D> for Tensor<S, E, D> {
+    fn try_grads_view<F>(&self, grads: &mut Gradients<E, D>, f: &mut F) -> Result<(), Error>
+    where
+        F: FnMut(&Tensor<(usize,), E, D>) -> Result<(), Error>,
+    {
+        let Some(grad) = grads.remove(self) else {
+            return Ok(());
+        };
+        let grad = self.flat_grad(grad);
+        let result = f(&grad);
+        let grad = std::sync::Arc::try_unwrap(grad.data).unwrap_or_else(|data| (*data).clone());
+        grads.insert(self, grad);
+        result
+    }
+
+    fn try_grads_map<F>(&self, grads: &mut Gradients<E, D>, f: &mut F) -> Result<(), Error>
+    where
+        F: FnMut(Tensor<(usize,), E, D>) -> Result<Tensor<(usize,), E, D>, Error>,
+    {
+        let Some(grad) = grads.remove(self) else {
+            return Ok(());
+        };
+        let len = Storage::<E>::len(&self.device, &grad);
+        let grad = f(self.flat_grad(grad))?;
+        let grad = std::sync::Arc::try_unwrap(grad.data).unwrap_or_else(|data| (*data).clone());
+        if Storage::<E>::len(&self.device, &grad) != len {
+            return Err(Error::WrongNumElements);
+        }
+        grads.insert(self, grad);
+        Ok(())
+    }
+
+    fn register_grad_hooks<F>(&self, grads: &mut Gradients<E, D>, hook: &F)
+    where
+        F: 'static
+            + Clone
+            + Send
+            + Sync
+            + Fn(UniqueId, Tensor<(usize,), E, D>) -> Result<Tensor<(usize,), E, D>, Error>,
+    {
+        let (id, hook) = (self.id, hook.clone());
+        grads.register_hook(self, move |grad: Tensor<S, E, D>| {
+            let len = Storage::<E>::len(&grad.device, &grad.data);
+            let flat = Tensor {
+                id: grad.id,
+                shape: (len,),
+                strides: [1],
+                data: grad.data,
+                device: grad.device.clone(),
+                tape: Default::default(),
+            };
+            let flat = hook(id, flat)?;
+            if flat.shape.0 != len {
+                return Err(Error::WrongNumElements);
+            }
+            Ok(Tensor {
+                id: flat.id,
+                data: flat.data,
+                shape: grad.shape,
+                strides: grad.strides,
+                device: grad.device,
+                tape: Default::default(),
+            })
+        });
+    }
+}
+
+impl<S: Shape, E: Dtype, D: Device<E>> Tensor<S, E, D> {
+    fn flat_grad(&self, grad: <D as Storage<E>>::Vec) -> Tensor<(usize,), E, D> {
+        Tensor {
+            id: crate::tensor::unique_id(),
+            shape: (Storage::<E>::len(&self.device, &grad),),
+            strides: [1],
+            data: std::sync::Arc::new(grad),
+            device: self.device.clone(),
+            tape: Default::default(),
+        }
+    }
+}
+
 #[cfg(feature = "safetensors")]
 /// Something that can be saved to a .safetensors file.
 pub trait SaveSafeTensors {
@@ -259,3 +460,84 @@ pub trait BuildModuleExt<M>: Sized {
     }
 }
 impl<D, M> BuildModuleExt<M> for D {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{tensor::*, tensor_ops::*, tests::*};
+
+    #[test]
+    fn test_grads_clip_norm() {
+        let dev: TestDevice = Default::default();
+        let a = dev.tensor([1.0, -2.0, 3.0]).to_dtype::<TestDtype>();
+        let b = dev.tensor([0.5, 4.0]).to_dtype::<TestDtype>();
+        let grads = a.leaky_trace().square().sum().backward();
+        let mut grads = b.trace(grads).square().sum().backward();
+
+        let params = (a.clone(), b.clone());
+        let norm = params.grads_norm_squared(&mut grads);
+        assert_close!(norm, NumCast::from(121.0).unwrap());
+
+        let norm = params.grads_clip_norm(&mut grads, NumCast::from(5.5).unwrap());
+        assert_close!(norm, NumCast::from(11.0).unwrap());
+        assert_close_to_literal!(grads.get(&a), [1.0, -2.0, 3.0]);
+        assert_close_to_literal!(grads.get(&b), [0.5, 4.0]);
+
+        // already within the max norm, so nothing changes
+        let norm = params.grads_clip_norm(&mut grads, NumCast::from(10.0).unwrap());
+        assert_close!(norm, NumCast::from(5.5).unwrap());
+        assert_close_to_literal!(grads.get(&a), [1.0, -2.0, 3.0]);
+    }
+
+    #[test]
+    fn test_grads_clip_value() {
+        let dev: TestDevice = Default::default();
+        let a = dev.tensor([1.0, -2.0, 3.0]).to_dtype::<TestDtype>();
+        let b = dev.tensor([0.5, 4.0]).to_dtype::<TestDtype>();
+        let grads = a.leaky_trace().square().sum().backward();
+        let mut grads = b.trace(grads).square().sum().backward();
+
+        vec![a.clone()].grads_clip_value(&mut grads, NumCast::from(1.5).unwrap());
+        assert_close_to_literal!(grads.get(&a), [1.5, -1.5, 1.5]);
+        assert_close_to_literal!(grads.get(&b), [1.0, 8.0]);
+    }
+
+    #[test]
+    fn test_register_grad_hooks() {
+        let dev: TestDevice = Default::default();
+        let a = dev.tensor([1.0, -2.0, 3.0]).to_dtype::<TestDtype>();
+        let b = dev.tensor([0.5, 4.0]).to_dtype::<TestDtype>();
+        let params = vec![(a.clone(), b.clone())];
+
+        let seen = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+        let a_id = a.id;
+        let mut grads = Gradients::leaky();
+        params.register_grad_hooks(&mut grads, &{
+            let seen = seen.clone();
+            move |id, g: Tensor<(usize,), TestDtype, TestDevice>| {
+                seen.lock().unwrap().push((id, g.shape.0));
+                if id == a_id {
+                    g.try_mul(0.5)
+                } else {
+                    Ok(g)
+                }
+            }
+        });
+
+        let grads = a.trace(grads).square().sum().backward();
+        assert_close_to_literal!(grads.get(&a), [1.0, -2.0, 3.0]);
+
+        // hooks only run on the gradients computed by each pass
+        let grads = b.trace(grads).square().sum().backward();
+        assert_close_to_literal!(grads.get(&a), [1.0, -2.0, 3.0]);
+        assert_close_to_literal!(grads.get(&b), [1.0, 8.0]);
+
+        // and gradients accumulated from earlier passes are not modified again
+        let grads = a.trace(grads).square().sum().backward();
+        assert_close_to_literal!(grads.get(&a), [2.0, -4.0, 6.0]);
+        assert_eq!(
+            seen.lock().unwrap().as_slice(),
+            &[(a.id, 3), (b.id, 2), (a.id, 3)]
+        );
+    }
+}
@@ -1,6 +1,6 @@
 use crate::{
     dtypes::Dtype,
-    tensor::{Error, UniqueId},
+    tensor::{Error, Tensor, UniqueId},
     tensor_ops::Device,
 };
 
@@ -58,6 +58,10 @@ macro_rules! tuple_impls {
                 $(self.$idx.try_update_params(optimizer, gradients, missing_tensors)?;)+
                 Ok(())
             }
+
+            fn visit_params<F: FnMut(&str, &[usize], &Tensor<(usize,), Elem, Dev>)>(&self, location: &str, f: &mut F) {
+                $(self.$idx.visit_params(&format!("{location}{}.", $idx), f);)+
+            }
         }
 
         impl<Dev: Device<Elem>, Elem: Dtype, $($name: crate::nn_traits::ZeroGrads<Elem, Dev>),+> crate::nn_traits::ZeroGrads<Elem, Dev> for ($($name,)+) {
@@ -67,6 +71,31 @@ macro_rules! tuple_impls {
             }
         }
 
+        impl<Dev: Device<Elem>, Elem: Dtype, $($name: crate::nn_traits::WithGrads<Elem, Dev>),+> crate::nn_traits::WithGrads<Elem, Dev> for ($($name,)+) {
+            fn try_grads_view<F>(&self, grads: &mut crate::prelude::Gradients<Elem, Dev>, f: &mut F) -> Result<(), Error>
+            where
+                F: FnMut(&crate::prelude::Tensor<(usize,), Elem, Dev>) -> Result<(), Error>,
+            {
+                $(self.$idx.try_grads_view(grads, f)?;)+
+                Ok(())
+            }
+
+            fn try_grads_map<F>(&self, grads: &mut crate::prelude::Gradients<Elem, Dev>, f: &mut F) -> Result<(), Error>
+            where
+                F: FnMut(crate::prelude::Tensor<(usize,), Elem, Dev>) -> Result<crate::prelude::Tensor<(usize,), Elem, Dev>, Error>,
+            {
+                $(self.$idx.try_grads_map(grads, f)?;)+
+                Ok(())
+            }
+
+            fn register_grad_hooks<F>(&self, grads: &mut crate::prelude::Gradients<Elem, Dev>, hook: &F)
+            where
+                F: 'static + Clone + Send + Sync + Fn(UniqueId, crate::prelude::Tensor<(usize,), Elem, Dev>) -> Result<crate::prelude::Tensor<(usize,), Elem, Dev>, Error>,
+            {
+                $(self.$idx.register_grad_hooks(grads, hook);)+
+            }
+        }
+
         /*This macro expands like this for a 4-tuple:
 
         impl<
@@ -1,6 +1,6 @@
 use crate::{
     dtypes::Dtype,
-    tensor::{Error, UniqueId},
+    tensor::{Error, Tensor, UniqueId},
     tensor_ops::Device,
 };
 
@@ -42,6 +42,16 @@ impl<E: Dtype, D: Device<E>, T: crate::nn_traits::UpdateParams<E, D>>
         }
         Ok(())
     }
+
+    fn visit_params<F: FnMut(&str, &[usize], &Tensor<(usize,), E, D>)>(
+        &self,
+        location: &str,
+        f: &mut F,
+    ) {
+        for (i, m_i) in self.iter().enumerate() {
+            m_i.visit_params(&format!("{location}{i}."), f);
+        }
+    }
 }
 
 impl<E: Dtype, D: Device<E>, T: crate::nn_traits::ZeroGrads<E, D>> crate::nn_traits::ZeroGrads<E, D>
@@ -58,6 +68,56 @@ impl<E: Dtype, D: Device<E>, T: crate::nn_traits::ZeroGrads<E, D>> crate::nn_tra
     }
 }
 
+impl<E: Dtype, D: Device<E>, T: crate::nn_traits::WithGrads<E, D>> crate::nn_traits::WithGrads<E, D>
+    for Vec<T>
+{
+    fn try_grads_view<F>(
+        &self,
+        grads: &mut crate::tensor::Gradients<E, D>,
+        f: &mut F,
+    ) -> Result<(), crate::tensor::Error>
+    where
+        F: FnMut(&crate::tensor::Tensor<(usize,), E, D>) -> Result<(), crate::tensor::Error>,
+    {
+        for m_i in self.iter() {
+            m_i.try_grads_view(grads, f)?;
+        }
+        Ok(())
+    }
+
+    fn try_grads_map<F>(
+        &self,
+        grads: &mut crate::tensor::Gradients<E, D>,
+        f: &mut F,
+    ) -> Result<(), crate::tensor::Error>
+    where
+        F: FnMut(
+            crate::tensor::Tensor<(usize,), E, D>,
+        ) -> Result<crate::tensor::Tensor<(usize,), E, D>, crate::tensor::Error>,
+    {
+        for m_i in self.iter() {
+            m_i.try_grads_map(grads, f)?;
+        }
+        Ok(())
+    }
+
+    fn register_grad_hooks<F>(&self, grads: &mut crate::tensor::Gradients<E, D>, hook: &F)
+    where
+        F: 'static
+            + Clone
+            + Send
+            + Sync
+            + Fn(
+                crate::tensor::UniqueId,
+                crate::tensor::Tensor<(usize,), E, D>,
+            ) -> Result<crate::tensor::Tensor<(usize,), E, D>, crate::tensor::Error>,
+    {
+        for m_i in self.iter() {
+            m_i.register_grad_hooks(grads, hook);
+        }
+    }
+}
+
 #[cfg(feature = "safetensors")]
 impl<T: crate::nn_traits::SaveSafeTensors> crate::nn_traits::SaveSafeTensors for Vec<T> {
     fn write_safetensors(
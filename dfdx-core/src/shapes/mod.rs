@@ -23,7 +23,7 @@ pub use broadcasts::{
 };
 pub use permutes::{PermuteShapeTo, PermuteStridesTo};
 pub use realize::RealizeShapeTo;
-pub use replace_dim::{RemoveDimTo, ReplaceDimTo};
+pub use replace_dim::{RemoveDimTo, ReplaceDimAlong, ReplaceDimTo};
 
 pub use same_numel::AssertSameNumel;
 pub use slice::SliceShape;
@@ -89,6 +89,19 @@ pub trait ReplaceDimTo<Dst: Shape, Idx: Shape>: Shape {
     }
 }
 
+/// Marker for shapes that can have the dimension along an axis replaced with a new one,
+/// keeping all other dimensions
+pub trait ReplaceDimAlong<Ax: Axes<Array = [isize; 1]>, New: Dim>: Shape {
+    type Output: Shape<Concrete = Self::Concrete>;
+
+    #[inline]
+    fn replace_along(&self, new: New) -> Self::Output {
+        let mut dims = self.concrete();
+        dims[Ax::as_array()[0] as usize] = new.size();
+        Self::Output::from_concrete(&dims).unwrap()
+    }
+}
+
 macro_rules! replace {
     (($($DimVars:tt),*), $Ax:ty, $Dst:ty, $Idx:ty) => {
 impl<$($DimVars: Dim, )* New: Dim> ReplaceDimTo<$Dst, $Idx> for ($($DimVars, )*) {
@@ -105,12 +118,21 @@ impl<$($DimVars: Dim, )*> RemoveDimTo<$Dst, $Idx> for ($($DimVars, )*) {
     };
 }
 
+macro_rules! replace_along {
+    (($($DimVars:tt),*), $Ax:ty, $Dst:ty) => {
+impl<$($DimVars: Dim, )* New: Dim> ReplaceDimAlong<$Ax, New> for ($($DimVars, )*) {
+    type Output = $Dst;
+}
+    };
+}
+
 macro_rules! replace_and_remove_all {
     ($(@ $x:tt)? [] $i:tt) => {
     };
     (@ [$($befores:ident)*] [$cur:ident $($afters:ident)*] [$idx:tt $($idxs:tt)*]) => {
         replace!(($($befores,)* $cur $(,$afters)*), Axis<$idx>, ($($befores,)* New, $($afters),*), ($($befores,)* New,));
         removed!(($($befores,)* $cur $(,$afters)*), Axis<$idx>, ($($befores,)* $($afters,)*), ($($befores,)*));
+        replace_along!(($($befores,)* $cur $(,$afters)*), Axis<$idx>, ($($befores,)* New, $($afters),*));
 
         replace_and_remove_all!(@ [$($befores)* $cur] [$($afters)*] [$($idxs)*]);
     };
@@ -127,6 +127,29 @@ impl RandomU64 for Cpu {
     }
 }
 
+impl RngState for Cpu {
+    fn replace_rng(&self, rng: StdRng) -> StdRng {
+        #[cfg(not(feature = "no-std"))]
+        {
+            std::mem::replace(&mut *self.rng.lock().unwrap(), rng)
+        }
+        #[cfg(feature = "no-std")]
+        {
+            std::mem::replace(&mut *self.rng.lock(), rng)
+        }
+    }
+    fn rng_state(&self) -> StdRng {
+        #[cfg(not(feature = "no-std"))]
+        {
+            self.rng.lock().unwrap().clone()
+        }
+        #[cfg(feature = "no-std")]
+        {
+            self.rng.lock().clone()
+        }
+    }
+}
+
 impl<E: Unit> Storage<E> for Cpu {
     type Vec = CachableVec<E>;
 
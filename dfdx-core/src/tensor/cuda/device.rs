@@ -1,7 +1,7 @@
 use crate::shapes::{Shape, Unit};
 use crate::tensor::cpu::Cpu;
 use crate::tensor::{
-    cache::TensorCache, Cache, Error, NoneTape, RandomU64, Storage, Synchronize, Tensor,
+    cache::TensorCache, Cache, Error, NoneTape, RandomU64, RngState, Storage, Synchronize, Tensor,
 };
 
 use cudarc::driver::{DevicePtr, DevicePtrMut, DeviceRepr};
@@ -220,6 +220,15 @@ impl RandomU64 for Cuda {
     }
 }
 
+impl RngState for Cuda {
+    fn replace_rng(&self, rng: rand::rngs::StdRng) -> rand::rngs::StdRng {
+        self.cpu.replace_rng(rng)
+    }
+    fn rng_state(&self) -> rand::rngs::StdRng {
+        self.cpu.rng_state()
+    }
+}
+
 impl Cache for Cuda {
     fn try_enable_cache(&self) -> Result<(), Error> {
         self.cache.enable();
@@ -7,8 +7,35 @@ pub enum Error {
     OutOfMemory,
     /// Not enough elements were provided when creating a tensor
     WrongNumElements,
+    /// Two tensors that must have the same shape don't.
+    ShapeMismatch {
+        expected: std::vec::Vec<usize>,
+        found: std::vec::Vec<usize>,
+    },
+    /// `k` of [crate::tensor_ops::SortAlong::try_topk()] is larger than the size of the axis.
+    TopKTooLarge { k: usize, size: usize },
+    /// A tensor doesn't have the strides it must have, e.g. the result of a gradient hook.
+    StridesMismatch {
+        expected: std::vec::Vec<usize>,
+        found: std::vec::Vec<usize>,
+    },
     /// Some tensors were unused by an optimizer in a graph.
     UnusedTensors(std::vec::Vec<crate::tensor::UniqueId>),
+    /// A backward operation was recorded without a differentiable form, so higher
+    /// order gradients (or forward mode tangents) can't be computed through it.
+    NoHigherOrderGradient,
+    /// Backprop was run again through a backward operation that can only run once,
+    /// see [crate::tensor::Tape::add_retainable_backward_op].
+    NotRetainable,
+    /// An operation produced a NaN or infinite value, found while detecting anomalies
+    /// with [crate::tensor::Tensor::detect_anomaly()]. `backward` is true if the value
+    /// was in a gradient computed by the operation's backward pass.
+    NonFinite {
+        op: &'static str,
+        backward: bool,
+        input_shapes: std::vec::Vec<std::vec::Vec<usize>>,
+        output_shape: std::vec::Vec<usize>,
+    },
     #[cfg(feature = "cuda")]
     CublasError(cudarc::cublas::result::CublasError),
     #[cfg(feature = "cuda")]
@@ -2,11 +2,15 @@
 #![allow(clippy::type_complexity)]
 
 use std::collections::{BTreeMap, BTreeSet};
+use std::sync::Arc;
 use std::{boxed::Box, vec::Vec};
 
+use super::higher_order::HigherOrderOps;
+use super::op_info::OpInfo;
 use super::tensorlike::Tensorlike;
-use super::{storage_traits::Storage, unique_id, Error, Tensor, UniqueId};
-use crate::shapes::Shape;
+use super::{storage_traits::Storage, unique_id, Error, PutTape, SplitTape, Tensor, UniqueId};
+use crate::shapes::{Dtype, Shape};
+use crate::tensor_ops::{axpy::AxpyKernel, Device, ReshapeTo};
 
 /// A generic container for keeping gradients of tensors keyed by the
 /// tensor's [UniqueId].
@@ -16,12 +20,41 @@ use crate::shapes::Shape;
 /// 2. Remove entries
 /// 3. Access references to arrays
 /// 4. Access mutable references to arrays
-#[derive(Clone, Debug)]
+/// 5. Register hooks that are run on gradients during backprop
+#[derive(Clone)]
 pub struct Gradients<E, D: Storage<E>> {
     /// Using BTreeMap for no-std support
     gradient_by_id: BTreeMap<UniqueId, D::Vec>,
     /// Using BTreeSet for no-std support
     leaf_ids: Option<BTreeSet<UniqueId>>,
+    /// Hooks run on the gradients at the end of [OwnedTape::execute].
+    hooks: BTreeMap<UniqueId, GradHooks<E, D>>,
+}
+
+/// A type erased gradient hook, see [Gradients::register_hook].
+type GradHook<E, D> =
+    Arc<dyn Fn(&D, <D as Storage<E>>::Vec) -> Result<<D as Storage<E>>::Vec, Error> + Send + Sync>;
+
+/// All the hooks registered for one tensor.
+#[derive(Clone)]
+struct GradHooks<E, D: Storage<E>> {
+    device: D,
+    hooks: Vec<GradHook<E, D>>,
+    /// Adds the gradient from earlier passes to the gradient of the current pass.
+    accumulate: fn(&D, &mut D::Vec, &D::Vec) -> Result<(), Error>,
+}
+
+impl<E, D: Storage<E>> std::fmt::Debug for Gradients<E, D> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Gradients")
+            .field("gradient_by_id", &self.gradient_by_id)
+            .field("leaf_ids", &self.leaf_ids)
+            .field(
+                "num_hooks",
+                &self.hooks.values().map(|h| h.hooks.len()).sum::<usize>(),
+            )
+            .finish()
+    }
 }
 
 impl<E, D: Storage<E>> Gradients<E, D> {
@@ -35,6 +68,7 @@ impl<E, D: Storage<E>> Gradients<E, D> {
         Self {
             gradient_by_id: Default::default(),
             leaf_ids: None,
+            hooks: Default::default(),
         }
     }
 }
@@ -97,6 +131,141 @@ impl<E, D: Storage<E>> Gradients<E, D> {
         self.gradient_by_id.get(&t.id()).unwrap()
     }
 
+    /// Removes the gradient associated with `t` if present.
+    pub(crate) fn remove<S: Shape>(&mut self, t: &impl Tensorlike<S, E, D>) -> Option<D::Vec> {
+        self.gradient_by_id.remove(&t.id())
+    }
+
+    /// Inserts `grad` as the gradient of `t`, replacing any previous gradient.
+    pub(crate) fn insert<S: Shape>(&mut self, t: &impl Tensorlike<S, E, D>, grad: D::Vec) {
+        self.gradient_by_id.insert(t.id(), grad);
+    }
+
+    /// Moves the gradients of tensors created before `id` out of `other`, accumulating
+    /// them with `add` where `self` already has a gradient for the tensor.
+    pub(crate) fn accumulate_created_before<F>(
+        &mut self,
+        other: Self,
+        id: UniqueId,
+        mut add: F,
+    ) -> Result<(), Error>
+    where
+        F: FnMut(&mut D::Vec, &D::Vec) -> Result<(), Error>,
+    {
+        for (k, grad) in other.gradient_by_id.into_iter().filter(|(k, _)| *k < id) {
+            match self.gradient_by_id.get_mut(&k) {
+                Some(existing) => add(existing, &grad)?,
+                None => {
+                    self.gradient_by_id.insert(k, grad);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Registers `hook` to run on the gradient of `t` at the end of each backward pass
+    /// that uses these gradients. The hook receives the gradient of `t`, and returns the
+    /// gradient to store in its place, so it can be used to both inspect and modify gradients.
+    ///
+    /// Hooks run after all backward operations, so they see the complete gradient of the
+    /// current pass, and changes are not propagated any further. This makes them suited for
+    /// parameters. The result is then added to the gradient accumulated from earlier passes,
+    /// so each pass is only modified once, and hooks don't run in passes that don't compute
+    /// a gradient for `t`. To modify the gradient of an intermediate value before it flows
+    /// into the values it was computed from, use [Tensor::with_grad_hook()].
+    ///
+    /// Multiple hooks on the same tensor run in the order they were registered. Hooks
+    /// are kept when [Gradients] are cloned or reused for the next pass.
+    ///
+    /// Backprop returns [Error::ShapeMismatch] if `hook` returns a tensor with a different
+    /// shape. See [Tensor::with_grad_hook()] for the strides of the returned tensor.
+    ///
+    /// ```rust
+    /// # use dfdx_core::prelude::*;
+    /// # let dev: Cpu = Default::default();
+    /// let w: Tensor<Rank1<3>, f32, _> = dev.tensor([1.0, 2.0, 3.0]);
+    /// let mut grads = Gradients::leaky();
+    /// // zero out the gradient of the first element
+    /// let mask = dev.tensor([0.0, 1.0, 1.0]);
+    /// grads.register_hook(&w, move |g| g.try_mul(mask.clone()));
+    /// let g = w.trace(grads).square().sum().backward();
+    /// assert_eq!(g.get(&w).array(), [0.0, 4.0, 6.0]);
+    /// ```
+    pub fn register_hook<S: Shape, F>(&mut self, t: &impl Tensorlike<S, E, D>, hook: F)
+    where
+        E: Dtype,
+        D: Device<E>,
+        F: 'static + Send + Sync + Fn(Tensor<S, E, D>) -> Result<Tensor<S, E, D>, Error>,
+    {
+        self.hooks
+            .entry(t.id())
+            .or_insert_with(|| GradHooks {
+                device: t.dev().clone(),
+                hooks: Vec::new(),
+                accumulate: |device, grad, previous| {
+                    AxpyKernel::forward(device, grad, E::ONE, previous, E::ONE)
+                },
+            })
+            .hooks
+            .push(erase_hook(t, hook));
+    }
+
+    /// Removes all hooks registered for `t`.
+    pub fn remove_hooks<S: Shape>(&mut self, t: &impl Tensorlike<S, E, D>) {
+        self.hooks.remove(&t.id());
+    }
+
+    /// Removes all hooks.
+    pub fn clear_hooks(&mut self) {
+        self.hooks.clear();
+    }
+
+    /// Empty gradients with the same leafs and hooks as these ones.
+    pub(crate) fn without_values(&self) -> Self
+    where
+        E: Clone,
+    {
+        Self {
+            gradient_by_id: Default::default(),
+            leaf_ids: self.leaf_ids.clone(),
+            hooks: self.hooks.clone(),
+        }
+    }
+
+    /// Removes the gradients that have hooks registered, so that the next pass computes
+    /// them from scratch. Pass the result to [Gradients::run_hooks] after the pass.
+    fn take_hooked(&mut self) -> BTreeMap<UniqueId, D::Vec> {
+        let mut previous = BTreeMap::new();
+        for id in self.hooks.keys() {
+            if let Some(grad) = self.gradient_by_id.remove(id) {
+                previous.insert(*id, grad);
+            }
+        }
+        previous
+    }
+
+    /// Runs all registered hooks on the gradients of the current pass, and then adds
+    /// the `previous` gradients from [Gradients::take_hooked] back in.
+    fn run_hooks(&mut self, mut previous: BTreeMap<UniqueId, D::Vec>) -> Result<(), Error> {
+        for (id, hooks) in self.hooks.iter() {
+            let previous = previous.remove(id);
+            let Some(mut grad) = self.gradient_by_id.remove(id) else {
+                if let Some(previous) = previous {
+                    self.gradient_by_id.insert(*id, previous);
+                }
+                continue;
+            };
+            for hook in hooks.hooks.iter() {
+                grad = hook(&hooks.device, grad)?;
+            }
+            if let Some(previous) = previous {
+                (hooks.accumulate)(&hooks.device, &mut grad, &previous)?;
+            }
+            self.gradient_by_id.insert(*id, grad);
+        }
+        Ok(())
+    }
+
     /// Clones the gradient and transforms it into a tensor.
     ///
     /// # Panics
@@ -175,12 +344,103 @@ impl<E, D: Storage<E>> Gradients<E, D> {
     }
 }
 
+/// Wraps `hook` to work on the raw gradient of `t`, which is in the physical layout of `t`.
+fn erase_hook<S: Shape, E: Dtype, D: Device<E>, F>(
+    t: &impl Tensorlike<S, E, D>,
+    hook: F,
+) -> GradHook<E, D>
+where
+    F: 'static + Send + Sync + Fn(Tensor<S, E, D>) -> Result<Tensor<S, E, D>, Error>,
+{
+    let (shape, strides) = (*t.shape(), t.strides());
+    let contiguous = strides == shape.strides();
+    Arc::new(move |device, grad| {
+        let grad = hook(Tensor {
+            id: unique_id(),
+            data: Arc::new(grad),
+            shape,
+            strides,
+            device: device.clone(),
+            tape: Default::default(),
+        })?;
+        if grad.shape != shape {
+            return Err(Error::ShapeMismatch {
+                expected: shape.concrete().into(),
+                found: grad.shape.concrete().into(),
+            });
+        }
+        let grad = if grad.strides == strides {
+            grad
+        } else if contiguous {
+            grad.try_contiguous()?
+        } else {
+            return Err(Error::StridesMismatch {
+                expected: strides.into(),
+                found: grad.strides.into(),
+            });
+        };
+        Ok(Arc::try_unwrap(grad.data).unwrap_or_else(|data| (*data).clone()))
+    })
+}
+
+impl<S: Shape, E: Dtype, D: Storage<E>> Tensor<S, E, D, OwnedTape<E, D>> {
+    /// Registers `hook` to run on the gradient of this tensor during backprop, once the
+    /// gradient is complete and before it is propagated to the tensors this one was
+    /// computed from. The hook receives the gradient and returns the gradient to use
+    /// in its place.
+    ///
+    /// Unlike [Gradients::register_hook()], this only applies to the current graph. If
+    /// this is called multiple times on the same tensor, the last hook runs first.
+    ///
+    /// Backprop returns [Error::ShapeMismatch] if `hook` returns a tensor with a different
+    /// shape. If this tensor is contiguous, the returned tensor can have any strides.
+    /// Otherwise it must keep the strides of the gradient it received, or backprop returns
+    /// [Error::StridesMismatch].
+    ///
+    /// ```rust
+    /// # use dfdx_core::prelude::*;
+    /// # let dev: Cpu = Default::default();
+    /// let x: Tensor<Rank1<3>, f32, _> = dev.tensor([1.0, 2.0, 3.0]);
+    /// let y = x.leaky_trace().square().with_grad_hook(|g| g.try_clamp(0.0, 0.5));
+    /// let g = y.sum().backward();
+    /// assert_eq!(g.get(&x).array(), [1.0, 2.0, 3.0]);
+    /// ```
+    pub fn with_grad_hook<F>(self, hook: F) -> Self
+    where
+        D: Device<E>,
+        F: 'static + Send + Sync + Fn(Tensor<S, E, D>) -> Result<Tensor<S, E, D>, Error>,
+    {
+        let (t, mut tape) = self.split_tape();
+        let t_ghost = t.ghost();
+        let hook = erase_hook(&t_ghost, hook);
+        tape.add_retainable_backward_op(move |grads| {
+            if let Some(grad) = grads.remove(&t_ghost) {
+                let grad = hook(&t_ghost.dev, grad)?;
+                grads.insert(&t_ghost, grad);
+            }
+            Ok(())
+        });
+        t.put_tape(tape)
+    }
+}
+
 /// Contains a [Gradients] and list of backward operations.
 pub struct OwnedTape<E, D: Storage<E>> {
     /// A list of (Time, BackwardOp) pairs. The Time is used to ensure operations
     /// from merged tapes are executed in the correct order.
     pub(crate) operations: Vec<(UniqueId, BackwardOp<E, D>)>,
     pub(crate) gradients: Gradients<E, D>,
+    /// Whether operations should also record a differentiable form of their backward pass.
+    pub(crate) higher_order: bool,
+    /// Differentiable backward operations, keyed by the time of the [BackwardOp] they mirror.
+    pub(crate) higher_order_operations: Vec<(UniqueId, HigherOrderOp<E, D>)>,
+    /// Checks data for NaN/Inf values if anomaly detection is enabled.
+    pub(crate) anomaly_check: Option<NonFiniteCheck<E, D>>,
+    /// Descriptions of backward operations, keyed by the time of the [BackwardOp] they describe.
+    /// This is only recorded after [Tensor::record_graph()].
+    pub(crate) op_infos: Option<Vec<(UniqueId, OpInfo)>>,
+    /// The description of the next backward operation.
+    pending_op_info: Option<OpInfo>,
 }
 
 impl<E, D: Storage<E>> Default for OwnedTape<E, D> {
@@ -188,6 +448,11 @@ impl<E, D: Storage<E>> Default for OwnedTape<E, D> {
         Self {
             operations: Default::default(),
             gradients: Gradients::leaky(),
+            higher_order: false,
+            higher_order_operations: Default::default(),
+            anomaly_check: None,
+            op_infos: None,
+            pending_op_info: None,
         }
     }
 }
@@ -197,6 +462,8 @@ impl<E: std::fmt::Debug, D: Storage<E>> std::fmt::Debug for OwnedTape<E, D> {
         f.debug_struct("OwnedTape")
             .field("num_operations", &self.operations.len())
             .field("gradients", &self.gradients)
+            .field("higher_order", &self.higher_order)
+            .field("detect_anomaly", &self.anomaly_check.is_some())
             .finish()
     }
 }
@@ -206,6 +473,11 @@ impl<E, D: Storage<E>> From<Gradients<E, D>> for OwnedTape<E, D> {
         Self {
             operations: Default::default(),
             gradients,
+            higher_order: false,
+            higher_order_operations: Default::default(),
+            anomaly_check: None,
+            op_infos: None,
+            pending_op_info: None,
         }
     }
 }
@@ -213,7 +485,8 @@ impl<E, D: Storage<E>> From<Gradients<E, D>> for OwnedTape<E, D> {
 impl<E, D: Storage<E>> OwnedTape<E, D> {
     /// Compute the [Gradients]! This just runs all the operations on a new [Gradients] struct.
     ///
-    /// Note that this method takes ownership of self, so it can't be called twice!
+    /// Note that this removes all the operations, so it can't be called twice! See
+    /// [OwnedTape::execute_retained()] for that.
     pub(crate) fn execute(&mut self) -> Result<Gradients<E, D>, Error> {
         // We must ensure that the operations are sorted in execution time order.
         // Otherwise an backward operation may not be executed in the right order
@@ -221,14 +494,120 @@ impl<E, D: Storage<E>> OwnedTape<E, D> {
         self.operations.sort_by_key(|(k, _)| *k);
         // In case the same operation is present multiple times, we dedup it.
         self.operations.dedup_by_key(|(k, _)| *k);
+        self.higher_order_operations.clear();
+        if let Some(op_infos) = self.op_infos.as_mut() {
+            op_infos.clear();
+        }
+        let previous = self.gradients.take_hooked();
         for (_, operation) in self.operations.drain(..).rev() {
-            (operation)(&mut self.gradients)?;
+            operation.call_once(&mut self.gradients)?;
         }
+        self.gradients.run_hooks(previous)?;
         Ok(std::mem::replace(&mut self.gradients, Gradients::leaky()))
     }
+
+    /// Like [OwnedTape::execute], but runs the operations on new gradients without
+    /// removing them, so the tape can be executed again. `seed` sets the gradient of the
+    /// tensor that backprop starts from.
+    ///
+    /// Returns [Error::NotRetainable] without running anything if an operation was added
+    /// with [Tape::add_backward_op], since those can only run once.
+    pub(crate) fn execute_retained<F>(&mut self, seed: F) -> Result<Gradients<E, D>, Error>
+    where
+        E: Clone,
+        F: FnOnce(&mut Gradients<E, D>) -> Result<(), Error>,
+    {
+        self.operations.sort_by_key(|(k, _)| *k);
+        self.operations.dedup_by_key(|(k, _)| *k);
+        if !self.operations.iter().all(|(_, op)| op.is_retainable()) {
+            return Err(Error::NotRetainable);
+        }
+        let mut gradients = self.gradients.without_values();
+        seed(&mut gradients)?;
+        for (_, operation) in self.operations.iter().rev() {
+            operation.call(&mut gradients)?;
+        }
+        gradients.run_hooks(Default::default())?;
+        Ok(gradients)
+    }
+
+    /// Adds `operation` to the tape, along with the pending [OpInfo] if there is one.
+    fn push_backward_op(&mut self, operation: BackwardFn<E, D>) {
+        let time = unique_id();
+        let info = self.pending_op_info.take();
+        let anomaly_check = match (&self.anomaly_check, &info) {
+            (Some(check), Some(info)) => Some((check.clone(), info.clone())),
+            _ => None,
+        };
+        self.operations.push((
+            time,
+            BackwardOp {
+                operation,
+                anomaly_check,
+            },
+        ));
+        if let (Some(op_infos), Some(info)) = (self.op_infos.as_mut(), info) {
+            op_infos.push((time, info));
+        }
+    }
+}
+
+/// A backward operation, which can be run more than once if it was added with
+/// [Tape::add_retainable_backward_op].
+pub(crate) enum BackwardFn<E, D: Storage<E>> {
+    Once(Box<dyn FnOnce(&mut Gradients<E, D>) -> Result<(), Error>>),
+    Retainable(Box<dyn Fn(&mut Gradients<E, D>) -> Result<(), Error>>),
+}
+
+/// A [BackwardFn] and, if anomaly detection is enabled, the check of the gradients
+/// of its inputs.
+pub(crate) struct BackwardOp<E, D: Storage<E>> {
+    operation: BackwardFn<E, D>,
+    anomaly_check: Option<(NonFiniteCheck<E, D>, OpInfo)>,
+}
+
+impl<E, D: Storage<E>> BackwardOp<E, D> {
+    fn is_retainable(&self) -> bool {
+        matches!(self.operation, BackwardFn::Retainable(_))
+    }
+
+    fn call_once(self, grads: &mut Gradients<E, D>) -> Result<(), Error> {
+        match self.operation {
+            BackwardFn::Once(operation) => operation(grads)?,
+            BackwardFn::Retainable(operation) => operation(grads)?,
+        }
+        Self::check_inputs(&self.anomaly_check, grads)
+    }
+
+    fn call(&self, grads: &mut Gradients<E, D>) -> Result<(), Error> {
+        match &self.operation {
+            BackwardFn::Once(_) => return Err(Error::NotRetainable),
+            BackwardFn::Retainable(operation) => operation(grads)?,
+        }
+        Self::check_inputs(&self.anomaly_check, grads)
+    }
+
+    fn check_inputs(
+        anomaly_check: &Option<(NonFiniteCheck<E, D>, OpInfo)>,
+        grads: &Gradients<E, D>,
+    ) -> Result<(), Error> {
+        if let Some((check, info)) = anomaly_check {
+            for input in info.inputs.iter() {
+                if let Some(grad) = grads.gradient_by_id.get(&input.id) {
+                    if check(grad)? {
+                        return Err(info.non_finite(true));
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
 }
 
-type BackwardOp<E, D> = Box<dyn FnOnce(&mut Gradients<E, D>) -> Result<(), Error>>;
+/// Returns whether the data contains any NaN or infinite values.
+pub(crate) type NonFiniteCheck<E, D> = Arc<dyn Fn(&<D as Storage<E>>::Vec) -> Result<bool, Error>>;
+pub(crate) type HigherOrderOp<E, D> =
+    Box<dyn FnOnce(&mut dyn HigherOrderOps<E, D>) -> Result<(), Error>>;
 
 /// Contains nothing. When [Tape::add_backward_op] is called, this struct does nothing.
 #[derive(Default, Debug, Clone, Copy)]
@@ -241,6 +620,48 @@ pub trait Tape<E, D: Storage<E>>: Default + Merge<Self> + Merge<NoneTape> {
     fn add_backward_op<F>(&mut self, operation: F)
     where
         F: 'static + FnOnce(&mut Gradients<E, D>) -> Result<(), Error>;
+
+    /// Like [Tape::add_backward_op], but for operations that can run more than once, so
+    /// backprop can run through them again with
+    /// [crate::tensor_ops::Backward::backward_retain_graph()]. Tapes that can't run
+    /// backprop more than once can leave the default, which calls [Tape::add_backward_op].
+    fn add_retainable_backward_op<F>(&mut self, operation: F)
+    where
+        F: 'static + Fn(&mut Gradients<E, D>) -> Result<(), Error>,
+    {
+        self.add_backward_op(operation)
+    }
+
+    /// An empty tape that records operations the same way as this one.
+    fn empty_like(&self) -> Self {
+        Default::default()
+    }
+
+    /// Whether the backward operations should also be recorded in a differentiable
+    /// form with [Tape::add_higher_order_op].
+    fn is_higher_order(&self) -> bool {
+        false
+    }
+
+    /// Records the differentiable form of the most recently added backward operation.
+    /// This is only stored if [Tape::is_higher_order] is true.
+    fn add_higher_order_op<F>(&mut self, _operation: F)
+    where
+        F: 'static + FnOnce(&mut dyn HigherOrderOps<E, D>) -> Result<(), Error>,
+    {
+    }
+
+    /// Describes the next backward operation that will be added, which produced `out`.
+    /// `info` is only called if the tape makes use of it.
+    ///
+    /// Returns [Error::NonFinite] if anomaly detection is enabled and `out` contains NaN/Inf.
+    fn try_add_op_info<S: Shape, F: FnOnce() -> OpInfo>(
+        &mut self,
+        _out: &Tensor<S, E, D>,
+        _info: F,
+    ) -> Result<(), Error> {
+        Ok(())
+    }
 }
 
 impl<E, D: Storage<E>> Tape<E, D> for OwnedTape<E, D> {
@@ -249,7 +670,57 @@ impl<E, D: Storage<E>> Tape<E, D> for OwnedTape<E, D> {
     where
         F: 'static + FnOnce(&mut Gradients<E, D>) -> Result<(), Error>,
     {
-        self.operations.push((unique_id(), Box::new(operation)));
+        self.push_backward_op(BackwardFn::Once(Box::new(operation)));
+    }
+
+    fn add_retainable_backward_op<F>(&mut self, operation: F)
+    where
+        F: 'static + Fn(&mut Gradients<E, D>) -> Result<(), Error>,
+    {
+        self.push_backward_op(BackwardFn::Retainable(Box::new(operation)));
+    }
+
+    fn empty_like(&self) -> Self {
+        Self {
+            higher_order: self.higher_order,
+            anomaly_check: self.anomaly_check.clone(),
+            op_infos: self.op_infos.as_ref().map(|_| Vec::new()),
+            ..Default::default()
+        }
+    }
+
+    fn is_higher_order(&self) -> bool {
+        self.higher_order
+    }
+
+    fn add_higher_order_op<F>(&mut self, operation: F)
+    where
+        F: 'static + FnOnce(&mut dyn HigherOrderOps<E, D>) -> Result<(), Error>,
+    {
+        if self.higher_order {
+            if let Some((time, _)) = self.operations.last() {
+                self.higher_order_operations
+                    .push((*time, Box::new(operation)));
+            }
+        }
+    }
+
+    fn try_add_op_info<S: Shape, F: FnOnce() -> OpInfo>(
+        &mut self,
+        out: &Tensor<S, E, D>,
+        info: F,
+    ) -> Result<(), Error> {
+        if self.anomaly_check.is_none() && self.op_infos.is_none() {
+            return Ok(());
+        }
+        let info = info();
+        if let Some(check) = &self.anomaly_check {
+            if check(&out.data)? {
+                return Err(info.non_finite(false));
+            }
+        }
+        self.pending_op_info = Some(info);
+        Ok(())
     }
 }
 
@@ -280,6 +751,20 @@ impl<E, D: Storage<E>> Merge<NoneTape> for OwnedTape<E, D> {
     }
 }
 
+/// Records the graph of a merged tape if either tape was recording it.
+fn merge_op_infos(
+    lhs: Option<Vec<(UniqueId, OpInfo)>>,
+    rhs: Option<Vec<(UniqueId, OpInfo)>>,
+) -> Option<Vec<(UniqueId, OpInfo)>> {
+    match (lhs, rhs) {
+        (Some(mut lhs), Some(mut rhs)) => {
+            lhs.append(&mut rhs);
+            Some(lhs)
+        }
+        (lhs, rhs) => lhs.or(rhs),
+    }
+}
+
 impl<E, D: Storage<E>> Merge<OwnedTape<E, D>> for OwnedTape<E, D> {
     fn merge(mut self, mut other: Self) -> Self {
         self.gradients
@@ -291,7 +776,13 @@ impl<E, D: Storage<E>> Merge<OwnedTape<E, D>> for OwnedTape<E, D> {
                 .get_or_insert_with(Default::default)
                 .extend(leafs);
         }
+        self.gradients.hooks.extend(other.gradients.hooks);
         self.operations.append(&mut other.operations);
+        self.higher_order |= other.higher_order;
+        self.higher_order_operations
+            .append(&mut other.higher_order_operations);
+        self.op_infos = merge_op_infos(self.op_infos, other.op_infos);
+        self.anomaly_check = self.anomaly_check.or(other.anomaly_check);
         self
     }
 }
@@ -318,7 +809,15 @@ impl<E, D: Storage<E>> Merge<Self> for std::sync::Arc<std::sync::Mutex<OwnedTape
                     .get_or_insert_with(Default::default)
                     .append(leafs);
             }
+            lhs.gradients.hooks.append(&mut rhs.gradients.hooks);
             lhs.operations.append(&mut rhs.operations);
+            lhs.higher_order |= rhs.higher_order;
+            lhs.higher_order_operations
+                .append(&mut rhs.higher_order_operations);
+            lhs.op_infos = merge_op_infos(lhs.op_infos.take(), rhs.op_infos.take());
+            if lhs.anomaly_check.is_none() {
+                lhs.anomaly_check = rhs.anomaly_check.clone();
+            }
         }
         self
     }
@@ -334,4 +833,275 @@ impl<E, D: Storage<E>> Tape<E, D> for std::sync::Arc<std::sync::Mutex<OwnedTape<
         let mut tape = self.lock().unwrap();
         tape.add_backward_op(operation);
     }
+
+    fn add_retainable_backward_op<F>(&mut self, operation: F)
+    where
+        F: 'static + Fn(&mut Gradients<E, D>) -> Result<(), Error>,
+    {
+        let mut tape = self.lock().unwrap();
+        tape.add_retainable_backward_op(operation);
+    }
+
+    fn is_higher_order(&self) -> bool {
+        self.lock().unwrap().higher_order
+    }
+
+    fn add_higher_order_op<F>(&mut self, operation: F)
+    where
+        F: 'static + FnOnce(&mut dyn HigherOrderOps<E, D>) -> Result<(), Error>,
+    {
+        let mut tape = self.lock().unwrap();
+        tape.add_higher_order_op(operation);
+    }
+
+    fn try_add_op_info<S: Shape, F: FnOnce() -> OpInfo>(
+        &mut self,
+        out: &Tensor<S, E, D>,
+        info: F,
+    ) -> Result<(), Error> {
+        let mut tape = self.lock().unwrap();
+        tape.try_add_op_info(out, info)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{shapes::*, tensor::*, tensor_ops::*, tests::*};
+
+    #[test]
+    fn test_register_hook_on_param() {
+        let dev: TestDevice = Default::default();
+        let w: Tensor<Rank2<3, 2>, TestDtype, _> = dev.tensor([[1.0, 2.0], [3.0, 4.0], [5.0, 6.0]]);
+        let mut grads = Gradients::leaky();
+
+        // mask out the gradient of the first row, like a frozen embedding row
+        let mask: Tensor<Rank2<3, 2>, TestDtype, _> = dev.tensor([[0.0; 2], [1.0; 2], [1.0; 2]]);
+        grads.register_hook(&w, move |g| g.try_mul(mask.clone()));
+        let grads = w.trace(grads).square().sum().backward();
+        assert_close_to_literal!(grads.get(&w), [[0.0, 0.0], [6.0, 8.0], [10.0, 12.0]]);
+
+        // hooks are kept for the next pass, and only see the gradient of that pass
+        let seen = std::sync::Arc::new(std::sync::Mutex::new(None));
+        let mut grads = {
+            let mut grads = grads;
+            let seen = seen.clone();
+            grads.register_hook(&w, move |g| {
+                *seen.lock().unwrap() = Some(g.array());
+                Ok(g)
+            });
+            grads
+        };
+        grads = w.trace(grads).sum().backward();
+        assert_close_to_literal!(grads.get(&w), [[0.0, 0.0], [7.0, 9.0], [11.0, 13.0]]);
+        assert_eq!(
+            seen.lock().unwrap().unwrap(),
+            [[0.0, 0.0], [1.0, 1.0], [1.0, 1.0]].map(|r| r.map(|v| v as TestDtype))
+        );
+
+        grads.remove_hooks(&w);
+        let grads = w.trace(grads).sum().backward();
+        assert_close_to_literal!(grads.get(&w), [[1.0, 1.0], [8.0, 10.0], [12.0, 14.0]]);
+    }
+
+    #[test]
+    fn test_hook_returning_other_strides() {
+        let dev: TestDevice = Default::default();
+        let w: Tensor<Rank2<3, 2>, TestDtype, _> = dev.tensor([[1.0, 2.0], [3.0, 4.0], [5.0, 6.0]]);
+        let mut grads = Gradients::leaky();
+        // same shape, but strides of [1, 3]
+        grads.register_hook(&w, |g| {
+            g.try_permute::<Rank2<2, 3>, _>()?
+                .try_contiguous()?
+                .try_permute::<Rank2<3, 2>, _>()?
+                .try_mul(2.0)
+        });
+        let grads = w.trace(grads).square().sum().backward();
+        assert_close_to_literal!(grads.get(&w), [[4.0, 8.0], [12.0, 16.0], [20.0, 24.0]]);
+
+        let y = w.leaky_trace().with_grad_hook(|g| {
+            g.try_permute::<Rank2<2, 3>, _>()?
+                .try_contiguous()?
+                .try_permute()
+        });
+        let grads = y.square().sum().backward();
+        assert_close_to_literal!(grads.get(&w), [[2.0, 4.0], [6.0, 8.0], [10.0, 12.0]]);
+    }
+
+    #[test]
+    fn test_hook_returning_other_shape() {
+        let dev: TestDevice = Default::default();
+        let w: Tensor<(usize,), TestDtype, _> = dev.tensor_from_vec(std::vec![1.0, 2.0], (2,));
+        let mut grads = Gradients::leaky();
+        let dev2 = dev.clone();
+        grads.register_hook(&w, move |_| dev2.try_zeros_like(&(3,)));
+        assert!(matches!(
+            w.trace(grads).sum().try_backward(),
+            Err(Error::ShapeMismatch { .. })
+        ));
+
+        let y = w
+            .leaky_trace()
+            .with_grad_hook(move |_| dev.try_zeros_like(&(3,)));
+        assert!(matches!(
+            y.sum().try_backward(),
+            Err(Error::ShapeMismatch { .. })
+        ));
+    }
+
+    #[test]
+    fn test_grad_hook_on_intermediate() {
+        let dev: TestDevice = Default::default();
+        let x: Tensor<Rank1<3>, TestDtype, _> = dev.tensor([1.0, -2.0, 3.0]);
+        let y = x
+            .leaky_trace()
+            .exp()
+            .with_grad_hook(|g| g.try_mul(2.0))
+            .with_grad_hook(|g| g.try_add(1.0));
+        let y2 = y.with_empty_tape().square();
+        let g = (y + y2).sum().backward();
+        // the hooks see the complete gradient 1 + 2 * exp(x), the last hook runs first,
+        // and the modified gradient is propagated back to x
+        let e = x.clone().exp();
+        let expected = ((e.clone() * 2.0 + 1.0) + 1.0) * 2.0 * e;
+        assert_close_to_tensor!(g.get(&x), expected);
+    }
+
+    #[test]
+    fn test_detect_anomaly_forward() {
+        let dev: TestDevice = Default::default();
+        let x: Tensor<Rank2<2, 2>, TestDtype, _> = dev.tensor([[1.0, -1.0], [2.0, 3.0]]);
+
+        // without anomaly detection, NaNs just propagate
+        assert!(x.leaky_trace().try_ln().is_ok());
+
+        assert!(x.leaky_trace().detect_anomaly().square().try_ln().is_ok());
+        match x.leaky_trace().detect_anomaly().try_ln() {
+            Err(Error::NonFinite {
+                op,
+                backward,
+                input_shapes,
+                output_shape,
+            }) => {
+                assert_eq!(op, "Ln");
+                assert!(!backward);
+                assert_eq!(input_shapes, [std::vec![2, 2]]);
+                assert_eq!(output_shape, [2, 2]);
+            }
+            r => panic!("expected NonFinite, found {r:?}"),
+        }
+
+        let y = x.leaky_trace().detect_anomaly().try_div(dev.zeros());
+        assert!(matches!(
+            y,
+            Err(Error::NonFinite { op: "BinaryDiv", input_shapes, .. }) if input_shapes.len() == 2
+        ));
+    }
+
+    #[test]
+    fn test_detect_anomaly_backward() {
+        let dev: TestDevice = Default::default();
+        let x: Tensor<Rank1<2>, TestDtype, _> = dev.tensor([0.0, 1.0]);
+        let y = x.leaky_trace().detect_anomaly().sqrt().sum::<Rank0, _>();
+        assert!(matches!(
+            y.try_backward(),
+            Err(Error::NonFinite {
+                op: "Sqrt",
+                backward: true,
+                ..
+            })
+        ));
+
+        // the tape used inside a checkpoint also detects anomalies
+        let y = try_checkpoint(
+            x.leaky_trace().detect_anomaly(),
+            |x| x.try_ln(),
+            |x| x.try_ln(),
+        );
+        assert!(matches!(y, Err(Error::NonFinite { op: "Ln", .. })));
+    }
+
+    #[test]
+    fn test_backward_retain_graph_twice() {
+        let dev: TestDevice = Default::default();
+        let x: Tensor<Rank1<3>, TestDtype, _> = dev.tensor([1.0, 2.0, 3.0]);
+        let w: Tensor<Rank1<3>, TestDtype, _> = dev.tensor([-1.0, 0.5, 2.0]);
+        let mut loss = (x.leaky_trace().exp() * w).square().sum();
+
+        let g1 = loss.backward_retain_graph();
+        let g2 = loss.backward_retain_graph();
+        assert_close_to_tensor!(g1.get(&x), g2.get(&x));
+
+        // gradients of the retained runs are not accumulated into the final one
+        let g3 = loss.backward();
+        assert_close_to_tensor!(g1.get(&x), g3.get(&x));
+    }
+
+    #[test]
+    fn test_backward_retain_graph_not_retainable() {
+        let dev: TestDevice = Default::default();
+        let x: Tensor<Rank1<3>, TestDtype, _> = dev.tensor([1.0, 2.0, 3.0]);
+        let w: Tensor<Rank1<3>, TestDtype, _> = dev.tensor([-1.0, 0.5, 2.0]);
+        let w2 = w.clone();
+        let y = try_checkpoint(
+            x.leaky_trace(),
+            |x| x.try_exp()?.try_mul(w),
+            move |x| x.try_exp()?.try_mul(w2),
+        )
+        .unwrap();
+        let mut loss = y.square().sum();
+
+        // checkpointing can only recompute once
+        assert!(matches!(
+            loss.try_backward_retain_graph(),
+            Err(Error::NotRetainable)
+        ));
+        // and nothing was run, so normal backprop still works
+        let g = loss.backward();
+        let expected = x.leaky_trace().exp() * dev.tensor([-1.0, 0.5, 2.0]);
+        let g_expected = expected.square().sum().backward();
+        assert_close_to_tensor!(g.get(&x), g_expected.get(&x));
+    }
+
+    #[test]
+    #[allow(clippy::arc_with_non_send_sync)]
+    fn test_backward_retain_graph_separate_heads() {
+        let dev: TestDevice = Default::default();
+        let x: Tensor<Rank1<3>, TestDtype, _> = dev.tensor([1.0, 2.0, 3.0]);
+        let (trunk, tape) = x.leaky_trace().square().split_tape();
+        let trunk = trunk.put_tape(std::sync::Arc::new(std::sync::Mutex::new(tape)));
+        let mut loss_a = trunk.clone().sum();
+        let mut loss_b = (trunk * 2.0).sum();
+
+        let grads_a = loss_a.backward_retain_graph();
+        let grads_b = loss_b.backward_retain_graph();
+        assert_close_to_literal!(grads_a.get(&x), [2.0, 4.0, 6.0]);
+        assert_close_to_literal!(grads_b.get(&x), [4.0, 8.0, 12.0]);
+        let grads_a = loss_a.backward();
+        assert_close_to_literal!(grads_a.get(&x), [2.0, 4.0, 6.0]);
+    }
+
+    #[test]
+    #[allow(clippy::arc_with_non_send_sync)]
+    fn test_backward_retain_graph_custom_op_in_trunk() {
+        let dev: TestDevice = Default::default();
+        let x: Tensor<Rank1<3>, TestDtype, _> = dev.tensor([1.0, 2.0, 3.0]);
+        let trunk = custom_op(
+            x.leaky_trace(),
+            |x| Ok(x.clone().square()),
+            |x, _y, grad_y| Ok(x.clone() * grad_y * 2.0),
+        );
+        let (trunk, tape) = trunk.split_tape();
+        let trunk = trunk.put_tape(std::sync::Arc::new(std::sync::Mutex::new(tape)));
+        let mut loss_a = trunk.clone().sum();
+        let loss_b = (trunk * 2.0).sum();
+
+        // the custom op in the shared trunk can only run once
+        assert!(matches!(
+            loss_a.try_backward_retain_graph(),
+            Err(Error::NotRetainable)
+        ));
+        // so only one of the heads can run backprop
+        let grads_b = loss_b.backward();
+        assert_close_to_literal!(grads_b.get(&x), [4.0, 8.0, 12.0]);
+    }
 }
@@ -0,0 +1,100 @@
+//! Differentiable backward operations, used to compute higher order gradients.
+//!
+//! When an [super::OwnedTape] is in higher order mode (see [super::Tensor::higher_order()]),
+//! every operation records a second version of its backward pass in terms of [HigherOrderOps].
+//! Executing those with something that records onto a tape (like
+//! [crate::tensor_ops::TracedGradients]) produces gradients that can be differentiated again.
+
+use super::{Error, Storage, Tensor, UniqueId};
+
+use std::vec::Vec;
+
+/// A handle to a 1d tensor held by a [HigherOrderOps]. Handles are only valid
+/// inside of the backward operation that created them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HigherOrderVar(pub(crate) usize);
+
+/// Differentiable operations on flat (1d) tensors that higher order backward
+/// operations are written in.
+///
+/// All binary operations expect their arguments to have the same length.
+pub trait HigherOrderOps<E, D: Storage<E>> {
+    /// The gradient accumulated so far for the tensor with `id`, if any.
+    /// `len` is the physical length of the tensor.
+    fn grad(&mut self, id: UniqueId, len: usize) -> Option<HigherOrderVar>;
+
+    /// Adds `grad` to the gradient of the tensor with `id`.
+    fn accumulate_grad(&mut self, id: UniqueId, grad: HigherOrderVar) -> Result<(), Error>;
+
+    /// Makes `t` available to the other operations. Gradients of the result flow back
+    /// into the tensor with `t`'s id.
+    fn value(&mut self, t: Tensor<(usize,), E, D>) -> HigherOrderVar;
+
+    /// The data of `a`, without any gradient tracking.
+    fn detach(&mut self, a: HigherOrderVar) -> Tensor<(usize,), E, D>;
+
+    /// A tensor of `len` ones, without any gradient tracking.
+    fn ones(&mut self, len: usize) -> Result<Tensor<(usize,), E, D>, Error>;
+
+    /// `a + b`
+    fn add(&mut self, a: HigherOrderVar, b: HigherOrderVar) -> Result<HigherOrderVar, Error>;
+    /// `a - b`
+    fn sub(&mut self, a: HigherOrderVar, b: HigherOrderVar) -> Result<HigherOrderVar, Error>;
+    /// `a * b`
+    fn mul(&mut self, a: HigherOrderVar, b: HigherOrderVar) -> Result<HigherOrderVar, Error>;
+    /// `a / b`
+    fn div(&mut self, a: HigherOrderVar, b: HigherOrderVar) -> Result<HigherOrderVar, Error>;
+    /// `a * scalar`
+    fn scale(&mut self, a: HigherOrderVar, scalar: f64) -> Result<HigherOrderVar, Error>;
+    /// `a + scalar`
+    fn add_scalar(&mut self, a: HigherOrderVar, scalar: f64) -> Result<HigherOrderVar, Error>;
+    /// `a^2`
+    fn square(&mut self, a: HigherOrderVar) -> Result<HigherOrderVar, Error>;
+    /// `a^p`
+    fn powf(&mut self, a: HigherOrderVar, p: f64) -> Result<HigherOrderVar, Error>;
+    /// `a^p`
+    fn powi(&mut self, a: HigherOrderVar, p: i32) -> Result<HigherOrderVar, Error>;
+    /// `1 / a`
+    fn recip(&mut self, a: HigherOrderVar) -> Result<HigherOrderVar, Error>;
+    /// `sin(a)`
+    fn sin(&mut self, a: HigherOrderVar) -> Result<HigherOrderVar, Error>;
+    /// `cos(a)`
+    fn cos(&mut self, a: HigherOrderVar) -> Result<HigherOrderVar, Error>;
+    /// `sigmoid(a)`
+    fn sigmoid(&mut self, a: HigherOrderVar) -> Result<HigherOrderVar, Error>;
+
+    /// `1` where `a == b` and `0` elsewhere. Gradients do not flow through the result.
+    fn eq(&mut self, a: HigherOrderVar, b: HigherOrderVar) -> Result<HigherOrderVar, Error>;
+
+    /// `out[i] = a[idx[i]]`
+    fn gather(&mut self, a: HigherOrderVar, idx: Vec<usize>) -> Result<HigherOrderVar, Error>;
+
+    /// `out[idx[i]] += a[i]`, where `out` starts as `len` zeros.
+    fn scatter_add(
+        &mut self,
+        a: HigherOrderVar,
+        idx: Vec<usize>,
+        len: usize,
+    ) -> Result<HigherOrderVar, Error>;
+
+    /// Transposes the last two dimensions of `a`, viewed as `(batch, rows, cols)`.
+    fn transpose(
+        &mut self,
+        a: HigherOrderVar,
+        batch: usize,
+        rows: usize,
+        cols: usize,
+    ) -> Result<HigherOrderVar, Error>;
+
+    /// Batched matrix multiplication of `lhs`, viewed as `(batch, m, k)`, and `rhs`,
+    /// viewed as `(batch, k, n)`.
+    fn matmul(
+        &mut self,
+        lhs: HigherOrderVar,
+        rhs: HigherOrderVar,
+        batch: usize,
+        m: usize,
+        k: usize,
+        n: usize,
+    ) -> Result<HigherOrderVar, Error>;
+}
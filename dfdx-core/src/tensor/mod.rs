@@ -142,9 +142,11 @@ pub(crate) mod cpu;
 pub(crate) mod cuda;
 mod ghost;
 mod gradients;
+mod higher_order;
 mod masks;
 #[cfg(feature = "numpy")]
 pub(crate) mod numpy;
+mod op_info;
 #[cfg(feature = "webgpu")]
 pub(crate) mod webgpu;
 #[cfg(feature = "numpy")]
@@ -180,7 +182,7 @@ pub use webgpu::Webgpu;
 pub type AutoDevice = Webgpu;
 
 pub use storage_traits::{AsArray, CopySlice, TensorFrom, TensorFromVec, TensorToArray};
-pub use storage_traits::{Cache, RandomU64, Storage, Synchronize};
+pub use storage_traits::{Cache, RandomU64, RngState, Storage, Synchronize};
 pub use storage_traits::{OnesTensor, SampleTensor, TriangleTensor, ZerosTensor};
 
 pub use tensor_impls::{PutTape, SplitTape, Tensor, Trace, WithEmptyTape};
@@ -190,6 +192,8 @@ pub(crate) use unique_id::unique_id;
 pub use unique_id::UniqueId;
 
 pub use gradients::{Gradients, Merge, NoneTape, OwnedTape, Tape};
+pub use higher_order::{HigherOrderOps, HigherOrderVar};
+pub use op_info::{OpInfo, TensorInfo};
 
 #[cfg(test)]
 mod tests {
@@ -0,0 +1,212 @@
+use super::{storage_traits::Storage, tensorlike::Tensorlike, OwnedTape, Tensor, UniqueId};
+use crate::shapes::Shape;
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::Write;
+use std::{string::String, vec::Vec};
+
+/// The id and shape of a tensor read or written by an operation, see [OpInfo].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TensorInfo {
+    pub id: UniqueId,
+    pub shape: Vec<usize>,
+}
+
+impl TensorInfo {
+    pub(crate) fn of<S: Shape, E, D: Storage<E>>(t: &impl Tensorlike<S, E, D>) -> Self {
+        Self {
+            id: t.id(),
+            shape: t.shape().concrete().into(),
+        }
+    }
+}
+
+/// A description of an operation recorded on a tape: its name, the tensors it
+/// was computed from, and the tensor it produced.
+///
+/// Operations describe themselves with [crate::tensor::Tape::try_add_op_info].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OpInfo {
+    pub name: &'static str,
+    pub inputs: Vec<TensorInfo>,
+    pub output: TensorInfo,
+}
+
+impl OpInfo {
+    pub(crate) fn new<S: Shape, E, D: Storage<E>>(
+        name: &'static str,
+        output: &impl Tensorlike<S, E, D>,
+    ) -> Self {
+        Self {
+            name,
+            inputs: Vec::new(),
+            output: TensorInfo::of(output),
+        }
+    }
+
+    pub(crate) fn input<S: Shape, E, D: Storage<E>>(
+        mut self,
+        t: &impl Tensorlike<S, E, D>,
+    ) -> Self {
+        self.inputs.push(TensorInfo::of(t));
+        self
+    }
+
+    /// The error to return when a NaN or infinity was found while running this operation.
+    pub(crate) fn non_finite(&self, backward: bool) -> super::Error {
+        super::Error::NonFinite {
+            op: self.name,
+            backward,
+            input_shapes: self.inputs.iter().map(|t| t.shape.clone()).collect(),
+            output_shape: self.output.shape.clone(),
+        }
+    }
+}
+
+impl<S: Shape, E, D: Storage<E>> Tensor<S, E, D, OwnedTape<E, D>> {
+    /// Starts recording an [OpInfo] for every operation applied to this tensor (and anything
+    /// it is merged with), so the graph can be inspected with [Tensor::graph()] and
+    /// [Tensor::to_dot()]. Recording is off by default, so training doesn't pay for it.
+    pub fn record_graph(mut self) -> Self {
+        self.tape.op_infos.get_or_insert_with(Vec::new);
+        self
+    }
+
+    /// Returns the operations recorded on the tape that this tensor was computed from,
+    /// in the order they were run. Operations on the tape that this tensor does not depend
+    /// on (e.g. from tapes it was merged with) are not included.
+    ///
+    /// Only operations run after [Tensor::record_graph()] are recorded. A parameter that does
+    /// not show up in the inputs of any of these operations will not receive a gradient
+    /// from this tensor.
+    ///
+    /// ```rust
+    /// # use dfdx_core::prelude::*;
+    /// # let dev: Cpu = Default::default();
+    /// let x: Tensor<Rank1<3>, f32, _> = dev.sample_normal();
+    /// let w: Tensor<Rank2<3, 2>, f32, _> = dev.sample_normal();
+    /// let y = x.leaky_trace().record_graph().matmul(w).exp().sum();
+    /// let names: Vec<_> = y.graph().iter().map(|op| op.name).collect();
+    /// assert_eq!(names, ["MatMul", "Exp", "Sum"]);
+    /// ```
+    pub fn graph(&self) -> Vec<OpInfo> {
+        let by_output: BTreeMap<UniqueId, (UniqueId, &OpInfo)> = self
+            .tape
+            .op_infos
+            .iter()
+            .flatten()
+            .map(|(time, op)| (op.output.id, (*time, op)))
+            .collect();
+        let mut visited = BTreeSet::new();
+        let mut ops = BTreeMap::new();
+        let mut stack = std::vec![self.id];
+        while let Some(id) = stack.pop() {
+            if !visited.insert(id) {
+                continue;
+            }
+            if let Some((time, op)) = by_output.get(&id) {
+                ops.insert(*time, (*op).clone());
+                stack.extend(op.inputs.iter().map(|t| t.id));
+            }
+        }
+        ops.into_values().collect()
+    }
+
+    /// Renders [Tensor::graph()] in the Graphviz DOT format. Tensors are labeled with their
+    /// id (see [Tensorlike::id()]) and shape, and tensors that were not computed by a
+    /// recorded operation (e.g. inputs and parameters) are filled in.
+    ///
+    /// ```rust
+    /// # use dfdx_core::prelude::*;
+    /// # let dev: Cpu = Default::default();
+    /// let x: Tensor<Rank1<3>, f32, _> = dev.sample_normal();
+    /// let dot = x.leaky_trace().record_graph().square().sum().to_dot();
+    /// assert!(dot.starts_with("digraph {"));
+    /// ```
+    pub fn to_dot(&self) -> String {
+        let ops = self.graph();
+        let outputs: BTreeSet<UniqueId> = ops.iter().map(|op| op.output.id).collect();
+        let mut tensors = BTreeMap::new();
+        for op in ops.iter() {
+            for t in op.inputs.iter().chain(std::iter::once(&op.output)) {
+                tensors.entry(t.id).or_insert(&t.shape);
+            }
+        }
+
+        let mut dot = String::from("digraph {\n");
+        for (id, shape) in tensors {
+            let style = if outputs.contains(&id) {
+                ""
+            } else {
+                ", style=filled"
+            };
+            writeln!(
+                dot,
+                "    t{id} [label=\"#{id}\\n{shape:?}\", shape=box{style}];"
+            )
+            .unwrap();
+        }
+        for (i, op) in ops.iter().enumerate() {
+            writeln!(dot, "    op{i} [label=\"{}\"];", op.name).unwrap();
+            for t in op.inputs.iter() {
+                writeln!(dot, "    t{} -> op{i};", t.id).unwrap();
+            }
+            writeln!(dot, "    op{i} -> t{};", op.output.id).unwrap();
+        }
+        dot.push_str("}\n");
+        dot
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{shapes::*, tensor::*, tensor_ops::*, tests::*};
+
+    #[test]
+    fn test_graph_only_has_dependencies() {
+        let dev: TestDevice = Default::default();
+        let x: Tensor<Rank1<2>, TestDtype, _> = dev.tensor([1.0, 2.0]);
+        let w: Tensor<Rank1<2>, TestDtype, _> = dev.tensor([3.0, 4.0]);
+
+        // record an operation that `y` doesn't depend on in the same tape
+        let (a, tape) = x.leaky_trace().record_graph().exp().split_tape();
+        let (_, tape) = x.clone().put_tape(tape).cos().split_tape();
+        let y = (a.put_tape(tape) * w.clone()).sum::<Rank0, _>();
+
+        let graph = y.graph();
+        let names: std::vec::Vec<_> = graph.iter().map(|op| op.name).collect();
+        assert_eq!(names, ["Exp", "BinaryMul", "Sum"]);
+        assert_eq!(graph[0].inputs, [TensorInfo::of(&x)]);
+        assert_eq!(graph[1].inputs[1], TensorInfo::of(&w));
+        assert_eq!(graph[2].output, TensorInfo::of(&y));
+    }
+
+    #[test]
+    fn test_graph_not_recorded_by_default() {
+        let dev: TestDevice = Default::default();
+        let x: Tensor<Rank1<2>, TestDtype, _> = dev.tensor([1.0, 2.0]);
+        let y = x.leaky_trace().exp().sum::<Rank0, _>();
+        assert!(y.tape.op_infos.is_none());
+        assert!(y.graph().is_empty());
+    }
+
+    #[test]
+    fn test_to_dot() {
+        let dev: TestDevice = Default::default();
+        let x: Tensor<Rank1<2>, TestDtype, _> = dev.tensor([1.0, 2.0]);
+        let y = x.leaky_trace().record_graph().square();
+        let expected = std::format!(
+            "digraph {{
+    t{x} [label=\"#{x}\\n[2]\", shape=box, style=filled];
+    t{y} [label=\"#{y}\\n[2]\", shape=box];
+    op0 [label=\"Square\"];
+    t{x} -> op0;
+    op0 -> t{y};
+}}
+",
+            x = x.id,
+            y = y.id,
+        );
+        assert_eq!(y.to_dot(), expected);
+    }
+}
@@ -11,6 +11,15 @@ pub trait RandomU64 {
     fn random_u64(&self) -> u64;
 }
 
+/// Something whose random number generator can be saved and restored, to replay the
+/// same random numbers, e.g. when recomputing a forward pass.
+pub trait RngState: RandomU64 {
+    /// Replaces the state of the random number generator, returning the previous state.
+    fn replace_rng(&self, rng: rand::rngs::StdRng) -> rand::rngs::StdRng;
+    /// A copy of the current state of the random number generator.
+    fn rng_state(&self) -> rand::rngs::StdRng;
+}
+
 /// Something that can store nd arrays for a given [Shape] and [Dtype]
 pub trait Storage<E>: 'static + std::fmt::Debug + Default + Clone {
     /// Generic Storage type
@@ -82,10 +82,65 @@ impl<S: Shape, E: Unit, F: Unit, D: Storage<F> + Storage<E>> Trace<E, D>
         self.put_tape(Default::default())
     }
     fn traced(self, gradients: Gradients<E, D>) -> Self::Traced {
-        self.put_tape(OwnedTape {
-            gradients,
-            operations: std::vec::Vec::new(),
-        })
+        self.put_tape(OwnedTape::from(gradients))
+    }
+}
+
+impl<S: Shape, E, D: Storage<E>> Tensor<S, E, D, OwnedTape<E, D>> {
+    /// Records a differentiable form of every backward operation applied to this
+    /// tensor (and anything it is merged with), so that gradients computed with
+    /// [crate::tensor_ops::TracedBackward] can be differentiated again.
+    ///
+    /// ```rust
+    /// # use dfdx_core::prelude::*;
+    /// # let dev: Cpu = Default::default();
+    /// let x: Tensor<Rank1<2>, f32, _> = dev.tensor([1.0, 2.0]);
+    /// let y = x.leaky_trace().higher_order().powi(3).sum();
+    /// let g = y.traced_backward();
+    /// let dx = g.get(&x); // 3 * x^2
+    /// let g2 = dx.sum().backward();
+    /// assert_eq!(g2.get(&x).array(), [6.0, 12.0]); // 6 * x
+    /// ```
+    pub fn higher_order(mut self) -> Self {
+        self.tape.higher_order = true;
+        self
+    }
+}
+
+impl<S: Shape, E: Dtype, D: Storage<E>> Tensor<S, E, D, OwnedTape<E, D>> {
+    /// Enables anomaly detection for every operation applied to this tensor (and anything
+    /// it is merged with): the output of each operation, and the gradients computed by its
+    /// backward pass, are checked for NaN and infinite values. The first operation that
+    /// produces one returns [Error::NonFinite] with its name and shapes, instead of the bad
+    /// values silently spreading to the loss.
+    ///
+    /// This copies every output and gradient to the host to check it, so it is slow, and
+    /// only meant for debugging.
+    ///
+    /// ```rust
+    /// # use dfdx_core::prelude::*;
+    /// # let dev: Cpu = Default::default();
+    /// let x: Tensor<Rank1<2>, f32, _> = dev.tensor([1.0, -1.0]);
+    /// let y = x.leaky_trace().detect_anomaly().try_sqrt();
+    /// assert!(matches!(y, Err(Error::NonFinite { op: "Sqrt", backward: false, .. })));
+    /// ```
+    pub fn detect_anomaly(mut self) -> Self {
+        let device = self.device.clone();
+        self.tape.anomaly_check = Some(Arc::new(move |data: &D::Vec| {
+            let t = Tensor {
+                id: unique_id(),
+                data: Arc::new(data.clone()),
+                shape: (device.len(data),),
+                strides: [1],
+                device: device.clone(),
+                tape: NoneTape,
+            };
+            let values = device.tensor_to_vec(&t);
+            Ok(values
+                .iter()
+                .any(|v| num_traits::ToPrimitive::to_f64(v).map_or(false, |v| !v.is_finite())))
+        }));
+        self
     }
 }
 
@@ -175,7 +230,7 @@ pub trait WithEmptyTape {
     fn with_empty_tape(&self) -> Self;
 }
 
-impl<S: Shape, E, D: Storage<E>, T: Default> WithEmptyTape for Tensor<S, E, D, T> {
+impl<S: Shape, E, D: Storage<E>, T: Tape<E, D>> WithEmptyTape for Tensor<S, E, D, T> {
     fn with_empty_tape(&self) -> Self {
         Tensor {
             id: self.id,
@@ -183,7 +238,7 @@ impl<S: Shape, E, D: Storage<E>, T: Default> WithEmptyTape for Tensor<S, E, D, T
             shape: self.shape,
             strides: self.strides,
             device: self.device.clone(),
-            tape: Default::default(),
+            tape: self.tape.empty_like(),
         }
     }
 }
@@ -9,8 +9,8 @@ use crate::{
     prelude::webgpu_kernels::HasGlslType,
     shapes::{Shape, Unit},
     tensor::{
-        cache::TensorCache, cpu::Cpu, Cache, Error, NoneTape, RandomU64, Storage, Synchronize,
-        Tensor,
+        cache::TensorCache, cpu::Cpu, Cache, Error, NoneTape, RandomU64, RngState, Storage,
+        Synchronize, Tensor,
     },
 };
 
@@ -385,6 +385,15 @@ impl RandomU64 for Webgpu {
     }
 }
 
+impl RngState for Webgpu {
+    fn replace_rng(&self, rng: rand::rngs::StdRng) -> rand::rngs::StdRng {
+        self.cpu.replace_rng(rng)
+    }
+    fn rng_state(&self) -> rand::rngs::StdRng {
+        self.cpu.rng_state()
+    }
+}
+
 impl Cache for Webgpu {
     fn try_enable_cache(&self) -> Result<(), Error> {
         self.cache.enable();
@@ -7,12 +7,24 @@ mod cuda_kernel;
 mod webgpu_kernel;
 
 use super::ops::{try_unary_op, UnaryKernel};
+use super::utilities::higher_order::UnaryDerivative;
 use crate::{shapes::*, tensor::*};
 
 #[repr(C)]
 #[derive(Debug, Default, Copy, Clone)]
 pub struct AbsKernelOp;
 
+impl<E> UnaryDerivative<E> for AbsKernelOp {
+    fn df<D: Storage<E>>(
+        &self,
+        _ops: &mut dyn HigherOrderOps<E, D>,
+        _x: HigherOrderVar,
+        _y: HigherOrderVar,
+    ) -> Result<Option<HigherOrderVar>, Error> {
+        Ok(None)
+    }
+}
+
 /// [Absolute value (abs)](https://en.wikipedia.org/wiki/Absolute_value). `|t|`
 ///
 /// The derivative is -1.0 for t < 0, 0 for t == 0, and 1.0 for t > 0.
@@ -7,12 +7,24 @@ mod cuda_kernel;
 mod webgpu_kernel;
 
 use super::ops::{try_unary_op, UnaryKernel};
+use super::utilities::higher_order::UnaryDerivative;
 use crate::{shapes::*, tensor::*};
 
 #[repr(C)]
 #[derive(Debug, Default, Copy, Clone)]
 pub struct AccurateGeLUKernelOp;
 
+impl<E> UnaryDerivative<E> for AccurateGeLUKernelOp {
+    fn df<D: Storage<E>>(
+        &self,
+        _ops: &mut dyn HigherOrderOps<E, D>,
+        _x: HigherOrderVar,
+        _y: HigherOrderVar,
+    ) -> Result<Option<HigherOrderVar>, Error> {
+        Err(Error::NoHigherOrderGradient)
+    }
+}
+
 /// [Accurate Gaussian Linear Unit (GeLU)](https://paperswithcode.com/method/gelu). This is defined as `x * Phi(x)` where `Phi(x)` is the cumulative
 /// distribution function of a standard normal distribution. This can be calculated via the Error
 /// Function `erf(x)` using
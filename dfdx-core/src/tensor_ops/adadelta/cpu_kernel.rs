@@ -0,0 +1,87 @@
+use super::{AdadeltaConfig, AdadeltaKernel, WeightDecay};
+use crate::{
+    dtypes::{Dtype, NotMixedPrecision},
+    tensor::{Cpu, Error},
+};
+
+#[cfg(feature = "f16")]
+impl AdadeltaKernel<crate::dtypes::AMP<crate::dtypes::f16>> for Cpu {
+    fn adadelta_kernel(
+        &self,
+        cfg: &AdadeltaConfig,
+        param: &mut Self::Vec,
+        square_avg: &mut Self::Vec,
+        acc_delta: &mut Self::Vec,
+        grad: &Self::Vec,
+    ) -> Result<(), Error> {
+        let rho = cfg.rho as f32;
+        let eps = cfg.eps as f32;
+        let lr = cfg.lr as f32;
+
+        for ((p, g), (s_avg, a_delta)) in param
+            .iter_mut()
+            .zip(grad.iter().cloned())
+            .zip(square_avg.iter_mut().zip(acc_delta.iter_mut()))
+        {
+            let p_f32 = p.0.to_f32();
+            let mut g_f32 = g.0.to_f32();
+            let mut s_avg_f32 = s_avg.0.to_f32();
+            let mut a_delta_f32 = a_delta.0.to_f32();
+
+            if let Some(WeightDecay::L2(wd)) = cfg.weight_decay {
+                g_f32 += (wd as f32) * p_f32;
+            }
+
+            s_avg_f32 = s_avg_f32 * rho + g_f32 * g_f32 * (1.0 - rho);
+            let delta = (a_delta_f32 + eps).sqrt() / (s_avg_f32 + eps).sqrt() * g_f32;
+            a_delta_f32 = a_delta_f32 * rho + delta * delta * (1.0 - rho);
+            g_f32 = lr * delta;
+
+            if let Some(WeightDecay::Decoupled(wd)) = cfg.weight_decay {
+                g_f32 += (wd * cfg.lr) as f32 * p_f32;
+            }
+
+            p.0 = crate::dtypes::f16::from_f32(p_f32 - g_f32);
+            s_avg.0 = crate::dtypes::f16::from_f32(s_avg_f32);
+            a_delta.0 = crate::dtypes::f16::from_f32(a_delta_f32);
+        }
+        Ok(())
+    }
+}
+
+impl<E: num_traits::Float + Dtype + NotMixedPrecision> AdadeltaKernel<E> for Cpu {
+    fn adadelta_kernel(
+        &self,
+        cfg: &AdadeltaConfig,
+        param: &mut Self::Vec,
+        square_avg: &mut Self::Vec,
+        acc_delta: &mut Self::Vec,
+        grad: &Self::Vec,
+    ) -> Result<(), Error> {
+        let rho = E::from_f64(cfg.rho).unwrap();
+        let eps = E::from_f64(cfg.eps).unwrap();
+        let lr = E::from_f64(cfg.lr).unwrap();
+
+        for ((p, mut g), (s_avg, a_delta)) in param
+            .iter_mut()
+            .zip(grad.iter().cloned())
+            .zip(square_avg.iter_mut().zip(acc_delta.iter_mut()))
+        {
+            if let Some(WeightDecay::L2(wd)) = cfg.weight_decay {
+                g += E::from_f64(wd).unwrap() * *p;
+            }
+
+            *s_avg = *s_avg * rho + g * g * (E::one() - rho);
+            let delta = (*a_delta + eps).sqrt() / (*s_avg + eps).sqrt() * g;
+            *a_delta = *a_delta * rho + delta * delta * (E::one() - rho);
+            g = lr * delta;
+
+            if let Some(WeightDecay::Decoupled(wd)) = cfg.weight_decay {
+                g += E::from_f64(wd * cfg.lr).unwrap() * *p;
+            }
+
+            *p -= g;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,14 @@
+use crate::prelude::{Dtype, Webgpu};
+
+impl<E: Dtype> super::AdadeltaKernel<E> for Webgpu {
+    fn adadelta_kernel(
+        &self,
+        cfg: &crate::prelude::AdadeltaConfig,
+        param: &mut Self::Vec,
+        square_avg: &mut Self::Vec,
+        acc_delta: &mut Self::Vec,
+        grad: &Self::Vec,
+    ) -> Result<(), crate::prelude::Error> {
+        todo!()
+    }
+}
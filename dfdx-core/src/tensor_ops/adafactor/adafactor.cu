@@ -0,0 +1,225 @@
+#include "cuda_utils.cuh"
+
+enum WeightDecayType {
+    WdNone,
+    L2,
+    Decoupled
+};
+
+struct AdafactorConfig {
+    double lr;
+    double rho;
+    double beta2;
+    double eps1;
+    double eps2;
+    double clip_threshold;
+    WeightDecayType weight_decay_type;
+    double weight_decay;
+};
+
+// stats holds the sums of the squared param, of the squared update, the mean of the row
+// averages, and then the sums of each row & column of the squared gradients.
+#define PARAM_SQ 0
+#define UPDATE_SQ 1
+#define ROW_MEAN 2
+#define LINE_SUMS 3
+
+__device__ __forceinline__ double to_f64(float x) { return x; }
+__device__ __forceinline__ double to_f64(double x) { return x; }
+__device__ __forceinline__ double to_f64(__half x) { return __half2float(x); }
+
+template<typename T, typename C>
+__device__ C adafactor_grad(const AdafactorConfig cfg, const T* param, const T* grad, unsigned int i) {
+    C g = grad[i];
+    if (cfg.weight_decay_type == L2) {
+        C weight_decay = cfg.weight_decay;
+        C p = param[i];
+        g += weight_decay * p;
+    }
+    return g;
+}
+
+template<typename T, typename C>
+__device__ C adafactor_var(
+    const bool factored,
+    const size_t rows,
+    const size_t cols,
+    const T* exp_avg_sq,
+    const double* stats,
+    unsigned int i
+) {
+    if (factored) {
+        C r = exp_avg_sq[i / cols];
+        C c = exp_avg_sq[rows + i % cols];
+        C row_mean = stats[ROW_MEAN];
+        return r * c / row_mean;
+    }
+    C v = exp_avg_sq[i];
+    return v;
+}
+
+// Expects one thread per element.
+template<typename T, typename C>
+__device__ void adafactor_sums(
+    const AdafactorConfig cfg,
+    const size_t numel,
+    const bool factored,
+    const size_t rows,
+    const size_t cols,
+    const T* param,
+    T* exp_avg_sq,
+    const T* grad,
+    double* stats
+) {
+    unsigned int i = blockIdx.x * blockDim.x + threadIdx.x;
+    if (i >= numel) {
+        return;
+    }
+
+    C beta2 = cfg.beta2;
+    C eps1 = cfg.eps1;
+    C one = 1.0;
+    C p = param[i];
+    C g = adafactor_grad<T, C>(cfg, param, grad, i);
+    C g_sq = g * g + eps1;
+
+    chunk_sum(numel, to_f64(p) * to_f64(p), stats + PARAM_SQ);
+    if (factored) {
+        chunk_sum(cols, to_f64(g_sq), stats + LINE_SUMS);
+        atomicAdd(stats + LINE_SUMS + rows + i % cols, to_f64(g_sq));
+    } else {
+        C v = exp_avg_sq[i];
+        exp_avg_sq[i] = v * beta2 + g_sq * (one - beta2);
+    }
+}
+
+template<typename T, typename C>
+__device__ void adafactor_factors(
+    const AdafactorConfig cfg,
+    const size_t rows,
+    const size_t cols,
+    T* exp_avg_sq,
+    double* stats
+) {
+    unsigned int i = blockIdx.x * blockDim.x + threadIdx.x;
+    if (i >= rows + cols) {
+        return;
+    }
+
+    C beta2 = cfg.beta2;
+    C one = 1.0;
+    C mean = stats[LINE_SUMS + i] / (i < rows ? cols : rows);
+    C v = exp_avg_sq[i];
+    v = v * beta2 + mean * (one - beta2);
+    exp_avg_sq[i] = v;
+    if (i < rows) {
+        atomicAdd(stats + ROW_MEAN, to_f64(v) / rows);
+    }
+}
+
+// Expects one thread per element.
+template<typename T, typename C>
+__device__ void adafactor_update_sums(
+    const AdafactorConfig cfg,
+    const size_t numel,
+    const bool factored,
+    const size_t rows,
+    const size_t cols,
+    const T* param,
+    const T* exp_avg_sq,
+    const T* grad,
+    double* stats
+) {
+    unsigned int i = blockIdx.x * blockDim.x + threadIdx.x;
+    if (i >= numel) {
+        return;
+    }
+
+    C g = adafactor_grad<T, C>(cfg, param, grad, i);
+    C u = g / sqrtg(adafactor_var<T, C>(factored, rows, cols, exp_avg_sq, stats, i));
+    chunk_sum(numel, to_f64(u) * to_f64(u), stats + UPDATE_SQ);
+}
+
+template<typename T, typename C>
+__device__ void adafactor_apply(
+    const AdafactorConfig cfg,
+    const size_t numel,
+    const bool factored,
+    const size_t rows,
+    const size_t cols,
+    T* param,
+    const T* exp_avg_sq,
+    const T* grad,
+    const double* stats
+) {
+    double alpha = fmax(cfg.eps2, sqrt(stats[PARAM_SQ] / numel)) * cfg.rho;
+    double clip = fmax(sqrt(stats[UPDATE_SQ] / numel) / cfg.clip_threshold, 1.0);
+    C lr = alpha / clip;
+    C decoupled = cfg.weight_decay * cfg.lr;
+
+    for (unsigned int i = blockIdx.x * blockDim.x + threadIdx.x; i < numel; i += blockDim.x * gridDim.x) {
+        C p = param[i];
+        C g = adafactor_grad<T, C>(cfg, param, grad, i);
+        C update = lr * g / sqrtg(adafactor_var<T, C>(factored, rows, cols, exp_avg_sq, stats, i));
+        if (cfg.weight_decay_type == Decoupled) {
+            update += decoupled * p;
+        }
+        param[i] = p - update;
+    }
+}
+
+#define ADAFACTOR(TYPENAME, COMPUTE, SUFFIX) \
+extern "C" __global__ void adafactor_sums_##SUFFIX( \
+    const AdafactorConfig cfg, \
+    const size_t numel, \
+    const bool factored, \
+    const size_t rows, \
+    const size_t cols, \
+    const TYPENAME* param, \
+    TYPENAME* exp_avg_sq, \
+    const TYPENAME* grad, \
+    double* stats \
+) { \
+    adafactor_sums<TYPENAME, COMPUTE>(cfg, numel, factored, rows, cols, param, exp_avg_sq, grad, stats); \
+} \
+extern "C" __global__ void adafactor_factors_##SUFFIX( \
+    const AdafactorConfig cfg, \
+    const size_t rows, \
+    const size_t cols, \
+    TYPENAME* exp_avg_sq, \
+    double* stats \
+) { \
+    adafactor_factors<TYPENAME, COMPUTE>(cfg, rows, cols, exp_avg_sq, stats); \
+} \
+extern "C" __global__ void adafactor_update_sums_##SUFFIX( \
+    const AdafactorConfig cfg, \
+    const size_t numel, \
+    const bool factored, \
+    const size_t rows, \
+    const size_t cols, \
+    const TYPENAME* param, \
+    const TYPENAME* exp_avg_sq, \
+    const TYPENAME* grad, \
+    double* stats \
+) { \
+    adafactor_update_sums<TYPENAME, COMPUTE>(cfg, numel, factored, rows, cols, param, exp_avg_sq, grad, stats); \
+} \
+extern "C" __global__ void adafactor_apply_##SUFFIX( \
+    const AdafactorConfig cfg, \
+    const size_t numel, \
+    const bool factored, \
+    const size_t rows, \
+    const size_t cols, \
+    TYPENAME* param, \
+    const TYPENAME* exp_avg_sq, \
+    const TYPENAME* grad, \
+    const double* stats \
+) { \
+    adafactor_apply<TYPENAME, COMPUTE>(cfg, numel, factored, rows, cols, param, exp_avg_sq, grad, stats); \
+}
+
+// mixed precision computes in float
+ADAFACTOR(__half, float, amp_f16);
+ADAFACTOR(__half, __half, f16);
+ADAFACTOR(float, float, f32);
+ADAFACTOR(double, double, f64);
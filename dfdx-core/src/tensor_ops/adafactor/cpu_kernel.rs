@@ -0,0 +1,123 @@
+use super::{AdafactorConfig, AdafactorKernel, WeightDecay};
+use crate::{
+    dtypes::{Dtype, NotMixedPrecision},
+    tensor::{Cpu, Error},
+};
+
+/// Adafactor with the computations done in `F`, which is `f32` for mixed precision.
+/// The statistics of the whole tensor are accumulated in `f64`.
+#[allow(clippy::too_many_arguments)]
+fn adafactor<E: Copy, F: num_traits::Float>(
+    t: i32,
+    cfg: &AdafactorConfig,
+    factored: Option<[usize; 2]>,
+    param: &mut [E],
+    exp_avg_sq: &mut [E],
+    grad: &[E],
+    to_f: impl Fn(E) -> F,
+    from_f: impl Fn(F) -> E,
+) {
+    let f = |x: f64| F::from(x).unwrap();
+    let numel = param.len() as f64;
+    let t = t as f64;
+    let beta2 = 1.0 - t.powf(cfg.beta2_decay);
+    let rho = cfg.lr.min(1.0 / t.sqrt());
+    let [eps1, eps2] = cfg.eps;
+    let grad_of = |p: F, g: F| match cfg.weight_decay {
+        Some(WeightDecay::L2(wd)) => g + f(wd) * p,
+        _ => g,
+    };
+
+    let mut param_sq = 0.0;
+    let [rows, cols] = factored.unwrap_or([0, 0]);
+    let mut row_sums = vec![0.0; rows];
+    let mut col_sums = vec![0.0; cols];
+    for (i, (p, g)) in param.iter().zip(grad.iter()).enumerate() {
+        let p = to_f(*p);
+        let g = grad_of(p, to_f(*g));
+        param_sq += p.to_f64().unwrap().powi(2);
+        let g_sq = g * g + f(eps1);
+        if factored.is_some() {
+            row_sums[i / cols] += g_sq.to_f64().unwrap();
+            col_sums[i % cols] += g_sq.to_f64().unwrap();
+        } else {
+            let v = to_f(exp_avg_sq[i]);
+            exp_avg_sq[i] = from_f(f(beta2) * v + f(1.0 - beta2) * g_sq);
+        }
+    }
+
+    let mut row_mean = 0.0;
+    if factored.is_some() {
+        let (row_var, col_var) = exp_avg_sq.split_at_mut(rows);
+        for (r, sum) in row_var.iter_mut().zip(row_sums) {
+            *r = from_f(f(beta2) * to_f(*r) + f((1.0 - beta2) * sum / cols as f64));
+            row_mean += to_f(*r).to_f64().unwrap() / rows as f64;
+        }
+        for (c, sum) in col_var.iter_mut().zip(col_sums) {
+            *c = from_f(f(beta2) * to_f(*c) + f((1.0 - beta2) * sum / rows as f64));
+        }
+    }
+    let exp_avg_sq = &*exp_avg_sq;
+    let var = |i: usize| match factored {
+        Some(_) => to_f(exp_avg_sq[i / cols]) * to_f(exp_avg_sq[rows + i % cols]) / f(row_mean),
+        None => to_f(exp_avg_sq[i]),
+    };
+
+    let mut update_sq = 0.0;
+    for (i, (p, g)) in param.iter().zip(grad.iter()).enumerate() {
+        let u = grad_of(to_f(*p), to_f(*g)) / var(i).sqrt();
+        update_sq += u.to_f64().unwrap().powi(2);
+    }
+
+    let alpha = eps2.max((param_sq / numel).sqrt()) * rho;
+    let clip = ((update_sq / numel).sqrt() / cfg.clip_threshold).max(1.0);
+    let lr = f(alpha / clip);
+    for (i, (p, g)) in param.iter_mut().zip(grad.iter()).enumerate() {
+        let p_f = to_f(*p);
+        let mut update = lr * grad_of(p_f, to_f(*g)) / var(i).sqrt();
+        if let Some(WeightDecay::Decoupled(wd)) = cfg.weight_decay {
+            update = update + f(wd * cfg.lr) * p_f;
+        }
+        *p = from_f(p_f - update);
+    }
+}
+
+#[cfg(feature = "f16")]
+impl AdafactorKernel<crate::dtypes::AMP<crate::dtypes::f16>> for Cpu {
+    fn adafactor_kernel(
+        &self,
+        t: i32,
+        cfg: &AdafactorConfig,
+        factored: Option<[usize; 2]>,
+        param: &mut Self::Vec,
+        exp_avg_sq: &mut Self::Vec,
+        grad: &Self::Vec,
+    ) -> Result<(), Error> {
+        adafactor(
+            t,
+            cfg,
+            factored,
+            param,
+            exp_avg_sq,
+            grad,
+            |x| x.0.to_f32(),
+            |x| crate::dtypes::AMP(crate::dtypes::f16::from_f32(x)),
+        );
+        Ok(())
+    }
+}
+
+impl<E: num_traits::Float + Dtype + NotMixedPrecision> AdafactorKernel<E> for Cpu {
+    fn adafactor_kernel(
+        &self,
+        t: i32,
+        cfg: &AdafactorConfig,
+        factored: Option<[usize; 2]>,
+        param: &mut Self::Vec,
+        exp_avg_sq: &mut Self::Vec,
+        grad: &Self::Vec,
+    ) -> Result<(), Error> {
+        adafactor(t, cfg, factored, param, exp_avg_sq, grad, |x| x, |x| x);
+        Ok(())
+    }
+}
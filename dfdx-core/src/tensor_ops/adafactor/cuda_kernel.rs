@@ -0,0 +1,166 @@
+use crate::{
+    dtypes::*,
+    tensor::{launch_cfg, Cuda, Error},
+    tensor_ops::optim::*,
+};
+
+use cudarc::driver::{DeviceRepr, DeviceSlice, LaunchAsync};
+
+#[repr(C)]
+struct CudaAdafactorConfig {
+    lr: f64,
+    rho: f64,
+    beta2: f64,
+    eps1: f64,
+    eps2: f64,
+    clip_threshold: f64,
+    weight_decay_type: WeightDecayType,
+    weight_decay: f64,
+}
+
+unsafe impl DeviceRepr for CudaAdafactorConfig {}
+
+fn adafactor_config_to_cuda(t: i32, config: &super::AdafactorConfig) -> CudaAdafactorConfig {
+    let (weight_decay_type, weight_decay) = weight_decay_to_cuda(config.weight_decay);
+    let t = t as f64;
+
+    CudaAdafactorConfig {
+        lr: config.lr,
+        rho: config.lr.min(1.0 / t.sqrt()),
+        beta2: 1.0 - t.powf(config.beta2_decay),
+        eps1: config.eps[0],
+        eps2: config.eps[1],
+        clip_threshold: config.clip_threshold,
+        weight_decay_type,
+        weight_decay,
+    }
+}
+
+const PTX_SRC: &str = include_str!(concat!(env!("OUT_DIR"), "/adafactor.ptx"));
+
+trait HasCudaKernel<E> {
+    const MOD: &'static str;
+    const FNS: &'static [&'static str];
+}
+
+#[cfg(feature = "f16")]
+impl HasCudaKernel<AMP<f16>> for Cuda {
+    const MOD: &'static str = "adafactor_amp_f16";
+    const FNS: &'static [&'static str] = &[
+        "adafactor_sums_amp_f16",
+        "adafactor_factors_amp_f16",
+        "adafactor_update_sums_amp_f16",
+        "adafactor_apply_amp_f16",
+    ];
+}
+
+#[cfg(feature = "f16")]
+impl HasCudaKernel<f16> for Cuda {
+    const MOD: &'static str = "adafactor_f16";
+    const FNS: &'static [&'static str] = &[
+        "adafactor_sums_f16",
+        "adafactor_factors_f16",
+        "adafactor_update_sums_f16",
+        "adafactor_apply_f16",
+    ];
+}
+
+impl HasCudaKernel<f32> for Cuda {
+    const MOD: &'static str = "adafactor_f32";
+    const FNS: &'static [&'static str] = &[
+        "adafactor_sums_f32",
+        "adafactor_factors_f32",
+        "adafactor_update_sums_f32",
+        "adafactor_apply_f32",
+    ];
+}
+
+impl HasCudaKernel<f64> for Cuda {
+    const MOD: &'static str = "adafactor_f64";
+    const FNS: &'static [&'static str] = &[
+        "adafactor_sums_f64",
+        "adafactor_factors_f64",
+        "adafactor_update_sums_f64",
+        "adafactor_apply_f64",
+    ];
+}
+
+impl<E: Dtype> super::AdafactorKernel<E> for Cuda
+where
+    Self: HasCudaKernel<E>,
+{
+    fn adafactor_kernel(
+        &self,
+        t: i32,
+        cfg: &super::AdafactorConfig,
+        factored: Option<[usize; 2]>,
+        param: &mut Self::Vec,
+        exp_avg_sq: &mut Self::Vec,
+        grad: &Self::Vec,
+    ) -> Result<(), Error> {
+        if !self.dev.has_func(Self::MOD, Self::FNS[0]) {
+            self.dev.load_ptx(PTX_SRC.into(), Self::MOD, Self::FNS)?;
+        }
+
+        let numel = param.len();
+        let is_factored = factored.is_some();
+        let [rows, cols] = factored.unwrap_or([0, 0]);
+        // see adafactor.cu for the layout
+        let mut stats = self.dev.alloc_zeros::<f64>(3 + rows + cols)?;
+
+        let func = self.dev.get_func(Self::MOD, Self::FNS[0]).unwrap();
+        let params = (
+            adafactor_config_to_cuda(t, cfg),
+            numel,
+            is_factored,
+            rows,
+            cols,
+            &*param,
+            &mut *exp_avg_sq,
+            grad,
+            &mut stats,
+        );
+        unsafe { func.launch(launch_cfg::<128>(numel as u32), params) }?;
+
+        if is_factored {
+            let func = self.dev.get_func(Self::MOD, Self::FNS[1]).unwrap();
+            let params = (
+                adafactor_config_to_cuda(t, cfg),
+                rows,
+                cols,
+                &mut *exp_avg_sq,
+                &mut stats,
+            );
+            unsafe { func.launch(launch_cfg::<128>((rows + cols) as u32), params) }?;
+        }
+
+        let func = self.dev.get_func(Self::MOD, Self::FNS[2]).unwrap();
+        let params = (
+            adafactor_config_to_cuda(t, cfg),
+            numel,
+            is_factored,
+            rows,
+            cols,
+            &*param,
+            &*exp_avg_sq,
+            grad,
+            &mut stats,
+        );
+        unsafe { func.launch(launch_cfg::<128>(numel as u32), params) }?;
+
+        let func = self.dev.get_func(Self::MOD, Self::FNS[3]).unwrap();
+        let params = (
+            adafactor_config_to_cuda(t, cfg),
+            numel,
+            is_factored,
+            rows,
+            cols,
+            param,
+            &*exp_avg_sq,
+            grad,
+            &stats,
+        );
+        unsafe { func.launch(launch_cfg::<128>(numel as u32), params) }?;
+        Ok(())
+    }
+}
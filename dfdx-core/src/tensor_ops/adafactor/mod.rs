@@ -0,0 +1,114 @@
+mod cpu_kernel;
+
+#[cfg(feature = "cuda")]
+mod cuda_kernel;
+
+#[cfg(feature = "webgpu")]
+mod webgpu_kernel;
+
+use crate::{
+    shapes::{Dtype, Shape},
+    tensor::{Error, Storage, Tensor},
+};
+
+use super::WeightDecay;
+
+/// Configuration of hyperparameters for Adafactor.
+///
+/// Changing all default parameters:
+/// ```rust
+/// # use dfdx_core::prelude::*;
+/// AdafactorConfig {
+///     lr: 1e-3,
+///     beta2_decay: -0.5,
+///     eps: [1e-30, 1e-2],
+///     clip_threshold: 2.0,
+///     weight_decay: Some(WeightDecay::Decoupled(1e-2)),
+/// };
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct AdafactorConfig {
+    /// Upper bound of the relative step size, which is `min(lr, 1 / sqrt(t))` at step `t`.
+    /// The step is scaled by the root mean square of the parameter. Defaults to `1e-2`.
+    pub lr: f64,
+
+    /// The coefficient of the running average of squared gradients at step `t` is
+    /// `1 - t^beta2_decay`. Defaults to `-0.8`.
+    pub beta2_decay: f64,
+
+    /// Regularization constants added to the squared gradients, and lower bound of the
+    /// root mean square of the parameter. Defaults to `[1e-30, 1e-3]`.
+    pub eps: [f64; 2],
+
+    /// Updates with a larger root mean square are scaled down to it. Defaults to `1.0`.
+    pub clip_threshold: f64,
+
+    /// Optional weight decay. Defaults to `None`.
+    pub weight_decay: Option<WeightDecay>,
+}
+
+impl Default for AdafactorConfig {
+    fn default() -> Self {
+        Self {
+            lr: 1e-2,
+            beta2_decay: -0.8,
+            eps: [1e-30, 1e-3],
+            clip_threshold: 1.0,
+            weight_decay: None,
+        }
+    }
+}
+
+pub trait AdafactorKernel<E: Dtype>: Storage<E> {
+    /// `exp_avg_sq` holds the running averages of the rows followed by the ones of
+    /// the columns if `factored` is the `[rows, cols]` of `param`.
+    fn adafactor_kernel(
+        &self,
+        t: i32,
+        cfg: &AdafactorConfig,
+        factored: Option<[usize; 2]>,
+        param: &mut Self::Vec,
+        exp_avg_sq: &mut Self::Vec,
+        grad: &Self::Vec,
+    ) -> Result<(), Error>;
+}
+
+impl AdafactorConfig {
+    /// The number of elements of the running average of squared gradients for a
+    /// tensor with `dims`. The average is factored into rows and columns for tensors
+    /// of rank 2, so it only has `rows + cols` elements for them.
+    pub fn exp_avg_sq_len(dims: &[usize]) -> usize {
+        match Self::factored(dims) {
+            Some([rows, cols]) => rows + cols,
+            None => dims.iter().product(),
+        }
+    }
+
+    fn factored(dims: &[usize]) -> Option<[usize; 2]> {
+        match *dims {
+            [rows, cols] => Some([rows, cols]),
+            _ => None,
+        }
+    }
+
+    /// Update a single tensor using Adafactor. `exp_avg_sq` must have
+    /// [AdafactorConfig::exp_avg_sq_len()] elements.
+    pub fn try_update<S: Shape, E: Dtype, D: AdafactorKernel<E>>(
+        &self,
+        t: i32,
+        param: &mut Tensor<S, E, D>,
+        exp_avg_sq: &mut Tensor<(usize,), E, D>,
+        grad: &D::Vec,
+    ) -> Result<(), crate::tensor::Error> {
+        let dims = param.shape.concrete();
+        assert_eq!(exp_avg_sq.shape.0, Self::exp_avg_sq_len(dims.as_ref()));
+        param.device.adafactor_kernel(
+            t,
+            self,
+            Self::factored(dims.as_ref()),
+            std::sync::Arc::make_mut(&mut param.data),
+            std::sync::Arc::make_mut(&mut exp_avg_sq.data),
+            grad,
+        )
+    }
+}
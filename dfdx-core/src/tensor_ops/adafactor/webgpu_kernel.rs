@@ -0,0 +1,15 @@
+use crate::prelude::{Dtype, Webgpu};
+
+impl<E: Dtype> super::AdafactorKernel<E> for Webgpu {
+    fn adafactor_kernel(
+        &self,
+        t: i32,
+        cfg: &crate::prelude::AdafactorConfig,
+        factored: Option<[usize; 2]>,
+        param: &mut Self::Vec,
+        exp_avg_sq: &mut Self::Vec,
+        grad: &Self::Vec,
+    ) -> Result<(), crate::prelude::Error> {
+        todo!()
+    }
+}
@@ -0,0 +1,92 @@
+#include "cuda_utils.cuh"
+
+enum WeightDecayType {
+    WdNone,
+    L2,
+    Decoupled
+};
+
+struct AdaGradConfig {
+    double lr;
+    double eps;
+    WeightDecayType weight_decay_type;
+    double weight_decay;
+};
+
+template<typename T>
+__device__ void adagrad_update(
+    const AdaGradConfig cfg,
+    const size_t numel,
+    T* param,
+    T* sum,
+    const T* grad
+) {
+    T lr = cfg.lr;
+    T weight_decay = cfg.weight_decay;
+    T eps = cfg.eps;
+    for (unsigned int i = blockIdx.x * blockDim.x + threadIdx.x; i < numel; i += blockDim.x * gridDim.x) {
+        T p = param[i];
+        T g = grad[i];
+        T s = sum[i];
+
+        if (cfg.weight_decay_type == L2) {
+            g += weight_decay * p;
+        }
+
+        s += g * g;
+        g = lr * g / (sqrtg(s) + eps);
+
+        if (cfg.weight_decay_type == Decoupled) {
+            g += (weight_decay * lr) * p;
+        }
+
+        sum[i] = s;
+        param[i] -= g;
+    }
+}
+
+#define ADAGRAD(TYPENAME, FN) \
+extern "C" __global__ void FN( \
+    const AdaGradConfig cfg, \
+    const size_t numel, \
+    TYPENAME* param, \
+    TYPENAME* sum, \
+    const TYPENAME* grad \
+) { \
+    adagrad_update(cfg, numel, param, sum, grad); \
+}
+
+ADAGRAD(__half, adagrad_update_f16);
+ADAGRAD(float, adagrad_update_f32);
+ADAGRAD(double, adagrad_update_f64);
+
+extern "C" __global__ void adagrad_update_amp_f16(
+    const AdaGradConfig cfg,
+    const size_t numel,
+    __half* param,
+    __half* sum,
+    const __half* grad
+) {
+    float lr = cfg.lr;
+    float weight_decay = cfg.weight_decay;
+    float eps = cfg.eps;
+    for (unsigned int i = blockIdx.x * blockDim.x + threadIdx.x; i < numel; i += blockDim.x * gridDim.x) {
+        float p = param[i];
+        float g = grad[i];
+        float s = sum[i];
+
+        if (cfg.weight_decay_type == L2) {
+            g += weight_decay * p;
+        }
+
+        s += g * g;
+        g = lr * g / (sqrtg(s) + eps);
+
+        if (cfg.weight_decay_type == Decoupled) {
+            g += (weight_decay * lr) * p;
+        }
+
+        sum[i] = s;
+        param[i] -= g;
+    }
+}
@@ -0,0 +1,79 @@
+use super::{AdaGradConfig, AdaGradKernel, WeightDecay};
+use crate::{
+    dtypes::{Dtype, NotMixedPrecision},
+    tensor::{Cpu, Error},
+};
+
+#[cfg(feature = "f16")]
+impl AdaGradKernel<crate::dtypes::AMP<crate::dtypes::f16>> for Cpu {
+    fn adagrad_kernel(
+        &self,
+        t: i32,
+        cfg: &AdaGradConfig,
+        param: &mut Self::Vec,
+        sum: &mut Self::Vec,
+        grad: &Self::Vec,
+    ) -> Result<(), Error> {
+        let eps = cfg.eps as f32;
+        let lr = (cfg.lr / (1.0 + (t - 1) as f64 * cfg.lr_decay)) as f32;
+
+        for ((p, g), s) in param
+            .iter_mut()
+            .zip(grad.iter().cloned())
+            .zip(sum.iter_mut())
+        {
+            let p_f32 = p.0.to_f32();
+            let mut g_f32 = g.0.to_f32();
+            let mut s_f32 = s.0.to_f32();
+
+            if let Some(WeightDecay::L2(wd)) = cfg.weight_decay {
+                g_f32 += (wd as f32) * p_f32;
+            }
+
+            s_f32 += g_f32 * g_f32;
+            g_f32 = lr * g_f32 / (s_f32.sqrt() + eps);
+
+            if let Some(WeightDecay::Decoupled(wd)) = cfg.weight_decay {
+                g_f32 += (wd as f32) * lr * p_f32;
+            }
+
+            p.0 = crate::dtypes::f16::from_f32(p_f32 - g_f32);
+            s.0 = crate::dtypes::f16::from_f32(s_f32);
+        }
+        Ok(())
+    }
+}
+
+impl<E: num_traits::Float + Dtype + NotMixedPrecision> AdaGradKernel<E> for Cpu {
+    fn adagrad_kernel(
+        &self,
+        t: i32,
+        cfg: &AdaGradConfig,
+        param: &mut Self::Vec,
+        sum: &mut Self::Vec,
+        grad: &Self::Vec,
+    ) -> Result<(), Error> {
+        let eps = E::from_f64(cfg.eps).unwrap();
+        let lr = E::from_f64(cfg.lr / (1.0 + (t - 1) as f64 * cfg.lr_decay)).unwrap();
+
+        for ((p, mut g), s) in param
+            .iter_mut()
+            .zip(grad.iter().cloned())
+            .zip(sum.iter_mut())
+        {
+            if let Some(WeightDecay::L2(wd)) = cfg.weight_decay {
+                g += E::from_f64(wd).unwrap() * *p;
+            }
+
+            *s += g * g;
+            g = lr * g / (s.sqrt() + eps);
+
+            if let Some(WeightDecay::Decoupled(wd)) = cfg.weight_decay {
+                g += E::from_f64(wd).unwrap() * lr * *p;
+            }
+
+            *p -= g;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,83 @@
+use crate::{
+    dtypes::*,
+    tensor::{launch_cfg, Cuda, Error},
+    tensor_ops::optim::*,
+};
+
+use cudarc::driver::{DeviceRepr, DeviceSlice, LaunchAsync};
+
+#[repr(C)]
+struct CudaAdaGradConfig {
+    lr: f64,
+    eps: f64,
+    weight_decay_type: WeightDecayType,
+    weight_decay: f64,
+}
+
+unsafe impl DeviceRepr for CudaAdaGradConfig {}
+
+fn adagrad_config_to_cuda(t: i32, config: &super::AdaGradConfig) -> CudaAdaGradConfig {
+    let (weight_decay_type, weight_decay) = weight_decay_to_cuda(config.weight_decay);
+
+    CudaAdaGradConfig {
+        lr: config.lr / (1.0 + (t - 1) as f64 * config.lr_decay),
+        eps: config.eps,
+        weight_decay_type,
+        weight_decay,
+    }
+}
+
+const PTX_SRC: &str = include_str!(concat!(env!("OUT_DIR"), "/adagrad.ptx"));
+
+trait HasCudaKernel<E> {
+    const MOD: &'static str;
+    const FWD: &'static str;
+}
+
+#[cfg(feature = "f16")]
+impl HasCudaKernel<AMP<f16>> for Cuda {
+    const MOD: &'static str = "adagrad_amp_f16";
+    const FWD: &'static str = "adagrad_update_amp_f16";
+}
+
+#[cfg(feature = "f16")]
+impl HasCudaKernel<f16> for Cuda {
+    const MOD: &'static str = "adagrad_f16";
+    const FWD: &'static str = "adagrad_update_f16";
+}
+
+impl HasCudaKernel<f32> for Cuda {
+    const MOD: &'static str = "adagrad_f32";
+    const FWD: &'static str = "adagrad_update_f32";
+}
+
+impl HasCudaKernel<f64> for Cuda {
+    const MOD: &'static str = "adagrad_f64";
+    const FWD: &'static str = "adagrad_update_f64";
+}
+
+impl<E: Dtype> super::AdaGradKernel<E> for Cuda
+where
+    Self: HasCudaKernel<E>,
+{
+    fn adagrad_kernel(
+        &self,
+        t: i32,
+        cfg: &super::AdaGradConfig,
+        param: &mut Self::Vec,
+        sum: &mut Self::Vec,
+        grad: &Self::Vec,
+    ) -> Result<(), Error> {
+        if !self.dev.has_func(Self::MOD, Self::FWD) {
+            self.dev.load_ptx(PTX_SRC.into(), Self::MOD, &[Self::FWD])?;
+        }
+
+        let opt_cfg = adagrad_config_to_cuda(t, cfg);
+        let numel = param.len();
+        let func = self.dev.get_func(Self::MOD, Self::FWD).unwrap();
+        let cfg = launch_cfg::<128>(numel as u32);
+        let params = (opt_cfg, numel, param, sum, grad);
+        unsafe { func.launch(cfg, params) }?;
+        Ok(())
+    }
+}
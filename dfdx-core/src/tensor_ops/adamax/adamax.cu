@@ -0,0 +1,114 @@
+#include "cuda_utils.cuh"
+
+enum WeightDecayType {
+    WdNone,
+    L2,
+    Decoupled
+};
+
+struct AdamaxConfig {
+    double lr;
+    double beta1;
+    double beta2;
+    double eps;
+    WeightDecayType weight_decay_type;
+    double weight_decay;
+};
+
+template<typename T>
+__device__ void adamax_update(
+    const AdamaxConfig cfg,
+    const size_t numel,
+    const int t_int,
+    T* param,
+    T* exp_avg,
+    T* exp_inf,
+    const T* grad
+) {
+    T beta1 = cfg.beta1;
+    T beta2 = cfg.beta2;
+    T lr = cfg.lr;
+    T weight_decay = cfg.weight_decay;
+    T eps = cfg.eps;
+    T one = 1.0;
+    T t = t_int;
+    for (unsigned int i = blockIdx.x * blockDim.x + threadIdx.x; i < numel; i += blockDim.x * gridDim.x) {
+        T p = param[i];
+        T g = grad[i];
+        T m = exp_avg[i];
+        T u = exp_inf[i];
+
+        if (cfg.weight_decay_type == L2) {
+            g += weight_decay * p;
+        }
+
+        m = m * beta1 + g * (one - beta1);
+        u = maxg(u * beta2, absg(g) + eps);
+        g = lr / (one - powg(beta1, t)) * m / u;
+
+        if (cfg.weight_decay_type == Decoupled) {
+            g += (weight_decay * lr) * p;
+        }
+
+        exp_avg[i] = m;
+        exp_inf[i] = u;
+        param[i] -= g;
+    }
+}
+
+#define ADAMAX(TYPENAME, FN) \
+extern "C" __global__ void FN( \
+    const AdamaxConfig cfg, \
+    const size_t numel, \
+    const int t, \
+    TYPENAME* param, \
+    TYPENAME* exp_avg, \
+    TYPENAME* exp_inf, \
+    const TYPENAME* grad \
+) { \
+    adamax_update(cfg, numel, t, param, exp_avg, exp_inf, grad); \
+}
+
+ADAMAX(__half, adamax_update_f16);
+ADAMAX(float, adamax_update_f32);
+ADAMAX(double, adamax_update_f64);
+
+extern "C" __global__ void adamax_update_amp_f16(
+    const AdamaxConfig cfg,
+    const size_t numel,
+    const int t_int,
+    __half* param,
+    __half* exp_avg,
+    __half* exp_inf,
+    const __half* grad
+) {
+    float beta1 = cfg.beta1;
+    float beta2 = cfg.beta2;
+    float lr = cfg.lr;
+    float weight_decay = cfg.weight_decay;
+    float eps = cfg.eps;
+    float one = 1.0;
+    float t = t_int;
+    for (unsigned int i = blockIdx.x * blockDim.x + threadIdx.x; i < numel; i += blockDim.x * gridDim.x) {
+        float p = param[i];
+        float g = grad[i];
+        float m = exp_avg[i];
+        float u = exp_inf[i];
+
+        if (cfg.weight_decay_type == L2) {
+            g += weight_decay * p;
+        }
+
+        m = m * beta1 + g * (one - beta1);
+        u = maxg(u * beta2, absg(g) + eps);
+        g = lr / (one - powg(beta1, t)) * m / u;
+
+        if (cfg.weight_decay_type == Decoupled) {
+            g += (weight_decay * lr) * p;
+        }
+
+        exp_avg[i] = m;
+        exp_inf[i] = u;
+        param[i] -= g;
+    }
+}
@@ -0,0 +1,87 @@
+use super::{AdamaxConfig, AdamaxKernel, WeightDecay};
+use crate::{
+    dtypes::{Dtype, NotMixedPrecision},
+    tensor::{Cpu, Error},
+};
+
+#[cfg(feature = "f16")]
+impl AdamaxKernel<crate::dtypes::AMP<crate::dtypes::f16>> for Cpu {
+    fn adamax_kernel(
+        &self,
+        t: i32,
+        cfg: &AdamaxConfig,
+        param: &mut Self::Vec,
+        exp_avg: &mut Self::Vec,
+        exp_inf: &mut Self::Vec,
+        grad: &Self::Vec,
+    ) -> Result<(), Error> {
+        let betas = cfg.betas.map(|x| x as f32);
+        let eps = cfg.eps as f32;
+        let lr = cfg.lr as f32;
+
+        for ((p, g), (m, u)) in param
+            .iter_mut()
+            .zip(grad.iter().cloned())
+            .zip(exp_avg.iter_mut().zip(exp_inf.iter_mut()))
+        {
+            let p_f32 = p.0.to_f32();
+            let mut g_f32 = g.0.to_f32();
+            let mut m_f32 = m.0.to_f32();
+            let mut u_f32 = u.0.to_f32();
+
+            if let Some(WeightDecay::L2(wd)) = cfg.weight_decay {
+                g_f32 += (wd as f32) * p_f32;
+            }
+
+            m_f32 = m_f32 * betas[0] + g_f32 * (1.0 - betas[0]);
+            u_f32 = (u_f32 * betas[1]).max(g_f32.abs() + eps);
+            g_f32 = lr * (1.0 - betas[0].powi(t)).recip() * m_f32 / u_f32;
+
+            if let Some(WeightDecay::Decoupled(wd)) = cfg.weight_decay {
+                g_f32 += (wd * cfg.lr) as f32 * p_f32;
+            }
+
+            p.0 = crate::dtypes::f16::from_f32(p_f32 - g_f32);
+            m.0 = crate::dtypes::f16::from_f32(m_f32);
+            u.0 = crate::dtypes::f16::from_f32(u_f32);
+        }
+        Ok(())
+    }
+}
+
+impl<E: num_traits::Float + Dtype + NotMixedPrecision> AdamaxKernel<E> for Cpu {
+    fn adamax_kernel(
+        &self,
+        t: i32,
+        cfg: &AdamaxConfig,
+        param: &mut Self::Vec,
+        exp_avg: &mut Self::Vec,
+        exp_inf: &mut Self::Vec,
+        grad: &Self::Vec,
+    ) -> Result<(), Error> {
+        let betas = cfg.betas.map(E::from_f64).map(Option::unwrap);
+        let eps = E::from_f64(cfg.eps).unwrap();
+        let lr = E::from_f64(cfg.lr).unwrap();
+
+        for ((p, mut g), (m, u)) in param
+            .iter_mut()
+            .zip(grad.iter().cloned())
+            .zip(exp_avg.iter_mut().zip(exp_inf.iter_mut()))
+        {
+            if let Some(WeightDecay::L2(wd)) = cfg.weight_decay {
+                g += E::from_f64(wd).unwrap() * *p;
+            }
+
+            *m = *m * betas[0] + g * (E::one() - betas[0]);
+            *u = (*u * betas[1]).max(g.abs() + eps);
+            g = lr * (E::one() - betas[0].powi(t)).recip() * *m / *u;
+
+            if let Some(WeightDecay::Decoupled(wd)) = cfg.weight_decay {
+                g += E::from_f64(wd * cfg.lr).unwrap() * *p;
+            }
+
+            *p -= g;
+        }
+        Ok(())
+    }
+}
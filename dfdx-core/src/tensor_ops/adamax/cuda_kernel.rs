@@ -0,0 +1,88 @@
+use crate::{
+    dtypes::*,
+    tensor::{launch_cfg, Cuda, Error},
+    tensor_ops::optim::*,
+};
+
+use cudarc::driver::{DeviceRepr, DeviceSlice, LaunchAsync};
+
+#[repr(C)]
+struct CudaAdamaxConfig {
+    lr: f64,
+    beta1: f64,
+    beta2: f64,
+    eps: f64,
+    weight_decay_type: WeightDecayType,
+    weight_decay: f64,
+}
+
+unsafe impl DeviceRepr for CudaAdamaxConfig {}
+
+fn adamax_config_to_cuda(config: &super::AdamaxConfig) -> CudaAdamaxConfig {
+    let (weight_decay_type, weight_decay) = weight_decay_to_cuda(config.weight_decay);
+
+    CudaAdamaxConfig {
+        lr: config.lr,
+        beta1: config.betas[0],
+        beta2: config.betas[1],
+        eps: config.eps,
+        weight_decay_type,
+        weight_decay,
+    }
+}
+
+const PTX_SRC: &str = include_str!(concat!(env!("OUT_DIR"), "/adamax.ptx"));
+
+trait HasCudaKernel<E> {
+    const MOD: &'static str;
+    const FWD: &'static str;
+}
+
+#[cfg(feature = "f16")]
+impl HasCudaKernel<AMP<f16>> for Cuda {
+    const MOD: &'static str = "adamax_amp_f16";
+    const FWD: &'static str = "adamax_update_amp_f16";
+}
+
+#[cfg(feature = "f16")]
+impl HasCudaKernel<f16> for Cuda {
+    const MOD: &'static str = "adamax_f16";
+    const FWD: &'static str = "adamax_update_f16";
+}
+
+impl HasCudaKernel<f32> for Cuda {
+    const MOD: &'static str = "adamax_f32";
+    const FWD: &'static str = "adamax_update_f32";
+}
+
+impl HasCudaKernel<f64> for Cuda {
+    const MOD: &'static str = "adamax_f64";
+    const FWD: &'static str = "adamax_update_f64";
+}
+
+impl<E: Dtype> super::AdamaxKernel<E> for Cuda
+where
+    Self: HasCudaKernel<E>,
+{
+    fn adamax_kernel(
+        &self,
+        t: i32,
+        cfg: &super::AdamaxConfig,
+        param: &mut Self::Vec,
+        exp_avg: &mut Self::Vec,
+        exp_inf: &mut Self::Vec,
+        grad: &Self::Vec,
+    ) -> Result<(), Error> {
+        if !self.dev.has_func(Self::MOD, Self::FWD) {
+            self.dev.load_ptx(PTX_SRC.into(), Self::MOD, &[Self::FWD])?;
+        }
+
+        let opt_cfg = adamax_config_to_cuda(cfg);
+        let numel = param.len();
+        let func = self.dev.get_func(Self::MOD, Self::FWD).unwrap();
+        let cfg = launch_cfg::<128>(numel as u32);
+        let params = (opt_cfg, numel, t, param, exp_avg, exp_inf, grad);
+        unsafe { func.launch(cfg, params) }?;
+        Ok(())
+    }
+}
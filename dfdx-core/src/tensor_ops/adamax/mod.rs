@@ -0,0 +1,86 @@
+mod cpu_kernel;
+
+#[cfg(feature = "cuda")]
+mod cuda_kernel;
+
+#[cfg(feature = "webgpu")]
+mod webgpu_kernel;
+
+use crate::{
+    shapes::{Dtype, Shape},
+    tensor::{Error, Storage, Tensor},
+};
+
+use super::WeightDecay;
+
+/// Configuration of hyperparameters for Adamax.
+///
+/// Changing all default parameters:
+/// ```rust
+/// # use dfdx_core::prelude::*;
+/// AdamaxConfig {
+///     lr: 1e-2,
+///     betas: [0.1, 0.2],
+///     eps: 1e-6,
+///     weight_decay: Some(WeightDecay::L2(1e-1)),
+/// };
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct AdamaxConfig {
+    /// Learning rate. Defaults to `2e-3`.
+    pub lr: f64,
+
+    /// Coefficients of the running average of gradients and of the exponentially weighted
+    /// infinity norm. Defaults to `[0.9, 0.999]`.
+    pub betas: [f64; 2],
+
+    /// Epsilon for numerical stability. Defaults to `1e-8`.
+    pub eps: f64,
+
+    /// Optional weight decay. Defaults to `None`.
+    pub weight_decay: Option<WeightDecay>,
+}
+
+impl Default for AdamaxConfig {
+    fn default() -> Self {
+        Self {
+            lr: 2e-3,
+            betas: [0.9, 0.999],
+            eps: 1e-8,
+            weight_decay: None,
+        }
+    }
+}
+
+pub trait AdamaxKernel<E: Dtype>: Storage<E> {
+    fn adamax_kernel(
+        &self,
+        t: i32,
+        cfg: &AdamaxConfig,
+        param: &mut Self::Vec,
+        exp_avg: &mut Self::Vec,
+        exp_inf: &mut Self::Vec,
+        grad: &Self::Vec,
+    ) -> Result<(), Error>;
+}
+
+impl AdamaxConfig {
+    /// Update a single tensor using Adamax.
+    pub fn try_update<S: Shape, E: Dtype, D: AdamaxKernel<E>>(
+        &self,
+        t: i32,
+        param: &mut Tensor<S, E, D>,
+        exp_avg: &mut D::Vec,
+        exp_inf: &mut D::Vec,
+        grad: &D::Vec,
+    ) -> Result<(), crate::tensor::Error> {
+        param.device.adamax_kernel(
+            t,
+            self,
+            std::sync::Arc::make_mut(&mut param.data),
+            exp_avg,
+            exp_inf,
+            grad,
+        )
+    }
+}
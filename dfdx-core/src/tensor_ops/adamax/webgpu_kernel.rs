@@ -0,0 +1,15 @@
+use crate::prelude::{Dtype, Webgpu};
+
+impl<E: Dtype> super::AdamaxKernel<E> for Webgpu {
+    fn adamax_kernel(
+        &self,
+        t: i32,
+        cfg: &crate::prelude::AdamaxConfig,
+        param: &mut Self::Vec,
+        exp_avg: &mut Self::Vec,
+        exp_inf: &mut Self::Vec,
+        grad: &Self::Vec,
+    ) -> Result<(), crate::prelude::Error> {
+        todo!()
+    }
+}
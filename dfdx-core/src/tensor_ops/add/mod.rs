@@ -7,21 +7,44 @@ mod cuda_kernel;
 mod webgpu_kernel;
 
 use super::ops::*;
+use super::utilities::higher_order::{BinaryDerivative, UnaryDerivative};
 use crate::{
     shapes::*,
-    tensor::{Error, Merge, Storage, Tape, Tensor},
+    tensor::{Error, HigherOrderOps, HigherOrderVar, Merge, Storage, Tape, Tensor},
 };
 
 #[repr(C)]
 #[derive(Debug, Default, Clone, Copy)]
 pub struct BinaryAddKernelOp;
 
+impl<E> BinaryDerivative<E> for BinaryAddKernelOp {
+    fn df<D: Storage<E>>(
+        &self,
+        _ops: &mut dyn HigherOrderOps<E, D>,
+        _x: HigherOrderVar,
+        _y: HigherOrderVar,
+    ) -> Result<Option<(HigherOrderVar, HigherOrderVar)>, Error> {
+        Ok(None)
+    }
+}
+
 #[repr(C)]
 #[derive(Debug, Clone, Copy)]
 pub struct ScalarAddKernelOp<E> {
     scalar: E,
 }
 
+impl<E> UnaryDerivative<E> for ScalarAddKernelOp<E> {
+    fn df<D: Storage<E>>(
+        &self,
+        _ops: &mut dyn HigherOrderOps<E, D>,
+        _x: HigherOrderVar,
+        _y: HigherOrderVar,
+    ) -> Result<Option<HigherOrderVar>, Error> {
+        Ok(None)
+    }
+}
+
 /// Element wise and scalar addition.
 ///
 /// Example:
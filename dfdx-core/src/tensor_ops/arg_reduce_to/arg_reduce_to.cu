@@ -0,0 +1,41 @@
+#include "cuda_utils.cuh"
+
+// One thread per output element. dims & strides are permuted so that the reduced
+// axes are last, so the elements reduced into out[i] are the logical indices
+// [i * chunk_len, (i + 1) * chunk_len). NaN replaces any other value, and ties
+// keep the earlier index.
+#define ARG_REDUCE_OP(TYPENAME, FWD, SYMBOL) \
+extern "C" __global__ void FWD( \
+    const size_t numel, \
+    const size_t num_dims, \
+    const size_t chunk_len, \
+    const size_t *info, \
+    const TYPENAME *inp, \
+    size_t *out \
+) { \
+    const size_t *dims = info; \
+    const size_t *strides = info + num_dims; \
+    for (unsigned int i = blockIdx.x * blockDim.x + threadIdx.x; i < numel; i += blockDim.x * gridDim.x) { \
+        size_t best_k = 0; \
+        if (chunk_len > 0) { \
+            TYPENAME best = inp[get_strided_index(i * chunk_len, num_dims, dims, strides)]; \
+            for (size_t k = 1; k < chunk_len && !isnang(best); k++) { \
+                TYPENAME x = inp[get_strided_index(i * chunk_len + k, num_dims, dims, strides)]; \
+                if (isnang(x) || x SYMBOL best) { \
+                    best = x; \
+                    best_k = k; \
+                } \
+            } \
+        } \
+        out[i] = best_k; \
+    } \
+}
+
+ARG_REDUCE_OP(__half, argmax_fwd_f16, >)
+ARG_REDUCE_OP(__half, argmin_fwd_f16, <)
+
+ARG_REDUCE_OP(float, argmax_fwd_f32, >)
+ARG_REDUCE_OP(float, argmin_fwd_f32, <)
+
+ARG_REDUCE_OP(double, argmax_fwd_f64, >)
+ARG_REDUCE_OP(double, argmin_fwd_f64, <)
@@ -0,0 +1,54 @@
+use crate::{
+    shapes::{Axes, Dtype, HasAxes, ReduceShapeTo, Shape},
+    tensor::{Cpu, Error, Tensor, ZerosTensor},
+    tensor_ops::utilities::reduction_utils::index_for_reductions,
+};
+
+use super::{ArgMaxKernelOp, ArgMinKernelOp};
+
+/// Whether `x` replaces `best` as the extremum of an arg reduction, where NaN wins over
+/// all other values and ties are broken by keeping the earlier index.
+trait ArgReduceOp<E> {
+    fn replaces(x: E, best: E) -> bool;
+}
+
+#[allow(clippy::eq_op)]
+impl<E: Dtype> ArgReduceOp<E> for ArgMaxKernelOp {
+    fn replaces(x: E, best: E) -> bool {
+        best == best && (x != x || x > best)
+    }
+}
+
+#[allow(clippy::eq_op)]
+impl<E: Dtype> ArgReduceOp<E> for ArgMinKernelOp {
+    fn replaces(x: E, best: E) -> bool {
+        best == best && (x != x || x < best)
+    }
+}
+
+impl<Op: ArgReduceOp<E>, E: Dtype> super::ArgReduceKernel<Op, E> for Cpu {
+    fn forward<Src: Shape, Dst: Shape, Ax: Axes, T>(
+        &self,
+        dst: Dst,
+        inp: &Tensor<Src, E, Self, T>,
+    ) -> Result<Tensor<Dst, usize, Self>, Error>
+    where
+        Src: ReduceShapeTo<Dst, Ax>,
+    {
+        let mut out = self.try_zeros_like(&dst)?;
+        let num_elems_reduced = <Src as HasAxes<Ax>>::size(&inp.shape);
+        let inp_buf = inp.data.as_ref();
+        let mut idx = index_for_reductions::<Src, Ax>(inp.shape, inp.strides);
+        for o in out.buf_iter_mut() {
+            let mut best: Option<E> = None;
+            for i in 0..num_elems_reduced {
+                let x = inp_buf[idx.next().unwrap()];
+                if best.map_or(true, |best| Op::replaces(x, best)) {
+                    best = Some(x);
+                    *o = i;
+                }
+            }
+        }
+        Ok(out)
+    }
+}
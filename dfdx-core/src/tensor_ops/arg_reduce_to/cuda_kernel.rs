@@ -0,0 +1,82 @@
+use crate::{
+    dtypes::*,
+    shapes::*,
+    tensor::{launch_cfg, Cuda, Error, Tensor},
+    tensor_ops::reduction_utils::index_for_reductions,
+};
+use cudarc::driver::LaunchAsync;
+
+use super::{ArgMaxKernelOp, ArgMinKernelOp, ArgReduceKernel};
+
+use std::vec::Vec;
+
+const PTX_SRC: &str = include_str!(concat!(env!("OUT_DIR"), "/arg_reduce_to.ptx"));
+
+trait ArgReduceOpCudaKernel<E: Unit> {
+    /// Unique name for the kernel
+    const MODULE_NAME: &'static str;
+
+    /// Name of function in the .cu file
+    const FWD_FN_NAME: &'static str;
+}
+
+impl<E: Dtype, Op: ArgReduceOpCudaKernel<E>> ArgReduceKernel<Op, E> for Cuda {
+    fn forward<Src: Shape, Dst: Shape, Ax: Axes, T>(
+        &self,
+        dst: Dst,
+        inp: &Tensor<Src, E, Self, T>,
+    ) -> Result<Tensor<Dst, usize, Self>, Error>
+    where
+        Src: ReduceShapeTo<Dst, Ax>,
+    {
+        if !self.dev.has_func(Op::MODULE_NAME, Op::FWD_FN_NAME) {
+            self.dev
+                .load_ptx(PTX_SRC.into(), Op::MODULE_NAME, &[Op::FWD_FN_NAME])?;
+        }
+
+        // unlike the other reductions, all axes are kept (including broadcasted ones) in
+        // their logical order, so the indices are the same as on the cpu
+        let idx = index_for_reductions::<Src, Ax>(inp.shape, inp.strides);
+        let mut info = Vec::with_capacity(Src::NUM_DIMS * 2);
+        info.extend(idx.shape);
+        info.extend(idx.strides);
+        let info = self.dev.htod_copy(info)?;
+
+        let numel = dst.num_elements();
+        let chunk_len = <Src as HasAxes<Ax>>::size(&inp.shape);
+        let mut storage = unsafe { self.alloc_empty::<usize>(numel) }?;
+
+        let fwd_fn = self.dev.get_func(Op::MODULE_NAME, Op::FWD_FN_NAME).unwrap();
+        let cfg = launch_cfg::<128>(numel as u32);
+        let params = (
+            numel,             // const size_t numel,
+            Src::NUM_DIMS,     // const size_t num_dims,
+            chunk_len,         // const size_t chunk_len,
+            &info,             // const size_t *info,
+            inp.data.as_ref(), // const float *inp,
+            &mut storage,      // size_t *out
+        );
+        unsafe { fwd_fn.launch(cfg, params) }?;
+        Ok(self.build_tensor(dst, dst.strides(), storage))
+    }
+}
+
+macro_rules! arg_reduces {
+    ($TypeName:ty, $ArgMax:tt, $ArgMin:tt) => {
+        impl ArgReduceOpCudaKernel<$TypeName> for ArgMaxKernelOp {
+            const MODULE_NAME: &'static str = $ArgMax;
+            const FWD_FN_NAME: &'static str = $ArgMax;
+        }
+        impl ArgReduceOpCudaKernel<$TypeName> for ArgMinKernelOp {
+            const MODULE_NAME: &'static str = $ArgMin;
+            const FWD_FN_NAME: &'static str = $ArgMin;
+        }
+    };
+}
+
+#[cfg(feature = "f16")]
+arg_reduces!(AMP<f16>, "argmax_fwd_f16", "argmin_fwd_f16");
+#[cfg(feature = "f16")]
+arg_reduces!(f16, "argmax_fwd_f16", "argmin_fwd_f16");
+arg_reduces!(f32, "argmax_fwd_f32", "argmin_fwd_f32");
+arg_reduces!(f64, "argmax_fwd_f64", "argmin_fwd_f64");
@@ -0,0 +1,221 @@
+mod cpu_kernel;
+
+#[cfg(feature = "cuda")]
+mod cuda_kernel;
+
+#[cfg(feature = "webgpu")]
+mod webgpu_kernel;
+
+use crate::{shapes::*, tensor::*};
+
+pub enum ArgMaxKernelOp {}
+pub enum ArgMinKernelOp {}
+
+pub trait ArgReduceKernel<Op, E: Dtype>: Storage<E> + Storage<usize> {
+    fn forward<Src: Shape, Dst: Shape, Ax: Axes, T>(
+        &self,
+        dst: Dst,
+        inp: &Tensor<Src, E, Self, T>,
+    ) -> Result<Tensor<Dst, usize, Self>, Error>
+    where
+        Src: ReduceShapeTo<Dst, Ax>;
+}
+
+/// Indices of the maximum values along multiple axes.
+pub trait ArgMaxTo: HasShape {
+    type Indices<Dst: Shape>;
+
+    /// Argmax reduction. **Pytorch equivalent**: `t.argmax(Ax)`
+    ///
+    /// Returns the index of the maximum value within the reduced axes, which for multiple
+    /// axes is the index into the reduced axes flattened in row major order. Ties are broken
+    /// by returning the smallest index, and NaN is greater than any other value, so the index
+    /// of the first NaN is returned if there is one. No gradients are tracked.
+    ///
+    /// Example reducing a single axis:
+    /// ```rust
+    /// # use dfdx_core::prelude::*;
+    /// # let dev: Cpu = Default::default();
+    /// let t: Tensor<Rank2<2, 3>, f32, _> = dev.tensor([[1.0, 3.0, 3.0], [-1.0, -2.0, -3.0]]);
+    /// let r = t.argmax::<Rank1<2>, _>(); // or `argmax::<_, Axis<1>>()`
+    /// assert_eq!(r.array(), [1, 0]);
+    /// ```
+    ///
+    /// Reducing multiple axes:
+    /// ```rust
+    /// # use dfdx_core::prelude::*;
+    /// # let dev: Cpu = Default::default();
+    /// # let t = dev.tensor([[1.0, 2.0, 3.0], [-1.0, -2.0, 4.0]]);
+    /// let r = t.argmax::<Rank0, _>();
+    /// assert_eq!(r.array(), 5);
+    /// ```
+    fn argmax<Dst: Shape, Ax: Axes>(&self) -> Self::Indices<Dst>
+    where
+        Self::Shape: ReduceShapeTo<Dst, Ax>,
+    {
+        self.try_argmax().unwrap()
+    }
+    /// Fallible version of [ArgMaxTo::argmax]
+    fn try_argmax<Dst: Shape, Ax: Axes>(&self) -> Result<Self::Indices<Dst>, Error>
+    where
+        Self::Shape: ReduceShapeTo<Dst, Ax>;
+}
+
+/// Indices of the minimum values along multiple axes.
+pub trait ArgMinTo: HasShape {
+    type Indices<Dst: Shape>;
+
+    /// Argmin reduction. **Pytorch equivalent**: `t.argmin(Ax)`
+    ///
+    /// Returns the index of the minimum value within the reduced axes, which for multiple
+    /// axes is the index into the reduced axes flattened in row major order. Ties are broken
+    /// by returning the smallest index, and NaN is smaller than any other value, so the index
+    /// of the first NaN is returned if there is one. No gradients are tracked.
+    ///
+    /// Example reducing a single axis:
+    /// ```rust
+    /// # use dfdx_core::prelude::*;
+    /// # let dev: Cpu = Default::default();
+    /// let t: Tensor<Rank2<2, 3>, f32, _> = dev.tensor([[1.0, 2.0, 3.0], [-1.0, -3.0, -3.0]]);
+    /// let r = t.argmin::<Rank1<2>, _>(); // or `argmin::<_, Axis<1>>()`
+    /// assert_eq!(r.array(), [0, 1]);
+    /// ```
+    ///
+    /// Reducing multiple axes:
+    /// ```rust
+    /// # use dfdx_core::prelude::*;
+    /// # let dev: Cpu = Default::default();
+    /// # let t = dev.tensor([[1.0, 2.0, 3.0], [-1.0, -2.0, 4.0]]);
+    /// let r = t.argmin::<Rank0, _>();
+    /// assert_eq!(r.array(), 4);
+    /// ```
+    fn argmin<Dst: Shape, Ax: Axes>(&self) -> Self::Indices<Dst>
+    where
+        Self::Shape: ReduceShapeTo<Dst, Ax>,
+    {
+        self.try_argmin().unwrap()
+    }
+    /// Fallible version of [ArgMinTo::argmin]
+    fn try_argmin<Dst: Shape, Ax: Axes>(&self) -> Result<Self::Indices<Dst>, Error>
+    where
+        Self::Shape: ReduceShapeTo<Dst, Ax>;
+}
+
+impl<S: Shape, E: Dtype, D: ArgReduceKernel<ArgMaxKernelOp, E>, T> ArgMaxTo for Tensor<S, E, D, T> {
+    type Indices<Dst: Shape> = Tensor<Dst, usize, D>;
+    fn try_argmax<Dst: Shape, Ax: Axes>(&self) -> Result<Self::Indices<Dst>, Error>
+    where
+        Self::Shape: ReduceShapeTo<Dst, Ax>,
+    {
+        let dst: Dst = self.shape().reduced();
+        self.device.forward(dst, self)
+    }
+}
+
+impl<S: Shape, E: Dtype, D: ArgReduceKernel<ArgMinKernelOp, E>, T> ArgMinTo for Tensor<S, E, D, T> {
+    type Indices<Dst: Shape> = Tensor<Dst, usize, D>;
+    fn try_argmin<Dst: Shape, Ax: Axes>(&self) -> Result<Self::Indices<Dst>, Error>
+    where
+        Self::Shape: ReduceShapeTo<Dst, Ax>,
+    {
+        let dst: Dst = self.shape().reduced();
+        self.device.forward(dst, self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tensor_ops::*;
+    use crate::tests::*;
+
+    #[test]
+    fn test_argmax_axis_0_2d() {
+        let dev: TestDevice = Default::default();
+        let t = dev
+            .tensor([[1.0, 2.0, 2.0], [3.0, -2.0, 2.0]])
+            .to_dtype::<TestDtype>();
+        let r = t.argmax::<_, Axis<0>>();
+        assert_eq!(r.array(), [1, 0, 0]);
+    }
+
+    #[test]
+    fn test_argmax_axis_1_2d() {
+        let dev: TestDevice = Default::default();
+        let t = dev
+            .tensor([[1.0, 2.0, 2.0], [3.0, -2.0, 2.0]])
+            .to_dtype::<TestDtype>();
+        let r = t.argmax::<_, Axis<1>>();
+        assert_eq!(r.array(), [1, 0]);
+        let r = t.argmin::<_, Axis<1>>();
+        assert_eq!(r.array(), [0, 1]);
+    }
+
+    #[test]
+    fn test_arg_reduce_multi_axes() {
+        let dev: TestDevice = Default::default();
+        let t = dev
+            .tensor([
+                [[1.0, 5.0, 2.0], [0.0, -3.0, 5.0]],
+                [[4.0, -1.0, 0.0], [-1.0, 2.0, 3.0]],
+            ])
+            .to_dtype::<TestDtype>();
+        // indices into the flattened reduced axes (1, 2)
+        assert_eq!(t.argmax::<Rank1<2>, Axes2<1, 2>>().array(), [1, 0]);
+        assert_eq!(t.argmin::<Rank1<2>, Axes2<1, 2>>().array(), [4, 1]);
+        // indices into the flattened reduced axes (0, 2)
+        assert_eq!(t.argmax::<Rank1<2>, Axes2<0, 2>>().array(), [1, 2]);
+        assert_eq!(t.argmin::<Rank1<2>, Axes2<0, 2>>().array(), [4, 1]);
+        assert_eq!(t.argmax::<Rank0, _>().array(), 1);
+        assert_eq!(t.argmin::<Rank0, _>().array(), 4);
+    }
+
+    #[test]
+    fn test_arg_reduce_ties_and_nans() {
+        let dev: TestDevice = Default::default();
+        let t = dev
+            .tensor([
+                [2.0, 1.0, 2.0, 1.0],
+                [0.0, f64::NAN, 3.0, f64::NAN],
+                [f64::INFINITY, f64::INFINITY, 0.0, f64::NEG_INFINITY],
+            ])
+            .to_dtype::<TestDtype>();
+        assert_eq!(t.argmax::<Rank1<3>, _>().array(), [0, 1, 0]);
+        assert_eq!(t.argmin::<Rank1<3>, _>().array(), [1, 1, 3]);
+    }
+
+    #[test]
+    fn test_arg_reduce_broadcasted() {
+        let dev: TestDevice = Default::default();
+        let t = dev.tensor([1.0, 3.0, 2.0]).to_dtype::<TestDtype>();
+        let r = t
+            .clone()
+            .broadcast::<Rank2<4, 3>, _>()
+            .argmax::<Rank1<3>, _>();
+        // all values are equal along the broadcasted axis
+        assert_eq!(r.array(), [0; 3]);
+        let r = t.broadcast::<Rank2<3, 4>, _>().argmin::<Rank1<4>, _>();
+        assert_eq!(r.array(), [0; 4]);
+    }
+
+    #[test]
+    fn test_arg_reduce_permuted() {
+        let dev: TestDevice = Default::default();
+        let t = dev
+            .tensor([[1.0, 2.0, 3.0], [6.0, 5.0, 4.0]])
+            .to_dtype::<TestDtype>();
+        let t = t.permute::<Rank2<3, 2>, _>();
+        assert_eq!(t.argmax::<Rank1<3>, _>().array(), [1, 1, 1]);
+        assert_eq!(t.argmax::<Rank1<2>, _>().array(), [2, 0]);
+        assert_eq!(t.argmin::<Rank1<2>, _>().array(), [0, 2]);
+    }
+
+    #[test]
+    fn test_argmax_traced() {
+        let dev: TestDevice = Default::default();
+        let t = dev.tensor([1.0, 3.0, 2.0]).to_dtype::<TestDtype>();
+        let t: Tensor<_, _, _, OwnedTape<TestDtype, _>> = t.leaky_trace();
+        let r = t.argmax::<Rank0, _>();
+        assert_eq!(r.array(), 1);
+    }
+}
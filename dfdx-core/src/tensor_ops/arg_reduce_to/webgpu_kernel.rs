@@ -0,0 +1,19 @@
+use crate::prelude::{Dtype, Webgpu};
+
+impl<Op, E: Dtype> super::ArgReduceKernel<Op, E> for Webgpu {
+    fn forward<
+        Src: crate::prelude::Shape,
+        Dst: crate::prelude::Shape,
+        Ax: crate::prelude::Axes,
+        T,
+    >(
+        &self,
+        dst: Dst,
+        inp: &crate::prelude::Tensor<Src, E, Self, T>,
+    ) -> Result<crate::prelude::Tensor<Dst, usize, Self>, crate::prelude::Error>
+    where
+        Src: crate::prelude::ReduceShapeTo<Dst, Ax>,
+    {
+        todo!()
+    }
+}
@@ -7,12 +7,27 @@ mod cuda_kernel;
 mod webgpu_kernel;
 
 use super::ops::{try_binary_op, BinaryKernel};
+use super::utilities::higher_order::BinaryDerivative;
 use crate::{shapes::*, tensor::*};
 
 #[repr(C)]
 #[derive(Debug, Default, Clone, Copy)]
 pub struct BCEKernelOp;
 
+impl<E> BinaryDerivative<E> for BCEKernelOp {
+    fn df<D: Storage<E>>(
+        &self,
+        ops: &mut dyn HigherOrderOps<E, D>,
+        x: HigherOrderVar,
+        y: HigherOrderVar,
+    ) -> Result<Option<(HigherOrderVar, HigherOrderVar)>, Error> {
+        let dfdx = ops.sigmoid(x)?;
+        let dfdx = ops.sub(dfdx, y)?;
+        let dfdy = ops.scale(x, -1.0)?;
+        Ok(Some((dfdx, dfdy)))
+    }
+}
+
 /// [Binary Cross Entropy](https://en.wikipedia.org/wiki/Cross_entropy#Cross-entropy_loss_function_and_logistic_regression) With Logits in numerically stable way.
 ///
 /// Computes `target_probs * log(sigmoid(logits)) + (1 - target_probs) * log(1 - sigmoid(logits))`
@@ -0,0 +1,123 @@
+use crate::{
+    shapes::{Dtype, Shape},
+    tensor::{unique_id, Error, OpInfo, OwnedTape, PutTape, SplitTape, Tape, Tensor, Trace},
+};
+
+use super::{axpy::AxpyKernel, Device};
+
+/// Gradient checkpointing: runs `forward` on `x` without keeping any of the intermediate
+/// values it creates, and re-runs `recompute` on `x` during backprop to get them back.
+///
+/// This trades an extra forward pass for not storing activations of the function
+/// in the tape. `recompute` must compute the same thing as `forward`. The device's
+/// random number generator is restored to its state at the time of `forward` before
+/// running `recompute`, so things like dropout produce the same results.
+///
+/// `recompute` can only run once, so [crate::tensor_ops::Backward::backward_retain_graph]
+/// returns [Error::NotRetainable] on a tape containing this operation.
+///
+/// ```rust
+/// # use dfdx_core::prelude::*;
+/// # let dev: Cpu = Default::default();
+/// let x: Tensor<Rank1<3>, f32, _> = dev.tensor([-1.0, 0.0, 1.0]);
+/// let y = try_checkpoint(
+///     x.leaky_trace(),
+///     |x| x.try_exp()?.try_sin(),
+///     |x| x.try_exp()?.try_sin(),
+/// )
+/// .unwrap();
+/// let g = y.sum().backward();
+/// # let _ = g.get(&x);
+/// ```
+pub fn try_checkpoint<S: Shape, Dst: Shape, E: Dtype, D: Device<E>, F, R>(
+    x: Tensor<S, E, D, OwnedTape<E, D>>,
+    forward: F,
+    recompute: R,
+) -> Result<Tensor<Dst, E, D, OwnedTape<E, D>>, Error>
+where
+    F: FnOnce(
+        Tensor<S, E, D, OwnedTape<E, D>>,
+    ) -> Result<Tensor<Dst, E, D, OwnedTape<E, D>>, Error>,
+    R: 'static
+        + FnOnce(
+            Tensor<S, E, D, OwnedTape<E, D>>,
+        ) -> Result<Tensor<Dst, E, D, OwnedTape<E, D>>, Error>,
+{
+    let rng = x.device.rng_state();
+    let (x, mut tape) = x.split_tape();
+
+    // the inner tape, and all of the intermediate values it holds, are dropped right away
+    let (y, _) = forward(x.clone().put_tape(tape.empty_like()))?.split_tape();
+    if y.id == x.id {
+        return Ok(y.put_tape(tape));
+    }
+
+    let y_ghost = y.ghost();
+    tape.try_add_op_info(&y, || OpInfo::new("Checkpoint", &y).input(&x))?;
+    tape.add_backward_op(move |grads| {
+        grads.try_alloc_for(&y_ghost)?;
+        let first_temporary_id = unique_id();
+
+        let device = x.device.clone();
+        let prev_rng = device.replace_rng(rng);
+        let recomputed = recompute(x.leaky_traced());
+        device.replace_rng(prev_rng);
+        let (z, mut inner) = recomputed?.split_tape();
+
+        inner.gradients.try_alloc_for(&z)?;
+        let grad_z = inner.gradients.get_mut(&z);
+        AxpyKernel::forward(&device, grad_z, E::ONE, grads.get_ref(&y_ghost), E::ONE)?;
+        let inner_grads = inner.execute()?;
+
+        // only tensors that existed before recomputing (x & any parameters) get gradients
+        grads.accumulate_created_before(inner_grads, first_temporary_id, |a, b| {
+            AxpyKernel::forward(&device, a, E::ONE, b, E::ONE)
+        })
+    });
+    Ok(y.put_tape(tape))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{shapes::*, tensor::*, tensor_ops::*, tests::*};
+
+    #[test]
+    fn test_checkpoint_same_grads() {
+        let dev: TestDevice = Default::default();
+        let x: Tensor<Rank2<2, 3>, TestDtype, _> = dev.sample_normal();
+        let w: Tensor<Rank2<3, 4>, TestDtype, _> = dev.sample_normal();
+
+        let f =
+            |x: Tensor<Rank2<2, 3>, TestDtype, TestDevice, OwnedTape<TestDtype, TestDevice>>,
+             w: Tensor<Rank2<3, 4>, TestDtype, TestDevice>| {
+                x.try_matmul(w)?.try_tanh()?.try_square()
+            };
+        let expected = f(x.leaky_trace(), w.clone()).unwrap();
+
+        let w2 = w.clone();
+        let y = try_checkpoint(x.leaky_trace(), |x| f(x, w.clone()), move |x| f(x, w2)).unwrap();
+        assert_close_to_tensor!(y, expected);
+
+        let g_expected = expected.exp().mean().backward();
+        let g = y.exp().mean().backward();
+        assert_close_to_tensor!(g.get(&x), g_expected.get(&x));
+        assert_close_to_tensor!(g.get(&w), g_expected.get(&w));
+    }
+
+    #[test]
+    fn test_checkpoint_replays_rng() {
+        let dev: TestDevice = Default::default();
+        let x: Tensor<Rank1<10>, TestDtype, _> = dev.ones();
+        let y = try_checkpoint(
+            x.leaky_trace(),
+            |x| x.try_dropout(0.5),
+            |x| x.try_dropout(0.5),
+        )
+        .unwrap();
+        // the gradient is only non-zero where the recomputed mask matches the original one
+        let mask = y.array().map(|v| if v == 0.0 { 0.0 } else { 2.0 });
+        let g = y.sum().backward();
+        assert_close_to_literal!(g.get(&x), mask);
+    }
+}
@@ -8,7 +8,7 @@ mod webgpu_kernel;
 
 use crate::{
     shapes::{Dtype, HasShape, Shape},
-    tensor::{Error, Merge, PutTape, SplitTape, Storage, Tape, Tensor},
+    tensor::{Error, Merge, OpInfo, PutTape, SplitTape, Storage, Tape, Tensor},
 };
 
 pub trait ChooseKernel<E: Dtype>: Storage<E> + Storage<bool> {
@@ -81,7 +81,8 @@ impl<
         let rhs_ghost = rhs.ghost();
         let out_ghost = out.ghost();
         let mut tape = tape.merge(rhs_tape);
-        tape.add_backward_op(move |grads| {
+        tape.try_add_op_info(&out, || OpInfo::new("Choose", &out).input(&lhs).input(&rhs))?;
+        tape.add_retainable_backward_op(move |grads| {
             grads.try_alloc_for(&lhs_ghost)?;
             grads.try_alloc_for(&rhs_ghost)?;
             grads.try_alloc_for(&out_ghost)?;
@@ -7,6 +7,7 @@ mod cuda_kernel;
 mod webgpu_kernel;
 
 use super::ops::{try_unary_op, UnaryKernel};
+use super::utilities::higher_order::UnaryDerivative;
 use crate::{shapes::*, tensor::*};
 
 #[repr(C)]
@@ -16,6 +17,17 @@ pub struct ClampKernelOp<E> {
     pub max: E,
 }
 
+impl<E> UnaryDerivative<E> for ClampKernelOp<E> {
+    fn df<D: Storage<E>>(
+        &self,
+        _ops: &mut dyn HigherOrderOps<E, D>,
+        _x: HigherOrderVar,
+        _y: HigherOrderVar,
+    ) -> Result<Option<HigherOrderVar>, Error> {
+        Ok(None)
+    }
+}
+
 /// Clamp all elements between the provided min and max values.
 ///
 /// Example:
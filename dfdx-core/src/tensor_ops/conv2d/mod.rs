@@ -311,7 +311,8 @@ where
         let lhs_ghost = lhs.ghost();
         let rhs_ghost = rhs.ghost();
         let out_ghost = out.ghost();
-        tape.add_backward_op(move |grads| {
+        tape.try_add_op_info(&out, || OpInfo::new("Conv2D", &out).input(&lhs).input(&rhs))?;
+        tape.add_retainable_backward_op(move |grads| {
             grads.try_alloc_for(&rhs_ghost)?;
             grads.try_alloc_for(&lhs_ghost)?;
             grads.try_alloc_for(&out_ghost)?;
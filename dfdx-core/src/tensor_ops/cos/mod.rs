@@ -7,12 +7,25 @@ mod cuda_kernel;
 mod webgpu_kernel;
 
 use super::ops::{try_unary_op, UnaryKernel};
+use super::utilities::higher_order::UnaryDerivative;
 use crate::{shapes::*, tensor::*};
 
 #[repr(C)]
 #[derive(Debug, Default, Copy, Clone)]
 pub struct CosKernelOp;
 
+impl<E> UnaryDerivative<E> for CosKernelOp {
+    fn df<D: Storage<E>>(
+        &self,
+        ops: &mut dyn HigherOrderOps<E, D>,
+        x: HigherOrderVar,
+        _y: HigherOrderVar,
+    ) -> Result<Option<HigherOrderVar>, Error> {
+        let sin = ops.sin(x)?;
+        ops.scale(sin, -1.0).map(Some)
+    }
+}
+
 /// [Cosine function](https://en.wikipedia.org/wiki/Sine_and_cosine).
 ///
 /// It's derivative is `-sin(t)`
@@ -0,0 +1,210 @@
+use crate::{
+    shapes::{Axes, Dtype, HasAxes, Shape},
+    tensor::{Cpu, Error, Storage, Tensor, ZerosTensor},
+    tensor_ops::utilities::reduction_utils::index_for_reductions,
+};
+
+use num_traits::Float;
+
+use super::{
+    CumMaxKernelOp, CumMinKernelOp, CumProdKernelOp, CumSumKernelOp, LogCumSumExpKernelOp,
+};
+
+/// A scan over a single row `x` of the input, writing the row `y` of the output.
+trait CumOp<E> {
+    fn forward(x: &[E], y: &mut [E]);
+    /// Computes the gradient `gx` of a row from the gradient `gy` of its output.
+    fn backward(x: &[E], y: &[E], gy: &[E], gx: &mut [E]);
+}
+
+impl<E: Float> CumOp<E> for CumSumKernelOp {
+    fn forward(x: &[E], y: &mut [E]) {
+        let mut acc = E::zero();
+        for (y, &x) in y.iter_mut().zip(x) {
+            acc = acc + x;
+            *y = acc;
+        }
+    }
+    fn backward(_: &[E], _: &[E], gy: &[E], gx: &mut [E]) {
+        let mut acc = E::zero();
+        for (gx, &gy) in gx.iter_mut().zip(gy).rev() {
+            acc = acc + gy;
+            *gx = acc;
+        }
+    }
+}
+
+impl<E: Float> CumOp<E> for CumProdKernelOp {
+    fn forward(x: &[E], y: &mut [E]) {
+        let mut acc = E::one();
+        for (y, &x) in y.iter_mut().zip(x) {
+            acc = acc * x;
+            *y = acc;
+        }
+    }
+    fn backward(x: &[E], y: &[E], gy: &[E], gx: &mut [E]) {
+        // gx[j] = y[j-1] * s[j], where s[j] = gy[j] + x[j+1] * s[j+1]
+        let n = x.len();
+        let mut s = E::zero();
+        for j in (0..n).rev() {
+            s = if j + 1 < n {
+                gy[j] + x[j + 1] * s
+            } else {
+                gy[j]
+            };
+            gx[j] = if j > 0 { y[j - 1] * s } else { s };
+        }
+    }
+}
+
+/// Whether `x` replaces `best` as the running extremum, where NaN wins over all other
+/// values and ties keep the earlier index.
+trait CumExtremumOp<E> {
+    fn replaces(x: E, best: E) -> bool;
+}
+
+#[allow(clippy::eq_op)]
+impl<E: Float> CumExtremumOp<E> for CumMaxKernelOp {
+    fn replaces(x: E, best: E) -> bool {
+        best == best && (x != x || x > best)
+    }
+}
+
+#[allow(clippy::eq_op)]
+impl<E: Float> CumExtremumOp<E> for CumMinKernelOp {
+    fn replaces(x: E, best: E) -> bool {
+        best == best && (x != x || x < best)
+    }
+}
+
+macro_rules! cum_extremum {
+    ($Op:ty) => {
+        impl<E: Float> CumOp<E> for $Op {
+            fn forward(x: &[E], y: &mut [E]) {
+                let mut best = x[0];
+                for (y, &x) in y.iter_mut().zip(x) {
+                    if <$Op as CumExtremumOp<E>>::replaces(x, best) {
+                        best = x;
+                    }
+                    *y = best;
+                }
+            }
+            fn backward(x: &[E], _: &[E], gy: &[E], gx: &mut [E]) {
+                let mut arg = 0;
+                for (i, &gy) in gy.iter().enumerate() {
+                    if <$Op as CumExtremumOp<E>>::replaces(x[i], x[arg]) {
+                        arg = i;
+                    }
+                    gx[arg] = gx[arg] + gy;
+                }
+            }
+        }
+    };
+}
+
+cum_extremum!(CumMaxKernelOp);
+cum_extremum!(CumMinKernelOp);
+
+impl<E: Float> CumOp<E> for LogCumSumExpKernelOp {
+    fn forward(x: &[E], y: &mut [E]) {
+        let mut acc = E::neg_infinity();
+        for (y, &x) in y.iter_mut().zip(x) {
+            let m = if x > acc { x } else { acc };
+            if !m.is_infinite() {
+                acc = m + ((acc - m).exp() + (x - m).exp()).ln();
+            } else if x.is_nan() {
+                acc = x;
+            } else {
+                acc = m;
+            }
+            *y = acc;
+        }
+    }
+    fn backward(x: &[E], y: &[E], gy: &[E], gx: &mut [E]) {
+        // gx[j] = exp(x[j] - y[j]) * r[j], where r[j] = gy[j] + exp(y[j] - y[j+1]) * r[j+1].
+        // -inf inputs have no effect on the output, so they get no gradient.
+        let n = x.len();
+        let mut r = E::zero();
+        for j in (0..n).rev() {
+            r = if j + 1 < n {
+                gy[j] + (y[j] - y[j + 1]).exp() * r
+            } else {
+                gy[j]
+            };
+            gx[j] = if x[j] == E::neg_infinity() {
+                E::zero()
+            } else {
+                (x[j] - y[j]).exp() * r
+            };
+        }
+    }
+}
+
+impl<Op: CumOp<E>, E: Dtype> super::CumKernel<Op, E> for Cpu {
+    fn forward<S: Shape, Ax: Axes>(
+        &self,
+        inp: &Tensor<S, E, Self>,
+    ) -> Result<Tensor<S, E, Self>, Error>
+    where
+        S: HasAxes<Ax>,
+    {
+        let mut out = self.try_zeros_like(&inp.shape)?;
+        let size = <S as HasAxes<Ax>>::size(&inp.shape);
+        if size == 0 {
+            return Ok(out);
+        }
+        let buf = std::sync::Arc::make_mut(&mut out.data);
+        let mut inp_idx = index_for_reductions::<S, Ax>(inp.shape, inp.strides);
+        let mut out_idx = index_for_reductions::<S, Ax>(inp.shape, inp.shape.strides());
+        let mut x = std::vec![E::default(); size];
+        let mut y = std::vec![E::default(); size];
+        for _ in 0..inp.shape.num_elements() / size {
+            for x in x.iter_mut() {
+                *x = inp.data[inp_idx.next().unwrap()];
+            }
+            Op::forward(&x, &mut y);
+            for &y in y.iter() {
+                buf[out_idx.next().unwrap()] = y;
+            }
+        }
+        Ok(out)
+    }
+
+    fn backward<S: Shape, Ax: Axes>(
+        &self,
+        inp: &Tensor<S, E, Self>,
+        grad_inp: &mut <Self as Storage<E>>::Vec,
+        out: &Tensor<S, E, Self>,
+        grad_out: &<Self as Storage<E>>::Vec,
+    ) -> Result<(), Error>
+    where
+        S: HasAxes<Ax>,
+    {
+        let size = <S as HasAxes<Ax>>::size(&inp.shape);
+        if size == 0 {
+            return Ok(());
+        }
+        let mut inp_idx = index_for_reductions::<S, Ax>(inp.shape, inp.strides);
+        let mut out_idx = index_for_reductions::<S, Ax>(out.shape, out.strides);
+        let mut inp_is = std::vec![0; size];
+        let mut x = std::vec![E::default(); size];
+        let mut y = std::vec![E::default(); size];
+        let mut gy = std::vec![E::default(); size];
+        let mut gx = std::vec![E::default(); size];
+        for _ in 0..inp.shape.num_elements() / size {
+            for k in 0..size {
+                inp_is[k] = inp_idx.next().unwrap();
+                let out_i = out_idx.next().unwrap();
+                x[k] = inp.data[inp_is[k]];
+                y[k] = out.data[out_i];
+                gy[k] = grad_out[out_i];
+                gx[k] = E::default();
+            }
+            Op::backward(&x, &y, &gy, &mut gx);
+            for (&i, &g) in inp_is.iter().zip(gx.iter()) {
+                grad_inp[i] += g;
+            }
+        }
+        Ok(())
+    }
+}
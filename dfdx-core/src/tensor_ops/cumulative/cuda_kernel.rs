@@ -0,0 +1,147 @@
+use crate::{
+    dtypes::*,
+    shapes::*,
+    tensor::{launch_cfg, Cuda, Error, Storage, Tensor},
+    tensor_ops::reduction_utils::index_for_reductions,
+};
+use cudarc::driver::{CudaSlice, LaunchAsync};
+
+use super::{
+    CumKernel, CumMaxKernelOp, CumMinKernelOp, CumProdKernelOp, CumSumKernelOp,
+    LogCumSumExpKernelOp,
+};
+
+use std::vec::Vec;
+
+const PTX_SRC: &str = include_str!(concat!(env!("OUT_DIR"), "/cumulative.ptx"));
+
+trait CumOpCudaKernel<E: Unit> {
+    /// Unique name for the kernel
+    const MODULE_NAME: &'static str;
+
+    /// Name of function in the .cu file
+    const FWD_FN_NAME: &'static str;
+
+    /// Name of function in the .cu file
+    const BWD_FN_NAME: &'static str;
+}
+
+impl Cuda {
+    /// The dims of `shape` with `Ax` permuted last, and the input & output strides in
+    /// the same order.
+    fn cum_info<S: Shape, Ax: Axes>(
+        &self,
+        shape: S,
+        inp_strides: S::Concrete,
+        out_strides: S::Concrete,
+    ) -> Result<CudaSlice<usize>, Error>
+    where
+        S: HasAxes<Ax>,
+    {
+        let inp_idx = index_for_reductions::<S, Ax>(shape, inp_strides);
+        let out_idx = index_for_reductions::<S, Ax>(shape, out_strides);
+        let mut info = Vec::with_capacity(3 * S::NUM_DIMS);
+        info.extend(inp_idx.shape);
+        info.extend(inp_idx.strides);
+        info.extend(out_idx.strides);
+        Ok(self.dev.htod_copy(info)?)
+    }
+}
+
+impl<E: Dtype, Op: CumOpCudaKernel<E>> CumKernel<Op, E> for Cuda {
+    fn forward<S: Shape, Ax: Axes>(
+        &self,
+        inp: &Tensor<S, E, Self>,
+    ) -> Result<Tensor<S, E, Self>, Error>
+    where
+        S: HasAxes<Ax>,
+    {
+        if !self.dev.has_func(Op::MODULE_NAME, Op::FWD_FN_NAME) {
+            self.dev.load_ptx(
+                PTX_SRC.into(),
+                Op::MODULE_NAME,
+                &[Op::FWD_FN_NAME, Op::BWD_FN_NAME],
+            )?;
+        }
+
+        let numel = inp.shape.num_elements();
+        let strides = inp.shape.strides();
+        let mut out = unsafe { self.alloc_empty::<E>(numel) }?;
+        let size = <S as HasAxes<Ax>>::size(&inp.shape);
+        if size == 0 {
+            return Ok(self.build_tensor(inp.shape, strides, out));
+        }
+        let num_rows = numel / size;
+        let info = self.cum_info::<S, Ax>(inp.shape, inp.strides, strides)?;
+
+        let fwd_fn = self.dev.get_func(Op::MODULE_NAME, Op::FWD_FN_NAME).unwrap();
+        let cfg = launch_cfg::<128>(num_rows as u32);
+        let params = (
+            num_rows,          // const size_t num_rows,
+            S::NUM_DIMS,       // const size_t num_dims,
+            size,              // const size_t size,
+            &info,             // const size_t *info,
+            inp.data.as_ref(), // const T *inp,
+            &mut out,          // T *out
+        );
+        unsafe { fwd_fn.launch(cfg, params) }?;
+        Ok(self.build_tensor(inp.shape, strides, out))
+    }
+
+    fn backward<S: Shape, Ax: Axes>(
+        &self,
+        inp: &Tensor<S, E, Self>,
+        grad_inp: &mut <Self as Storage<E>>::Vec,
+        out: &Tensor<S, E, Self>,
+        grad_out: &<Self as Storage<E>>::Vec,
+    ) -> Result<(), Error>
+    where
+        S: HasAxes<Ax>,
+    {
+        let size = <S as HasAxes<Ax>>::size(&inp.shape);
+        if size == 0 {
+            return Ok(());
+        }
+        let num_rows = inp.shape.num_elements() / size;
+        let info = self.cum_info::<S, Ax>(inp.shape, inp.strides, out.strides)?;
+
+        let bwd_fn = self.dev.get_func(Op::MODULE_NAME, Op::BWD_FN_NAME).unwrap();
+        let cfg = launch_cfg::<128>(num_rows as u32);
+        let params = (
+            num_rows,          // const size_t num_rows,
+            S::NUM_DIMS,       // const size_t num_dims,
+            size,              // const size_t size,
+            &info,             // const size_t *info,
+            inp.data.as_ref(), // const T *inp,
+            grad_inp,          // T *grad_inp,
+            out.data.as_ref(), // const T *out,
+            grad_out,          // const T *grad_out
+        );
+        unsafe { bwd_fn.launch(cfg, params) }?;
+        Ok(())
+    }
+}
+
+macro_rules! cum_ops {
+    ($TypeName:ty, $Suffix:literal) => {
+        cum_ops!($TypeName, CumSumKernelOp, "cumsum", $Suffix);
+        cum_ops!($TypeName, CumProdKernelOp, "cumprod", $Suffix);
+        cum_ops!($TypeName, CumMaxKernelOp, "cummax", $Suffix);
+        cum_ops!($TypeName, CumMinKernelOp, "cummin", $Suffix);
+        cum_ops!($TypeName, LogCumSumExpKernelOp, "logcumsumexp", $Suffix);
+    };
+    ($TypeName:ty, $Op:ty, $Name:literal, $Suffix:literal) => {
+        impl CumOpCudaKernel<$TypeName> for $Op {
+            const MODULE_NAME: &'static str = concat!($Name, "_", $Suffix);
+            const FWD_FN_NAME: &'static str = concat!($Name, "_fwd_", $Suffix);
+            const BWD_FN_NAME: &'static str = concat!($Name, "_bwd_", $Suffix);
+        }
+    };
+}
+
+#[cfg(feature = "f16")]
+cum_ops!(AMP<f16>, "f16");
+#[cfg(feature = "f16")]
+cum_ops!(f16, "f16");
+cum_ops!(f32, "f32");
+cum_ops!(f64, "f64");
@@ -0,0 +1,224 @@
+#include "cuda_utils.cuh"
+
+// One thread per row along the scanned axis. dims & strides are permuted so that the
+// axis is last, so the k'th element of row r is at logical index r * size + k. `info`
+// holds the permuted dims, then the input strides, then the output strides.
+
+__device__ __forceinline__ bool isinfg(float a) { return isinf(a); }
+__device__ __forceinline__ bool isinfg(double a) { return isinf(a); }
+__device__ __forceinline__ bool isinfg(__half a) { return __hisinf(a) != 0; }
+
+struct CumSumOp {
+    template<typename T>
+    __device__ T combine(T acc, T x) const { return acc + x; }
+};
+
+struct CumProdOp {
+    template<typename T>
+    __device__ T combine(T acc, T x) const { return acc * x; }
+};
+
+// NaN replaces any other value, and ties keep the earlier value.
+struct CumMaxOp {
+    template<typename T>
+    __device__ bool replaces(T x, T best) const { return !isnang(best) && (isnang(x) || x > best); }
+    template<typename T>
+    __device__ T combine(T acc, T x) const { return replaces(x, acc) ? x : acc; }
+};
+
+struct CumMinOp {
+    template<typename T>
+    __device__ bool replaces(T x, T best) const { return !isnang(best) && (isnang(x) || x < best); }
+    template<typename T>
+    __device__ T combine(T acc, T x) const { return replaces(x, acc) ? x : acc; }
+};
+
+struct LogCumSumExpOp {
+    template<typename T>
+    __device__ T combine(T acc, T x) const {
+        if (isnang(x)) {
+            return x;
+        }
+        T m = x > acc ? x : acc;
+        if (isinfg(m)) {
+            return m;
+        }
+        return m + logg(expg(acc - m) + expg(x - m));
+    }
+};
+
+template<typename T, typename Op>
+__device__ void cum_fwd(
+    const size_t num_rows,
+    const size_t num_dims,
+    const size_t size,
+    const size_t *info,
+    const T *inp,
+    T *out,
+    Op op
+) {
+    const size_t *dims = info;
+    const size_t *inp_strides = info + num_dims;
+    const size_t *out_strides = info + 2 * num_dims;
+    for (unsigned int r = blockIdx.x * blockDim.x + threadIdx.x; r < num_rows; r += blockDim.x * gridDim.x) {
+        T acc;
+        for (size_t k = 0; k < size; k++) {
+            unsigned int i = r * size + k;
+            T x = inp[get_strided_index(i, num_dims, dims, inp_strides)];
+            acc = k == 0 ? x : op.combine(acc, x);
+            out[get_strided_index(i, num_dims, dims, out_strides)] = acc;
+        }
+    }
+}
+
+template<typename T>
+__device__ void cumsum_bwd(
+    const size_t num_rows,
+    const size_t num_dims,
+    const size_t size,
+    const size_t *info,
+    const T *inp,
+    T *grad_inp,
+    const T *out,
+    const T *grad_out
+) {
+    const size_t *dims = info;
+    const size_t *inp_strides = info + num_dims;
+    const size_t *out_strides = info + 2 * num_dims;
+    for (unsigned int r = blockIdx.x * blockDim.x + threadIdx.x; r < num_rows; r += blockDim.x * gridDim.x) {
+        T acc = 0.0;
+        for (size_t k = size; k-- > 0;) {
+            unsigned int i = r * size + k;
+            acc = acc + grad_out[get_strided_index(i, num_dims, dims, out_strides)];
+            atomicAdd(grad_inp + get_strided_index(i, num_dims, dims, inp_strides), acc);
+        }
+    }
+}
+
+// grad_inp[k] = out[k - 1] * s[k], where s[k] = grad_out[k] + inp[k + 1] * s[k + 1],
+// which doesn't divide by the output so it works with zeros.
+template<typename T>
+__device__ void cumprod_bwd(
+    const size_t num_rows,
+    const size_t num_dims,
+    const size_t size,
+    const size_t *info,
+    const T *inp,
+    T *grad_inp,
+    const T *out,
+    const T *grad_out
+) {
+    const size_t *dims = info;
+    const size_t *inp_strides = info + num_dims;
+    const size_t *out_strides = info + 2 * num_dims;
+    for (unsigned int r = blockIdx.x * blockDim.x + threadIdx.x; r < num_rows; r += blockDim.x * gridDim.x) {
+        T s = 0.0;
+        for (size_t k = size; k-- > 0;) {
+            unsigned int i = r * size + k;
+            T gy = grad_out[get_strided_index(i, num_dims, dims, out_strides)];
+            s = k + 1 < size ? gy + inp[get_strided_index(i + 1, num_dims, dims, inp_strides)] * s : gy;
+            T g = k > 0 ? out[get_strided_index(i - 1, num_dims, dims, out_strides)] * s : s;
+            atomicAdd(grad_inp + get_strided_index(i, num_dims, dims, inp_strides), g);
+        }
+    }
+}
+
+// The gradient of each output goes to the position of the running extremum.
+template<typename T, typename Op>
+__device__ void cum_extremum_bwd(
+    const size_t num_rows,
+    const size_t num_dims,
+    const size_t size,
+    const size_t *info,
+    const T *inp,
+    T *grad_inp,
+    const T *grad_out,
+    Op op
+) {
+    const size_t *dims = info;
+    const size_t *inp_strides = info + num_dims;
+    const size_t *out_strides = info + 2 * num_dims;
+    for (unsigned int r = blockIdx.x * blockDim.x + threadIdx.x; r < num_rows; r += blockDim.x * gridDim.x) {
+        T best;
+        size_t best_i;
+        for (size_t k = 0; k < size; k++) {
+            unsigned int i = r * size + k;
+            size_t inp_i = get_strided_index(i, num_dims, dims, inp_strides);
+            T x = inp[inp_i];
+            if (k == 0 || op.replaces(x, best)) {
+                best = x;
+                best_i = inp_i;
+            }
+            atomicAdd(grad_inp + best_i, grad_out[get_strided_index(i, num_dims, dims, out_strides)]);
+        }
+    }
+}
+
+// grad_inp[k] = exp(inp[k] - out[k]) * s[k], where s[k] = grad_out[k] + exp(out[k] - out[k + 1]) * s[k + 1].
+// -inf inputs have no effect on the output, so they get no gradient.
+template<typename T>
+__device__ void logcumsumexp_bwd(
+    const size_t num_rows,
+    const size_t num_dims,
+    const size_t size,
+    const size_t *info,
+    const T *inp,
+    T *grad_inp,
+    const T *out,
+    const T *grad_out
+) {
+    const size_t *dims = info;
+    const size_t *inp_strides = info + num_dims;
+    const size_t *out_strides = info + 2 * num_dims;
+    for (unsigned int r = blockIdx.x * blockDim.x + threadIdx.x; r < num_rows; r += blockDim.x * gridDim.x) {
+        T s = 0.0;
+        for (size_t k = size; k-- > 0;) {
+            unsigned int i = r * size + k;
+            size_t inp_i = get_strided_index(i, num_dims, dims, inp_strides);
+            size_t out_i = get_strided_index(i, num_dims, dims, out_strides);
+            T y = out[out_i];
+            T gy = grad_out[out_i];
+            s = k + 1 < size ? gy + expg(y - out[get_strided_index(i + 1, num_dims, dims, out_strides)]) * s : gy;
+            T x = inp[inp_i];
+            if (!(isinfg(x) && x < T(0.0))) {
+                atomicAdd(grad_inp + inp_i, expg(x - y) * s);
+            }
+        }
+    }
+}
+
+#define CUM_OP(TYPENAME, FWD, BWD, OP, BWD_BODY) \
+extern "C" __global__ void FWD( \
+    const size_t num_rows, \
+    const size_t num_dims, \
+    const size_t size, \
+    const size_t *info, \
+    const TYPENAME *inp, \
+    TYPENAME *out \
+) { cum_fwd(num_rows, num_dims, size, info, inp, out, OP()); } \
+extern "C" __global__ void BWD( \
+    const size_t num_rows, \
+    const size_t num_dims, \
+    const size_t size, \
+    const size_t *info, \
+    const TYPENAME *inp, \
+    TYPENAME *grad_inp, \
+    const TYPENAME *out, \
+    const TYPENAME *grad_out \
+) { BWD_BODY; }
+
+#define CUM_OPS(TYPENAME, SUFFIX) \
+CUM_OP(TYPENAME, cumsum_fwd_##SUFFIX, cumsum_bwd_##SUFFIX, CumSumOp, \
+    cumsum_bwd(num_rows, num_dims, size, info, inp, grad_inp, out, grad_out)) \
+CUM_OP(TYPENAME, cumprod_fwd_##SUFFIX, cumprod_bwd_##SUFFIX, CumProdOp, \
+    cumprod_bwd(num_rows, num_dims, size, info, inp, grad_inp, out, grad_out)) \
+CUM_OP(TYPENAME, cummax_fwd_##SUFFIX, cummax_bwd_##SUFFIX, CumMaxOp, \
+    cum_extremum_bwd(num_rows, num_dims, size, info, inp, grad_inp, grad_out, CumMaxOp())) \
+CUM_OP(TYPENAME, cummin_fwd_##SUFFIX, cummin_bwd_##SUFFIX, CumMinOp, \
+    cum_extremum_bwd(num_rows, num_dims, size, info, inp, grad_inp, grad_out, CumMinOp())) \
+CUM_OP(TYPENAME, logcumsumexp_fwd_##SUFFIX, logcumsumexp_bwd_##SUFFIX, LogCumSumExpOp, \
+    logcumsumexp_bwd(num_rows, num_dims, size, info, inp, grad_inp, out, grad_out))
+
+CUM_OPS(__half, f16)
+CUM_OPS(float, f32)
+CUM_OPS(double, f64)
@@ -0,0 +1,333 @@
+mod cpu_kernel;
+
+#[cfg(feature = "cuda")]
+mod cuda_kernel;
+
+#[cfg(feature = "webgpu")]
+mod webgpu_kernel;
+
+use crate::{shapes::*, tensor::*};
+
+pub enum CumSumKernelOp {}
+pub enum CumProdKernelOp {}
+pub enum CumMaxKernelOp {}
+pub enum CumMinKernelOp {}
+pub enum LogCumSumExpKernelOp {}
+
+pub trait CumKernel<Op, E: Dtype>: Storage<E> {
+    /// The contiguous scan of `inp` along the single axis `Ax`.
+    fn forward<S: Shape, Ax: Axes>(
+        &self,
+        inp: &Tensor<S, E, Self>,
+    ) -> Result<Tensor<S, E, Self>, Error>
+    where
+        S: HasAxes<Ax>;
+
+    fn backward<S: Shape, Ax: Axes>(
+        &self,
+        inp: &Tensor<S, E, Self>,
+        grad_inp: &mut <Self as Storage<E>>::Vec,
+        out: &Tensor<S, E, Self>,
+        grad_out: &<Self as Storage<E>>::Vec,
+    ) -> Result<(), Error>
+    where
+        S: HasAxes<Ax>;
+}
+
+/// Cumulative scans along a single axis, where element `i` of the output combines
+/// elements `0..=i` of the input along that axis.
+///
+/// ```rust
+/// # use dfdx_core::prelude::*;
+/// # let dev: Cpu = Default::default();
+/// let t: Tensor<Rank2<2, 3>, f32, _> = dev.tensor([[1.0, 2.0, 3.0], [4.0, -1.0, 5.0]]);
+///
+/// let r = t.clone().cumsum::<Axis<1>>();
+/// assert_eq!(r.array(), [[1.0, 3.0, 6.0], [4.0, 3.0, 8.0]]);
+///
+/// let r = t.clone().cumprod::<Axis<0>>();
+/// assert_eq!(r.array(), [[1.0, 2.0, 3.0], [4.0, -2.0, 15.0]]);
+///
+/// let r = t.cummax::<Axis<1>>();
+/// assert_eq!(r.array(), [[1.0, 2.0, 3.0], [4.0, 4.0, 5.0]]);
+/// ```
+pub trait CumulativeAlong: Sized + HasShape {
+    /// Cumulative sum along `Ax`. **Pytorch equivalent**: `t.cumsum(dim=Ax)`
+    fn cumsum<Ax: Axes<Array = [isize; 1]>>(self) -> Self
+    where
+        Self::Shape: HasAxes<Ax>,
+    {
+        self.try_cumsum::<Ax>().unwrap()
+    }
+    /// Fallible version of [CumulativeAlong::cumsum]
+    fn try_cumsum<Ax: Axes<Array = [isize; 1]>>(self) -> Result<Self, Error>
+    where
+        Self::Shape: HasAxes<Ax>;
+
+    /// Cumulative product along `Ax`. **Pytorch equivalent**: `t.cumprod(dim=Ax)`
+    ///
+    /// Gradients are computed without dividing by the output, so they are correct when
+    /// the input contains zeros.
+    fn cumprod<Ax: Axes<Array = [isize; 1]>>(self) -> Self
+    where
+        Self::Shape: HasAxes<Ax>,
+    {
+        self.try_cumprod::<Ax>().unwrap()
+    }
+    /// Fallible version of [CumulativeAlong::cumprod]
+    fn try_cumprod<Ax: Axes<Array = [isize; 1]>>(self) -> Result<Self, Error>
+    where
+        Self::Shape: HasAxes<Ax>;
+
+    /// Running maximum along `Ax`. **Pytorch equivalent**: `t.cummax(dim=Ax).values`
+    ///
+    /// NaN is greater than any other value, and the gradient of each output goes to the
+    /// first position that attained it.
+    fn cummax<Ax: Axes<Array = [isize; 1]>>(self) -> Self
+    where
+        Self::Shape: HasAxes<Ax>,
+    {
+        self.try_cummax::<Ax>().unwrap()
+    }
+    /// Fallible version of [CumulativeAlong::cummax]
+    fn try_cummax<Ax: Axes<Array = [isize; 1]>>(self) -> Result<Self, Error>
+    where
+        Self::Shape: HasAxes<Ax>;
+
+    /// Running minimum along `Ax`. **Pytorch equivalent**: `t.cummin(dim=Ax).values`
+    ///
+    /// NaN is smaller than any other value, and the gradient of each output goes to the
+    /// first position that attained it.
+    fn cummin<Ax: Axes<Array = [isize; 1]>>(self) -> Self
+    where
+        Self::Shape: HasAxes<Ax>,
+    {
+        self.try_cummin::<Ax>().unwrap()
+    }
+    /// Fallible version of [CumulativeAlong::cummin]
+    fn try_cummin<Ax: Axes<Array = [isize; 1]>>(self) -> Result<Self, Error>
+    where
+        Self::Shape: HasAxes<Ax>;
+
+    /// Numerically stable `ln(cumsum(exp(t)))` along `Ax`.
+    /// **Pytorch equivalent**: `t.logcumsumexp(dim=Ax)`
+    fn logcumsumexp<Ax: Axes<Array = [isize; 1]>>(self) -> Self
+    where
+        Self::Shape: HasAxes<Ax>,
+    {
+        self.try_logcumsumexp::<Ax>().unwrap()
+    }
+    /// Fallible version of [CumulativeAlong::logcumsumexp]
+    fn try_logcumsumexp<Ax: Axes<Array = [isize; 1]>>(self) -> Result<Self, Error>
+    where
+        Self::Shape: HasAxes<Ax>;
+}
+
+impl<S: Shape, E: Dtype, D, T: Tape<E, D>> CumulativeAlong for Tensor<S, E, D, T>
+where
+    D: CumKernel<CumSumKernelOp, E>
+        + CumKernel<CumProdKernelOp, E>
+        + CumKernel<CumMaxKernelOp, E>
+        + CumKernel<CumMinKernelOp, E>
+        + CumKernel<LogCumSumExpKernelOp, E>,
+{
+    fn try_cumsum<Ax: Axes<Array = [isize; 1]>>(self) -> Result<Self, Error>
+    where
+        S: HasAxes<Ax>,
+    {
+        try_cumulative::<CumSumKernelOp, Ax, _, _, _, _>(self, "CumSum")
+    }
+
+    fn try_cumprod<Ax: Axes<Array = [isize; 1]>>(self) -> Result<Self, Error>
+    where
+        S: HasAxes<Ax>,
+    {
+        try_cumulative::<CumProdKernelOp, Ax, _, _, _, _>(self, "CumProd")
+    }
+
+    fn try_cummax<Ax: Axes<Array = [isize; 1]>>(self) -> Result<Self, Error>
+    where
+        S: HasAxes<Ax>,
+    {
+        try_cumulative::<CumMaxKernelOp, Ax, _, _, _, _>(self, "CumMax")
+    }
+
+    fn try_cummin<Ax: Axes<Array = [isize; 1]>>(self) -> Result<Self, Error>
+    where
+        S: HasAxes<Ax>,
+    {
+        try_cumulative::<CumMinKernelOp, Ax, _, _, _, _>(self, "CumMin")
+    }
+
+    fn try_logcumsumexp<Ax: Axes<Array = [isize; 1]>>(self) -> Result<Self, Error>
+    where
+        S: HasAxes<Ax>,
+    {
+        try_cumulative::<LogCumSumExpKernelOp, Ax, _, _, _, _>(self, "LogCumSumExp")
+    }
+}
+
+fn try_cumulative<
+    Op,
+    Ax: Axes,
+    S: Shape + HasAxes<Ax>,
+    E: Dtype,
+    D: CumKernel<Op, E>,
+    T: Tape<E, D>,
+>(
+    t: Tensor<S, E, D, T>,
+    name: &'static str,
+) -> Result<Tensor<S, E, D, T>, Error> {
+    let (inp, mut tape) = t.split_tape();
+    let out = inp.device.forward::<S, Ax>(&inp)?;
+    let inp_ghost = inp.ghost();
+    let out_ghost = out.ghost();
+    let out_clone = out.clone();
+    tape.try_add_op_info(&out, || OpInfo::new(name, &out).input(&inp))?;
+    tape.add_retainable_backward_op(move |grads| {
+        grads.try_alloc_for(&inp_ghost)?;
+        grads.try_alloc_for(&out_ghost)?;
+        let (grad_inp, grad_out) = grads.mut_and_ref(&inp_ghost, &out_ghost);
+        inp.device
+            .backward::<S, Ax>(&inp, grad_inp, &out_clone, grad_out)
+    });
+    Ok(out.put_tape(tape))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{tensor_ops::*, tests::*};
+
+    #[test]
+    fn test_cumsum_1d() {
+        let dev: TestDevice = Default::default();
+        let t = dev.tensor([1.0, 2.0, 3.0, 4.0]).to_dtype::<TestDtype>();
+        let r = t.leaky_trace().cumsum::<Axis<0>>();
+        assert_close_to_literal!(r, [1.0, 3.0, 6.0, 10.0]);
+        let w = dev.tensor([1.0, 2.0, 3.0, 4.0]).to_dtype::<TestDtype>();
+        let g = (r * w).sum().backward();
+        assert_close_to_literal!(g.get(&t), [10.0, 9.0, 7.0, 4.0]);
+    }
+
+    #[test]
+    fn test_cumsum_2d_each_axis() {
+        let dev: TestDevice = Default::default();
+        let t = dev
+            .tensor([[1.0, 2.0, 3.0], [4.0, 5.0, 6.0]])
+            .to_dtype::<TestDtype>();
+        let r = t.leaky_trace().cumsum::<Axis<0>>();
+        assert_close_to_literal!(r, [[1.0, 2.0, 3.0], [5.0, 7.0, 9.0]]);
+        let g = r.sum().backward();
+        assert_close_to_literal!(g.get(&t), [[2.0; 3], [1.0; 3]]);
+
+        let r = t.leaky_trace().cumsum::<Axis<1>>();
+        assert_close_to_literal!(r, [[1.0, 3.0, 6.0], [4.0, 9.0, 15.0]]);
+        let g = r.sum().backward();
+        assert_close_to_literal!(g.get(&t), [[3.0, 2.0, 1.0]; 2]);
+    }
+
+    #[test]
+    fn test_cumsum_broadcasted_and_permuted() {
+        let dev: TestDevice = Default::default();
+        let t = dev.tensor([1.0, 2.0, 3.0]).to_dtype::<TestDtype>();
+        let r = t
+            .leaky_trace()
+            .broadcast::<Rank2<2, 3>, Axis<0>>()
+            .cumsum::<Axis<0>>();
+        assert_close_to_literal!(r, [[1.0, 2.0, 3.0], [2.0, 4.0, 6.0]]);
+        let g = r.sum().backward();
+        assert_close_to_literal!(g.get(&t), [3.0; 3]);
+
+        let r = t
+            .leaky_trace()
+            .broadcast::<Rank2<2, 3>, Axis<0>>()
+            .cumsum::<Axis<1>>();
+        assert_close_to_literal!(r, [[1.0, 3.0, 6.0]; 2]);
+        let g = r.sum().backward();
+        assert_close_to_literal!(g.get(&t), [6.0, 4.0, 2.0]);
+
+        let t = dev.tensor([[1.0, 2.0], [3.0, 4.0]]).to_dtype::<TestDtype>();
+        let r = t.permute::<_, Axes2<1, 0>>().cumsum::<Axis<1>>();
+        assert_close_to_literal!(r, [[1.0, 4.0], [2.0, 6.0]]);
+    }
+
+    #[test]
+    fn test_cumprod_with_zeros() {
+        let dev: TestDevice = Default::default();
+        let t = dev.tensor([1.0, 2.0, 3.0]).to_dtype::<TestDtype>();
+        let r = t.leaky_trace().cumprod::<Axis<0>>();
+        assert_close_to_literal!(r, [1.0, 2.0, 6.0]);
+        let g = r.sum().backward();
+        assert_close_to_literal!(g.get(&t), [9.0, 4.0, 2.0]);
+
+        let t = dev.tensor([2.0, 0.0, 3.0, 4.0]).to_dtype::<TestDtype>();
+        let r = t.leaky_trace().cumprod::<Axis<0>>();
+        assert_close_to_literal!(r, [2.0, 0.0, 0.0, 0.0]);
+        let g = r.sum().backward();
+        assert_close_to_literal!(g.get(&t), [1.0, 32.0, 0.0, 0.0]);
+    }
+
+    #[test]
+    fn test_cummax_cummin_ties_and_nans() {
+        let dev: TestDevice = Default::default();
+        let t = dev
+            .tensor([1.0, 3.0, 2.0, 3.0, 5.0])
+            .to_dtype::<TestDtype>();
+        let r = t.leaky_trace().cummax::<Axis<0>>();
+        assert_close_to_literal!(r, [1.0, 3.0, 3.0, 3.0, 5.0]);
+        let g = r.sum().backward();
+        assert_close_to_literal!(g.get(&t), [1.0, 3.0, 0.0, 0.0, 1.0]);
+
+        let r = t.leaky_trace().cummin::<Axis<0>>();
+        assert_close_to_literal!(r, [1.0; 5]);
+        let g = r.sum().backward();
+        assert_close_to_literal!(g.get(&t), [5.0, 0.0, 0.0, 0.0, 0.0]);
+
+        let t = dev.tensor([1.0, f64::NAN, 2.0]).to_dtype::<TestDtype>();
+        let r = t.clone().cummax::<Axis<0>>().array();
+        assert_close_to_literal!(dev.tensor([r[0]]), [1.0]);
+        assert!(r[1].is_nan() && r[2].is_nan());
+        let r = t.cummin::<Axis<0>>().array();
+        assert!(r[1].is_nan() && r[2].is_nan());
+    }
+
+    #[test]
+    fn test_logcumsumexp() {
+        let dev: TestDevice = Default::default();
+        let t = dev
+            .tensor([[0.0, 1.0, -1.0, 2.0], [-1.0, 2.0, 0.0, 1.0]])
+            .to_dtype::<TestDtype>();
+        let r = t.leaky_trace().logcumsumexp::<Axis<1>>();
+        assert_close_to_literal!(
+            r,
+            [
+                [0.0, 1.3132617, 1.407606, 2.4401897],
+                [-1.0, 2.0485874, 2.169846, 2.4401897],
+            ]
+        );
+        let g = r.sum().backward();
+        assert_close_to_literal!(
+            g.get(&t),
+            [
+                [1.6008142, 1.6331824, 0.1220892, 0.6439143],
+                [1.1214945, 2.4402831, 0.2013395, 0.2368828],
+            ]
+        );
+
+        // leading -inf values stay -inf, and don't get NaN gradients
+        let t = dev
+            .tensor([f64::NEG_INFINITY, f64::NEG_INFINITY, 0.0, 0.0])
+            .to_dtype::<TestDtype>();
+        let r = t.leaky_trace().logcumsumexp::<Axis<0>>();
+        let r_array = r.array();
+        assert!(r_array[0].is_infinite() && r_array[0].is_sign_negative());
+        assert!(r_array[1].is_infinite() && r_array[1].is_sign_negative());
+        assert_close_to_literal!(
+            dev.tensor([r_array[2], r_array[3]]),
+            [0.0, std::f64::consts::LN_2]
+        );
+        let g = r.sum().backward();
+        assert_close_to_literal!(g.get(&t), [0.0, 0.0, 1.5, 0.5]);
+    }
+}
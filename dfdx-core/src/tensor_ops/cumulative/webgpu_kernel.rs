@@ -0,0 +1,26 @@
+use crate::prelude::{Dtype, Webgpu};
+
+impl<Op, E: Dtype> super::CumKernel<Op, E> for Webgpu {
+    fn forward<S: crate::prelude::Shape, Ax: crate::prelude::Axes>(
+        &self,
+        inp: &crate::prelude::Tensor<S, E, Self>,
+    ) -> Result<crate::prelude::Tensor<S, E, Self>, crate::prelude::Error>
+    where
+        S: crate::prelude::HasAxes<Ax>,
+    {
+        todo!()
+    }
+
+    fn backward<S: crate::prelude::Shape, Ax: crate::prelude::Axes>(
+        &self,
+        inp: &crate::prelude::Tensor<S, E, Self>,
+        grad_inp: &mut <Self as crate::prelude::Storage<E>>::Vec,
+        out: &crate::prelude::Tensor<S, E, Self>,
+        grad_out: &<Self as crate::prelude::Storage<E>>::Vec,
+    ) -> Result<(), crate::prelude::Error>
+    where
+        S: crate::prelude::HasAxes<Ax>,
+    {
+        todo!()
+    }
+}
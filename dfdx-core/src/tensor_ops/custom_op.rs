@@ -0,0 +1,296 @@
+use crate::{
+    shapes::{Dtype, HasShape, Shape},
+    tensor::{unique_id, Error, Merge, OpInfo, PutTape, SplitTape, Storage, Tape, Tensor},
+};
+
+use super::{axpy::AxpyKernel, Device, ReshapeTo};
+
+/// Applies a user defined differentiable operation to `x`.
+///
+/// - `forward` computes the output of the operation from the input.
+/// - `backward` computes the gradient of the input, given the input, the output, and
+///   the gradient of the output. The result is added to the gradient of `x`.
+///
+/// Splitting & merging tapes and allocating gradients is handled by this function,
+/// and the tensors passed to `backward` are always contiguous. The input & output are
+/// kept alive until the backward pass is run if `x` is traced.
+///
+/// `backward` can only run once, so [crate::tensor_ops::Backward::backward_retain_graph]
+/// returns [Error::NotRetainable] on a tape containing this operation.
+///
+/// **Pytorch equivalent** `torch.autograd.Function`.
+///
+/// Example, a custom fused activation `x * tanh(x)`:
+/// ```rust
+/// # use dfdx_core::prelude::*;
+/// # let dev: Cpu = Default::default();
+/// let x: Tensor<Rank1<3>, f32, _> = dev.tensor([-1.0, 0.0, 1.0]);
+/// let y = custom_op(
+///     x.leaky_trace(),
+///     |x| Ok(x.clone() * x.clone().tanh()),
+///     |x, _y, grad_y| {
+///         // d/dx x * tanh(x) = tanh(x) + x * (1 - tanh(x)^2)
+///         let t = x.clone().tanh();
+///         let dx = t.clone() + x.clone() * (t.square().negate() + 1.0);
+///         Ok(dx * grad_y)
+///     },
+/// );
+/// let g = y.sum().backward();
+/// ```
+pub fn custom_op<S: Shape, Dst: Shape, E: Dtype, D: Device<E>, T: Tape<E, D>, F, B>(
+    x: Tensor<S, E, D, T>,
+    forward: F,
+    backward: B,
+) -> Tensor<Dst, E, D, T>
+where
+    F: FnOnce(&Tensor<S, E, D>) -> Result<Tensor<Dst, E, D>, Error>,
+    B: 'static
+        + FnOnce(
+            &Tensor<S, E, D>,
+            &Tensor<Dst, E, D>,
+            Tensor<Dst, E, D>,
+        ) -> Result<Tensor<S, E, D>, Error>,
+{
+    try_custom_op(x, forward, backward).unwrap()
+}
+
+/// Fallible version of [custom_op()].
+pub fn try_custom_op<S: Shape, Dst: Shape, E: Dtype, D: Device<E>, T: Tape<E, D>, F, B>(
+    x: Tensor<S, E, D, T>,
+    forward: F,
+    backward: B,
+) -> Result<Tensor<Dst, E, D, T>, Error>
+where
+    F: FnOnce(&Tensor<S, E, D>) -> Result<Tensor<Dst, E, D>, Error>,
+    B: 'static
+        + FnOnce(
+            &Tensor<S, E, D>,
+            &Tensor<Dst, E, D>,
+            Tensor<Dst, E, D>,
+        ) -> Result<Tensor<S, E, D>, Error>,
+{
+    let (x, mut tape) = x.try_contiguous()?.split_tape();
+    let out = new_output(forward(&x)?)?;
+    if T::OWNS_TAPE {
+        let (x_ghost, out_ghost) = (x.ghost(), out.ghost());
+        let out_clone = out.clone();
+        tape.try_add_op_info(&out, || OpInfo::new("CustomOp", &out).input(&x))?;
+        tape.add_backward_op(move |grads| {
+            grads.try_alloc_for(&out_ghost)?;
+            let grad_out = grads.get(&out_ghost);
+            let grad_x = backward(&x, &out_clone, grad_out)?;
+            grads.try_alloc_for(&x_ghost)?;
+            accumulate(&x, grads.get_mut(&x_ghost), grad_x)
+        });
+    }
+    Ok(out.put_tape(tape))
+}
+
+/// Applies a user defined differentiable operation to `lhs` and `rhs`.
+///
+/// Same as [custom_op()], except `backward` returns the gradients of both inputs.
+///
+/// ```rust
+/// # use dfdx_core::prelude::*;
+/// # let dev: Cpu = Default::default();
+/// let a: Tensor<Rank1<3>, f32, _> = dev.tensor([1.0, 2.0, 3.0]);
+/// let b: Tensor<Rank1<3>, f32, _> = dev.tensor([4.0, 5.0, 6.0]);
+/// let y = custom_binary_op(
+///     a.leaky_trace(),
+///     b.clone(),
+///     |a, b| Ok(a.clone() * b.clone()),
+///     |a, b, _y, grad_y| Ok((b.clone() * grad_y.clone(), a.clone() * grad_y)),
+/// );
+/// let g = y.sum().backward();
+/// assert_eq!(g.get(&a).array(), [4.0, 5.0, 6.0]);
+/// ```
+pub fn custom_binary_op<L, R, Dst, E, D, LTape, RTape, F, B>(
+    lhs: Tensor<L, E, D, LTape>,
+    rhs: Tensor<R, E, D, RTape>,
+    forward: F,
+    backward: B,
+) -> Tensor<Dst, E, D, LTape>
+where
+    L: Shape,
+    R: Shape,
+    Dst: Shape,
+    E: Dtype,
+    D: Device<E>,
+    LTape: Tape<E, D> + Merge<RTape>,
+    RTape: Tape<E, D>,
+    F: FnOnce(&Tensor<L, E, D>, &Tensor<R, E, D>) -> Result<Tensor<Dst, E, D>, Error>,
+    B: 'static
+        + FnOnce(
+            &Tensor<L, E, D>,
+            &Tensor<R, E, D>,
+            &Tensor<Dst, E, D>,
+            Tensor<Dst, E, D>,
+        ) -> Result<(Tensor<L, E, D>, Tensor<R, E, D>), Error>,
+{
+    try_custom_binary_op(lhs, rhs, forward, backward).unwrap()
+}
+
+/// Fallible version of [custom_binary_op()].
+pub fn try_custom_binary_op<L, R, Dst, E, D, LTape, RTape, F, B>(
+    lhs: Tensor<L, E, D, LTape>,
+    rhs: Tensor<R, E, D, RTape>,
+    forward: F,
+    backward: B,
+) -> Result<Tensor<Dst, E, D, LTape>, Error>
+where
+    L: Shape,
+    R: Shape,
+    Dst: Shape,
+    E: Dtype,
+    D: Device<E>,
+    LTape: Tape<E, D> + Merge<RTape>,
+    RTape: Tape<E, D>,
+    F: FnOnce(&Tensor<L, E, D>, &Tensor<R, E, D>) -> Result<Tensor<Dst, E, D>, Error>,
+    B: 'static
+        + FnOnce(
+            &Tensor<L, E, D>,
+            &Tensor<R, E, D>,
+            &Tensor<Dst, E, D>,
+            Tensor<Dst, E, D>,
+        ) -> Result<(Tensor<L, E, D>, Tensor<R, E, D>), Error>,
+{
+    let (lhs, ltape) = lhs.try_contiguous()?.split_tape();
+    let (rhs, rtape) = rhs.try_contiguous()?.split_tape();
+    let mut tape = ltape.merge(rtape);
+    let out = new_output(forward(&lhs, &rhs)?)?;
+    if LTape::OWNS_TAPE || RTape::OWNS_TAPE {
+        let (lhs_ghost, rhs_ghost, out_ghost) = (lhs.ghost(), rhs.ghost(), out.ghost());
+        let out_clone = out.clone();
+        tape.try_add_op_info(&out, || {
+            OpInfo::new("CustomBinaryOp", &out).input(&lhs).input(&rhs)
+        })?;
+        tape.add_backward_op(move |grads| {
+            grads.try_alloc_for(&out_ghost)?;
+            let grad_out = grads.get(&out_ghost);
+            let (grad_lhs, grad_rhs) = backward(&lhs, &rhs, &out_clone, grad_out)?;
+            grads.try_alloc_for(&lhs_ghost)?;
+            accumulate(&lhs, grads.get_mut(&lhs_ghost), grad_lhs)?;
+            grads.try_alloc_for(&rhs_ghost)?;
+            accumulate(&rhs, grads.get_mut(&rhs_ghost), grad_rhs)
+        });
+    }
+    Ok(out.put_tape(tape))
+}
+
+/// Makes the output contiguous, and gives it a new id if `forward` returned
+/// one of its inputs as is.
+fn new_output<S: Shape, E: Dtype, D: Device<E>>(
+    out: Tensor<S, E, D>,
+) -> Result<Tensor<S, E, D>, Error> {
+    let mut out = out.try_contiguous()?;
+    out.id = unique_id();
+    Ok(out)
+}
+
+/// Adds `grad` (in logical order) to the gradient of the contiguous tensor `t`.
+///
+/// Returns [Error::ShapeMismatch] if `backward` returned a gradient with another shape.
+fn accumulate<S: Shape, E: Dtype, D: Device<E>>(
+    t: &Tensor<S, E, D>,
+    grad_t: &mut <D as Storage<E>>::Vec,
+    grad: Tensor<S, E, D>,
+) -> Result<(), Error> {
+    if t.shape() != grad.shape() {
+        return Err(Error::ShapeMismatch {
+            expected: t.shape().concrete().into(),
+            found: grad.shape().concrete().into(),
+        });
+    }
+    let grad = grad.try_contiguous()?;
+    AxpyKernel::forward(&t.device, grad_t, E::ONE, grad.data.as_ref(), E::ONE)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{shapes::*, tensor::*, tensor_ops::*, tests::*};
+
+    #[test]
+    fn test_custom_op_matches_builtin() {
+        let dev: TestDevice = Default::default();
+        let x: Tensor<Rank2<2, 3>, TestDtype, _> = dev.sample_normal();
+
+        let r = x.leaky_trace().sin();
+        let y = custom_op(
+            x.leaky_trace(),
+            |x| x.clone().try_sin(),
+            |x, _y, grad_y| x.clone().try_cos()?.try_mul(grad_y),
+        );
+        assert_close_to_tensor!(y, r);
+
+        let g_r = r.exp().mean().backward();
+        let g = y.exp().mean().backward();
+        assert_close_to_tensor!(g.get(&x), g_r.get(&x));
+    }
+
+    #[test]
+    fn test_custom_op_changes_shape_and_broadcast_input() {
+        let dev: TestDevice = Default::default();
+        let x: Tensor<Rank1<3>, TestDtype, _> = dev.tensor([1.0, 2.0, 3.0]);
+        let b = x.leaky_trace().broadcast::<Rank2<2, 3>, Axis<0>>();
+        let y = custom_op(
+            b,
+            |b| b.clone().try_sum::<Rank1<3>, Axis<0>>(),
+            |b, _y, grad_y| grad_y.try_broadcast_like::<_, Axis<0>>(b),
+        );
+        assert_close_to_literal!(y, [2.0, 4.0, 6.0]);
+        let g = (y * dev.tensor([1.0, 2.0, 3.0])).sum().backward();
+        assert_close_to_literal!(g.get(&x), [2.0, 4.0, 6.0]);
+    }
+
+    #[test]
+    fn test_custom_binary_op() {
+        let dev: TestDevice = Default::default();
+        let a: Tensor<Rank1<3>, TestDtype, _> = dev.tensor([1.0, 2.0, 3.0]);
+        let b: Tensor<Rank1<3>, TestDtype, _> = dev.tensor([-1.0, 0.5, 2.0]);
+
+        // a * b + b
+        let y = custom_binary_op(
+            a.leaky_trace(),
+            b.leaky_trace(),
+            |a, b| a.clone().try_mul(b.clone())?.try_add(b.clone()),
+            |a, b, _y, grad_y| {
+                let grad_a = b.clone().try_mul(grad_y.clone())?;
+                let grad_b = a.clone().try_add(1.0)?.try_mul(grad_y)?;
+                Ok((grad_a, grad_b))
+            },
+        );
+        assert_close_to_literal!(y, [-2.0, 1.5, 8.0]);
+        let g = y.square().sum().backward();
+        assert_close_to_literal!(g.get(&a), [4.0, 1.5, 32.0]);
+        assert_close_to_literal!(g.get(&b), [-8.0, 9.0, 64.0]);
+    }
+
+    #[test]
+    fn test_custom_op_returning_input() {
+        let dev: TestDevice = Default::default();
+        let x: Tensor<Rank1<3>, TestDtype, _> = dev.tensor([1.0, 2.0, 3.0]);
+        let y = custom_op(
+            x.leaky_trace(),
+            |x| Ok(x.clone()),
+            |_x, _y, grad_y| grad_y.try_mul(2.0),
+        );
+        let g = y.sum().backward();
+        assert_close_to_literal!(g.get(&x), [2.0; 3]);
+    }
+
+    #[test]
+    fn test_custom_op_wrong_grad_shape() {
+        let dev: TestDevice = Default::default();
+        let x: Tensor<(usize,), TestDtype, _> = dev.tensor_from_vec(std::vec![1.0, 2.0], (2,));
+        let y = custom_op(
+            x.leaky_trace(),
+            |x| Ok(x.clone()),
+            move |_x, _y, _grad_y| dev.try_zeros_like(&(3,)),
+        );
+        assert!(matches!(
+            y.sum().try_backward(),
+            Err(Error::ShapeMismatch { .. })
+        ));
+    }
+}
@@ -7,6 +7,7 @@ mod cuda_kernel;
 mod webgpu_kernel;
 
 use super::ops::*;
+use super::utilities::higher_order::{BinaryDerivative, UnaryDerivative};
 use crate::{shapes::*, tensor::*};
 
 #[repr(C)]
@@ -15,10 +16,36 @@ pub struct ScalarDivKernelOp<E> {
     pub(crate) scalar: E,
 }
 
+impl<E> UnaryDerivative<E> for ScalarDivKernelOp<E> {
+    fn df<D: Storage<E>>(
+        &self,
+        _ops: &mut dyn HigherOrderOps<E, D>,
+        _x: HigherOrderVar,
+        _y: HigherOrderVar,
+    ) -> Result<Option<HigherOrderVar>, Error> {
+        Ok(None)
+    }
+}
+
 #[repr(C)]
 #[derive(Debug, Default, Clone, Copy)]
 pub struct BinaryDivKernelOp;
 
+impl<E> BinaryDerivative<E> for BinaryDivKernelOp {
+    fn df<D: Storage<E>>(
+        &self,
+        ops: &mut dyn HigherOrderOps<E, D>,
+        x: HigherOrderVar,
+        y: HigherOrderVar,
+    ) -> Result<Option<(HigherOrderVar, HigherOrderVar)>, Error> {
+        let dfdx = ops.recip(y)?;
+        let dfdy = ops.square(dfdx)?;
+        let dfdy = ops.mul(x, dfdy)?;
+        let dfdy = ops.scale(dfdy, -1.0)?;
+        Ok(Some((dfdx, dfdy)))
+    }
+}
+
 /// Element wise and scalar division.
 ///
 /// Example:
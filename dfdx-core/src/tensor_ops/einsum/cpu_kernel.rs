@@ -0,0 +1,43 @@
+use crate::{
+    shapes::{Dtype, Shape},
+    tensor::{Cpu, Error, Tensor, ZerosTensor},
+};
+
+use super::Contraction;
+
+impl<E: Dtype> super::EinsumKernel<E> for Cpu {
+    fn forward<Dst: Shape>(
+        &self,
+        dst: Dst,
+        contraction: &Contraction,
+        lhs: &Self::Vec,
+        rhs: Option<&Self::Vec>,
+    ) -> Result<Tensor<Dst, E, Self>, Error> {
+        let mut out = self.try_zeros_like(&dst)?;
+        let buf = std::sync::Arc::make_mut(&mut out.data);
+        for [l, r, o] in contraction.offsets() {
+            buf[o] += lhs[l] * rhs.map_or(E::ONE, |rhs| rhs[r]);
+        }
+        Ok(out)
+    }
+
+    fn backward(
+        &self,
+        contraction: &Contraction,
+        lhs: &Self::Vec,
+        grad_lhs: &mut Self::Vec,
+        mut rhs: Option<(&Self::Vec, &mut Self::Vec)>,
+        grad_out: &Self::Vec,
+    ) -> Result<(), Error> {
+        for [l, r, o] in contraction.offsets() {
+            match rhs.as_mut() {
+                Some((rhs, grad_rhs)) => {
+                    grad_lhs[l] += grad_out[o] * rhs[r];
+                    grad_rhs[r] += grad_out[o] * lhs[l];
+                }
+                None => grad_lhs[l] += grad_out[o],
+            }
+        }
+        Ok(())
+    }
+}
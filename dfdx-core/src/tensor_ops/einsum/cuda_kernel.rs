@@ -0,0 +1,118 @@
+use crate::{
+    dtypes::*,
+    shapes::Shape,
+    tensor::{launch_cfg, Cuda, Error, Tensor},
+};
+
+use cudarc::driver::{CudaSlice, LaunchAsync};
+
+use super::Contraction;
+
+use std::vec::Vec;
+
+const PTX_SRC: &str = include_str!(concat!(env!("OUT_DIR"), "/einsum.ptx"));
+
+trait HasCudaKernel<E> {
+    const FNS: &'static [&'static str];
+}
+#[cfg(feature = "f16")]
+impl HasCudaKernel<f16> for Cuda {
+    const FNS: &'static [&'static str] = &["einsum_fwd_f16", "einsum_bwd_f16"];
+}
+#[cfg(feature = "f16")]
+impl HasCudaKernel<AMP<f16>> for Cuda {
+    const FNS: &'static [&'static str] = &["einsum_fwd_f16", "einsum_bwd_f16"];
+}
+impl HasCudaKernel<f32> for Cuda {
+    const FNS: &'static [&'static str] = &["einsum_fwd_f32", "einsum_bwd_f32"];
+}
+impl HasCudaKernel<f64> for Cuda {
+    const FNS: &'static [&'static str] = &["einsum_fwd_f64", "einsum_bwd_f64"];
+}
+
+impl Cuda {
+    /// The size of each label, and the strides of the lhs, rhs & output for each label.
+    fn einsum_info(&self, contraction: &Contraction) -> Result<CudaSlice<usize>, Error> {
+        let mut info = Vec::with_capacity(4 * contraction.sizes.len());
+        info.extend(&contraction.sizes);
+        info.extend(&contraction.lhs_strides);
+        info.extend(&contraction.rhs_strides);
+        info.extend(&contraction.out_strides);
+        Ok(self.dev.htod_copy(info)?)
+    }
+}
+
+impl<E: Dtype> super::EinsumKernel<E> for Cuda
+where
+    Self: HasCudaKernel<E>,
+{
+    fn forward<Dst: Shape>(
+        &self,
+        dst: Dst,
+        contraction: &Contraction,
+        lhs: &Self::Vec,
+        rhs: Option<&Self::Vec>,
+    ) -> Result<Tensor<Dst, E, Self>, Error> {
+        if !self.dev.has_func(Self::FNS[0], Self::FNS[0]) {
+            self.dev.load_ptx(PTX_SRC.into(), Self::FNS[0], Self::FNS)?;
+        }
+
+        let numel = dst.num_elements();
+        let strides = dst.strides();
+        let mut out = unsafe { self.alloc_empty::<E>(numel) }?;
+        let info = self.einsum_info(contraction)?;
+        let contracted: usize = contraction.sizes[contraction.num_out..].iter().product();
+
+        let fwd_fn = self.dev.get_func(Self::FNS[0], Self::FNS[0]).unwrap();
+        let cfg = launch_cfg::<128>(numel as u32);
+        let params = (
+            numel,
+            contracted,
+            contraction.sizes.len(),
+            &info,
+            rhs.is_some(),
+            lhs,
+            rhs.unwrap_or(lhs),
+            &mut out,
+        );
+        unsafe { fwd_fn.launch(cfg, params) }?;
+        Ok(self.build_tensor(dst, strides, out))
+    }
+
+    fn backward(
+        &self,
+        contraction: &Contraction,
+        lhs: &Self::Vec,
+        grad_lhs: &mut Self::Vec,
+        rhs: Option<(&Self::Vec, &mut Self::Vec)>,
+        grad_out: &Self::Vec,
+    ) -> Result<(), Error> {
+        let numel: usize = contraction.sizes.iter().product();
+        let num_labels = contraction.sizes.len();
+        let info = self.einsum_info(contraction)?;
+        let bwd_fn = self.dev.get_func(Self::FNS[0], Self::FNS[1]).unwrap();
+        let cfg = launch_cfg::<128>(numel as u32);
+
+        let other = rhs.as_ref().map_or(lhs, |(rhs, _)| *rhs);
+        let params = (
+            numel,
+            num_labels,
+            &info,
+            rhs.is_some(),
+            true,
+            other,
+            grad_lhs,
+            grad_out,
+        );
+        unsafe { bwd_fn.launch(cfg, params) }?;
+
+        if let Some((_, grad_rhs)) = rhs {
+            let bwd_fn = self.dev.get_func(Self::FNS[0], Self::FNS[1]).unwrap();
+            let params = (
+                numel, num_labels, &info, true, false, lhs, grad_rhs, grad_out,
+            );
+            unsafe { bwd_fn.launch(cfg, params) }?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,87 @@
+#include "cuda_utils.cuh"
+
+// `info` holds the size of each label, then the strides of the lhs, rhs & output for
+// each label. The output labels come first, so the contracted labels of out[i] are the
+// indices [i * contracted, (i + 1) * contracted) of the full label space. Without a rhs,
+// `rhs` is ignored and its values are treated as 1.
+template<typename T>
+__device__ void einsum_fwd(
+    const size_t numel,
+    const size_t contracted,
+    const size_t num_labels,
+    const size_t *info,
+    const bool binary,
+    const T *lhs,
+    const T *rhs,
+    T *out
+) {
+    const size_t *sizes = info;
+    const size_t *lhs_strides = info + num_labels;
+    const size_t *rhs_strides = info + 2 * num_labels;
+    for (unsigned int i = blockIdx.x * blockDim.x + threadIdx.x; i < numel; i += blockDim.x * gridDim.x) {
+        T acc = 0.0;
+        for (size_t j = 0; j < contracted; j++) {
+            unsigned int idx = i * contracted + j;
+            T x = lhs[get_strided_index(idx, num_labels, sizes, lhs_strides)];
+            if (binary) {
+                x = x * rhs[get_strided_index(idx, num_labels, sizes, rhs_strides)];
+            }
+            acc = acc + x;
+        }
+        out[i] = acc;
+    }
+}
+
+// One thread per element of the full label space, adding the gradient of the lhs if
+// `to_lhs` and of the rhs otherwise, where `other` is the other operand.
+template<typename T>
+__device__ void einsum_bwd(
+    const size_t numel,
+    const size_t num_labels,
+    const size_t *info,
+    const bool binary,
+    const bool to_lhs,
+    const T *other,
+    T *grad,
+    const T *grad_out
+) {
+    const size_t *sizes = info;
+    const size_t *lhs_strides = info + num_labels;
+    const size_t *rhs_strides = info + 2 * num_labels;
+    const size_t *out_strides = info + 3 * num_labels;
+    const size_t *grad_strides = to_lhs ? lhs_strides : rhs_strides;
+    const size_t *other_strides = to_lhs ? rhs_strides : lhs_strides;
+    for (unsigned int i = blockIdx.x * blockDim.x + threadIdx.x; i < numel; i += blockDim.x * gridDim.x) {
+        T g = grad_out[get_strided_index(i, num_labels, sizes, out_strides)];
+        if (binary) {
+            g = g * other[get_strided_index(i, num_labels, sizes, other_strides)];
+        }
+        atomicAdd(grad + get_strided_index(i, num_labels, sizes, grad_strides), g);
+    }
+}
+
+#define EINSUM(TYPENAME, FWD, BWD) \
+extern "C" __global__ void FWD( \
+    const size_t numel, \
+    const size_t contracted, \
+    const size_t num_labels, \
+    const size_t *info, \
+    const bool binary, \
+    const TYPENAME *lhs, \
+    const TYPENAME *rhs, \
+    TYPENAME *out \
+) { einsum_fwd(numel, contracted, num_labels, info, binary, lhs, rhs, out); } \
+extern "C" __global__ void BWD( \
+    const size_t numel, \
+    const size_t num_labels, \
+    const size_t *info, \
+    const bool binary, \
+    const bool to_lhs, \
+    const TYPENAME *other, \
+    TYPENAME *grad, \
+    const TYPENAME *grad_out \
+) { einsum_bwd(numel, num_labels, info, binary, to_lhs, other, grad, grad_out); }
+
+EINSUM(__half, einsum_fwd_f16, einsum_bwd_f16);
+EINSUM(float, einsum_fwd_f32, einsum_bwd_f32);
+EINSUM(double, einsum_fwd_f64, einsum_bwd_f64);
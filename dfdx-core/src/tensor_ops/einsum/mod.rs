@@ -0,0 +1,641 @@
+mod cpu_kernel;
+mod subscripts;
+
+#[cfg(feature = "cuda")]
+mod cuda_kernel;
+
+#[cfg(feature = "webgpu")]
+mod webgpu_kernel;
+
+pub use subscripts::Subscripts;
+
+use super::{matmul::MatMatBatch3Kernel, sum_to::SumKernel, SumTo, TryMatMul};
+use crate::{shapes::*, tensor::*};
+
+use std::vec::Vec;
+
+/// A contraction over a set of labels, where the output labels come first. The operands
+/// and the output have a stride for every label, which is 0 for labels they don't have.
+#[derive(Debug, Clone)]
+pub struct Contraction {
+    pub sizes: Vec<usize>,
+    pub num_out: usize,
+    pub lhs_strides: Vec<usize>,
+    pub rhs_strides: Vec<usize>,
+    pub out_strides: Vec<usize>,
+}
+
+impl Contraction {
+    /// The offsets into the lhs, rhs and output of every combination of labels, in
+    /// row major order.
+    pub fn offsets(&self) -> impl Iterator<Item = [usize; 3]> + '_ {
+        let total: usize = self.sizes.iter().product();
+        (0..total).map(move |mut i| {
+            let mut offsets = [0; 3];
+            for d in (0..self.sizes.len()).rev() {
+                let j = i % self.sizes[d];
+                i /= self.sizes[d];
+                offsets[0] += j * self.lhs_strides[d];
+                offsets[1] += j * self.rhs_strides[d];
+                offsets[2] += j * self.out_strides[d];
+            }
+            offsets
+        })
+    }
+}
+
+pub trait EinsumKernel<E: Dtype>: Storage<E> {
+    /// `out[o] = sum(lhs[l] * rhs[r])` over the labels that aren't in the output, where
+    /// a single operand has no `rhs`.
+    fn forward<Dst: Shape>(
+        &self,
+        dst: Dst,
+        contraction: &Contraction,
+        lhs: &Self::Vec,
+        rhs: Option<&Self::Vec>,
+    ) -> Result<Tensor<Dst, E, Self>, Error>;
+
+    fn backward(
+        &self,
+        contraction: &Contraction,
+        lhs: &Self::Vec,
+        grad_lhs: &mut Self::Vec,
+        rhs: Option<(&Self::Vec, &mut Self::Vec)>,
+        grad_out: &Self::Vec,
+    ) -> Result<(), Error>;
+}
+
+/// Einstein summation over one tensor, or a tuple of two tensors.
+/// **Pytorch equivalent**: `torch.einsum(subscripts, *operands)`
+///
+/// The output shape `Dst` must have the dims of the output labels, and can use [usize]
+/// or [Const] dims. See [Subscripts] for the syntax, and for checking subscripts at
+/// compile time.
+///
+/// Contractions are computed with batched matmuls where the operands can be viewed as
+/// `(batch, rows, cols)` matrices without copying them, and otherwise with a generic
+/// contraction kernel. A single operand is either a view of the input (for permutes and
+/// diagonals) or a sum.
+///
+/// Attention scores:
+/// ```rust
+/// # use dfdx_core::prelude::*;
+/// # let dev: Cpu = Default::default();
+/// let q: Tensor<Rank4<2, 4, 3, 8>, f32, _> = dev.sample_normal();
+/// let k: Tensor<Rank4<2, 4, 5, 8>, f32, _> = dev.sample_normal();
+/// let scores: Tensor<Rank4<2, 4, 3, 5>, f32, _> = (q, k).einsum("bhqd,bhkd->bhqk");
+/// ```
+///
+/// Single operands:
+/// ```rust
+/// # use dfdx_core::prelude::*;
+/// # let dev: Cpu = Default::default();
+/// let t: Tensor<Rank2<2, 2>, f32, _> = dev.tensor([[1.0, 2.0], [3.0, 4.0]]);
+/// assert_eq!(t.clone().einsum::<Rank0>("ii").array(), 5.0);
+/// assert_eq!(t.clone().einsum::<Rank1<2>>("ii->i").array(), [1.0, 4.0]);
+/// assert_eq!(t.einsum::<Rank2<2, 2>>("ij->ji").array(), [[1.0, 3.0], [2.0, 4.0]]);
+/// ```
+pub trait TryEinsum: Sized {
+    type Output<Dst: Shape>;
+
+    /// Panics if the subscripts don't match the operands or `Dst`.
+    fn einsum<Dst: Shape>(self, subscripts: impl Into<Subscripts>) -> Self::Output<Dst> {
+        self.try_einsum(subscripts).unwrap()
+    }
+    /// Fallible version of [TryEinsum::einsum]
+    fn try_einsum<Dst: Shape>(
+        self,
+        subscripts: impl Into<Subscripts>,
+    ) -> Result<Self::Output<Dst>, Error>;
+}
+
+/// The size & stride of each distinct label of an operand, in the order they first appear.
+/// Repeated labels take the diagonal, so their strides add up.
+fn label_layout(labels: &[u8], dims: &[usize], strides: &[usize]) -> Vec<(u8, usize, usize)> {
+    assert_eq!(
+        labels.len(),
+        dims.len(),
+        "einsum operand has {} labels, but {} dims",
+        labels.len(),
+        dims.len()
+    );
+    let mut layout: Vec<(u8, usize, usize)> = Vec::with_capacity(labels.len());
+    for ((&label, &size), &stride) in labels.iter().zip(dims).zip(strides) {
+        match layout.iter_mut().find(|(l, _, _)| *l == label) {
+            Some((_, s, st)) => {
+                assert_eq!(
+                    *s, size,
+                    "einsum label '{}' is repeated with different sizes",
+                    label as char
+                );
+                *st += stride;
+            }
+            None => layout.push((label, size, stride)),
+        }
+    }
+    layout
+}
+
+fn find(layout: &[(u8, usize, usize)], label: u8) -> Option<(usize, usize)> {
+    layout
+        .iter()
+        .find(|(l, _, _)| *l == label)
+        .map(|&(_, size, stride)| (size, stride))
+}
+
+/// The size of each label across all operands, which must agree.
+fn label_sizes(layouts: &[&[(u8, usize, usize)]]) -> Vec<(u8, usize)> {
+    let mut sizes: Vec<(u8, usize)> = Vec::new();
+    for &(label, size, _) in layouts.iter().flat_map(|l| l.iter()) {
+        match sizes.iter().find(|(l, _)| *l == label) {
+            Some(&(_, s)) => assert_eq!(
+                s, size,
+                "einsum label '{}' has different sizes in the operands",
+                label as char
+            ),
+            None => sizes.push((label, size)),
+        }
+    }
+    sizes
+}
+
+fn size_of(sizes: &[(u8, usize)], label: u8) -> usize {
+    sizes.iter().find(|(l, _)| *l == label).unwrap().1
+}
+
+fn output_shape<Dst: Shape>(labels: &[u8], sizes: &[(u8, usize)]) -> Dst {
+    assert_eq!(
+        labels.len(),
+        Dst::NUM_DIMS,
+        "einsum output has {} labels, but Dst has {} dims",
+        labels.len(),
+        Dst::NUM_DIMS
+    );
+    let mut concrete: Dst::Concrete = Default::default();
+    for (i, &label) in labels.iter().enumerate() {
+        concrete[i] = size_of(sizes, label);
+    }
+    Dst::from_concrete(&concrete)
+        .unwrap_or_else(|| panic!("einsum output has dims {concrete:?}, which don't match Dst"))
+}
+
+/// The strides of `labels` in a contiguous tensor with that order.
+fn contiguous_strides(labels: &[u8], sizes: &[(u8, usize)]) -> Vec<(u8, usize)> {
+    let mut strides: Vec<(u8, usize)> = Vec::with_capacity(labels.len());
+    let mut stride = 1;
+    for &label in labels.iter().rev() {
+        strides.push((label, stride));
+        stride *= size_of(sizes, label);
+    }
+    strides
+}
+
+/// The strides of `Dst` from the stride of each of its labels.
+fn output_strides<Dst: Shape>(labels: &[u8], strides: &[(u8, usize)]) -> Dst::Concrete {
+    let mut concrete: Dst::Concrete = Default::default();
+    for (i, &label) in labels.iter().enumerate() {
+        concrete[i] = strides.iter().find(|(l, _)| *l == label).unwrap().1;
+    }
+    concrete
+}
+
+/// Merges the labels in `group` into a single dimension, if they are laid out like a
+/// contiguous tensor relative to each other. Labels with size 1 don't matter.
+fn merge(layout: &[(u8, usize, usize)], group: &[u8]) -> Option<(usize, usize)> {
+    let mut merged: Option<(usize, usize)> = None;
+    for &label in group {
+        let (size, stride) = find(layout, label).unwrap();
+        if size == 1 {
+            continue;
+        }
+        merged = Some(match merged {
+            None => (size, stride),
+            Some((m, s)) if s == stride * size => (m * size, stride),
+            Some(_) => return None,
+        });
+    }
+    Some(merged.unwrap_or((1, 0)))
+}
+
+/// A `(batch, rows, cols)` view of `layout` that the matmul kernels accept, which need a
+/// non-zero batch stride and one of the matrix strides to be 1.
+fn matmul_view(
+    layout: &[(u8, usize, usize)],
+    [batch, rows, cols]: [&[u8]; 3],
+) -> Option<((usize, usize, usize), [usize; 3])> {
+    let (b, sb) = merge(layout, batch)?;
+    let (m, sm) = merge(layout, rows)?;
+    let (k, sk) = merge(layout, cols)?;
+    // strides of dims with size 1 are never used, so they can be anything
+    let sb = if b == 1 { 1 } else { sb };
+    let sm = match (m, sk) {
+        (1, 1) => k,
+        (1, _) => 1,
+        _ => sm,
+    };
+    let sk = match (k, sm) {
+        (1, 1) => m,
+        (1, _) => 1,
+        _ => sk,
+    };
+    let valid = sb != 0 && ((sk == 1 && sm >= k) || (sm == 1 && sk >= m));
+    valid.then_some(((b, m, k), [sb, sm, sk]))
+}
+
+/// Orders the labels as the output first, and then the contracted labels.
+fn contraction(
+    out: &[u8],
+    sizes: &[(u8, usize)],
+    lhs: &[(u8, usize, usize)],
+    rhs: &[(u8, usize, usize)],
+) -> Contraction {
+    let contracted = sizes.iter().map(|&(l, _)| l).filter(|l| !out.contains(l));
+    let labels: Vec<u8> = out.iter().copied().chain(contracted).collect();
+    let out_strides = contiguous_strides(out, sizes);
+    let stride_in = |layout: &[(u8, usize, usize)], l| find(layout, l).map_or(0, |(_, s)| s);
+    Contraction {
+        sizes: labels.iter().map(|&l| size_of(sizes, l)).collect(),
+        num_out: out.len(),
+        lhs_strides: labels.iter().map(|&l| stride_in(lhs, l)).collect(),
+        rhs_strides: labels.iter().map(|&l| stride_in(rhs, l)).collect(),
+        out_strides: labels
+            .iter()
+            .map(|&l| out_strides.iter().find(|(o, _)| *o == l).map_or(0, |o| o.1))
+            .collect(),
+    }
+}
+
+impl<S: Shape, E: Dtype, D, T: Tape<E, D>> TryEinsum for Tensor<S, E, D, T>
+where
+    D: EinsumKernel<E> + SumKernel<E>,
+{
+    type Output<Dst: Shape> = Tensor<Dst, E, D, T>;
+
+    fn try_einsum<Dst: Shape>(
+        self,
+        subscripts: impl Into<Subscripts>,
+    ) -> Result<Self::Output<Dst>, Error> {
+        let subscripts = subscripts.into();
+        assert_eq!(subscripts.num_operands(), 1, "einsum expected 1 operand");
+        let out_labels = subscripts.output();
+        let layout = label_layout(
+            subscripts.operand(0),
+            self.shape.concrete().as_ref(),
+            self.strides.as_ref(),
+        );
+        let sizes = label_sizes(&[&layout]);
+        let dst: Dst = output_shape(out_labels, &sizes);
+
+        let (kept, summed): (Vec<u8>, Vec<u8>) = layout
+            .iter()
+            .map(|&(l, _, _)| l)
+            .partition(|l| out_labels.contains(l));
+
+        if summed.is_empty() {
+            // permutes & diagonals are views of the input
+            let strides: Vec<(u8, usize)> = layout.iter().map(|&(l, _, s)| (l, s)).collect();
+            return Ok(Tensor {
+                id: self.id,
+                data: self.data,
+                shape: dst,
+                strides: output_strides::<Dst>(out_labels, &strides),
+                device: self.device,
+                tape: self.tape,
+            });
+        }
+
+        if let (Some((k, sk)), Some((s, ss))) = (merge(&layout, &kept), merge(&layout, &summed)) {
+            let view: Tensor<(usize, usize), E, D, T> = Tensor {
+                id: self.id,
+                data: self.data,
+                shape: (k, s),
+                strides: [sk, ss],
+                device: self.device,
+                tape: self.tape,
+            };
+            let out = view.try_sum::<(usize,), Axis<1>>()?;
+            // the sum is contiguous in the order the labels appear in the input
+            let strides = contiguous_strides(&kept, &sizes);
+            return Ok(Tensor {
+                id: out.id,
+                data: out.data,
+                shape: dst,
+                strides: output_strides::<Dst>(out_labels, &strides),
+                device: out.device,
+                tape: out.tape,
+            });
+        }
+
+        let contraction = contraction(out_labels, &sizes, &layout, &[]);
+        let (inp, mut tape) = self.split_tape();
+        let out = EinsumKernel::forward(&inp.device, dst, &contraction, inp.data.as_ref(), None)?;
+        let inp_ghost = inp.ghost();
+        let out_ghost = out.ghost();
+        tape.try_add_op_info(&out, || OpInfo::new("Einsum", &out).input(&inp))?;
+        tape.add_retainable_backward_op(move |grads| {
+            grads.try_alloc_for(&inp_ghost)?;
+            grads.try_alloc_for(&out_ghost)?;
+            let (grad_inp, grad_out) = grads.mut_and_ref(&inp_ghost, &out_ghost);
+            let inp_data = inp.data.as_ref();
+            EinsumKernel::backward(
+                &inp.device,
+                &contraction,
+                inp_data,
+                grad_inp,
+                None,
+                grad_out,
+            )
+        });
+        Ok(out.put_tape(tape))
+    }
+}
+
+impl<L: Shape, R: Shape, E: Dtype, D, T, RT> TryEinsum for (Tensor<L, E, D, T>, Tensor<R, E, D, RT>)
+where
+    D: EinsumKernel<E> + MatMatBatch3Kernel<E>,
+    T: Tape<E, D> + Merge<RT>,
+    RT: Tape<E, D>,
+{
+    type Output<Dst: Shape> = Tensor<Dst, E, D, T>;
+
+    fn try_einsum<Dst: Shape>(
+        self,
+        subscripts: impl Into<Subscripts>,
+    ) -> Result<Self::Output<Dst>, Error> {
+        let subscripts = subscripts.into();
+        assert_eq!(subscripts.num_operands(), 2, "einsum expected 2 operands");
+        let (lhs, rhs) = self;
+        let out_labels = subscripts.output();
+        let lhs_layout = label_layout(
+            subscripts.operand(0),
+            lhs.shape.concrete().as_ref(),
+            lhs.strides.as_ref(),
+        );
+        let rhs_layout = label_layout(
+            subscripts.operand(1),
+            rhs.shape.concrete().as_ref(),
+            rhs.strides.as_ref(),
+        );
+        let sizes = label_sizes(&[&lhs_layout, &rhs_layout]);
+        let dst: Dst = output_shape(out_labels, &sizes);
+
+        let in_lhs = |l: &u8| find(&lhs_layout, *l).is_some();
+        let in_rhs = |l: &u8| find(&rhs_layout, *l).is_some();
+        let in_out = |l: &u8| out_labels.contains(l);
+        let labels = |layout: &[(u8, usize, usize)]| layout.iter().map(|&(l, _, _)| l).collect();
+        let (lhs_labels, rhs_labels): (Vec<u8>, Vec<u8>) =
+            (labels(&lhs_layout), labels(&rhs_layout));
+
+        let batch: Vec<u8> = lhs_labels
+            .iter()
+            .copied()
+            .filter(|l| in_rhs(l) && in_out(l))
+            .collect();
+        let rows: Vec<u8> = lhs_labels.iter().copied().filter(|l| !in_rhs(l)).collect();
+        let inner: Vec<u8> = lhs_labels
+            .iter()
+            .copied()
+            .filter(|l| in_rhs(l) && !in_out(l))
+            .collect();
+        let cols: Vec<u8> = rhs_labels.iter().copied().filter(|l| !in_lhs(l)).collect();
+
+        // labels that are summed away within one operand can't be expressed as a matmul
+        let summed_alone = rows.iter().chain(cols.iter()).any(|l| !in_out(l));
+        let views = (!summed_alone)
+            .then(|| {
+                let lhs_view = matmul_view(&lhs_layout, [&batch, &rows, &inner])?;
+                let rhs_view = matmul_view(&rhs_layout, [&batch, &inner, &cols])?;
+                Some((lhs_view, rhs_view))
+            })
+            .flatten();
+
+        if let Some(((lhs_shape, lhs_strides), (rhs_shape, rhs_strides))) = views {
+            let lhs: Tensor<(usize, usize, usize), E, D, T> = Tensor {
+                id: lhs.id,
+                data: lhs.data,
+                shape: lhs_shape,
+                strides: lhs_strides,
+                device: lhs.device,
+                tape: lhs.tape,
+            };
+            let rhs: Tensor<(usize, usize, usize), E, D, RT> = Tensor {
+                id: rhs.id,
+                data: rhs.data,
+                shape: rhs_shape,
+                strides: rhs_strides,
+                device: rhs.device,
+                tape: rhs.tape,
+            };
+            let out = lhs.try_matmul(rhs)?;
+            // the matmul is contiguous in (batch, rows, cols) order
+            let order: Vec<u8> = batch.iter().chain(&rows).chain(&cols).copied().collect();
+            let strides = contiguous_strides(&order, &sizes);
+            return Ok(Tensor {
+                id: out.id,
+                data: out.data,
+                shape: dst,
+                strides: output_strides::<Dst>(out_labels, &strides),
+                device: out.device,
+                tape: out.tape,
+            });
+        }
+
+        let contraction = contraction(out_labels, &sizes, &lhs_layout, &rhs_layout);
+        let (lhs, ltape) = lhs.split_tape();
+        let (rhs, rtape) = rhs.split_tape();
+        let mut tape = ltape.merge(rtape);
+        let out = EinsumKernel::forward(
+            &lhs.device,
+            dst,
+            &contraction,
+            lhs.data.as_ref(),
+            Some(rhs.data.as_ref()),
+        )?;
+        let lhs_ghost = lhs.ghost();
+        let rhs_ghost = rhs.ghost();
+        let out_ghost = out.ghost();
+        tape.try_add_op_info(&out, || OpInfo::new("Einsum", &out).input(&lhs).input(&rhs))?;
+        tape.add_retainable_backward_op(move |grads| {
+            grads.try_alloc_for(&lhs_ghost)?;
+            grads.try_alloc_for(&rhs_ghost)?;
+            grads.try_alloc_for(&out_ghost)?;
+            let (grad_lhs, grad_rhs, grad_out) =
+                grads.muts_and_ref(&lhs_ghost, &rhs_ghost, &out_ghost);
+            EinsumKernel::backward(
+                &lhs.device,
+                &contraction,
+                lhs.data.as_ref(),
+                grad_lhs,
+                Some((rhs.data.as_ref(), grad_rhs)),
+                grad_out,
+            )
+        });
+        Ok(out.put_tape(tape))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{tensor_ops::*, tests::*};
+
+    #[test]
+    fn test_parse_subscripts() {
+        let s = Subscripts::new("ij, jk -> ik");
+        assert_eq!(s.num_operands(), 2);
+        assert_eq!(s.operand(0), b"ij");
+        assert_eq!(s.operand(1), b"jk");
+        assert_eq!(s.output(), b"ik");
+
+        // implicit outputs are the labels that appear once, in alphabetical order
+        assert_eq!(Subscripts::new("kj,ji").output(), b"ik");
+        assert_eq!(Subscripts::new("ii").output(), b"");
+        assert_eq!(Subscripts::new("iJ").output(), b"Ji");
+        assert_eq!(Subscripts::new("i,->i").operand(1), b"");
+    }
+
+    #[test]
+    #[should_panic = "einsum output has a label that isn't in any operand"]
+    fn test_parse_missing_output_label() {
+        Subscripts::new("ij,jk->il");
+    }
+
+    #[test]
+    #[should_panic = "einsum supports at most two operands"]
+    fn test_parse_too_many_operands() {
+        Subscripts::new("ij,jk,kl->il");
+    }
+
+    #[test]
+    #[should_panic = "einsum output has 2 labels, but Dst has 1 dims"]
+    fn test_einsum_wrong_dst() {
+        let dev: TestDevice = Default::default();
+        let a: Tensor<Rank2<2, 3>, TestDtype, _> = dev.zeros();
+        let b: Tensor<Rank2<3, 4>, TestDtype, _> = dev.zeros();
+        let _: Tensor<Rank1<2>, _, _> = (a, b).einsum("ij,jk->ik");
+    }
+
+    #[test]
+    fn test_einsum_matmul() {
+        let dev: TestDevice = Default::default();
+        let a: Tensor<Rank2<2, 3>, TestDtype, _> = dev.sample_normal();
+        let b: Tensor<Rank2<3, 4>, TestDtype, _> = dev.sample_normal();
+        let r: Tensor<Rank2<2, 4>, _, _, _> = (a.leaky_trace(), b.clone()).einsum("ij,jk->ik");
+        let m = a.leaky_trace().matmul(b.clone());
+        assert_close_to_tensor!(r, m);
+        let g = r.exp().sum().backward();
+        let g2 = m.exp().sum().backward();
+        assert_close_to_tensor!(g.get(&a), g2.get(&a));
+
+        // transposed & with usize dims
+        let r: Tensor<(usize, usize), _, _, _> = (b.trace(g), a.clone()).einsum("jk,ij->ki");
+        assert_eq!(r.shape().concrete(), [4, 2]);
+        let m = a.matmul(b.clone()).permute::<Rank2<4, 2>, _>();
+        assert_close_to_tensor!(r.realize::<Rank2<4, 2>>(), m);
+    }
+
+    #[test]
+    fn test_einsum_attention() {
+        let dev: TestDevice = Default::default();
+        let q: Tensor<Rank4<2, 3, 4, 5>, TestDtype, _> = dev.sample_normal();
+        let k: Tensor<Rank4<2, 3, 6, 5>, TestDtype, _> = dev.sample_normal();
+        let r: Tensor<Rank4<2, 3, 4, 6>, _, _, _> =
+            (q.leaky_trace(), k.leaky_trace()).einsum("bhqd,bhkd->bhqk");
+        let m = q
+            .leaky_trace()
+            .matmul(k.leaky_trace().permute::<_, Axes4<0, 1, 3, 2>>());
+        assert_close_to_tensor!(r, m);
+        let g = r.exp().mean().backward();
+        let g2 = m.exp().mean().backward();
+        assert_close_to_tensor!(g.get(&q), g2.get(&q));
+        assert_close_to_tensor!(g.get(&k), g2.get(&k));
+
+        // heads first in the output
+        let r: Tensor<Rank4<3, 2, 6, 4>, _, _, _> =
+            (q.clone(), k.clone()).einsum("bhqd,bhkd->hbkq");
+        let m = q.matmul(k.permute::<_, Axes4<0, 1, 3, 2>>());
+        assert_close_to_tensor!(r, m.permute::<_, Axes4<1, 0, 3, 2>>());
+    }
+
+    #[test]
+    fn test_einsum_outer_and_dot() {
+        let dev: TestDevice = Default::default();
+        let a = dev.tensor([1.0, 2.0, 3.0]).to_dtype::<TestDtype>();
+        let b = dev.tensor([4.0, 5.0]).to_dtype::<TestDtype>();
+        let r: Tensor<Rank2<3, 2>, _, _, _> = (a.leaky_trace(), b.clone()).einsum("i,j->ij");
+        assert_close_to_literal!(r, [[4.0, 5.0], [8.0, 10.0], [12.0, 15.0]]);
+        let g = r.sum().backward();
+        assert_close_to_literal!(g.get(&a), [9.0; 3]);
+
+        let c = dev.tensor([1.0, -1.0, 2.0]).to_dtype::<TestDtype>();
+        let r: Tensor<Rank0, _, _, _> = (a.leaky_trace(), c).einsum("i,i");
+        assert_close_to_literal!(r, 5.0);
+        let g = r.backward();
+        assert_close_to_literal!(g.get(&a), [1.0, -1.0, 2.0]);
+    }
+
+    #[test]
+    fn test_einsum_single_operand() {
+        let dev: TestDevice = Default::default();
+        let t = dev
+            .tensor([[1.0, 2.0, 3.0], [4.0, 5.0, 6.0], [7.0, 8.0, 9.0]])
+            .to_dtype::<TestDtype>();
+
+        let r = t.leaky_trace().einsum::<Rank0>("ii->");
+        assert_close_to_literal!(r, 15.0);
+        let g = r.backward();
+        assert_close_to_literal!(
+            g.get(&t),
+            [[1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]]
+        );
+
+        let r = t.leaky_trace().einsum::<Rank1<3>>("ii->i");
+        assert_close_to_literal!(r, [1.0, 5.0, 9.0]);
+
+        let r = t.leaky_trace().einsum::<Rank1<3>>("ij->j");
+        assert_close_to_literal!(r, [12.0, 15.0, 18.0]);
+        let g = (r * dev.tensor([1.0, 2.0, 3.0]).to_dtype::<TestDtype>())
+            .sum()
+            .backward();
+        assert_close_to_literal!(g.get(&t), [[1.0, 2.0, 3.0]; 3]);
+
+        let r = t.leaky_trace().einsum::<Rank2<3, 3>>("ij->ji");
+        assert_close_to_tensor!(r, t.clone().permute::<_, Axes2<1, 0>>());
+    }
+
+    #[test]
+    fn test_einsum_generic_contraction() {
+        let dev: TestDevice = Default::default();
+        let a = dev.tensor([[1.0, 2.0], [3.0, 4.0]]).to_dtype::<TestDtype>();
+        let b = dev
+            .tensor([[1.0, -1.0, 2.0], [0.5, 1.0, 0.0]])
+            .to_dtype::<TestDtype>();
+
+        // k is summed away in b alone, so this isn't a matmul
+        let r: Tensor<Rank1<2>, _, _, _> = (a.leaky_trace(), b.leaky_trace()).einsum("ij,jk->i");
+        assert_close_to_literal!(r, [5.0, 12.0]);
+        let g = r.sum().backward();
+        assert_close_to_literal!(g.get(&a), [[2.0, 1.5]; 2]);
+        assert_close_to_literal!(g.get(&b), [[4.0; 3], [6.0; 3]]);
+
+        // the diagonal of a broadcasted tensor can't be viewed as a matrix
+        let r: Tensor<Rank2<2, 3>, _, _, _> = (
+            a.leaky_trace().broadcast::<Rank3<2, 2, 3>, _>(),
+            b.leaky_trace(),
+        )
+            .einsum("iik,ik->ik");
+        assert_close_to_literal!(r, [[1.0, -1.0, 2.0], [2.0, 4.0, 0.0]]);
+        let g = r.sum().backward();
+        assert_close_to_literal!(g.get(&a), [[2.0, 0.0], [0.0, 1.5]]);
+        assert_close_to_literal!(g.get(&b), [[1.0; 3], [4.0; 3]]);
+
+        // a single operand whose kept & summed labels are interleaved
+        let t = dev
+            .tensor([[[1.0, 2.0], [3.0, 4.0]], [[5.0, 6.0], [7.0, 8.0]]])
+            .to_dtype::<TestDtype>();
+        let r = t.leaky_trace().einsum::<Rank1<2>>("ijk->j");
+        assert_close_to_literal!(r, [14.0, 22.0]);
+        let g = r.sum().backward();
+        assert_close_to_literal!(g.get(&t), [[[1.0; 2]; 2]; 2]);
+    }
+}
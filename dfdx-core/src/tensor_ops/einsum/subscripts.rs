@@ -0,0 +1,150 @@
+/// The most labels a single operand or the output can have.
+const MAX_LABELS: usize = 6;
+
+/// Parsed subscripts of an [einsum](super::TryEinsum), like `"bhqd,bhkd->bhqk"`.
+///
+/// Each operand gets one letter per axis, and operands are separated by `,`. The labels
+/// of the output follow `->`; if there is no `->`, the output has the labels that appear
+/// exactly once, in alphabetical order. Whitespace is ignored, and ellipsis (`...`) is
+/// not supported.
+///
+/// [Subscripts::new] is a `const fn`, so malformed subscripts in a constant are a compile
+/// error instead of a panic at runtime:
+/// ```rust
+/// # use dfdx_core::prelude::*;
+/// const ATTENTION: Subscripts = Subscripts::new("bhqd,bhkd->bhqk");
+/// assert_eq!(ATTENTION, Subscripts::from("bhqd, bhkd -> bhqk"));
+/// assert_eq!(Subscripts::new("ij,jk"), Subscripts::new("ij,jk->ik"));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Subscripts {
+    operands: [[u8; MAX_LABELS]; 2],
+    operand_lens: [usize; 2],
+    num_operands: usize,
+    output: [u8; MAX_LABELS],
+    output_len: usize,
+}
+
+impl Subscripts {
+    /// Parses `subscripts`, panicking if they are malformed.
+    pub const fn new(subscripts: &str) -> Self {
+        let bytes = subscripts.as_bytes();
+        let mut operands = [[0; MAX_LABELS]; 2];
+        let mut operand_lens = [0; 2];
+        let mut operand = 0;
+        let mut output = [0; MAX_LABELS];
+        let mut output_len = 0;
+        let mut explicit = false;
+
+        let mut i = 0;
+        while i < bytes.len() {
+            let c = bytes[i];
+            i += 1;
+            if c.is_ascii_whitespace() {
+                continue;
+            } else if c == b',' {
+                assert!(!explicit, "einsum output can't contain ','");
+                assert!(operand == 0, "einsum supports at most two operands");
+                operand += 1;
+            } else if c == b'-' {
+                assert!(
+                    i < bytes.len() && bytes[i] == b'>',
+                    "einsum subscripts contain '-' without '>'"
+                );
+                assert!(!explicit, "einsum subscripts contain '->' twice");
+                explicit = true;
+                i += 1;
+            } else {
+                assert!(
+                    c.is_ascii_alphabetic(),
+                    "einsum labels must be ascii letters"
+                );
+                if explicit {
+                    assert!(output_len < MAX_LABELS, "einsum output has too many labels");
+                    let mut j = 0;
+                    while j < output_len {
+                        assert!(output[j] != c, "einsum output has a repeated label");
+                        j += 1;
+                    }
+                    output[output_len] = c;
+                    output_len += 1;
+                } else {
+                    assert!(
+                        operand_lens[operand] < MAX_LABELS,
+                        "einsum operand has too many labels"
+                    );
+                    operands[operand][operand_lens[operand]] = c;
+                    operand_lens[operand] += 1;
+                }
+            }
+        }
+
+        let num_operands = operand + 1;
+        if explicit {
+            let mut j = 0;
+            while j < output_len {
+                assert!(
+                    count(&operands, &operand_lens, output[j]) > 0,
+                    "einsum output has a label that isn't in any operand"
+                );
+                j += 1;
+            }
+        } else {
+            let mut c = b'A';
+            while c <= b'z' {
+                if count(&operands, &operand_lens, c) == 1 {
+                    assert!(output_len < MAX_LABELS, "einsum output has too many labels");
+                    output[output_len] = c;
+                    output_len += 1;
+                }
+                c += 1;
+            }
+        }
+
+        Self {
+            operands,
+            operand_lens,
+            num_operands,
+            output,
+            output_len,
+        }
+    }
+
+    /// The number of operands, which is 1 or 2.
+    pub fn num_operands(&self) -> usize {
+        self.num_operands
+    }
+
+    /// The labels of the `i`th operand.
+    pub fn operand(&self, i: usize) -> &[u8] {
+        &self.operands[i][..self.operand_lens[i]]
+    }
+
+    /// The labels of the output.
+    pub fn output(&self) -> &[u8] {
+        &self.output[..self.output_len]
+    }
+}
+
+/// How many times `label` appears across all operands.
+const fn count(operands: &[[u8; MAX_LABELS]; 2], lens: &[usize; 2], label: u8) -> usize {
+    let mut n = 0;
+    let mut i = 0;
+    while i < 2 {
+        let mut j = 0;
+        while j < lens[i] {
+            if operands[i][j] == label {
+                n += 1;
+            }
+            j += 1;
+        }
+        i += 1;
+    }
+    n
+}
+
+impl From<&str> for Subscripts {
+    fn from(subscripts: &str) -> Self {
+        Self::new(subscripts)
+    }
+}
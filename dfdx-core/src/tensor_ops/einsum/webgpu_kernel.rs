@@ -0,0 +1,24 @@
+use crate::prelude::{Dtype, Webgpu};
+
+impl<E: Dtype> super::EinsumKernel<E> for Webgpu {
+    fn forward<Dst: crate::prelude::Shape>(
+        &self,
+        dst: Dst,
+        contraction: &super::Contraction,
+        lhs: &Self::Vec,
+        rhs: Option<&Self::Vec>,
+    ) -> Result<crate::prelude::Tensor<Dst, E, Self>, crate::prelude::Error> {
+        todo!()
+    }
+
+    fn backward(
+        &self,
+        contraction: &super::Contraction,
+        lhs: &Self::Vec,
+        grad_lhs: &mut Self::Vec,
+        rhs: Option<(&Self::Vec, &mut Self::Vec)>,
+        grad_out: &Self::Vec,
+    ) -> Result<(), crate::prelude::Error> {
+        todo!()
+    }
+}
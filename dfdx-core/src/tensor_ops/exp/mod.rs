@@ -7,12 +7,24 @@ mod cuda_kernel;
 mod webgpu_kernel;
 
 use super::ops::{try_unary_op, UnaryKernel};
+use super::utilities::higher_order::UnaryDerivative;
 use crate::{shapes::*, tensor::*};
 
 #[repr(C)]
 #[derive(Debug, Default, Copy, Clone)]
 pub struct ExpKernelOp;
 
+impl<E> UnaryDerivative<E> for ExpKernelOp {
+    fn df<D: Storage<E>>(
+        &self,
+        _ops: &mut dyn HigherOrderOps<E, D>,
+        _x: HigherOrderVar,
+        y: HigherOrderVar,
+    ) -> Result<Option<HigherOrderVar>, Error> {
+        Ok(Some(y))
+    }
+}
+
 /// [Exponential function (exp)](https://en.wikipedia.org/wiki/Natural_logarithm). `e^t`
 ///
 /// It's derivative is itself! `e^t`.
@@ -7,6 +7,7 @@ mod cuda_kernel;
 mod webgpu_kernel;
 
 use super::ops::{try_unary_op, UnaryKernel};
+use super::utilities::higher_order::UnaryDerivative;
 use crate::{shapes::*, tensor::*};
 
 #[allow(unused)]
@@ -17,6 +18,17 @@ pub type GeLUKernelOp = FastGeLUKernelOp;
 #[derive(Debug, Default, Copy, Clone)]
 pub struct FastGeLUKernelOp;
 
+impl<E> UnaryDerivative<E> for FastGeLUKernelOp {
+    fn df<D: Storage<E>>(
+        &self,
+        _ops: &mut dyn HigherOrderOps<E, D>,
+        _x: HigherOrderVar,
+        _y: HigherOrderVar,
+    ) -> Result<Option<HigherOrderVar>, Error> {
+        Err(Error::NoHigherOrderGradient)
+    }
+}
+
 /// [Fast Gaussian Linear Unit (GeLU)](https://paperswithcode.com/method/gelu). A fast version of the gaussiane linear unit
 /// calculated by
 /// ```text
@@ -6,6 +6,7 @@ mod cuda_kernel;
 #[cfg(feature = "webgpu")]
 mod webgpu_kernel;
 
+use super::utilities::higher_order::BinaryDerivative;
 use super::{ops::try_binary_op, Device};
 use crate::{shapes::*, tensor::*};
 
@@ -15,6 +16,17 @@ pub struct HuberErrorKernelOp<E> {
     pub delta: E,
 }
 
+impl<E> BinaryDerivative<E> for HuberErrorKernelOp<E> {
+    fn df<D: Storage<E>>(
+        &self,
+        _ops: &mut dyn HigherOrderOps<E, D>,
+        _x: HigherOrderVar,
+        _y: HigherOrderVar,
+    ) -> Result<Option<(HigherOrderVar, HigherOrderVar)>, Error> {
+        Err(Error::NoHigherOrderGradient)
+    }
+}
+
 /// [Huber Loss](https://en.wikipedia.org/wiki/Huber_loss)
 /// uses absolute error when the error is higher than `beta`, and squared error when the
 /// error is lower than `beta`.
@@ -0,0 +1,376 @@
+use crate::{
+    shapes::{Dtype, HasShape, Rank0, Shape},
+    tensor::{
+        Error, GhostTensor, Gradients, OwnedTape, PutTape, SplitTape, Storage, Tensor, Trace,
+    },
+};
+
+use super::{Backward, Device, ReshapeTo, SumTo, TryMul, TryStack};
+
+use std::{sync::Arc, vec::Vec};
+
+/// Computes `f(x)` and the vector jacobian product of `f` at `x` with `v`, which is the
+/// gradient of `sum(f(x) * v)` with respect to `x`.
+///
+/// ```rust
+/// # use dfdx_core::prelude::*;
+/// # let dev: Cpu = Default::default();
+/// let x: Tensor<Rank1<2>, f32, _> = dev.tensor([1.0, 2.0]);
+/// let v: Tensor<Rank1<2>, f32, _> = dev.tensor([1.0, -1.0]);
+/// let (y, g) = vjp(|x| x.try_square(), &x, &v);
+/// assert_eq!(y.array(), [1.0, 4.0]);
+/// assert_eq!(g.array(), [2.0, -4.0]);
+/// ```
+pub fn vjp<S: Shape, Dst: Shape, E: Dtype, D: Device<E>, F>(
+    f: F,
+    x: &Tensor<S, E, D>,
+    v: &Tensor<Dst, E, D>,
+) -> (Tensor<Dst, E, D>, Tensor<S, E, D>)
+where
+    F: FnOnce(
+        Tensor<S, E, D, OwnedTape<E, D>>,
+    ) -> Result<Tensor<Dst, E, D, OwnedTape<E, D>>, Error>,
+{
+    try_vjp(f, x, v).unwrap()
+}
+
+/// Fallible version of [vjp()].
+#[allow(clippy::type_complexity)]
+pub fn try_vjp<S: Shape, Dst: Shape, E: Dtype, D: Device<E>, F>(
+    f: F,
+    x: &Tensor<S, E, D>,
+    v: &Tensor<Dst, E, D>,
+) -> Result<(Tensor<Dst, E, D>, Tensor<S, E, D>), Error>
+where
+    F: FnOnce(
+        Tensor<S, E, D, OwnedTape<E, D>>,
+    ) -> Result<Tensor<Dst, E, D, OwnedTape<E, D>>, Error>,
+{
+    let (y, tape) = f(x.clone().traced(Gradients::leaky()))?.split_tape();
+    check_shapes(y.shape(), v.shape())?;
+    let mut grads = y
+        .clone()
+        .put_tape(tape)
+        .try_mul(v.clone())?
+        .try_sum::<Rank0, _>()?
+        .try_backward()?;
+    grads.try_alloc_for(x)?;
+    Ok((y, grads.get(x)))
+}
+
+/// Computes the dense jacobian of `f` at `x`. Element `[i, j]` of the result is the
+/// derivative of the `i`th element of `f(x)` with respect to the `j`th element of `x`,
+/// where elements are numbered in row major order.
+///
+/// This runs `f` once, and then backprop once per element of the output through the
+/// retained graph (see [crate::tensor_ops::Backward::backward_retain_graph()]). If `f`
+/// uses an operation that can't be retained, like [crate::tensor_ops::try_checkpoint()],
+/// `f` is run again for each of the remaining elements of the output instead.
+///
+/// ```rust
+/// # use dfdx_core::prelude::*;
+/// # let dev: Cpu = Default::default();
+/// let x: Tensor<Rank1<2>, f32, _> = dev.tensor([1.0, 2.0]);
+/// let w: Tensor<Rank2<2, 3>, f32, _> = dev.tensor([[1.0, 2.0, 3.0], [4.0, 5.0, 6.0]]);
+/// let j = jacobian(|x| x.try_matmul(w.clone()), &x);
+/// assert_eq!(j.shape(), &(3, 2));
+/// assert_eq!(j.as_vec(), [1.0, 4.0, 2.0, 5.0, 3.0, 6.0]);
+/// ```
+pub fn jacobian<S: Shape, Dst: Shape, E: Dtype, D: Device<E>, F>(
+    f: F,
+    x: &Tensor<S, E, D>,
+) -> Tensor<(usize, usize), E, D>
+where
+    F: FnMut(Tensor<S, E, D, OwnedTape<E, D>>) -> Result<Tensor<Dst, E, D, OwnedTape<E, D>>, Error>,
+{
+    try_jacobian(f, x).unwrap()
+}
+
+/// Fallible version of [jacobian()].
+pub fn try_jacobian<S: Shape, Dst: Shape, E: Dtype, D: Device<E>, F>(
+    mut f: F,
+    x: &Tensor<S, E, D>,
+) -> Result<Tensor<(usize, usize), E, D>, Error>
+where
+    F: FnMut(Tensor<S, E, D, OwnedTape<E, D>>) -> Result<Tensor<Dst, E, D, OwnedTape<E, D>>, Error>,
+{
+    let n = x.shape().num_elements();
+    let mut y = Some(f(x.clone().traced(Gradients::leaky()))?);
+    let y_ghost = y.as_ref().unwrap().ghost();
+    let shape = y_ghost.shape;
+    let mut rows = Vec::with_capacity(shape.num_elements());
+    for i in 0..shape.num_elements() {
+        let retained = y.as_mut().map(|y| {
+            y.tape.execute_retained(|grads| {
+                grads.insert(&y_ghost, one_hot(&y_ghost, i)?);
+                Ok(())
+            })
+        });
+        let mut grads = match retained {
+            Some(Err(Error::NotRetainable)) | None => {
+                let y = match y.take() {
+                    Some(y) => y,
+                    None => f(x.clone().traced(Gradients::leaky()))?,
+                };
+                y.try_mul(one_hot_tensor(&x.device, shape, i)?)?
+                    .try_sum::<Rank0, _>()?
+                    .try_backward()?
+            }
+            Some(grads) => grads?,
+        };
+        grads.try_alloc_for(x)?;
+        rows.push(grads.get(x).try_reshape_like(&(n,))?);
+    }
+    stack_rows(&x.device, rows, n)
+}
+
+/// Computes the dense hessian of the scalar function `f` at `x`. Element `[i, j]` of the
+/// result is the second derivative of `f(x)` with respect to the `i`th and `j`th elements
+/// of `x`, where elements are numbered in row major order.
+///
+/// This uses [crate::tensor_ops::TracedBackward], so every operation in `f` must support
+/// higher order gradients. `f` is run once, and then backprop through the traced gradient
+/// is run once per element of `x`.
+///
+/// ```rust
+/// # use dfdx_core::prelude::*;
+/// # let dev: Cpu = Default::default();
+/// let x: Tensor<Rank1<2>, f32, _> = dev.tensor([1.0, 2.0]);
+/// let h = hessian(|x| x.try_powi(3)?.try_sum(), &x);
+/// assert_eq!(h.shape(), &(2, 2));
+/// assert_eq!(h.as_vec(), [6.0, 0.0, 0.0, 12.0]);
+/// ```
+#[cfg(feature = "std")]
+pub fn hessian<S: Shape, E: Dtype, D: Device<E>, F>(
+    f: F,
+    x: &Tensor<S, E, D>,
+) -> Tensor<(usize, usize), E, D>
+where
+    F: FnOnce(
+        Tensor<S, E, D, OwnedTape<E, D>>,
+    ) -> Result<Tensor<Rank0, E, D, OwnedTape<E, D>>, Error>,
+{
+    try_hessian(f, x).unwrap()
+}
+
+/// Fallible version of [hessian()].
+#[cfg(feature = "std")]
+pub fn try_hessian<S: Shape, E: Dtype, D: Device<E>, F>(
+    f: F,
+    x: &Tensor<S, E, D>,
+) -> Result<Tensor<(usize, usize), E, D>, Error>
+where
+    F: FnOnce(
+        Tensor<S, E, D, OwnedTape<E, D>>,
+    ) -> Result<Tensor<Rank0, E, D, OwnedTape<E, D>>, Error>,
+{
+    use super::TracedBackward;
+
+    let n = x.shape().num_elements();
+    let y = f(x.clone().traced(Gradients::leaky()).higher_order())?;
+    let Some(g) = y.try_traced_backward()?.get_checked(x) else {
+        return x.device.try_zeros_like(&(n, n));
+    };
+    let (g, tape) = g.try_reshape_like(&(n,))?.split_tape();
+    let g_ghost = g.ghost();
+    let mut rows = Vec::with_capacity(n);
+    for i in 0..n {
+        let mut grads = tape.lock().unwrap().execute_retained(|grads| {
+            grads.insert(&g_ghost, one_hot(&g_ghost, i)?);
+            Ok(())
+        })?;
+        grads.try_alloc_for(x)?;
+        rows.push(grads.get(x).try_reshape_like(&(n,))?);
+    }
+    stack_rows(&x.device, rows, n)
+}
+
+fn one_hot_tensor<S: Shape, E: Dtype, D: Device<E>>(
+    device: &D,
+    shape: S,
+    i: usize,
+) -> Result<Tensor<S, E, D>, Error> {
+    let mut data = std::vec![E::default(); shape.num_elements()];
+    data[i] = E::ONE;
+    device.try_tensor_from_vec(data, shape)
+}
+
+/// The gradient of `t` that is 1 at the `i`th element of `t` in row major order, and 0
+/// everywhere else. Gradients have the physical layout of `t`, so `i` is mapped through
+/// the strides of `t`.
+fn one_hot<S: Shape, E: Dtype, D: Device<E>>(
+    t: &GhostTensor<S, E, D>,
+    i: usize,
+) -> Result<<D as Storage<E>>::Vec, Error> {
+    let dims = t.shape.concrete();
+    let mut physical_i = 0;
+    let mut rem = i;
+    for d in (0..S::NUM_DIMS).rev() {
+        physical_i += (rem % dims[d]) * t.strides[d];
+        rem /= dims[d];
+    }
+    let mut data = std::vec![E::default(); t.len];
+    data[physical_i] = E::ONE;
+    let t = t.dev.try_tensor_from_vec(data, (t.len,))?;
+    Ok(Arc::try_unwrap(t.data).unwrap_or_else(|data| data.as_ref().clone()))
+}
+
+fn check_shapes<S: Shape>(expected: &S, found: &S) -> Result<(), Error> {
+    if expected == found {
+        Ok(())
+    } else {
+        Err(Error::ShapeMismatch {
+            expected: expected.concrete().into(),
+            found: found.concrete().into(),
+        })
+    }
+}
+
+fn stack_rows<E: Dtype, D: Device<E>>(
+    device: &D,
+    rows: Vec<Tensor<(usize,), E, D>>,
+    n: usize,
+) -> Result<Tensor<(usize, usize), E, D>, Error> {
+    if rows.is_empty() {
+        device.try_zeros_like(&(0, n))
+    } else {
+        rows.try_stack()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{shapes::*, tensor::*, tensor_ops::*, tests::*};
+
+    #[test]
+    fn test_vjp() {
+        let dev: TestDevice = Default::default();
+        let x: Tensor<Rank2<2, 2>, TestDtype, _> = dev.tensor([[0.5, -1.0], [2.0, 0.0]]);
+        let v: Tensor<Rank1<2>, TestDtype, _> = dev.tensor([1.0, 2.0]);
+        let (y, g) = vjp(|x| x.try_exp()?.try_sum::<Rank1<2>, Axis<1>>(), &x, &v);
+        assert_close_to_tensor!(y, x.clone().exp().sum::<Rank1<2>, Axis<1>>());
+        let expected = x.exp() * v.broadcast::<Rank2<2, 2>, Axis<1>>();
+        assert_close_to_tensor!(g, expected);
+    }
+
+    #[test]
+    fn test_vjp_shape_mismatch() {
+        let dev: TestDevice = Default::default();
+        let x: Tensor<(usize,), TestDtype, _> = dev.tensor_from_vec(std::vec![1.0, 2.0], (2,));
+        let v: Tensor<(usize,), TestDtype, _> = dev.tensor_from_vec(std::vec![1.0; 3], (3,));
+        assert!(matches!(
+            try_vjp(|x| x.try_square(), &x, &v),
+            Err(Error::ShapeMismatch { .. })
+        ));
+    }
+
+    #[test]
+    fn test_jacobian_elementwise_is_diagonal() {
+        let dev: TestDevice = Default::default();
+        let x: Tensor<Rank1<3>, TestDtype, _> = dev.tensor([0.0, 1.0, -2.0]);
+        let j = jacobian(|x| x.try_square(), &x);
+        assert_eq!(j.shape(), &(3, 3));
+        assert_close_to_literal!(
+            j.reshape_like(&(Const::<3>, Const::<3>)),
+            [[0.0, 0.0, 0.0], [0.0, 2.0, 0.0], [0.0, 0.0, -4.0]]
+        );
+    }
+
+    #[test]
+    fn test_jacobian_runtime_dims() {
+        let dev: TestDevice = Default::default();
+        let x: Tensor<(usize, usize), TestDtype, _> =
+            dev.tensor_from_vec(std::vec![1.0, 2.0, 3.0, 4.0], (2, 2));
+        // sum over rows
+        let j = jacobian(|x| x.try_sum::<(usize,), Axis<0>>(), &x);
+        assert_eq!(j.shape(), &(2, 4));
+        assert_close_to_literal!(
+            j.reshape_like(&(Const::<2>, Const::<4>)),
+            [[1.0, 0.0, 1.0, 0.0], [0.0, 1.0, 0.0, 1.0]]
+        );
+    }
+
+    #[test]
+    fn test_jacobian_permuted_output() {
+        let dev: TestDevice = Default::default();
+        let x: Tensor<Rank2<2, 3>, TestDtype, _> = dev.sample_normal();
+        let expected = jacobian(
+            |x| {
+                x.try_permute::<Rank2<3, 2>, Axes2<1, 0>>()?
+                    .try_contiguous()
+            },
+            &x,
+        );
+        let j = jacobian(|x| x.try_permute::<Rank2<3, 2>, Axes2<1, 0>>(), &x);
+        assert_eq!(j.as_vec(), expected.as_vec());
+        // element [i, j] of the output is element [j, i] of x
+        assert_close_to_literal!(
+            j.reshape_like(&(Const::<6>, Const::<6>)),
+            [
+                [1.0, 0.0, 0.0, 0.0, 0.0, 0.0],
+                [0.0, 0.0, 0.0, 1.0, 0.0, 0.0],
+                [0.0, 1.0, 0.0, 0.0, 0.0, 0.0],
+                [0.0, 0.0, 0.0, 0.0, 1.0, 0.0],
+                [0.0, 0.0, 1.0, 0.0, 0.0, 0.0],
+                [0.0, 0.0, 0.0, 0.0, 0.0, 1.0],
+            ]
+        );
+    }
+
+    #[test]
+    fn test_jacobian_broadcasted_output() {
+        let dev: TestDevice = Default::default();
+        let x: Tensor<Rank1<2>, TestDtype, _> = dev.tensor([1.0, 2.0]);
+        let j = jacobian(|x| x.try_square()?.try_broadcast::<Rank2<2, 2>, Axis<0>>(), &x);
+        assert_close_to_literal!(
+            j.reshape_like(&(Const::<4>, Const::<2>)),
+            [[2.0, 0.0], [0.0, 4.0], [2.0, 0.0], [0.0, 4.0]]
+        );
+    }
+
+    #[test]
+    fn test_jacobian_not_retainable() {
+        let dev: TestDevice = Default::default();
+        let x: Tensor<Rank1<2>, TestDtype, _> = dev.tensor([1.0, 2.0]);
+        let mut calls = 0;
+        let j = jacobian(
+            |x| {
+                calls += 1;
+                try_checkpoint(x, |x| x.try_square(), |x| x.try_square())
+            },
+            &x,
+        );
+        // the checkpoint can't be retained, so `f` is rerun for the second row
+        assert_eq!(calls, 2);
+        assert_close_to_literal!(
+            j.reshape_like(&(Const::<2>, Const::<2>)),
+            [[2.0, 0.0], [0.0, 4.0]]
+        );
+    }
+
+    #[test]
+    fn test_hessian() {
+        let dev: TestDevice = Default::default();
+        let x: Tensor<Rank1<2>, TestDtype, _> = dev.tensor([1.0, 2.0]);
+        let w: Tensor<Rank1<2>, TestDtype, _> = dev.tensor([3.0, -1.0]);
+        // f(x) = sum(sin(x) * w) + sum(x)^2
+        let h = hessian(
+            |x| {
+                let a = x
+                    .with_empty_tape()
+                    .try_sin()?
+                    .try_mul(w.clone())?
+                    .try_sum::<Rank0, _>()?;
+                let b = x.try_sum::<Rank0, _>()?.try_square()?;
+                a.try_add(b)
+            },
+            &x,
+        );
+        // diagonal: -sin(x) * w + 2, off-diagonal: 2
+        assert_close_to_literal!(
+            h.reshape_like(&(Const::<2>, Const::<2>)),
+            [[-0.5244129, 2.0], [2.0, 2.909297]]
+        );
+    }
+}
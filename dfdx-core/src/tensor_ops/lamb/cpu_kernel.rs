@@ -0,0 +1,113 @@
+use super::{LambConfig, LambKernel, WeightDecay};
+use crate::{
+    dtypes::{Dtype, NotMixedPrecision},
+    tensor::{Cpu, Error},
+};
+
+#[cfg(feature = "f16")]
+impl LambKernel<crate::dtypes::AMP<crate::dtypes::f16>> for Cpu {
+    fn lamb_kernel(
+        &self,
+        t: i32,
+        cfg: &LambConfig,
+        param: &mut Self::Vec,
+        moment1: &mut Self::Vec,
+        moment2: &mut Self::Vec,
+        grad: &Self::Vec,
+    ) -> Result<(), Error> {
+        let betas = cfg.betas.map(|x| x as f32);
+        let eps = cfg.eps as f32;
+        let bias_correction1 = 1.0 - betas[0].powi(t);
+        let bias_correction2 = 1.0 - betas[1].powi(t);
+        let update = |p: f32, m: f32, v: f32| {
+            let mut u = (m / bias_correction1) / ((v / bias_correction2).sqrt() + eps);
+            if let Some(WeightDecay::Decoupled(wd)) = cfg.weight_decay {
+                u += (wd as f32) * p;
+            }
+            u
+        };
+
+        let mut param_norm = 0.0;
+        let mut update_norm = 0.0;
+        for ((p, g), (m, v)) in param
+            .iter()
+            .zip(grad.iter().cloned())
+            .zip(moment1.iter_mut().zip(moment2.iter_mut()))
+        {
+            let p_f32 = p.0.to_f32();
+            let mut g_f32 = g.0.to_f32();
+            let mut m_f32 = m.0.to_f32();
+            let mut v_f32 = v.0.to_f32();
+
+            if let Some(WeightDecay::L2(wd)) = cfg.weight_decay {
+                g_f32 += (wd as f32) * p_f32;
+            }
+
+            m_f32 = m_f32 * betas[0] + g_f32 * (1.0 - betas[0]);
+            v_f32 = v_f32 * betas[1] + g_f32.powi(2) * (1.0 - betas[1]);
+            let u = update(p_f32, m_f32, v_f32);
+            param_norm += (p_f32 as f64).powi(2);
+            update_norm += (u as f64).powi(2);
+
+            m.0 = crate::dtypes::f16::from_f32(m_f32);
+            v.0 = crate::dtypes::f16::from_f32(v_f32);
+        }
+
+        let lr = (cfg.lr * cfg.trust_ratio(param_norm.sqrt(), update_norm.sqrt())) as f32;
+        for (p, (m, v)) in param.iter_mut().zip(moment1.iter().zip(moment2.iter())) {
+            let p_f32 = p.0.to_f32();
+            let u = update(p_f32, m.0.to_f32(), v.0.to_f32());
+            p.0 = crate::dtypes::f16::from_f32(p_f32 - lr * u);
+        }
+        Ok(())
+    }
+}
+
+impl<E: num_traits::Float + Dtype + NotMixedPrecision> LambKernel<E> for Cpu {
+    fn lamb_kernel(
+        &self,
+        t: i32,
+        cfg: &LambConfig,
+        param: &mut Self::Vec,
+        moment1: &mut Self::Vec,
+        moment2: &mut Self::Vec,
+        grad: &Self::Vec,
+    ) -> Result<(), Error> {
+        let betas = cfg.betas.map(E::from_f64).map(Option::unwrap);
+        let eps = E::from_f64(cfg.eps).unwrap();
+        let bias_correction1 = E::one() - betas[0].powi(t);
+        let bias_correction2 = E::one() - betas[1].powi(t);
+        let update = |p: E, m: E, v: E| {
+            let mut u = (m / bias_correction1) / ((v / bias_correction2).sqrt() + eps);
+            if let Some(WeightDecay::Decoupled(wd)) = cfg.weight_decay {
+                u += E::from_f64(wd).unwrap() * p;
+            }
+            u
+        };
+
+        let mut param_norm = 0.0;
+        let mut update_norm = 0.0;
+        for ((p, mut g), (m, v)) in param
+            .iter()
+            .zip(grad.iter().cloned())
+            .zip(moment1.iter_mut().zip(moment2.iter_mut()))
+        {
+            if let Some(WeightDecay::L2(wd)) = cfg.weight_decay {
+                g += E::from_f64(wd).unwrap() * *p;
+            }
+
+            *m = *m * betas[0] + g * (E::one() - betas[0]);
+            *v = *v * betas[1] + g.powi(2) * (E::one() - betas[1]);
+            let u = update(*p, *m, *v);
+            param_norm += p.to_f64().unwrap().powi(2);
+            update_norm += u.to_f64().unwrap().powi(2);
+        }
+
+        let lr =
+            E::from_f64(cfg.lr * cfg.trust_ratio(param_norm.sqrt(), update_norm.sqrt())).unwrap();
+        for (p, (m, v)) in param.iter_mut().zip(moment1.iter().zip(moment2.iter())) {
+            *p -= lr * update(*p, *m, *v);
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,122 @@
+use crate::{
+    dtypes::*,
+    tensor::{launch_cfg, Cuda, Error},
+    tensor_ops::optim::*,
+};
+
+use cudarc::driver::{DeviceRepr, DeviceSlice, LaunchAsync};
+
+#[repr(C)]
+struct CudaLambConfig {
+    lr: f64,
+    beta1: f64,
+    beta2: f64,
+    eps: f64,
+    has_max_trust_ratio: bool,
+    max_trust_ratio: f64,
+    weight_decay_type: WeightDecayType,
+    weight_decay: f64,
+}
+
+unsafe impl DeviceRepr for CudaLambConfig {}
+
+fn lamb_config_to_cuda(config: &super::LambConfig) -> CudaLambConfig {
+    let (weight_decay_type, weight_decay) = weight_decay_to_cuda(config.weight_decay);
+
+    CudaLambConfig {
+        lr: config.lr,
+        beta1: config.betas[0],
+        beta2: config.betas[1],
+        eps: config.eps,
+        has_max_trust_ratio: config.max_trust_ratio.is_some(),
+        max_trust_ratio: config.max_trust_ratio.unwrap_or_default(),
+        weight_decay_type,
+        weight_decay,
+    }
+}
+
+const PTX_SRC: &str = include_str!(concat!(env!("OUT_DIR"), "/lamb.ptx"));
+
+trait HasCudaKernel<E> {
+    const MOD: &'static str;
+    const MOMENTS: &'static str;
+    const APPLY: &'static str;
+}
+
+#[cfg(feature = "f16")]
+impl HasCudaKernel<AMP<f16>> for Cuda {
+    const MOD: &'static str = "lamb_amp_f16";
+    const MOMENTS: &'static str = "lamb_moments_amp_f16";
+    const APPLY: &'static str = "lamb_apply_amp_f16";
+}
+
+#[cfg(feature = "f16")]
+impl HasCudaKernel<f16> for Cuda {
+    const MOD: &'static str = "lamb_f16";
+    const MOMENTS: &'static str = "lamb_moments_f16";
+    const APPLY: &'static str = "lamb_apply_f16";
+}
+
+impl HasCudaKernel<f32> for Cuda {
+    const MOD: &'static str = "lamb_f32";
+    const MOMENTS: &'static str = "lamb_moments_f32";
+    const APPLY: &'static str = "lamb_apply_f32";
+}
+
+impl HasCudaKernel<f64> for Cuda {
+    const MOD: &'static str = "lamb_f64";
+    const MOMENTS: &'static str = "lamb_moments_f64";
+    const APPLY: &'static str = "lamb_apply_f64";
+}
+
+impl<E: Dtype> super::LambKernel<E> for Cuda
+where
+    Self: HasCudaKernel<E>,
+{
+    fn lamb_kernel(
+        &self,
+        t: i32,
+        cfg: &super::LambConfig,
+        param: &mut Self::Vec,
+        moment1: &mut Self::Vec,
+        moment2: &mut Self::Vec,
+        grad: &Self::Vec,
+    ) -> Result<(), Error> {
+        if !self.dev.has_func(Self::MOD, Self::APPLY) {
+            self.dev
+                .load_ptx(PTX_SRC.into(), Self::MOD, &[Self::MOMENTS, Self::APPLY])?;
+        }
+
+        let numel = param.len();
+        // squared norms of the param and of its update, summed by the first kernel
+        let mut norms = self.dev.alloc_zeros::<f64>(2)?;
+
+        let func = self.dev.get_func(Self::MOD, Self::MOMENTS).unwrap();
+        let launch = launch_cfg::<128>(numel as u32);
+        let params = (
+            lamb_config_to_cuda(cfg),
+            numel,
+            t,
+            &*param,
+            &mut *moment1,
+            &mut *moment2,
+            grad,
+            &mut norms,
+        );
+        unsafe { func.launch(launch, params) }?;
+
+        let func = self.dev.get_func(Self::MOD, Self::APPLY).unwrap();
+        let launch = launch_cfg::<128>(numel as u32);
+        let params = (
+            lamb_config_to_cuda(cfg),
+            numel,
+            t,
+            param,
+            &*moment1,
+            &*moment2,
+            &norms,
+        );
+        unsafe { func.launch(launch, params) }?;
+        Ok(())
+    }
+}
@@ -0,0 +1,192 @@
+#include "cuda_utils.cuh"
+
+enum WeightDecayType {
+    WdNone,
+    L2,
+    Decoupled
+};
+
+struct LambConfig {
+    double lr;
+    double beta1;
+    double beta2;
+    double eps;
+    bool has_max_trust_ratio;
+    double max_trust_ratio;
+    WeightDecayType weight_decay_type;
+    double weight_decay;
+};
+
+__device__ __forceinline__ double to_f64(float x) { return x; }
+__device__ __forceinline__ double to_f64(double x) { return x; }
+__device__ __forceinline__ double to_f64(__half x) { return __half2float(x); }
+
+// norms holds the squared norms of the parameter & of its update
+__device__ double trust_ratio(const LambConfig cfg, const double *norms) {
+    double param_norm = sqrt(norms[0]);
+    double update_norm = sqrt(norms[1]);
+    double ratio = (param_norm > 0.0 && update_norm > 0.0) ? param_norm / update_norm : 1.0;
+    if (cfg.has_max_trust_ratio) {
+        ratio = fmin(ratio, cfg.max_trust_ratio);
+    }
+    return ratio;
+}
+
+template<typename T>
+__device__ T lamb_direction(const LambConfig cfg, const int t_int, T p, T m, T v) {
+    T beta1 = cfg.beta1;
+    T beta2 = cfg.beta2;
+    T eps = cfg.eps;
+    T weight_decay = cfg.weight_decay;
+    T one = 1.0;
+    T t = t_int;
+    T m_hat = m / (one - powg(beta1, t));
+    T v_hat = v / (one - powg(beta2, t));
+    T u = m_hat / (sqrtg(v_hat) + eps);
+    if (cfg.weight_decay_type == Decoupled) {
+        u += weight_decay * p;
+    }
+    return u;
+}
+
+// Updates the moments, and sums the squared norms into norms. Expects one thread per element.
+template<typename T>
+__device__ void lamb_moments(
+    const LambConfig cfg,
+    const size_t numel,
+    const int t,
+    const T* param,
+    T* moment1,
+    T* moment2,
+    const T* grad,
+    double* norms
+) {
+    unsigned int i = blockIdx.x * blockDim.x + threadIdx.x;
+    if (i >= numel) {
+        return;
+    }
+
+    T beta1 = cfg.beta1;
+    T beta2 = cfg.beta2;
+    T weight_decay = cfg.weight_decay;
+    T one = 1.0;
+    T p = param[i];
+    T g = grad[i];
+    T m = moment1[i];
+    T v = moment2[i];
+
+    if (cfg.weight_decay_type == L2) {
+        g += weight_decay * p;
+    }
+
+    m = m * beta1 + g * (one - beta1);
+    v = v * beta2 + g * g * (one - beta2);
+    moment1[i] = m;
+    moment2[i] = v;
+
+    double u = to_f64(lamb_direction(cfg, t, p, m, v));
+    chunk_sum(numel, to_f64(p) * to_f64(p), norms);
+    chunk_sum(numel, u * u, norms + 1);
+}
+
+template<typename T>
+__device__ void lamb_apply(
+    const LambConfig cfg,
+    const size_t numel,
+    const int t,
+    T* param,
+    const T* moment1,
+    const T* moment2,
+    const double* norms
+) {
+    T lr = cfg.lr * trust_ratio(cfg, norms);
+    for (unsigned int i = blockIdx.x * blockDim.x + threadIdx.x; i < numel; i += blockDim.x * gridDim.x) {
+        T p = param[i];
+        param[i] -= lr * lamb_direction(cfg, t, p, moment1[i], moment2[i]);
+    }
+}
+
+#define LAMB(TYPENAME, MOMENTS, APPLY) \
+extern "C" __global__ void MOMENTS( \
+    const LambConfig cfg, \
+    const size_t numel, \
+    const int t, \
+    const TYPENAME* param, \
+    TYPENAME* moment1, \
+    TYPENAME* moment2, \
+    const TYPENAME* grad, \
+    double* norms \
+) { \
+    lamb_moments(cfg, numel, t, param, moment1, moment2, grad, norms); \
+} \
+extern "C" __global__ void APPLY( \
+    const LambConfig cfg, \
+    const size_t numel, \
+    const int t, \
+    TYPENAME* param, \
+    const TYPENAME* moment1, \
+    const TYPENAME* moment2, \
+    const double* norms \
+) { \
+    lamb_apply(cfg, numel, t, param, moment1, moment2, norms); \
+}
+
+LAMB(__half, lamb_moments_f16, lamb_apply_f16);
+LAMB(float, lamb_moments_f32, lamb_apply_f32);
+LAMB(double, lamb_moments_f64, lamb_apply_f64);
+
+extern "C" __global__ void lamb_moments_amp_f16(
+    const LambConfig cfg,
+    const size_t numel,
+    const int t,
+    const __half* param,
+    __half* moment1,
+    __half* moment2,
+    const __half* grad,
+    double* norms
+) {
+    unsigned int i = blockIdx.x * blockDim.x + threadIdx.x;
+    if (i >= numel) {
+        return;
+    }
+
+    float beta1 = cfg.beta1;
+    float beta2 = cfg.beta2;
+    float weight_decay = cfg.weight_decay;
+    float one = 1.0;
+    float p = param[i];
+    float g = grad[i];
+    float m = moment1[i];
+    float v = moment2[i];
+
+    if (cfg.weight_decay_type == L2) {
+        g += weight_decay * p;
+    }
+
+    m = m * beta1 + g * (one - beta1);
+    v = v * beta2 + g * g * (one - beta2);
+    moment1[i] = m;
+    moment2[i] = v;
+
+    double u = lamb_direction(cfg, t, p, m, v);
+    chunk_sum(numel, to_f64(p) * to_f64(p), norms);
+    chunk_sum(numel, u * u, norms + 1);
+}
+
+extern "C" __global__ void lamb_apply_amp_f16(
+    const LambConfig cfg,
+    const size_t numel,
+    const int t,
+    __half* param,
+    const __half* moment1,
+    const __half* moment2,
+    const double* norms
+) {
+    float lr = cfg.lr * trust_ratio(cfg, norms);
+    for (unsigned int i = blockIdx.x * blockDim.x + threadIdx.x; i < numel; i += blockDim.x * gridDim.x) {
+        float p = param[i];
+        float m = moment1[i];
+        float v = moment2[i];
+        param[i] = p - lr * lamb_direction(cfg, t, p, m, v);
+    }
+}
@@ -0,0 +1,109 @@
+mod cpu_kernel;
+
+#[cfg(feature = "cuda")]
+mod cuda_kernel;
+
+#[cfg(feature = "webgpu")]
+mod webgpu_kernel;
+
+use crate::{
+    shapes::{Dtype, Shape},
+    tensor::{Error, Storage, Tensor},
+};
+
+use super::WeightDecay;
+
+/// Configuration of hyperparameters for LAMB.
+///
+/// Changing all default parameters:
+/// ```rust
+/// # use dfdx_core::prelude::*;
+/// LambConfig {
+///     lr: 1e-2,
+///     betas: [0.1, 0.2],
+///     eps: 1e-8,
+///     weight_decay: Some(WeightDecay::Decoupled(1e-2)),
+///     max_trust_ratio: Some(10.0),
+/// };
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct LambConfig {
+    /// Learning rate. Defaults to `1e-3`.
+    pub lr: f64,
+
+    /// Coefficients of the running averages of gradients and squared gradients.
+    /// Defaults to `[0.9, 0.999]`.
+    pub betas: [f64; 2],
+
+    /// Epsilon for numerical stability. Defaults to `1e-6`.
+    pub eps: f64,
+
+    /// Optional weight decay. Defaults to `None`.
+    ///
+    /// [WeightDecay::Decoupled] is added to the Adam update before the trust ratio is computed,
+    /// as in the LAMB paper.
+    pub weight_decay: Option<WeightDecay>,
+
+    /// Optional upper bound of the trust ratio `|param| / |update|`. Defaults to `None`.
+    pub max_trust_ratio: Option<f64>,
+}
+
+impl Default for LambConfig {
+    fn default() -> Self {
+        Self {
+            lr: 1e-3,
+            betas: [0.9, 0.999],
+            eps: 1e-6,
+            weight_decay: None,
+            max_trust_ratio: None,
+        }
+    }
+}
+
+pub trait LambKernel<E: Dtype>: Storage<E> {
+    fn lamb_kernel(
+        &self,
+        t: i32,
+        cfg: &LambConfig,
+        param: &mut Self::Vec,
+        moment1: &mut Self::Vec,
+        moment2: &mut Self::Vec,
+        grad: &Self::Vec,
+    ) -> Result<(), Error>;
+}
+
+impl LambConfig {
+    /// The trust ratio of a tensor given the norms of the tensor and of its update.
+    /// It is 1 if either norm is 0.
+    fn trust_ratio(&self, param_norm: f64, update_norm: f64) -> f64 {
+        let ratio = if param_norm > 0.0 && update_norm > 0.0 {
+            param_norm / update_norm
+        } else {
+            1.0
+        };
+        match self.max_trust_ratio {
+            Some(max) => ratio.min(max),
+            None => ratio,
+        }
+    }
+
+    /// Update a single tensor using LAMB. Unlike the other optimizers, the update depends on
+    /// the norms of the whole tensor.
+    pub fn try_update<S: Shape, E: Dtype, D: LambKernel<E>>(
+        &self,
+        t: i32,
+        param: &mut Tensor<S, E, D>,
+        moment1: &mut D::Vec,
+        moment2: &mut D::Vec,
+        grad: &D::Vec,
+    ) -> Result<(), crate::tensor::Error> {
+        param.device.lamb_kernel(
+            t,
+            self,
+            std::sync::Arc::make_mut(&mut param.data),
+            moment1,
+            moment2,
+            grad,
+        )
+    }
+}
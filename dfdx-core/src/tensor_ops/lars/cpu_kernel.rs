@@ -0,0 +1,116 @@
+use super::{LarsConfig, LarsKernel, Momentum, WeightDecay};
+use crate::{
+    dtypes::{Dtype, NotMixedPrecision},
+    tensor::{Cpu, Error},
+};
+
+#[cfg(feature = "f16")]
+impl LarsKernel<crate::dtypes::AMP<crate::dtypes::f16>> for Cpu {
+    fn lars_kernel(
+        &self,
+        cfg: &LarsConfig,
+        param: &mut Self::Vec,
+        momentum: &mut Self::Vec,
+        grad: &Self::Vec,
+    ) -> Result<(), Error> {
+        let mut param_norm = 0.0;
+        let mut grad_norm = 0.0;
+        for (p, g) in param.iter().zip(grad.iter()) {
+            param_norm += (p.0.to_f32() as f64).powi(2);
+            grad_norm += (g.0.to_f32() as f64).powi(2);
+        }
+        let ratio = cfg.trust_ratio(param_norm.sqrt(), grad_norm.sqrt()) as f32;
+        let lr = cfg.lr as f32;
+
+        for ((p, g), v) in param
+            .iter_mut()
+            .zip(grad.iter().cloned())
+            .zip(momentum.iter_mut())
+        {
+            let p_f32 = p.0.to_f32();
+            let mut g_f32 = g.0.to_f32();
+            let mut v_f32 = v.0.to_f32();
+
+            if let Some(WeightDecay::L2(wd)) = cfg.weight_decay {
+                g_f32 += (wd as f32) * p_f32;
+            }
+            g_f32 *= ratio;
+
+            match cfg.momentum {
+                Some(Momentum::Classic(u)) => {
+                    let u = u as f32;
+                    v_f32 = g_f32 + u * v_f32;
+                    g_f32 = v_f32 * lr;
+                }
+                Some(Momentum::Nesterov(u)) => {
+                    let u = u as f32;
+                    v_f32 = g_f32 + u * v_f32;
+                    g_f32 = (g_f32 + u * v_f32) * lr;
+                }
+                None => g_f32 *= lr,
+            }
+
+            if let Some(WeightDecay::Decoupled(wd)) = cfg.weight_decay {
+                g_f32 += (wd * cfg.lr) as f32 * p_f32;
+            }
+
+            p.0 = crate::dtypes::f16::from_f32(p_f32 - g_f32);
+            v.0 = crate::dtypes::f16::from_f32(v_f32);
+        }
+
+        Ok(())
+    }
+}
+
+impl<E: Dtype + NotMixedPrecision> LarsKernel<E> for Cpu {
+    fn lars_kernel(
+        &self,
+        cfg: &LarsConfig,
+        param: &mut Self::Vec,
+        momentum: &mut Self::Vec,
+        grad: &Self::Vec,
+    ) -> Result<(), Error> {
+        let mut param_norm = 0.0;
+        let mut grad_norm = 0.0;
+        for (p, g) in param.iter().zip(grad.iter()) {
+            param_norm += p.to_f64().unwrap().powi(2);
+            grad_norm += g.to_f64().unwrap().powi(2);
+        }
+        let ratio = E::from_f64(cfg.trust_ratio(param_norm.sqrt(), grad_norm.sqrt())).unwrap();
+        let lr = E::from_f64(cfg.lr).unwrap();
+
+        for ((p, mut g), v) in param
+            .iter_mut()
+            .zip(grad.iter().cloned())
+            .zip(momentum.iter_mut())
+        {
+            if let Some(WeightDecay::L2(wd)) = cfg.weight_decay {
+                let wd = E::from_f64(wd).unwrap();
+                g += wd * *p;
+            }
+            g *= ratio;
+
+            match cfg.momentum {
+                Some(Momentum::Classic(u)) => {
+                    let u = E::from_f64(u).unwrap();
+                    *v = g + u * *v;
+                    g = *v * lr;
+                }
+                Some(Momentum::Nesterov(u)) => {
+                    let u = E::from_f64(u).unwrap();
+                    *v = g + u * *v;
+                    g = (g + u * *v) * lr;
+                }
+                None => g *= lr,
+            }
+
+            if let Some(WeightDecay::Decoupled(wd)) = cfg.weight_decay {
+                g += E::from_f64(wd * cfg.lr).unwrap() * *p;
+            }
+
+            *p -= g;
+        }
+
+        Ok(())
+    }
+}
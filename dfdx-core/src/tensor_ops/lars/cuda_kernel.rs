@@ -0,0 +1,106 @@
+use crate::{
+    dtypes::*,
+    tensor::{launch_cfg, Cuda, Error},
+    tensor_ops::optim::*,
+};
+
+use cudarc::driver::{DeviceRepr, DeviceSlice, LaunchAsync};
+
+#[repr(C)]
+struct CudaLarsConfig {
+    lr: f64,
+    momentum_type: MomentumType,
+    momentum: f64,
+    trust_coefficient: f64,
+    eps: f64,
+    has_max_trust_ratio: bool,
+    max_trust_ratio: f64,
+    weight_decay_type: WeightDecayType,
+    weight_decay: f64,
+}
+
+unsafe impl DeviceRepr for CudaLarsConfig {}
+
+fn lars_config_to_cuda(config: &super::LarsConfig) -> CudaLarsConfig {
+    let (momentum_type, momentum) = momentum_to_cuda(config.momentum);
+    let (weight_decay_type, weight_decay) = weight_decay_to_cuda(config.weight_decay);
+
+    CudaLarsConfig {
+        lr: config.lr,
+        momentum_type,
+        momentum,
+        trust_coefficient: config.trust_coefficient,
+        eps: config.eps,
+        has_max_trust_ratio: config.max_trust_ratio.is_some(),
+        max_trust_ratio: config.max_trust_ratio.unwrap_or_default(),
+        weight_decay_type,
+        weight_decay,
+    }
+}
+
+const PTX_SRC: &str = include_str!(concat!(env!("OUT_DIR"), "/lars.ptx"));
+
+trait HasCudaKernel<E> {
+    const MOD: &'static str;
+    const NORMS: &'static str;
+    const FWD: &'static str;
+}
+
+#[cfg(feature = "f16")]
+impl HasCudaKernel<AMP<f16>> for Cuda {
+    const MOD: &'static str = "lars_amp_f16";
+    const NORMS: &'static str = "lars_norms_amp_f16";
+    const FWD: &'static str = "lars_update_amp_f16";
+}
+
+#[cfg(feature = "f16")]
+impl HasCudaKernel<f16> for Cuda {
+    const MOD: &'static str = "lars_f16";
+    const NORMS: &'static str = "lars_norms_f16";
+    const FWD: &'static str = "lars_update_f16";
+}
+
+impl HasCudaKernel<f32> for Cuda {
+    const MOD: &'static str = "lars_f32";
+    const NORMS: &'static str = "lars_norms_f32";
+    const FWD: &'static str = "lars_update_f32";
+}
+
+impl HasCudaKernel<f64> for Cuda {
+    const MOD: &'static str = "lars_f64";
+    const NORMS: &'static str = "lars_norms_f64";
+    const FWD: &'static str = "lars_update_f64";
+}
+
+impl<E: Dtype> super::LarsKernel<E> for Cuda
+where
+    Self: HasCudaKernel<E>,
+{
+    fn lars_kernel(
+        &self,
+        cfg: &super::LarsConfig,
+        param: &mut Self::Vec,
+        momentum: &mut Self::Vec,
+        grad: &Self::Vec,
+    ) -> Result<(), Error> {
+        if !self.dev.has_func(Self::MOD, Self::FWD) {
+            self.dev
+                .load_ptx(PTX_SRC.into(), Self::MOD, &[Self::NORMS, Self::FWD])?;
+        }
+
+        let numel = param.len();
+        // squared norms of the param and of its gradient, summed by the first kernel
+        let mut norms = self.dev.alloc_zeros::<f64>(2)?;
+
+        let func = self.dev.get_func(Self::MOD, Self::NORMS).unwrap();
+        let launch = launch_cfg::<128>(numel as u32);
+        unsafe { func.launch(launch, (numel, &*param, grad, &mut norms)) }?;
+
+        let opt_cfg = lars_config_to_cuda(cfg);
+        let func = self.dev.get_func(Self::MOD, Self::FWD).unwrap();
+        let launch = launch_cfg::<128>(numel as u32);
+        let params = (opt_cfg, numel, param, momentum, grad, &norms);
+        unsafe { func.launch(launch, params) }?;
+        Ok(())
+    }
+}
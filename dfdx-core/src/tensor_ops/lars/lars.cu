@@ -0,0 +1,181 @@
+#include "cuda_utils.cuh"
+
+enum MomentumType {
+    None,
+    Classic,
+    Nesterov,
+};
+
+enum WeightDecayType {
+    WdNone,
+    L2,
+    Decoupled
+};
+
+struct LarsConfig {
+    double lr;
+    MomentumType momentum_type;
+    double momentum;
+    double trust_coefficient;
+    double eps;
+    bool has_max_trust_ratio;
+    double max_trust_ratio;
+    WeightDecayType weight_decay_type;
+    double weight_decay;
+};
+
+__device__ __forceinline__ double to_f64(float x) { return x; }
+__device__ __forceinline__ double to_f64(double x) { return x; }
+__device__ __forceinline__ double to_f64(__half x) { return __half2float(x); }
+
+// norms holds the squared norms of the parameter & of its gradient
+__device__ double trust_ratio(const LarsConfig cfg, const double *norms) {
+    double param_norm = sqrt(norms[0]);
+    double grad_norm = sqrt(norms[1]);
+    double l2 = cfg.weight_decay_type == L2 ? cfg.weight_decay : 0.0;
+    double ratio = 1.0;
+    if (param_norm > 0.0 && grad_norm > 0.0) {
+        ratio = cfg.trust_coefficient * param_norm / (grad_norm + l2 * param_norm + cfg.eps);
+    }
+    if (cfg.has_max_trust_ratio) {
+        ratio = fmin(ratio, cfg.max_trust_ratio);
+    }
+    return ratio;
+}
+
+// Sums the squared norms into norms. Expects one thread per element.
+template<typename T>
+__device__ void lars_norms(
+    const size_t numel,
+    const T* param,
+    const T* grad,
+    double* norms
+) {
+    unsigned int i = blockIdx.x * blockDim.x + threadIdx.x;
+    if (i >= numel) {
+        return;
+    }
+
+    double p = to_f64(param[i]);
+    double g = to_f64(grad[i]);
+    chunk_sum(numel, p * p, norms);
+    chunk_sum(numel, g * g, norms + 1);
+}
+
+template<typename T>
+__device__ void lars_update(
+    const LarsConfig cfg,
+    const size_t numel,
+    T* param,
+    T* velocity,
+    const T* grad,
+    const double* norms
+) {
+    T weight_decay = cfg.weight_decay;
+    T lr = cfg.lr;
+    T momentum = cfg.momentum;
+    T ratio = trust_ratio(cfg, norms);
+
+    for (unsigned int i = blockIdx.x * blockDim.x + threadIdx.x; i < numel; i += blockDim.x * gridDim.x) {
+        T p = param[i];
+        T g = grad[i];
+        T v = velocity[i];
+
+        if (cfg.weight_decay_type == L2) {
+            g += weight_decay * p;
+        }
+        g *= ratio;
+
+        if (cfg.momentum_type == Classic) {
+            v = g + momentum * v;
+            g = v * lr;
+        } else if (cfg.momentum_type == Nesterov) {
+            v = g + momentum * v;
+            g = (g + momentum * v) * lr;
+        } else {
+            g *= lr;
+        }
+
+        if (cfg.weight_decay_type == Decoupled) {
+            g += weight_decay * lr * p;
+        }
+
+        velocity[i] = v;
+        param[i] -= g;
+    }
+}
+
+#define LARS(TYPENAME, NORMS, UPDATE) \
+extern "C" __global__ void NORMS( \
+    const size_t numel, \
+    const TYPENAME* param, \
+    const TYPENAME* grad, \
+    double* norms \
+) { \
+    lars_norms(numel, param, grad, norms); \
+} \
+extern "C" __global__ void UPDATE( \
+    const LarsConfig cfg, \
+    const size_t numel, \
+    TYPENAME* param, \
+    TYPENAME* velocity, \
+    const TYPENAME* grad, \
+    const double* norms \
+) { \
+    lars_update(cfg, numel, param, velocity, grad, norms); \
+}
+
+LARS(__half, lars_norms_f16, lars_update_f16);
+LARS(float, lars_norms_f32, lars_update_f32);
+LARS(double, lars_norms_f64, lars_update_f64);
+
+extern "C" __global__ void lars_norms_amp_f16(
+    const size_t numel,
+    const __half* param,
+    const __half* grad,
+    double* norms
+) {
+    lars_norms(numel, param, grad, norms);
+}
+
+extern "C" __global__ void lars_update_amp_f16(
+    const LarsConfig cfg,
+    const size_t numel,
+    __half* param,
+    __half* velocity,
+    const __half* grad,
+    const double* norms
+) {
+    float weight_decay = cfg.weight_decay;
+    float lr = cfg.lr;
+    float momentum = cfg.momentum;
+    float ratio = trust_ratio(cfg, norms);
+
+    for (unsigned int i = blockIdx.x * blockDim.x + threadIdx.x; i < numel; i += blockDim.x * gridDim.x) {
+        float p = param[i];
+        float g = grad[i];
+        float v = velocity[i];
+
+        if (cfg.weight_decay_type == L2) {
+            g += weight_decay * p;
+        }
+        g *= ratio;
+
+        if (cfg.momentum_type == Classic) {
+            v = g + momentum * v;
+            g = v * lr;
+        } else if (cfg.momentum_type == Nesterov) {
+            v = g + momentum * v;
+            g = (g + momentum * v) * lr;
+        } else {
+            g *= lr;
+        }
+
+        if (cfg.weight_decay_type == Decoupled) {
+            g += weight_decay * lr * p;
+        }
+
+        velocity[i] = v;
+        param[i] = p - g;
+    }
+}
@@ -0,0 +1,110 @@
+mod cpu_kernel;
+
+#[cfg(feature = "cuda")]
+mod cuda_kernel;
+
+#[cfg(feature = "webgpu")]
+mod webgpu_kernel;
+
+use crate::{
+    shapes::{Dtype, Shape},
+    tensor::{Error, Storage, Tensor},
+};
+
+use super::optim::{Momentum, WeightDecay};
+
+/// Configuration of hyperparameters for LARS.
+///
+/// Changing all default parameters:
+/// ```rust
+/// # use dfdx_core::prelude::*;
+/// LarsConfig {
+///     lr: 1e-1,
+///     momentum: Some(Momentum::Nesterov(0.5)),
+///     trust_coefficient: 1e-2,
+///     eps: 1e-6,
+///     weight_decay: Some(WeightDecay::L2(1e-4)),
+///     max_trust_ratio: Some(1.0),
+/// };
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct LarsConfig {
+    /// Learning rate. Defaults to `1e-2`.
+    pub lr: f64,
+
+    /// Optional momentum, applied to the scaled gradients. Defaults to `Some(Momentum::Classic(0.9))`.
+    pub momentum: Option<Momentum>,
+
+    /// The trust coefficient `eta` that the ratio of norms is multiplied with. Defaults to `1e-3`.
+    pub trust_coefficient: f64,
+
+    /// Epsilon for numerical stability. Defaults to `1e-8`.
+    pub eps: f64,
+
+    /// Optional weight decay. Defaults to `None`.
+    ///
+    /// [WeightDecay::L2] is also part of the trust ratio, as in the LARS paper.
+    pub weight_decay: Option<WeightDecay>,
+
+    /// Optional upper bound of the trust ratio. Defaults to `None`.
+    pub max_trust_ratio: Option<f64>,
+}
+
+impl Default for LarsConfig {
+    fn default() -> Self {
+        Self {
+            lr: 1e-2,
+            momentum: Some(Momentum::Classic(0.9)),
+            trust_coefficient: 1e-3,
+            eps: 1e-8,
+            weight_decay: None,
+            max_trust_ratio: None,
+        }
+    }
+}
+
+pub trait LarsKernel<E: Dtype>: Storage<E> {
+    fn lars_kernel(
+        &self,
+        cfg: &LarsConfig,
+        param: &mut Self::Vec,
+        momentum: &mut Self::Vec,
+        grad: &Self::Vec,
+    ) -> Result<(), Error>;
+}
+
+impl LarsConfig {
+    /// The trust ratio `eta * |param| / (|grad| + l2 * |param| + eps)` of a tensor.
+    /// It is 1 if either norm is 0.
+    fn trust_ratio(&self, param_norm: f64, grad_norm: f64) -> f64 {
+        let l2 = match self.weight_decay {
+            Some(WeightDecay::L2(wd)) => wd,
+            _ => 0.0,
+        };
+        let ratio = if param_norm > 0.0 && grad_norm > 0.0 {
+            self.trust_coefficient * param_norm / (grad_norm + l2 * param_norm + self.eps)
+        } else {
+            1.0
+        };
+        match self.max_trust_ratio {
+            Some(max) => ratio.min(max),
+            None => ratio,
+        }
+    }
+
+    /// Update a single tensor using LARS. The gradient is scaled by the trust ratio
+    /// of the whole tensor.
+    pub fn try_update<S: Shape, E: Dtype, D: LarsKernel<E>>(
+        &self,
+        param: &mut Tensor<S, E, D>,
+        momentum: &mut D::Vec,
+        grad: &D::Vec,
+    ) -> Result<(), crate::tensor::Error> {
+        param.device.lars_kernel(
+            self,
+            std::sync::Arc::make_mut(&mut param.data),
+            momentum,
+            grad,
+        )
+    }
+}
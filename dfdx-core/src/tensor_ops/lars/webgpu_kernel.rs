@@ -0,0 +1,13 @@
+use crate::prelude::{Dtype, Webgpu};
+
+impl<E: Dtype> super::LarsKernel<E> for Webgpu {
+    fn lars_kernel(
+        &self,
+        cfg: &crate::prelude::LarsConfig,
+        param: &mut Self::Vec,
+        momentum: &mut Self::Vec,
+        grad: &Self::Vec,
+    ) -> Result<(), crate::prelude::Error> {
+        todo!()
+    }
+}
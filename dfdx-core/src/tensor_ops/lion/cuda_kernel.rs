@@ -0,0 +1,83 @@
+use crate::{
+    dtypes::*,
+    tensor::{launch_cfg, Cuda, Error},
+    tensor_ops::optim::*,
+};
+
+use cudarc::driver::{DeviceRepr, DeviceSlice, LaunchAsync};
+
+#[repr(C)]
+struct CudaLionConfig {
+    lr: f64,
+    beta1: f64,
+    beta2: f64,
+    weight_decay_type: WeightDecayType,
+    weight_decay: f64,
+}
+
+unsafe impl DeviceRepr for CudaLionConfig {}
+
+fn lion_config_to_cuda(config: &super::LionConfig) -> CudaLionConfig {
+    let (weight_decay_type, weight_decay) = weight_decay_to_cuda(config.weight_decay);
+
+    CudaLionConfig {
+        lr: config.lr,
+        beta1: config.betas[0],
+        beta2: config.betas[1],
+        weight_decay_type,
+        weight_decay,
+    }
+}
+
+const PTX_SRC: &str = include_str!(concat!(env!("OUT_DIR"), "/lion.ptx"));
+
+trait HasCudaKernel<E> {
+    const MOD: &'static str;
+    const FWD: &'static str;
+}
+
+#[cfg(feature = "f16")]
+impl HasCudaKernel<AMP<f16>> for Cuda {
+    const MOD: &'static str = "lion_amp_f16";
+    const FWD: &'static str = "lion_update_amp_f16";
+}
+
+#[cfg(feature = "f16")]
+impl HasCudaKernel<f16> for Cuda {
+    const MOD: &'static str = "lion_f16";
+    const FWD: &'static str = "lion_update_f16";
+}
+
+impl HasCudaKernel<f32> for Cuda {
+    const MOD: &'static str = "lion_f32";
+    const FWD: &'static str = "lion_update_f32";
+}
+
+impl HasCudaKernel<f64> for Cuda {
+    const MOD: &'static str = "lion_f64";
+    const FWD: &'static str = "lion_update_f64";
+}
+
+impl<E: Dtype> super::LionKernel<E> for Cuda
+where
+    Self: HasCudaKernel<E>,
+{
+    fn lion_kernel(
+        &self,
+        cfg: &super::LionConfig,
+        param: &mut Self::Vec,
+        momentum: &mut Self::Vec,
+        grad: &Self::Vec,
+    ) -> Result<(), Error> {
+        if !self.dev.has_func(Self::MOD, Self::FWD) {
+            self.dev.load_ptx(PTX_SRC.into(), Self::MOD, &[Self::FWD])?;
+        }
+
+        let opt_cfg = lion_config_to_cuda(cfg);
+        let numel = param.len();
+        let func = self.dev.get_func(Self::MOD, Self::FWD).unwrap();
+        let cfg = launch_cfg::<128>(numel as u32);
+        unsafe { func.launch(cfg, (opt_cfg, numel, param, momentum, grad)) }?;
+        Ok(())
+    }
+}
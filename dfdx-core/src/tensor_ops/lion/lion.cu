@@ -0,0 +1,101 @@
+#include "cuda_fp16.h"
+
+enum WeightDecayType {
+    WdNone,
+    L2,
+    Decoupled
+};
+
+struct LionConfig {
+    double lr;
+    double beta1;
+    double beta2;
+    WeightDecayType weight_decay_type;
+    double weight_decay;
+};
+
+template<typename T>
+__device__ void lion_update(
+    const LionConfig cfg,
+    const size_t numel,
+    T* param,
+    T* momentum,
+    const T* grad
+) {
+    T beta1 = cfg.beta1;
+    T beta2 = cfg.beta2;
+    T lr = cfg.lr;
+    T weight_decay = cfg.weight_decay;
+    T one = 1.0;
+    T zero = 0.0;
+
+    for (unsigned int i = blockIdx.x * blockDim.x + threadIdx.x; i < numel; i += blockDim.x * gridDim.x) {
+        T p = param[i];
+        T g = grad[i];
+        T m = momentum[i];
+
+        if (cfg.weight_decay_type == L2) {
+            g += weight_decay * p;
+        }
+
+        T c = m * beta1 + g * (one - beta1);
+        T sign = c > zero ? one : (c < zero ? -one : zero);
+        T update = lr * sign;
+
+        if (cfg.weight_decay_type == Decoupled) {
+            update += weight_decay * lr * p;
+        }
+
+        momentum[i] = m * beta2 + g * (one - beta2);
+        param[i] -= update;
+    }
+}
+
+#define LION(TYPENAME, FN) \
+extern "C" __global__ void FN( \
+    const LionConfig cfg, \
+    const size_t numel, \
+    TYPENAME* param, \
+    TYPENAME* momentum, \
+    const TYPENAME* grad \
+) { \
+    lion_update(cfg, numel, param, momentum, grad); \
+}
+
+LION(__half, lion_update_f16);
+LION(float, lion_update_f32);
+LION(double, lion_update_f64);
+
+extern "C" __global__ void lion_update_amp_f16(
+    const LionConfig cfg,
+    const size_t numel,
+    __half* param,
+    __half* momentum,
+    const __half* grad
+) {
+    float beta1 = cfg.beta1;
+    float beta2 = cfg.beta2;
+    float lr = cfg.lr;
+    float weight_decay = cfg.weight_decay;
+
+    for (unsigned int i = blockIdx.x * blockDim.x + threadIdx.x; i < numel; i += blockDim.x * gridDim.x) {
+        float p = param[i];
+        float g = grad[i];
+        float m = momentum[i];
+
+        if (cfg.weight_decay_type == L2) {
+            g += weight_decay * p;
+        }
+
+        float c = m * beta1 + g * (1.0 - beta1);
+        float sign = c > 0.0 ? 1.0 : (c < 0.0 ? -1.0 : 0.0);
+        float update = lr * sign;
+
+        if (cfg.weight_decay_type == Decoupled) {
+            update += weight_decay * lr * p;
+        }
+
+        momentum[i] = m * beta2 + g * (1.0 - beta2);
+        param[i] = p - update;
+    }
+}
@@ -7,12 +7,24 @@ mod cuda_kernel;
 mod webgpu_kernel;
 
 use super::ops::{try_unary_op, UnaryKernel};
+use super::utilities::higher_order::UnaryDerivative;
 use crate::{shapes::*, tensor::*};
 
 #[repr(C)]
 #[derive(Debug, Default, Copy, Clone)]
 pub struct LnKernelOp;
 
+impl<E> UnaryDerivative<E> for LnKernelOp {
+    fn df<D: Storage<E>>(
+        &self,
+        ops: &mut dyn HigherOrderOps<E, D>,
+        x: HigherOrderVar,
+        _y: HigherOrderVar,
+    ) -> Result<Option<HigherOrderVar>, Error> {
+        ops.recip(x).map(Some)
+    }
+}
+
 /// [Natural Logarithm (ln)](https://en.wikipedia.org/wiki/Natural_logarithm). `log_e(t)`.
 ///
 /// It's derivative is `1 / t`.
@@ -10,10 +10,11 @@ pub(super) mod webgpu_kernel;
 
 use crate::{
     shapes::{Const, Dim, Dtype, Shape},
-    tensor::{Error, Merge, PutTape, SplitTape, Storage, Tape, Tensor},
+    tensor::{Error, Merge, OpInfo, PutTape, SplitTape, Storage, Tape, Tensor},
 };
 
 use super::reshape_to::{ReshapeKernel, ReshapeTo};
+use super::utilities::higher_order::{accumulate_logical, logical_grad, logical_value};
 
 /// Matrix * Matrix, Vector * Matrix, Vector * Vector, and broadcasted/batched versions.
 ///
@@ -89,12 +90,13 @@ fn try_binary_op<
     RhsTape: Tape<E, D>,
     LhsTape: Tape<E, D> + Merge<RhsTape>,
     Fwd: 'static + FnMut(&D, &Tensor<Lhs, E, D>, &Tensor<Rhs, E, D>) -> Result<Tensor<Out, E,D>, crate::tensor::Error>,
-    Bwd: 'static + FnMut(&D, &Tensor<Lhs, E, D>, &mut D::Vec, &Tensor<Rhs, E,D>, &mut D::Vec, &D::Vec) -> Result<(), crate::tensor::Error>,
+    Bwd: 'static + Fn(&D, &Tensor<Lhs, E, D>, &mut D::Vec, &Tensor<Rhs, E,D>, &mut D::Vec, &D::Vec) -> Result<(), crate::tensor::Error>,
 >(
     lhs: Tensor<Lhs, E, D, LhsTape>,
     rhs: Tensor<Rhs, E, D, RhsTape>,
+    [batch, m, k, n]: [usize; 4],
     mut fwd: Fwd,
-    mut bwd: Bwd,
+    bwd: Bwd,
 ) -> Result<Tensor<Out, E, D, LhsTape>, crate::tensor::Error> {
     let (lhs, ltape) = lhs.split_tape();
     let (rhs, rtape) = rhs.split_tape();
@@ -103,13 +105,31 @@ fn try_binary_op<
     let rhs_ghost = rhs.ghost();
     let out = fwd(&lhs.device, &lhs, &rhs)?;
     let out_ghost = out.ghost();
-    tape.add_backward_op(move |grads| {
+    let higher_order = tape.is_higher_order().then(|| (lhs.clone(), rhs.clone(), out.ghost()));
+    tape.try_add_op_info(&out, || OpInfo::new("MatMul", &out).input(&lhs).input(&rhs))?;
+    tape.add_retainable_backward_op(move |grads| {
         grads.try_alloc_for(&lhs_ghost)?;
         grads.try_alloc_for(&rhs_ghost)?;
         grads.try_alloc_for(&out_ghost)?;
         let (grad_lhs, grad_rhs, grad_out) = grads.muts_and_ref(&lhs_ghost, &rhs_ghost, &out_ghost);
         bwd(&lhs.device, &lhs, grad_lhs, &rhs, grad_rhs, grad_out)
     });
+    if let Some((lhs, rhs, out_ghost)) = higher_order {
+        // dA = dC * B^T, dB = A^T * dC, where everything is viewed as (batch, rows, cols)
+        tape.add_higher_order_op(move |ops| {
+            let Some(grad_out) = logical_grad(ops, &out_ghost)? else {
+                return Ok(());
+            };
+            let a = logical_value(ops, &lhs)?;
+            let b = logical_value(ops, &rhs)?;
+            let b_t = ops.transpose(b, batch, k, n)?;
+            let grad_lhs = ops.matmul(grad_out, b_t, batch, m, n, k)?;
+            accumulate_logical(ops, &lhs, grad_lhs)?;
+            let a_t = ops.transpose(a, batch, m, k)?;
+            let grad_rhs = ops.matmul(a_t, grad_out, batch, k, m, n)?;
+            accumulate_logical(ops, &rhs, grad_rhs)
+        });
+    }
     Ok(out.put_tape(tape))
 }
 
@@ -190,7 +210,13 @@ where
     /// ```
     fn try_matmul(self, rhs: Tensor<(K, N), E, D, R>) -> Result<Self::Output, Error> {
         assert_eq!(self.shape.1, rhs.shape.0);
-        try_binary_op(self, rhs, D::forward, D::backward)
+        let dims = [
+            1,
+            self.shape.0.size(),
+            self.shape.1.size(),
+            rhs.shape.1.size(),
+        ];
+        try_binary_op(self, rhs, dims, D::forward, D::backward)
     }
 }
 
@@ -227,7 +253,9 @@ where
     /// ```
     fn try_matmul(self, rhs: Tensor<(K, N), E, D, R>) -> Result<Self::Output, Error> {
         assert_eq!(self.shape.2, rhs.shape.0);
-        try_binary_op(self, rhs, D::forward, D::backward)
+        let (b, m, k) = self.shape;
+        let dims = [1, b.size() * m.size(), k.size(), rhs.shape.1.size()];
+        try_binary_op(self, rhs, dims, D::forward, D::backward)
     }
 }
 
@@ -266,7 +294,9 @@ where
     fn try_matmul(self, rhs: Tensor<(B, K, N), E, D, R>) -> Result<Self::Output, Error> {
         assert_eq!(self.shape.0, rhs.shape.0);
         assert_eq!(self.shape.2, rhs.shape.1);
-        try_binary_op(self, rhs, D::forward, D::backward)
+        let (b, m, k) = self.shape;
+        let dims = [b.size(), m.size(), k.size(), rhs.shape.2.size()];
+        try_binary_op(self, rhs, dims, D::forward, D::backward)
     }
 }
 
@@ -306,7 +336,9 @@ where
         assert_eq!(self.shape.0, rhs.shape.0);
         assert_eq!(self.shape.1, rhs.shape.1);
         assert_eq!(self.shape.3, rhs.shape.2);
-        try_binary_op(self, rhs, D::forward, D::backward)
+        let (b, s, m, k) = self.shape;
+        let dims = [b.size() * s.size(), m.size(), k.size(), rhs.shape.3.size()];
+        try_binary_op(self, rhs, dims, D::forward, D::backward)
     }
 }
 
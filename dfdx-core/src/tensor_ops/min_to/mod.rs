@@ -6,6 +6,7 @@ mod cuda_kernel;
 #[cfg(feature = "webgpu")]
 mod webgpu_kernel;
 
+use super::utilities::higher_order::extremum_backward;
 use crate::{shapes::*, tensor::*};
 
 pub trait MinReduceKernel<E: Dtype>: Storage<E> {
@@ -74,12 +75,19 @@ impl<S: Shape, E: Dtype, D: MinReduceKernel<E>, T: Tape<E, D>> MinTo for Tensor<
         let inp_ghost = inp.ghost();
         let out_ghost = out.ghost();
         let out_clone = out.clone();
-        tape.add_backward_op(move |grads| {
+        let higher_order = tape.is_higher_order().then(|| (inp.clone(), out.clone()));
+        tape.try_add_op_info(&out, || OpInfo::new("Min", &out).input(&inp))?;
+        tape.add_retainable_backward_op(move |grads| {
             grads.try_alloc_for(&inp_ghost)?;
             grads.try_alloc_for(&out_ghost)?;
             let (grad_inp, grad_out) = grads.mut_and_ref(&inp_ghost, &out_ghost);
             inp.device.backward(&inp, grad_inp, &out_clone, grad_out)
         });
+        if let Some((inp, out)) = higher_order {
+            tape.add_higher_order_op(move |ops| {
+                extremum_backward::<S, Dst, Ax, E, D>(ops, &inp, &out)
+            });
+        }
         Ok(out.put_tape(tape))
     }
 }
@@ -6,6 +6,7 @@ mod cuda_kernel;
 #[cfg(feature = "webgpu")]
 mod webgpu_kernel;
 
+use super::utilities::higher_order::BinaryDerivative;
 use super::{ops::try_binary_op, Device};
 use crate::{shapes::*, tensor::*};
 
@@ -13,6 +14,17 @@ use crate::{shapes::*, tensor::*};
 #[derive(Debug, Default, Clone, Copy)]
 pub struct MinimumKernelOp;
 
+impl<E> BinaryDerivative<E> for MinimumKernelOp {
+    fn df<D: Storage<E>>(
+        &self,
+        _ops: &mut dyn HigherOrderOps<E, D>,
+        _x: HigherOrderVar,
+        _y: HigherOrderVar,
+    ) -> Result<Option<(HigherOrderVar, HigherOrderVar)>, Error> {
+        Ok(None)
+    }
+}
+
 /// Element wise minimum.
 ///
 /// **Pytorch equivalent**: `torch.minimum(a, b)`
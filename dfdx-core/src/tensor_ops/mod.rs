@@ -56,6 +56,8 @@
 //!
 //! Complete list of reductions:
 //!
+//! - [ArgMaxTo]
+//! - [ArgMinTo]
 //! - [MaxTo]
 //! - [MeanTo]
 //! - [MinTo]
@@ -145,19 +147,46 @@
 //! let r = t.select::<Rank1<2>, _>(dev.tensor(1).broadcast());
 //! assert_eq!(r.array(), [2.0, 5.0]);
 //! ```
+//!
+//! The inverse, writing values at indices along an axis, is done with
+//! [ScatterTo::scatter], [ScatterTo::scatter_add] and [IndexAdd::index_add].
+//!
+//! [SortAlong] sorts along an axis, returning the values or the indices that sort them,
+//! and finds the [SortAlong::topk] largest values.
+//!
+//! [CumulativeAlong] computes running sums, products, extrema and log-sum-exps along an axis.
+//!
+//! # Einsum
+//!
+//! [TryEinsum] contracts one or two tensors with subscripts like `"bhqd,bhkd->bhqk"`,
+//! instead of chaining [PermuteTo], [ReshapeTo] and [TryMatMul]:
+//! ```rust
+//! # use dfdx_core::prelude::*;
+//! # let dev: Cpu = Default::default();
+//! let a: Tensor<Rank2<2, 3>, f32, _> = dev.tensor([[1.0, 2.0, 3.0], [4.0, 5.0, 6.0]]);
+//! let b: Tensor<Rank1<3>, f32, _> = dev.tensor([1.0, 0.0, -1.0]);
+//! let r: Tensor<Rank1<2>, f32, _> = (a, b).einsum("ij,j->i");
+//! assert_eq!(r.array(), [-2.0, -2.0]);
+//! ```
 
 mod utilities;
 pub use utilities::*;
 
 mod abs;
 mod accurate_gelu;
+mod adadelta;
+mod adafactor;
+mod adagrad;
 mod adam;
+mod adamax;
 mod add;
+mod arg_reduce_to;
 mod attention_reshape;
 pub(crate) mod axpy;
 mod bce;
 mod boolean;
 mod broadcast_to;
+mod checkpoint;
 mod choose;
 mod clamp;
 mod cmp;
@@ -166,11 +195,18 @@ mod concat_along;
 mod concat_shape_along;
 mod concat_tensor_along;
 mod cos;
+mod cumulative;
+mod custom_op;
 mod div;
 mod dropout;
+mod einsum;
 mod exp;
 mod fast_gelu;
 mod huber_error;
+mod jacobian;
+mod lamb;
+mod lars;
+mod lion;
 mod ln;
 mod log_softmax;
 mod logsumexp_to;
@@ -181,6 +217,7 @@ mod mean_to;
 mod min_to;
 mod minimum;
 mod mul;
+mod nadam;
 mod nans_to;
 mod negate;
 mod normalize;
@@ -188,18 +225,21 @@ pub(super) mod optim;
 mod permute_to;
 mod pow;
 mod prelu;
+mod radam;
 mod realize_to;
 mod recip;
 mod relu;
 mod reshape_to;
 mod rmsprop;
 mod roll;
+mod scatter;
 mod select_and_gather;
 mod sgd;
 mod sigmoid;
 mod sin;
 mod slice;
 mod softmax;
+mod sort;
 mod sqrt;
 mod square;
 mod stack;
@@ -214,13 +254,19 @@ mod var_to;
 
 pub use abs::abs;
 pub use accurate_gelu::accurate_gelu;
+pub use adadelta::AdadeltaConfig;
+pub use adafactor::AdafactorConfig;
+pub use adagrad::AdaGradConfig;
 pub use adam::AdamConfig;
+pub use adamax::AdamaxConfig;
 pub use add::{add, TryAdd};
+pub use arg_reduce_to::{ArgMaxTo, ArgMinTo};
 pub use attention_reshape::TryAttentionReshape;
 pub use axpy::axpy;
 pub use bce::bce_with_logits;
 pub use boolean::{bool_and, bool_not, bool_or, bool_xor};
 pub use broadcast_to::BroadcastTo;
+pub use checkpoint::try_checkpoint;
 pub use choose::ChooseFrom;
 pub use clamp::clamp;
 pub use cmp::{eq, ge, gt, le, lt, ne, TryEq, TryGe, TryGt, TryLe, TryLt, TryNe};
@@ -231,13 +277,22 @@ pub use concat_along::TryConcatAlong;
 pub use concat_shape_along::TryConcatShapeAlong;
 pub use concat_tensor_along::TryConcatTensorAlong;
 pub use cos::cos;
+pub use cumulative::CumulativeAlong;
+pub use custom_op::{custom_binary_op, custom_op, try_custom_binary_op, try_custom_op};
 pub use div::{div, TryDiv};
 pub use dropout::dropout;
+pub use einsum::{Subscripts, TryEinsum};
 pub use exp::exp;
 pub use fast_gelu::fast_gelu;
 #[allow(deprecated)]
 pub use fast_gelu::gelu;
 pub use huber_error::huber_error;
+#[cfg(feature = "std")]
+pub use jacobian::{hessian, try_hessian};
+pub use jacobian::{jacobian, try_jacobian, try_vjp, vjp};
+pub use lamb::LambConfig;
+pub use lars::LarsConfig;
+pub use lion::LionConfig;
 pub use ln::ln;
 pub use log_softmax::log_softmax;
 pub use logsumexp_to::LogSumExpTo;
@@ -248,6 +303,7 @@ pub use mean_to::MeanTo;
 pub use min_to::MinTo;
 pub use minimum::minimum;
 pub use mul::{mul, TryMul};
+pub use nadam::NAdamConfig;
 pub use nans_to::nans_to;
 pub use negate::negate;
 pub use normalize::normalize;
@@ -255,18 +311,21 @@ pub use optim::*;
 pub use permute_to::PermuteTo;
 pub use pow::{powf, powi};
 pub use prelu::{leakyrelu, prelu, TryPReLU};
+pub use radam::RAdamConfig;
 pub use realize_to::RealizeTo;
 pub use recip::recip;
 pub use relu::relu;
 pub use reshape_to::ReshapeTo;
 pub use rmsprop::RMSpropConfig;
 pub use roll::Roll;
+pub use scatter::{IndexAdd, ScatterTo};
 pub use select_and_gather::{GatherTo, SelectTo};
 pub use sgd::SgdConfig;
 pub use sigmoid::sigmoid;
 pub use sin::sin;
 pub use slice::slice;
 pub use softmax::softmax;
+pub use sort::SortAlong;
 pub use sqrt::sqrt;
 pub use square::square;
 pub use stack::{AddDim, TryStack};
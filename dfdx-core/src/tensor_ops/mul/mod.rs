@@ -7,18 +7,41 @@ mod cuda_kernel;
 mod webgpu_kernel;
 
 use super::ops::*;
+use super::utilities::higher_order::{BinaryDerivative, UnaryDerivative};
 use crate::{shapes::*, tensor::*};
 
 #[repr(C)]
 #[derive(Debug, Default, Clone, Copy)]
 pub struct BinaryMulKernelOp;
 
+impl<E> BinaryDerivative<E> for BinaryMulKernelOp {
+    fn df<D: Storage<E>>(
+        &self,
+        _ops: &mut dyn HigherOrderOps<E, D>,
+        x: HigherOrderVar,
+        y: HigherOrderVar,
+    ) -> Result<Option<(HigherOrderVar, HigherOrderVar)>, Error> {
+        Ok(Some((y, x)))
+    }
+}
+
 #[repr(C)]
 #[derive(Debug, Clone, Copy)]
 pub struct ScalarMulKernelOp<E> {
     scalar: E,
 }
 
+impl<E> UnaryDerivative<E> for ScalarMulKernelOp<E> {
+    fn df<D: Storage<E>>(
+        &self,
+        _ops: &mut dyn HigherOrderOps<E, D>,
+        _x: HigherOrderVar,
+        _y: HigherOrderVar,
+    ) -> Result<Option<HigherOrderVar>, Error> {
+        Ok(None)
+    }
+}
+
 /// Element wise and scalar multiplication.
 ///
 /// Example:
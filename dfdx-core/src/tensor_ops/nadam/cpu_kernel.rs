@@ -0,0 +1,96 @@
+use super::{NAdamConfig, NAdamKernel, WeightDecay};
+use crate::{
+    dtypes::{Dtype, NotMixedPrecision},
+    tensor::{Cpu, Error},
+};
+
+#[cfg(feature = "f16")]
+impl NAdamKernel<crate::dtypes::AMP<crate::dtypes::f16>> for Cpu {
+    fn nadam_kernel(
+        &self,
+        t: i32,
+        mu_product: f64,
+        cfg: &NAdamConfig,
+        param: &mut Self::Vec,
+        moment1: &mut Self::Vec,
+        moment2: &mut Self::Vec,
+        grad: &Self::Vec,
+    ) -> Result<(), Error> {
+        let betas = cfg.betas.map(|x| x as f32);
+        let eps = cfg.eps as f32;
+        let [grad_coef, moment_coef] = cfg.coefficients(t, mu_product).map(|x| x as f32);
+        let bias_correction2 = 1.0 - betas[1].powi(t);
+
+        for ((p, g), (m, v)) in param
+            .iter_mut()
+            .zip(grad.iter().cloned())
+            .zip(moment1.iter_mut().zip(moment2.iter_mut()))
+        {
+            let p_f32 = p.0.to_f32();
+            let mut g_f32 = g.0.to_f32();
+            let mut m_f32 = m.0.to_f32();
+            let mut v_f32 = v.0.to_f32();
+
+            if let Some(WeightDecay::L2(wd)) = cfg.weight_decay {
+                g_f32 += (wd as f32) * p_f32;
+            }
+
+            m_f32 = m_f32 * betas[0] + g_f32 * (1.0 - betas[0]);
+            v_f32 = v_f32 * betas[1] + g_f32.powi(2) * (1.0 - betas[1]);
+            let denom = (v_f32 / bias_correction2).sqrt() + eps;
+            g_f32 = (grad_coef * g_f32 + moment_coef * m_f32) / denom;
+
+            if let Some(WeightDecay::Decoupled(wd)) = cfg.weight_decay {
+                g_f32 += (wd * cfg.lr) as f32 * p_f32;
+            }
+
+            p.0 = crate::dtypes::f16::from_f32(p_f32 - g_f32);
+            m.0 = crate::dtypes::f16::from_f32(m_f32);
+            v.0 = crate::dtypes::f16::from_f32(v_f32);
+        }
+        Ok(())
+    }
+}
+
+impl<E: num_traits::Float + Dtype + NotMixedPrecision> NAdamKernel<E> for Cpu {
+    fn nadam_kernel(
+        &self,
+        t: i32,
+        mu_product: f64,
+        cfg: &NAdamConfig,
+        param: &mut Self::Vec,
+        moment1: &mut Self::Vec,
+        moment2: &mut Self::Vec,
+        grad: &Self::Vec,
+    ) -> Result<(), Error> {
+        let betas = cfg.betas.map(E::from_f64).map(Option::unwrap);
+        let eps = E::from_f64(cfg.eps).unwrap();
+        let [grad_coef, moment_coef] = cfg
+            .coefficients(t, mu_product)
+            .map(E::from_f64)
+            .map(Option::unwrap);
+        let bias_correction2 = E::one() - betas[1].powi(t);
+
+        for ((p, mut g), (m, v)) in param
+            .iter_mut()
+            .zip(grad.iter().cloned())
+            .zip(moment1.iter_mut().zip(moment2.iter_mut()))
+        {
+            if let Some(WeightDecay::L2(wd)) = cfg.weight_decay {
+                g += E::from_f64(wd).unwrap() * *p;
+            }
+
+            *m = *m * betas[0] + g * (E::one() - betas[0]);
+            *v = *v * betas[1] + g.powi(2) * (E::one() - betas[1]);
+            let denom = (*v / bias_correction2).sqrt() + eps;
+            g = (grad_coef * g + moment_coef * *m) / denom;
+
+            if let Some(WeightDecay::Decoupled(wd)) = cfg.weight_decay {
+                g += E::from_f64(wd * cfg.lr).unwrap() * *p;
+            }
+
+            *p -= g;
+        }
+        Ok(())
+    }
+}
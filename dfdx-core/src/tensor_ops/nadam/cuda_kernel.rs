@@ -0,0 +1,96 @@
+use crate::{
+    dtypes::*,
+    tensor::{launch_cfg, Cuda, Error},
+    tensor_ops::optim::*,
+};
+
+use cudarc::driver::{DeviceRepr, DeviceSlice, LaunchAsync};
+
+#[repr(C)]
+struct CudaNAdamConfig {
+    lr: f64,
+    beta1: f64,
+    beta2: f64,
+    eps: f64,
+    grad_coef: f64,
+    moment_coef: f64,
+    bias_correction2: f64,
+    weight_decay_type: WeightDecayType,
+    weight_decay: f64,
+}
+
+unsafe impl DeviceRepr for CudaNAdamConfig {}
+
+fn nadam_config_to_cuda(t: i32, mu_product: f64, config: &super::NAdamConfig) -> CudaNAdamConfig {
+    let (weight_decay_type, weight_decay) = weight_decay_to_cuda(config.weight_decay);
+    let [grad_coef, moment_coef] = config.coefficients(t, mu_product);
+
+    CudaNAdamConfig {
+        lr: config.lr,
+        beta1: config.betas[0],
+        beta2: config.betas[1],
+        eps: config.eps,
+        grad_coef,
+        moment_coef,
+        bias_correction2: 1.0 - config.betas[1].powi(t),
+        weight_decay_type,
+        weight_decay,
+    }
+}
+
+const PTX_SRC: &str = include_str!(concat!(env!("OUT_DIR"), "/nadam.ptx"));
+
+trait HasCudaKernel<E> {
+    const MOD: &'static str;
+    const FWD: &'static str;
+}
+
+#[cfg(feature = "f16")]
+impl HasCudaKernel<AMP<f16>> for Cuda {
+    const MOD: &'static str = "nadam_amp_f16";
+    const FWD: &'static str = "nadam_update_amp_f16";
+}
+
+#[cfg(feature = "f16")]
+impl HasCudaKernel<f16> for Cuda {
+    const MOD: &'static str = "nadam_f16";
+    const FWD: &'static str = "nadam_update_f16";
+}
+
+impl HasCudaKernel<f32> for Cuda {
+    const MOD: &'static str = "nadam_f32";
+    const FWD: &'static str = "nadam_update_f32";
+}
+
+impl HasCudaKernel<f64> for Cuda {
+    const MOD: &'static str = "nadam_f64";
+    const FWD: &'static str = "nadam_update_f64";
+}
+
+impl<E: Dtype> super::NAdamKernel<E> for Cuda
+where
+    Self: HasCudaKernel<E>,
+{
+    fn nadam_kernel(
+        &self,
+        t: i32,
+        mu_product: f64,
+        cfg: &super::NAdamConfig,
+        param: &mut Self::Vec,
+        moment1: &mut Self::Vec,
+        moment2: &mut Self::Vec,
+        grad: &Self::Vec,
+    ) -> Result<(), Error> {
+        if !self.dev.has_func(Self::MOD, Self::FWD) {
+            self.dev.load_ptx(PTX_SRC.into(), Self::MOD, &[Self::FWD])?;
+        }
+
+        let opt_cfg = nadam_config_to_cuda(t, mu_product, cfg);
+        let numel = param.len();
+        let func = self.dev.get_func(Self::MOD, Self::FWD).unwrap();
+        let cfg = launch_cfg::<128>(numel as u32);
+        let params = (opt_cfg, numel, param, moment1, moment2, grad);
+        unsafe { func.launch(cfg, params) }?;
+        Ok(())
+    }
+}
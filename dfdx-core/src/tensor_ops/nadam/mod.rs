@@ -0,0 +1,115 @@
+mod cpu_kernel;
+
+#[cfg(feature = "cuda")]
+mod cuda_kernel;
+
+#[cfg(feature = "webgpu")]
+mod webgpu_kernel;
+
+use crate::{
+    shapes::{Dtype, Shape},
+    tensor::{Error, Storage, Tensor},
+};
+
+use super::WeightDecay;
+
+/// Configuration of hyperparameters for NAdam.
+///
+/// Changing all default parameters:
+/// ```rust
+/// # use dfdx_core::prelude::*;
+/// NAdamConfig {
+///     lr: 1e-2,
+///     betas: [0.1, 0.2],
+///     eps: 1e-6,
+///     momentum_decay: 1e-3,
+///     weight_decay: Some(WeightDecay::L2(1e-1)),
+/// };
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct NAdamConfig {
+    /// Learning rate. Defaults to `2e-3`.
+    pub lr: f64,
+
+    /// Coefficients of the running averages of gradients and squared gradients.
+    /// Defaults to `[0.9, 0.999]`.
+    pub betas: [f64; 2],
+
+    /// Epsilon for numerical stability. Defaults to `1e-8`.
+    pub eps: f64,
+
+    /// Decay of the momentum schedule, see [Timothy Dozat, 2016](https://openreview.net/forum?id=OM0jvwB8jIp57ZJjtNEZ).
+    /// Defaults to `4e-3`.
+    pub momentum_decay: f64,
+
+    /// Optional weight decay. Defaults to `None`.
+    pub weight_decay: Option<WeightDecay>,
+}
+
+impl Default for NAdamConfig {
+    fn default() -> Self {
+        Self {
+            lr: 2e-3,
+            betas: [0.9, 0.999],
+            eps: 1e-8,
+            momentum_decay: 4e-3,
+            weight_decay: None,
+        }
+    }
+}
+
+pub trait NAdamKernel<E: Dtype>: Storage<E> {
+    /// `mu_product` is the product of the momentum coefficients up to & including step `t`.
+    #[allow(clippy::too_many_arguments)]
+    fn nadam_kernel(
+        &self,
+        t: i32,
+        mu_product: f64,
+        cfg: &NAdamConfig,
+        param: &mut Self::Vec,
+        moment1: &mut Self::Vec,
+        moment2: &mut Self::Vec,
+        grad: &Self::Vec,
+    ) -> Result<(), Error>;
+}
+
+impl NAdamConfig {
+    /// The momentum coefficient at step `t`.
+    fn mu(&self, t: i32) -> f64 {
+        self.betas[0] * (1.0 - 0.5 * 0.96f64.powf(t as f64 * self.momentum_decay))
+    }
+
+    /// The coefficients of the gradient & of the first moment in the update at step `t`,
+    /// before dividing by the root of the second moment.
+    fn coefficients(&self, t: i32, mu_product: f64) -> [f64; 2] {
+        let mu = self.mu(t);
+        let mu_next = self.mu(t + 1);
+        [
+            self.lr * (1.0 - mu) / (1.0 - mu_product),
+            self.lr * mu_next / (1.0 - mu_product * mu_next),
+        ]
+    }
+
+    /// Update a single tensor using NAdam. `mu_product` is the product of the momentum
+    /// coefficients of the previous steps, and is updated for step `t`. It starts at `1.0`.
+    pub fn try_update<S: Shape, E: Dtype, D: NAdamKernel<E>>(
+        &self,
+        t: i32,
+        mu_product: &mut f64,
+        param: &mut Tensor<S, E, D>,
+        moment1: &mut D::Vec,
+        moment2: &mut D::Vec,
+        grad: &D::Vec,
+    ) -> Result<(), crate::tensor::Error> {
+        *mu_product *= self.mu(t);
+        param.device.nadam_kernel(
+            t,
+            *mu_product,
+            self,
+            std::sync::Arc::make_mut(&mut param.data),
+            moment1,
+            moment2,
+            grad,
+        )
+    }
+}
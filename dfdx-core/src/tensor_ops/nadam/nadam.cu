@@ -0,0 +1,120 @@
+#include "cuda_utils.cuh"
+
+enum WeightDecayType {
+    WdNone,
+    L2,
+    Decoupled
+};
+
+struct NAdamConfig {
+    double lr;
+    double beta1;
+    double beta2;
+    double eps;
+    double grad_coef;
+    double moment_coef;
+    double bias_correction2;
+    WeightDecayType weight_decay_type;
+    double weight_decay;
+};
+
+template<typename T>
+__device__ void nadam_update(
+    const NAdamConfig cfg,
+    const size_t numel,
+    T* param,
+    T* moment1,
+    T* moment2,
+    const T* grad
+) {
+    T beta1 = cfg.beta1;
+    T beta2 = cfg.beta2;
+    T lr = cfg.lr;
+    T weight_decay = cfg.weight_decay;
+    T eps = cfg.eps;
+    T grad_coef = cfg.grad_coef;
+    T moment_coef = cfg.moment_coef;
+    T bias_correction2 = cfg.bias_correction2;
+    T one = 1.0;
+    for (unsigned int i = blockIdx.x * blockDim.x + threadIdx.x; i < numel; i += blockDim.x * gridDim.x) {
+        T p = param[i];
+        T g = grad[i];
+        T m = moment1[i];
+        T v = moment2[i];
+
+        if (cfg.weight_decay_type == L2) {
+            g += weight_decay * p;
+        }
+
+        m = m * beta1 + g * (one - beta1);
+        v = v * beta2 + g * g * (one - beta2);
+        T denom = sqrtg(v / bias_correction2) + eps;
+        g = (grad_coef * g + moment_coef * m) / denom;
+
+        if (cfg.weight_decay_type == Decoupled) {
+            g += (weight_decay * lr) * p;
+        }
+
+        moment1[i] = m;
+        moment2[i] = v;
+        param[i] -= g;
+    }
+}
+
+#define NADAM(TYPENAME, FN) \
+extern "C" __global__ void FN( \
+    const NAdamConfig cfg, \
+    const size_t numel, \
+    TYPENAME* param, \
+    TYPENAME* moment1, \
+    TYPENAME* moment2, \
+    const TYPENAME* grad \
+) { \
+    nadam_update(cfg, numel, param, moment1, moment2, grad); \
+}
+
+NADAM(__half, nadam_update_f16);
+NADAM(float, nadam_update_f32);
+NADAM(double, nadam_update_f64);
+
+extern "C" __global__ void nadam_update_amp_f16(
+    const NAdamConfig cfg,
+    const size_t numel,
+    __half* param,
+    __half* moment1,
+    __half* moment2,
+    const __half* grad
+) {
+    float beta1 = cfg.beta1;
+    float beta2 = cfg.beta2;
+    float lr = cfg.lr;
+    float weight_decay = cfg.weight_decay;
+    float eps = cfg.eps;
+    float grad_coef = cfg.grad_coef;
+    float moment_coef = cfg.moment_coef;
+    float bias_correction2 = cfg.bias_correction2;
+    float one = 1.0;
+    for (unsigned int i = blockIdx.x * blockDim.x + threadIdx.x; i < numel; i += blockDim.x * gridDim.x) {
+        float p = param[i];
+        float g = grad[i];
+        float m = moment1[i];
+        float v = moment2[i];
+
+        if (cfg.weight_decay_type == L2) {
+            g += weight_decay * p;
+        }
+
+        m = m * beta1 + g * (one - beta1);
+        v = v * beta2 + g * g * (one - beta2);
+        float denom = sqrtg(v / bias_correction2) + eps;
+        g = (grad_coef * g + moment_coef * m) / denom;
+
+        if (cfg.weight_decay_type == Decoupled) {
+            g += (weight_decay * lr) * p;
+        }
+
+        moment1[i] = m;
+        moment2[i] = v;
+        param[i] -= g;
+    }
+}
@@ -0,0 +1,16 @@
+use crate::prelude::{Dtype, Webgpu};
+
+impl<E: Dtype> super::NAdamKernel<E> for Webgpu {
+    fn nadam_kernel(
+        &self,
+        t: i32,
+        mu_product: f64,
+        cfg: &crate::prelude::NAdamConfig,
+        param: &mut Self::Vec,
+        moment1: &mut Self::Vec,
+        moment2: &mut Self::Vec,
+        grad: &Self::Vec,
+    ) -> Result<(), crate::prelude::Error> {
+        todo!()
+    }
+}
@@ -7,12 +7,24 @@ mod cuda_kernel;
 mod webgpu_kernel;
 
 use super::ops::{try_unary_op, UnaryKernel};
+use super::utilities::higher_order::UnaryDerivative;
 use crate::{shapes::*, tensor::*};
 
 #[repr(C)]
 #[derive(Debug, Clone, Copy)]
 pub struct NansToKernelOp<E>(E);
 
+impl<E> UnaryDerivative<E> for NansToKernelOp<E> {
+    fn df<D: Storage<E>>(
+        &self,
+        _ops: &mut dyn HigherOrderOps<E, D>,
+        _x: HigherOrderVar,
+        _y: HigherOrderVar,
+    ) -> Result<Option<HigherOrderVar>, Error> {
+        Ok(None)
+    }
+}
+
 /// Replaces any [std::f32::NAN] with `value`.
 ///
 /// **Pytorch equivalent**: `t.nan_to_num(value)`
@@ -206,7 +206,8 @@ where
         let img_ghost = img.ghost();
         let out_ghost = out.ghost();
         let out_clone = out.clone();
-        tape.add_backward_op(move |grads| {
+        tape.try_add_op_info(&out, || OpInfo::new("Pool2D", &out).input(&img))?;
+        tape.add_retainable_backward_op(move |grads| {
             grads.try_alloc_for(&img_ghost)?;
             grads.try_alloc_for(&out_ghost)?;
             let (grad_img, grad_out) = grads.mut_and_ref(&img_ghost, &out_ghost);
@@ -7,16 +7,42 @@ mod cuda_kernel;
 mod webgpu_kernel;
 
 use super::ops::{try_unary_op, UnaryKernel};
+use super::utilities::higher_order::UnaryDerivative;
 use crate::{shapes::*, tensor::*};
 
 #[repr(C)]
 #[derive(Debug, Clone, Copy)]
 pub struct PowiKernelOp(i32);
 
+impl<E> UnaryDerivative<E> for PowiKernelOp {
+    fn df<D: Storage<E>>(
+        &self,
+        ops: &mut dyn HigherOrderOps<E, D>,
+        x: HigherOrderVar,
+        _y: HigherOrderVar,
+    ) -> Result<Option<HigherOrderVar>, Error> {
+        let df = ops.powi(x, self.0 - 1)?;
+        ops.scale(df, self.0 as f64).map(Some)
+    }
+}
+
 #[repr(C)]
 #[derive(Debug, Clone, Copy)]
 pub struct PowfKernelOp<E>(E);
 
+impl<E: Dtype> UnaryDerivative<E> for PowfKernelOp<E> {
+    fn df<D: Storage<E>>(
+        &self,
+        ops: &mut dyn HigherOrderOps<E, D>,
+        x: HigherOrderVar,
+        _y: HigherOrderVar,
+    ) -> Result<Option<HigherOrderVar>, Error> {
+        let p = self.0.to_f64().unwrap();
+        let df = ops.powf(x, p - 1.0)?;
+        ops.scale(df, p).map(Some)
+    }
+}
+
 /// Raises to a float power; `t^i`.
 /// ```rust
 /// # use dfdx_core::prelude::*;
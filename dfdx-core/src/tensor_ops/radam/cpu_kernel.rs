@@ -0,0 +1,101 @@
+use super::{RAdamConfig, RAdamKernel, WeightDecay};
+use crate::{
+    dtypes::{Dtype, NotMixedPrecision},
+    tensor::{Cpu, Error},
+};
+
+#[cfg(feature = "f16")]
+impl RAdamKernel<crate::dtypes::AMP<crate::dtypes::f16>> for Cpu {
+    fn radam_kernel(
+        &self,
+        t: i32,
+        cfg: &RAdamConfig,
+        param: &mut Self::Vec,
+        moment1: &mut Self::Vec,
+        moment2: &mut Self::Vec,
+        grad: &Self::Vec,
+    ) -> Result<(), Error> {
+        let betas = cfg.betas.map(|x| x as f32);
+        let eps = cfg.eps as f32;
+        let lr = cfg.lr as f32;
+        let rect = cfg.rectification(t).map(|x| x as f32);
+        let bias_correction1 = 1.0 - betas[0].powi(t);
+        let bias_correction2 = 1.0 - betas[1].powi(t);
+
+        for ((p, g), (m, v)) in param
+            .iter_mut()
+            .zip(grad.iter().cloned())
+            .zip(moment1.iter_mut().zip(moment2.iter_mut()))
+        {
+            let p_f32 = p.0.to_f32();
+            let mut g_f32 = g.0.to_f32();
+            let mut m_f32 = m.0.to_f32();
+            let mut v_f32 = v.0.to_f32();
+
+            if let Some(WeightDecay::L2(wd)) = cfg.weight_decay {
+                g_f32 += (wd as f32) * p_f32;
+            }
+
+            m_f32 = m_f32 * betas[0] + g_f32 * (1.0 - betas[0]);
+            v_f32 = v_f32 * betas[1] + g_f32.powi(2) * (1.0 - betas[1]);
+            let m_hat = m_f32 / bias_correction1;
+            g_f32 = match rect {
+                Some(rect) => lr * m_hat * rect * bias_correction2.sqrt() / (v_f32.sqrt() + eps),
+                None => lr * m_hat,
+            };
+
+            if let Some(WeightDecay::Decoupled(wd)) = cfg.weight_decay {
+                g_f32 += (wd * cfg.lr) as f32 * p_f32;
+            }
+
+            p.0 = crate::dtypes::f16::from_f32(p_f32 - g_f32);
+            m.0 = crate::dtypes::f16::from_f32(m_f32);
+            v.0 = crate::dtypes::f16::from_f32(v_f32);
+        }
+        Ok(())
+    }
+}
+
+impl<E: num_traits::Float + Dtype + NotMixedPrecision> RAdamKernel<E> for Cpu {
+    fn radam_kernel(
+        &self,
+        t: i32,
+        cfg: &RAdamConfig,
+        param: &mut Self::Vec,
+        moment1: &mut Self::Vec,
+        moment2: &mut Self::Vec,
+        grad: &Self::Vec,
+    ) -> Result<(), Error> {
+        let betas = cfg.betas.map(E::from_f64).map(Option::unwrap);
+        let eps = E::from_f64(cfg.eps).unwrap();
+        let lr = E::from_f64(cfg.lr).unwrap();
+        let rect = cfg.rectification(t).map(|x| E::from_f64(x).unwrap());
+        let bias_correction1 = E::one() - betas[0].powi(t);
+        let bias_correction2 = E::one() - betas[1].powi(t);
+
+        for ((p, mut g), (m, v)) in param
+            .iter_mut()
+            .zip(grad.iter().cloned())
+            .zip(moment1.iter_mut().zip(moment2.iter_mut()))
+        {
+            if let Some(WeightDecay::L2(wd)) = cfg.weight_decay {
+                g += E::from_f64(wd).unwrap() * *p;
+            }
+
+            *m = *m * betas[0] + g * (E::one() - betas[0]);
+            *v = *v * betas[1] + g.powi(2) * (E::one() - betas[1]);
+            let m_hat = *m / bias_correction1;
+            g = match rect {
+                Some(rect) => lr * m_hat * rect * bias_correction2.sqrt() / (v.sqrt() + eps),
+                None => lr * m_hat,
+            };
+
+            if let Some(WeightDecay::Decoupled(wd)) = cfg.weight_decay {
+                g += E::from_f64(wd * cfg.lr).unwrap() * *p;
+            }
+
+            *p -= g;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,96 @@
+use crate::{
+    dtypes::*,
+    tensor::{launch_cfg, Cuda, Error},
+    tensor_ops::optim::*,
+};
+
+use cudarc::driver::{DeviceRepr, DeviceSlice, LaunchAsync};
+
+#[repr(C)]
+struct CudaRAdamConfig {
+    lr: f64,
+    beta1: f64,
+    beta2: f64,
+    eps: f64,
+    rectified: bool,
+    rect: f64,
+    weight_decay_type: WeightDecayType,
+    weight_decay: f64,
+}
+
+unsafe impl DeviceRepr for CudaRAdamConfig {}
+
+fn radam_config_to_cuda(t: i32, config: &super::RAdamConfig) -> CudaRAdamConfig {
+    let (weight_decay_type, weight_decay) = weight_decay_to_cuda(config.weight_decay);
+    let (rectified, rect) = match config.rectification(t) {
+        Some(rect) => (true, rect),
+        None => (false, Default::default()),
+    };
+
+    CudaRAdamConfig {
+        lr: config.lr,
+        beta1: config.betas[0],
+        beta2: config.betas[1],
+        eps: config.eps,
+        rectified,
+        rect,
+        weight_decay_type,
+        weight_decay,
+    }
+}
+
+const PTX_SRC: &str = include_str!(concat!(env!("OUT_DIR"), "/radam.ptx"));
+
+trait HasCudaKernel<E> {
+    const MOD: &'static str;
+    const FWD: &'static str;
+}
+
+#[cfg(feature = "f16")]
+impl HasCudaKernel<AMP<f16>> for Cuda {
+    const MOD: &'static str = "radam_amp_f16";
+    const FWD: &'static str = "radam_update_amp_f16";
+}
+
+#[cfg(feature = "f16")]
+impl HasCudaKernel<f16> for Cuda {
+    const MOD: &'static str = "radam_f16";
+    const FWD: &'static str = "radam_update_f16";
+}
+
+impl HasCudaKernel<f32> for Cuda {
+    const MOD: &'static str = "radam_f32";
+    const FWD: &'static str = "radam_update_f32";
+}
+
+impl HasCudaKernel<f64> for Cuda {
+    const MOD: &'static str = "radam_f64";
+    const FWD: &'static str = "radam_update_f64";
+}
+
+impl<E: Dtype> super::RAdamKernel<E> for Cuda
+where
+    Self: HasCudaKernel<E>,
+{
+    fn radam_kernel(
+        &self,
+        t: i32,
+        cfg: &super::RAdamConfig,
+        param: &mut Self::Vec,
+        moment1: &mut Self::Vec,
+        moment2: &mut Self::Vec,
+        grad: &Self::Vec,
+    ) -> Result<(), Error> {
+        if !self.dev.has_func(Self::MOD, Self::FWD) {
+            self.dev.load_ptx(PTX_SRC.into(), Self::MOD, &[Self::FWD])?;
+        }
+
+        let opt_cfg = radam_config_to_cuda(t, cfg);
+        let numel = param.len();
+        let func = self.dev.get_func(Self::MOD, Self::FWD).unwrap();
+        let cfg = launch_cfg::<128>(numel as u32);
+        let params = (opt_cfg, numel, t, param, moment1, moment2, grad);
+        unsafe { func.launch(cfg, params) }?;
+        Ok(())
+    }
+}
@@ -0,0 +1,100 @@
+mod cpu_kernel;
+
+#[cfg(feature = "cuda")]
+mod cuda_kernel;
+
+#[cfg(feature = "webgpu")]
+mod webgpu_kernel;
+
+use crate::{
+    shapes::{Dtype, Shape},
+    tensor::{Error, Storage, Tensor},
+};
+
+use super::WeightDecay;
+
+/// Configuration of hyperparameters for RAdam.
+///
+/// Changing all default parameters:
+/// ```rust
+/// # use dfdx_core::prelude::*;
+/// RAdamConfig {
+///     lr: 1e-2,
+///     betas: [0.1, 0.2],
+///     eps: 1e-6,
+///     weight_decay: Some(WeightDecay::L2(1e-1)),
+/// };
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct RAdamConfig {
+    /// Learning rate. Defaults to `1e-3`.
+    pub lr: f64,
+
+    /// Coefficients of the running averages of gradients and squared gradients.
+    /// Defaults to `[0.9, 0.999]`.
+    pub betas: [f64; 2],
+
+    /// Epsilon for numerical stability. Defaults to `1e-8`.
+    pub eps: f64,
+
+    /// Optional weight decay. Defaults to `None`.
+    pub weight_decay: Option<WeightDecay>,
+}
+
+impl Default for RAdamConfig {
+    fn default() -> Self {
+        Self {
+            lr: 1e-3,
+            betas: [0.9, 0.999],
+            eps: 1e-8,
+            weight_decay: None,
+        }
+    }
+}
+
+pub trait RAdamKernel<E: Dtype>: Storage<E> {
+    fn radam_kernel(
+        &self,
+        t: i32,
+        cfg: &RAdamConfig,
+        param: &mut Self::Vec,
+        moment1: &mut Self::Vec,
+        moment2: &mut Self::Vec,
+        grad: &Self::Vec,
+    ) -> Result<(), Error>;
+}
+
+impl RAdamConfig {
+    /// The rectification term of the adaptive learning rate at step `t`, or `None` if the
+    /// variance of the adaptive learning rate is intractable, in which case the update uses
+    /// the first moment only.
+    fn rectification(&self, t: i32) -> Option<f64> {
+        let beta2 = self.betas[1];
+        let bias_correction2 = 1.0 - beta2.powi(t);
+        let rho_inf = 2.0 / (1.0 - beta2) - 1.0;
+        let rho_t = rho_inf - 2.0 * t as f64 * beta2.powi(t) / bias_correction2;
+        (rho_t > 5.0).then(|| {
+            ((rho_t - 4.0) * (rho_t - 2.0) * rho_inf / ((rho_inf - 4.0) * (rho_inf - 2.0) * rho_t))
+                .sqrt()
+        })
+    }
+
+    /// Update a single tensor using RAdam.
+    pub fn try_update<S: Shape, E: Dtype, D: RAdamKernel<E>>(
+        &self,
+        t: i32,
+        param: &mut Tensor<S, E, D>,
+        moment1: &mut D::Vec,
+        moment2: &mut D::Vec,
+        grad: &D::Vec,
+    ) -> Result<(), crate::tensor::Error> {
+        param.device.radam_kernel(
+            t,
+            self,
+            std::sync::Arc::make_mut(&mut param.data),
+            moment1,
+            moment2,
+            grad,
+        )
+    }
+}
@@ -0,0 +1,132 @@
+#include "cuda_utils.cuh"
+
+enum WeightDecayType {
+    WdNone,
+    L2,
+    Decoupled
+};
+
+struct RAdamConfig {
+    double lr;
+    double beta1;
+    double beta2;
+    double eps;
+    bool rectified;
+    double rect;
+    WeightDecayType weight_decay_type;
+    double weight_decay;
+};
+
+template<typename T>
+__device__ void radam_update(
+    const RAdamConfig cfg,
+    const size_t numel,
+    const int t_int,
+    T* param,
+    T* moment1,
+    T* moment2,
+    const T* grad
+) {
+    T beta1 = cfg.beta1;
+    T beta2 = cfg.beta2;
+    T lr = cfg.lr;
+    T weight_decay = cfg.weight_decay;
+    T eps = cfg.eps;
+    T rect = cfg.rect;
+    T one = 1.0;
+    T t = t_int;
+    T bias_correction1 = one - powg(beta1, t);
+    T bias_correction2 = one - powg(beta2, t);
+    for (unsigned int i = blockIdx.x * blockDim.x + threadIdx.x; i < numel; i += blockDim.x * gridDim.x) {
+        T p = param[i];
+        T g = grad[i];
+        T m = moment1[i];
+        T v = moment2[i];
+
+        if (cfg.weight_decay_type == L2) {
+            g += weight_decay * p;
+        }
+
+        m = m * beta1 + g * (one - beta1);
+        v = v * beta2 + g * g * (one - beta2);
+        T m_hat = m / bias_correction1;
+        if (cfg.rectified) {
+            g = lr * m_hat * rect * sqrtg(bias_correction2) / (sqrtg(v) + eps);
+        } else {
+            g = lr * m_hat;
+        }
+
+        if (cfg.weight_decay_type == Decoupled) {
+            g += (weight_decay * lr) * p;
+        }
+
+        moment1[i] = m;
+        moment2[i] = v;
+        param[i] -= g;
+    }
+}
+
+#define RADAM(TYPENAME, FN) \
+extern "C" __global__ void FN( \
+    const RAdamConfig cfg, \
+    const size_t numel, \
+    const int t, \
+    TYPENAME* param, \
+    TYPENAME* moment1, \
+    TYPENAME* moment2, \
+    const TYPENAME* grad \
+) { \
+    radam_update(cfg, numel, t, param, moment1, moment2, grad); \
+}
+
+RADAM(__half, radam_update_f16);
+RADAM(float, radam_update_f32);
+RADAM(double, radam_update_f64);
+
+extern "C" __global__ void radam_update_amp_f16(
+    const RAdamConfig cfg,
+    const size_t numel,
+    const int t_int,
+    __half* param,
+    __half* moment1,
+    __half* moment2,
+    const __half* grad
+) {
+    float beta1 = cfg.beta1;
+    float beta2 = cfg.beta2;
+    float lr = cfg.lr;
+    float weight_decay = cfg.weight_decay;
+    float eps = cfg.eps;
+    float rect = cfg.rect;
+    float one = 1.0;
+    float t = t_int;
+    float bias_correction1 = one - powg(beta1, t);
+    float bias_correction2 = one - powg(beta2, t);
+    for (unsigned int i = blockIdx.x * blockDim.x + threadIdx.x; i < numel; i += blockDim.x * gridDim.x) {
+        float p = param[i];
+        float g = grad[i];
+        float m = moment1[i];
+        float v = moment2[i];
+
+        if (cfg.weight_decay_type == L2) {
+            g += weight_decay * p;
+        }
+
+        m = m * beta1 + g * (one - beta1);
+        v = v * beta2 + g * g * (one - beta2);
+        float m_hat = m / bias_correction1;
+        if (cfg.rectified) {
+            g = lr * m_hat * rect * sqrtg(bias_correction2) / (sqrtg(v) + eps);
+        } else {
+            g = lr * m_hat;
+        }
+
+        if (cfg.weight_decay_type == Decoupled) {
+            g += (weight_decay * lr) * p;
+        }
+
+        moment1[i] = m;
+        moment2[i] = v;
+        param[i] -= g;
+    }
+}
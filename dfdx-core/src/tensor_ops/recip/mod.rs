@@ -7,12 +7,25 @@ mod cuda_kernel;
 mod webgpu_kernel;
 
 use super::ops::{try_unary_op, UnaryKernel};
+use super::utilities::higher_order::UnaryDerivative;
 use crate::{shapes::*, tensor::*};
 
 #[repr(C)]
 #[derive(Debug, Default, Copy, Clone)]
 pub struct RecipKernelOp;
 
+impl<E> UnaryDerivative<E> for RecipKernelOp {
+    fn df<D: Storage<E>>(
+        &self,
+        ops: &mut dyn HigherOrderOps<E, D>,
+        _x: HigherOrderVar,
+        y: HigherOrderVar,
+    ) -> Result<Option<HigherOrderVar>, Error> {
+        let y2 = ops.square(y)?;
+        ops.scale(y2, -1.0).map(Some)
+    }
+}
+
 /// `1 / x`
 ///
 /// Examples:
@@ -6,6 +6,7 @@ mod cuda_kernel;
 #[cfg(feature = "webgpu")]
 mod webgpu_kernel;
 
+use super::utilities::higher_order::{accumulate_logical, logical_grad};
 use crate::{shapes::*, tensor::*};
 
 pub trait ReshapeKernel<E: Dtype>: Storage<E> {
@@ -104,12 +105,21 @@ impl<S: Shape, E: Dtype, D: ReshapeKernel<E>, T: Tape<E, D>> ReshapeTo for Tenso
             let inp_ghost = inp.ghost();
             let out_ghost = out.ghost();
             let dst = *dst;
-            tape.add_backward_op(move |grads| {
+            let higher_order = tape.is_higher_order().then(|| (inp.ghost(), out.ghost()));
+            tape.try_add_op_info(&out, || OpInfo::new("Reshape", &out).input(&inp))?;
+            tape.add_retainable_backward_op(move |grads| {
                 grads.try_alloc_for(&inp_ghost)?;
                 grads.try_alloc_for(&out_ghost)?;
                 let (grad_inp, grad_out) = grads.mut_and_ref(&inp_ghost, &out_ghost);
                 inp.device.backward(&dst, &inp, grad_inp, grad_out)
             });
+            if let Some((inp_ghost, out_ghost)) = higher_order {
+                // the output holds the elements of the input in logical order
+                tape.add_higher_order_op(move |ops| match logical_grad(ops, &out_ghost)? {
+                    Some(grad_out) => accumulate_logical(ops, &inp_ghost, grad_out),
+                    None => Ok(()),
+                });
+            }
             Ok(out.put_tape(tape))
         }
     }
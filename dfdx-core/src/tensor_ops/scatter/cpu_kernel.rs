@@ -0,0 +1,86 @@
+use crate::{
+    shapes::{Dtype, Shape},
+    tensor::{cpu::NdIndex, *},
+};
+
+/// The index into the contiguous output of each element of `src`, in the order of `src`'s
+/// physical indices.
+fn scattered_indices<'a, Dst: Shape, Src: Shape<Concrete = Dst::Concrete>>(
+    ax: usize,
+    dst: Dst,
+    idx: &'a Tensor<Src, usize, Cpu>,
+    src_strides: Src::Concrete,
+) -> impl Iterator<Item = (usize, usize)> + 'a {
+    let size = dst.concrete()[ax];
+    let out_strides = dst.strides();
+    let mut src_idx = NdIndex::new(idx.shape, src_strides);
+    let mut idx_idx = NdIndex::new(idx.shape, idx.strides);
+    std::iter::from_fn(move || {
+        let (src_i, index) = src_idx.next_with_idx()?;
+        let j = idx.data[idx_idx.next().unwrap()];
+        assert!(
+            j < size,
+            "index {j} is out of bounds for axis {ax} with size {size}"
+        );
+        let out_i = index
+            .into_iter()
+            .enumerate()
+            .map(|(d, i)| if d == ax { j } else { i } * out_strides[d])
+            .sum();
+        Some((src_i, out_i))
+    })
+}
+
+impl<E: Dtype> super::ScatterKernel<E> for Cpu {
+    fn forward<Dst: Shape, Src: Shape<Concrete = Dst::Concrete>>(
+        &self,
+        ax: usize,
+        add: bool,
+        inp: &Tensor<Dst, E, Self>,
+        idx: &Tensor<Src, usize, Self>,
+        src: &Tensor<Src, E, Self>,
+    ) -> Result<Tensor<Dst, E, Self>, Error> {
+        let mut out = self.try_zeros_like(&inp.shape)?;
+        let buf = std::sync::Arc::make_mut(&mut out.data);
+        let mut inp_idx = NdIndex::new(inp.shape, inp.strides);
+        for o in buf.iter_mut() {
+            *o = inp.data[inp_idx.next().unwrap()];
+        }
+        for (src_i, out_i) in scattered_indices(ax, inp.shape, idx, src.strides) {
+            if add {
+                buf[out_i] += src.data[src_i];
+            } else {
+                buf[out_i] = src.data[src_i];
+            }
+        }
+        Ok(out)
+    }
+
+    fn backward<Dst: Shape, Src: Shape<Concrete = Dst::Concrete>>(
+        &self,
+        ax: usize,
+        add: bool,
+        inp: &GhostTensor<Dst, E, Self>,
+        grad_inp: &mut <Self as Storage<E>>::Vec,
+        idx: &Tensor<Src, usize, Self>,
+        src: &GhostTensor<Src, E, Self>,
+        grad_src: &mut <Self as Storage<E>>::Vec,
+        grad_out: &<Self as Storage<E>>::Vec,
+    ) -> Result<(), Error> {
+        let mut overwritten = vec![false; if add { 0 } else { grad_out.len() }];
+        for (src_i, out_i) in scattered_indices(ax, inp.shape, idx, src.strides) {
+            grad_src[src_i] += grad_out[out_i];
+            if !add {
+                overwritten[out_i] = true;
+            }
+        }
+        let mut inp_idx = NdIndex::new(inp.shape, inp.strides);
+        for (out_i, &g) in grad_out.iter().enumerate() {
+            let inp_i = inp_idx.next().unwrap();
+            if add || !overwritten[out_i] {
+                grad_inp[inp_i] += g;
+            }
+        }
+        Ok(())
+    }
+}
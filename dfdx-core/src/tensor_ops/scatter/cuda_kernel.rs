@@ -0,0 +1,174 @@
+use crate::{
+    dtypes::*,
+    shapes::Shape,
+    tensor::{launch_cfg, Cuda, Error, GhostTensor, Storage, Tensor},
+};
+
+use cudarc::driver::{CudaSlice, DeviceSlice, LaunchAsync};
+
+use std::vec::Vec;
+
+const PTX_SRC: &str = include_str!(concat!(env!("OUT_DIR"), "/scatter.ptx"));
+
+trait HasCudaKernel<E> {
+    const FNS: &'static [&'static str];
+}
+#[cfg(feature = "f16")]
+impl HasCudaKernel<f16> for Cuda {
+    const FNS: &'static [&'static str] =
+        &["scatter_fwd_f16", "scatter_bwd_src_f16", "scatter_copy_f16"];
+}
+#[cfg(feature = "f16")]
+impl HasCudaKernel<AMP<f16>> for Cuda {
+    const FNS: &'static [&'static str] =
+        &["scatter_fwd_f16", "scatter_bwd_src_f16", "scatter_copy_f16"];
+}
+impl HasCudaKernel<f32> for Cuda {
+    const FNS: &'static [&'static str] =
+        &["scatter_fwd_f32", "scatter_bwd_src_f32", "scatter_copy_f32"];
+}
+impl HasCudaKernel<f64> for Cuda {
+    const FNS: &'static [&'static str] =
+        &["scatter_fwd_f64", "scatter_bwd_src_f64", "scatter_copy_f64"];
+}
+
+impl Cuda {
+    /// The dims & strides of `src`, the strides of `idx` & the contiguous output, and the
+    /// size of the output along `ax`.
+    fn scatter_info<Dst: Shape, Src: Shape<Concrete = Dst::Concrete>>(
+        &self,
+        ax: usize,
+        dst: &Dst,
+        idx_strides: Src::Concrete,
+        src: &Src,
+        src_strides: Src::Concrete,
+    ) -> Result<CudaSlice<usize>, Error> {
+        let mut info = Vec::with_capacity(4 * Src::NUM_DIMS + 1);
+        info.extend(src.concrete());
+        info.extend(src_strides);
+        info.extend(idx_strides);
+        info.extend(dst.strides());
+        info.push(dst.concrete()[ax]);
+        Ok(self.dev.htod_copy(info)?)
+    }
+}
+
+impl<E: Dtype> super::ScatterKernel<E> for Cuda
+where
+    Self: HasCudaKernel<E>,
+{
+    fn forward<Dst: Shape, Src: Shape<Concrete = Dst::Concrete>>(
+        &self,
+        ax: usize,
+        add: bool,
+        inp: &Tensor<Dst, E, Self>,
+        idx: &Tensor<Src, usize, Self>,
+        src: &Tensor<Src, E, Self>,
+    ) -> Result<Tensor<Dst, E, Self>, Error> {
+        if !self.dev.has_func(Self::FNS[0], Self::FNS[0]) {
+            self.dev.load_ptx(PTX_SRC.into(), Self::FNS[0], Self::FNS)?;
+        }
+
+        let numel = inp.shape.num_elements();
+        let strides = inp.shape.strides();
+        let mut out = unsafe { self.alloc_empty::<E>(numel) }?;
+
+        let dims = self.dev.htod_copy(inp.shape.concrete().into())?;
+        let inp_strides = self.dev.htod_copy(inp.strides.into())?;
+        let copy_fn = self.dev.get_func(Self::FNS[0], Self::FNS[2]).unwrap();
+        let cfg = launch_cfg::<128>(numel as u32);
+        let params = (
+            numel,
+            Dst::NUM_DIMS,
+            true,
+            &dims,
+            &inp_strides,
+            inp.data.as_ref(),
+            &mut out,
+        );
+        unsafe { copy_fn.launch(cfg, params) }?;
+
+        let info = self.scatter_info(ax, &inp.shape, idx.strides, &src.shape, src.strides)?;
+        let src_numel = src.shape.num_elements();
+        let fwd_fn = self.dev.get_func(Self::FNS[0], Self::FNS[0]).unwrap();
+        let cfg = launch_cfg::<128>(src_numel as u32);
+        let params = (
+            src_numel,
+            Src::NUM_DIMS,
+            ax,
+            add,
+            &info,
+            idx.data.as_ref(),
+            src.data.as_ref(),
+            &mut out,
+        );
+        unsafe { fwd_fn.launch(cfg, params) }?;
+        Ok(self.build_tensor(inp.shape, strides, out))
+    }
+
+    fn backward<Dst: Shape, Src: Shape<Concrete = Dst::Concrete>>(
+        &self,
+        ax: usize,
+        add: bool,
+        inp: &GhostTensor<Dst, E, Self>,
+        grad_inp: &mut <Self as Storage<E>>::Vec,
+        idx: &Tensor<Src, usize, Self>,
+        src: &GhostTensor<Src, E, Self>,
+        grad_src: &mut <Self as Storage<E>>::Vec,
+        grad_out: &<Self as Storage<E>>::Vec,
+    ) -> Result<(), Error> {
+        let info = self.scatter_info(ax, &inp.shape, idx.strides, &src.shape, src.strides)?;
+        let src_numel = src.shape.num_elements();
+        let bwd_fn = self.dev.get_func(Self::FNS[0], Self::FNS[1]).unwrap();
+        let cfg = launch_cfg::<128>(src_numel as u32);
+        let params = (
+            src_numel,
+            Src::NUM_DIMS,
+            ax,
+            false,
+            &info,
+            idx.data.as_ref(),
+            &mut *grad_src,
+            grad_out,
+        );
+        unsafe { bwd_fn.launch(cfg, params) }?;
+
+        // the values that were overwritten don't get gradients
+        let mut masked = None;
+        if !add {
+            let mut tmp = unsafe { self.alloc_empty::<E>(grad_out.len()) }?;
+            self.dev.dtod_copy(grad_out, &mut tmp)?;
+            let bwd_fn = self.dev.get_func(Self::FNS[0], Self::FNS[1]).unwrap();
+            let params = (
+                src_numel,
+                Src::NUM_DIMS,
+                ax,
+                true,
+                &info,
+                idx.data.as_ref(),
+                &mut *grad_src,
+                &mut tmp,
+            );
+            unsafe { bwd_fn.launch(cfg, params) }?;
+            masked = Some(tmp);
+        }
+        let grad_out: &CudaSlice<E> = masked.as_ref().unwrap_or(grad_out);
+
+        let numel = inp.shape.num_elements();
+        let dims = self.dev.htod_copy(inp.shape.concrete().into())?;
+        let inp_strides = self.dev.htod_copy(inp.strides.into())?;
+        let copy_fn = self.dev.get_func(Self::FNS[0], Self::FNS[2]).unwrap();
+        let cfg = launch_cfg::<128>(numel as u32);
+        let params = (
+            numel,
+            Dst::NUM_DIMS,
+            false,
+            &dims,
+            &inp_strides,
+            grad_inp,
+            grad_out,
+        );
+        unsafe { copy_fn.launch(cfg, params) }?;
+        Ok(())
+    }
+}
@@ -0,0 +1,379 @@
+mod cpu_kernel;
+
+#[cfg(feature = "cuda")]
+mod cuda_kernel;
+
+#[cfg(feature = "webgpu")]
+mod webgpu_kernel;
+
+use crate::{shapes::*, tensor::*};
+
+pub trait ScatterKernel<E: Dtype>: Storage<E> + Storage<usize> {
+    /// Copies `inp` into a new tensor, then writes (or adds if `add`) each value of `src` at
+    /// the position along `ax` given by `idx`.
+    fn forward<Dst: Shape, Src: Shape<Concrete = Dst::Concrete>>(
+        &self,
+        ax: usize,
+        add: bool,
+        inp: &Tensor<Dst, E, Self>,
+        idx: &Tensor<Src, usize, Self>,
+        src: &Tensor<Src, E, Self>,
+    ) -> Result<Tensor<Dst, E, Self>, Error>;
+
+    #[allow(clippy::too_many_arguments)]
+    fn backward<Dst: Shape, Src: Shape<Concrete = Dst::Concrete>>(
+        &self,
+        ax: usize,
+        add: bool,
+        inp: &GhostTensor<Dst, E, Self>,
+        grad_inp: &mut <Self as Storage<E>>::Vec,
+        idx: &Tensor<Src, usize, Self>,
+        src: &GhostTensor<Src, E, Self>,
+        grad_src: &mut <Self as Storage<E>>::Vec,
+        grad_out: &<Self as Storage<E>>::Vec,
+    ) -> Result<(), Error>;
+}
+
+/// Writes values into a tensor at indices along a single axis, the inverse of reading them
+/// with [GatherTo](super::GatherTo). **Pytorch equivalent**: `torch.scatter` and
+/// `torch.scatter_add`.
+///
+/// `idx` and `src` have the same shape, which is the shape of `self` except along the axis.
+/// Each value of `src` is written at the same position in `self`, except along the axis
+/// where its position is given by the value of `idx` at the same position:
+/// ```text
+/// out[i][idx[i][j][k]][k] = src[i][j][k] // for axis 1
+/// ```
+///
+/// Building one-hot targets from labels:
+/// ```rust
+/// # use dfdx_core::prelude::*;
+/// # let dev: Cpu = Default::default();
+/// let labels: Tensor<Rank2<3, 1>, usize, _> = dev.tensor([[2], [0], [1]]);
+/// let zeros: Tensor<Rank2<3, 4>, f32, _> = dev.zeros();
+/// let one_hot = zeros.scatter::<Axis<1>>(labels, dev.ones());
+/// assert_eq!(
+///     one_hot.array(),
+///     [[0.0, 0.0, 1.0, 0.0], [1.0, 0.0, 0.0, 0.0], [0.0, 1.0, 0.0, 0.0]]
+/// );
+/// ```
+///
+/// Adding values that are scattered to the same position:
+/// ```rust
+/// # use dfdx_core::prelude::*;
+/// # let dev: Cpu = Default::default();
+/// let idx: Tensor<Rank2<2, 3>, usize, _> = dev.tensor([[0, 1, 0], [2, 2, 2]]);
+/// let src: Tensor<Rank2<2, 3>, f32, _> = dev.tensor([[1.0, 2.0, 3.0], [4.0, 5.0, 6.0]]);
+/// let zeros: Tensor<Rank2<2, 3>, f32, _> = dev.zeros();
+/// let r = zeros.scatter_add::<Axis<1>>(idx, src);
+/// assert_eq!(r.array(), [[4.0, 2.0, 0.0], [0.0, 0.0, 15.0]]);
+/// ```
+pub trait ScatterTo<Idx, Src>: Sized + HasShape {
+    /// Writes `src` into `self` at `idx` along `Ax`. If multiple values are written to the
+    /// same position, which one is kept is unspecified.
+    fn scatter<Ax: Axes<Array = [isize; 1]>>(self, idx: Idx, src: Src) -> Self
+    where
+        Self::Shape: HasAxes<Ax>,
+    {
+        self.try_scatter::<Ax>(idx, src).unwrap()
+    }
+
+    /// Fallible version of [ScatterTo::scatter]
+    fn try_scatter<Ax: Axes<Array = [isize; 1]>>(self, idx: Idx, src: Src) -> Result<Self, Error>
+    where
+        Self::Shape: HasAxes<Ax>;
+
+    /// Adds `src` to `self` at `idx` along `Ax`.
+    fn scatter_add<Ax: Axes<Array = [isize; 1]>>(self, idx: Idx, src: Src) -> Self
+    where
+        Self::Shape: HasAxes<Ax>,
+    {
+        self.try_scatter_add::<Ax>(idx, src).unwrap()
+    }
+
+    /// Fallible version of [ScatterTo::scatter_add]
+    fn try_scatter_add<Ax: Axes<Array = [isize; 1]>>(
+        self,
+        idx: Idx,
+        src: Src,
+    ) -> Result<Self, Error>
+    where
+        Self::Shape: HasAxes<Ax>;
+}
+
+/// Adds slices of a tensor to `self` at indices along a single axis.
+/// **Pytorch equivalent**: `torch.index_add`
+///
+/// `src` has the shape of `self` except along the axis, where its size is the number of
+/// indices. Slice `i` of `src` along the axis is added to slice `idx[i]` of `self`:
+/// ```text
+/// out[i][idx[j]][k] += src[i][j][k] // for axis 1
+/// ```
+///
+/// Aggregating the messages of a graph's edges into their target nodes:
+/// ```rust
+/// # use dfdx_core::prelude::*;
+/// # let dev: Cpu = Default::default();
+/// let nodes: Tensor<Rank2<3, 2>, f32, _> = dev.zeros();
+/// let targets: Tensor<Rank1<4>, usize, _> = dev.tensor([0, 2, 0, 1]);
+/// let messages: Tensor<Rank2<4, 2>, f32, _> =
+///     dev.tensor([[1.0, 2.0], [3.0, 4.0], [5.0, 6.0], [7.0, 8.0]]);
+/// let r = nodes.index_add::<Axis<0>>(targets, messages);
+/// assert_eq!(r.array(), [[6.0, 8.0], [7.0, 8.0], [3.0, 4.0]]);
+/// ```
+pub trait IndexAdd<Idx, Src>: Sized + HasShape {
+    /// Adds slices of `src` to `self` at `idx` along `Ax`.
+    fn index_add<Ax: Axes<Array = [isize; 1]>>(self, idx: Idx, src: Src) -> Self
+    where
+        Self::Shape: HasAxes<Ax>,
+    {
+        self.try_index_add::<Ax>(idx, src).unwrap()
+    }
+
+    /// Fallible version of [IndexAdd::index_add]
+    fn try_index_add<Ax: Axes<Array = [isize; 1]>>(self, idx: Idx, src: Src) -> Result<Self, Error>
+    where
+        Self::Shape: HasAxes<Ax>;
+}
+
+impl<
+        Dst: Shape,
+        Src: Shape<Concrete = Dst::Concrete>,
+        E: Dtype,
+        D: ScatterKernel<E>,
+        T: Tape<E, D> + Merge<R>,
+        R: Tape<E, D>,
+    > ScatterTo<Tensor<Src, usize, D>, Tensor<Src, E, D, R>> for Tensor<Dst, E, D, T>
+{
+    fn try_scatter<Ax: Axes<Array = [isize; 1]>>(
+        self,
+        idx: Tensor<Src, usize, D>,
+        src: Tensor<Src, E, D, R>,
+    ) -> Result<Self, Error>
+    where
+        Dst: HasAxes<Ax>,
+    {
+        assert_eq!(idx.shape.concrete(), src.shape.concrete());
+        try_scatter(Ax::as_array()[0] as usize, false, self, idx, src)
+    }
+
+    fn try_scatter_add<Ax: Axes<Array = [isize; 1]>>(
+        self,
+        idx: Tensor<Src, usize, D>,
+        src: Tensor<Src, E, D, R>,
+    ) -> Result<Self, Error>
+    where
+        Dst: HasAxes<Ax>,
+    {
+        assert_eq!(idx.shape.concrete(), src.shape.concrete());
+        try_scatter(Ax::as_array()[0] as usize, true, self, idx, src)
+    }
+}
+
+impl<
+        Dst: Shape,
+        Src: Shape<Concrete = Dst::Concrete>,
+        Z: Dim,
+        E: Dtype,
+        D: ScatterKernel<E>,
+        T: Tape<E, D> + Merge<R>,
+        R: Tape<E, D>,
+    > IndexAdd<Tensor<(Z,), usize, D>, Tensor<Src, E, D, R>> for Tensor<Dst, E, D, T>
+{
+    fn try_index_add<Ax: Axes<Array = [isize; 1]>>(
+        self,
+        idx: Tensor<(Z,), usize, D>,
+        src: Tensor<Src, E, D, R>,
+    ) -> Result<Self, Error>
+    where
+        Dst: HasAxes<Ax>,
+    {
+        let ax = Ax::as_array()[0] as usize;
+        assert_eq!(idx.shape.0.size(), src.shape.concrete()[ax]);
+        // the same indices for every slice of `src` along the other axes
+        let mut strides: Src::Concrete = Default::default();
+        strides[ax] = idx.strides[0];
+        let idx = Tensor {
+            id: unique_id(),
+            data: idx.data,
+            shape: src.shape,
+            strides,
+            device: idx.device,
+            tape: NoneTape,
+        };
+        try_scatter(ax, true, self, idx, src)
+    }
+}
+
+fn try_scatter<
+    Dst: Shape,
+    Src: Shape<Concrete = Dst::Concrete>,
+    E: Dtype,
+    D: ScatterKernel<E>,
+    T: Tape<E, D> + Merge<R>,
+    R: Tape<E, D>,
+>(
+    ax: usize,
+    add: bool,
+    inp: Tensor<Dst, E, D, T>,
+    idx: Tensor<Src, usize, D>,
+    src: Tensor<Src, E, D, R>,
+) -> Result<Tensor<Dst, E, D, T>, Error> {
+    let dst_dims = inp.shape.concrete();
+    let src_dims = src.shape.concrete();
+    for i in 0..Dst::NUM_DIMS {
+        if i != ax {
+            assert_eq!(dst_dims[i], src_dims[i], "dimension {i} not the same");
+        }
+    }
+
+    let (inp, tape) = inp.split_tape();
+    let (src, src_tape) = src.split_tape();
+    let out = inp.device.forward(ax, add, &inp, &idx, &src)?;
+
+    let inp_ghost = inp.ghost();
+    let src_ghost = src.ghost();
+    let out_ghost = out.ghost();
+    let mut tape = tape.merge(src_tape);
+    tape.try_add_op_info(&out, || {
+        OpInfo::new(if add { "ScatterAdd" } else { "Scatter" }, &out)
+            .input(&inp)
+            .input(&src)
+    })?;
+    tape.add_retainable_backward_op(move |grads| {
+        grads.try_alloc_for(&inp_ghost)?;
+        grads.try_alloc_for(&src_ghost)?;
+        grads.try_alloc_for(&out_ghost)?;
+        let (grad_inp, grad_src, grad_out) = grads.muts_and_ref(&inp_ghost, &src_ghost, &out_ghost);
+        inp_ghost.dev.backward(
+            ax, add, &inp_ghost, grad_inp, &idx, &src_ghost, grad_src, grad_out,
+        )
+    });
+    Ok(out.put_tape(tape))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{tensor_ops::*, tests::*};
+
+    #[test]
+    fn test_scatter_2d_axis_1() {
+        let dev: TestDevice = Default::default();
+        let inp = dev
+            .tensor([[1.0, 2.0, 3.0], [4.0, 5.0, 6.0]])
+            .to_dtype::<TestDtype>();
+        let src = dev.tensor([[10.0], [20.0]]).to_dtype::<TestDtype>();
+        let idx = dev.tensor([[2], [0]]);
+        let r = inp.leaky_trace().scatter::<Axis<1>>(idx, src.leaky_trace());
+        assert_close_to_literal!(r, [[1.0, 2.0, 10.0], [20.0, 5.0, 6.0]]);
+
+        let w = dev
+            .tensor([[1.0, 2.0, 3.0], [4.0, 5.0, 6.0]])
+            .to_dtype::<TestDtype>();
+        let g = (r * w).sum().backward();
+        // the values that were overwritten don't get gradients
+        assert_close_to_literal!(g.get(&inp), [[1.0, 2.0, 0.0], [0.0, 5.0, 6.0]]);
+        assert_close_to_literal!(g.get(&src), [[3.0], [4.0]]);
+    }
+
+    #[test]
+    fn test_scatter_add_2d_axis_0() {
+        let dev: TestDevice = Default::default();
+        let inp: Tensor<Rank2<3, 2>, TestDtype, _> = dev.ones();
+        let src = dev.tensor([[1.0, 2.0], [3.0, 4.0]]).to_dtype::<TestDtype>();
+        let idx = dev.tensor([[0, 2], [0, 0]]);
+        let r = inp
+            .leaky_trace()
+            .scatter_add::<Axis<0>>(idx, src.leaky_trace());
+        assert_close_to_literal!(r, [[5.0, 5.0], [1.0, 1.0], [1.0, 3.0]]);
+
+        let w = dev
+            .tensor([[1.0, 2.0], [3.0, 4.0], [5.0, 6.0]])
+            .to_dtype::<TestDtype>();
+        let g = (r * w).sum().backward();
+        assert_close_to_literal!(g.get(&inp), [[1.0, 2.0], [3.0, 4.0], [5.0, 6.0]]);
+        assert_close_to_literal!(g.get(&src), [[1.0, 6.0], [1.0, 2.0]]);
+    }
+
+    #[test]
+    fn test_scatter_3d_axis_2() {
+        let dev: TestDevice = Default::default();
+        let inp: Tensor<Rank3<2, 2, 3>, TestDtype, _> = dev.zeros();
+        let src = dev
+            .tensor([[[1.0, 2.0], [3.0, 4.0]], [[5.0, 6.0], [7.0, 8.0]]])
+            .to_dtype::<TestDtype>();
+        let idx = dev.tensor([[[2, 0], [1, 1]], [[0, 1], [2, 0]]]);
+        let r = inp.scatter_add::<Axis<2>>(idx, src);
+        assert_close_to_literal!(
+            r,
+            [
+                [[2.0, 0.0, 1.0], [0.0, 7.0, 0.0]],
+                [[5.0, 6.0, 0.0], [8.0, 0.0, 7.0]],
+            ]
+        );
+    }
+
+    #[test]
+    fn test_scatter_broadcasted() {
+        let dev: TestDevice = Default::default();
+        let inp: Tensor<Rank1<4>, TestDtype, _> = dev.zeros();
+        let src = dev.tensor(1.0).to_dtype::<TestDtype>();
+        let idx = dev.tensor([[2], [0], [2]]);
+        let r = inp
+            .leaky_trace()
+            .broadcast::<Rank2<3, 4>, _>()
+            .scatter::<Axis<1>>(idx, src.leaky_trace().broadcast::<Rank2<3, 1>, _>());
+        assert_close_to_literal!(
+            r,
+            [
+                [0.0, 0.0, 1.0, 0.0],
+                [1.0, 0.0, 0.0, 0.0],
+                [0.0, 0.0, 1.0, 0.0],
+            ]
+        );
+        let g = r.sum().backward();
+        assert_close_to_literal!(g.get(&inp), [2.0, 3.0, 1.0, 3.0]);
+        assert_close_to_literal!(g.get(&src), 3.0);
+    }
+
+    #[test]
+    fn test_index_add_2d_axis_1() {
+        let dev: TestDevice = Default::default();
+        let inp: Tensor<Rank2<2, 3>, TestDtype, _> = dev.zeros();
+        let src = dev
+            .tensor([[1.0, 2.0, 3.0, 4.0], [5.0, 6.0, 7.0, 8.0]])
+            .to_dtype::<TestDtype>();
+        let idx = dev.tensor([2, 0, 2, 1]);
+        let r = inp
+            .leaky_trace()
+            .index_add::<Axis<1>>(idx, src.leaky_trace());
+        assert_close_to_literal!(r, [[2.0, 4.0, 4.0], [6.0, 8.0, 12.0]]);
+
+        let w = dev
+            .tensor([[1.0, 2.0, 3.0], [4.0, 5.0, 6.0]])
+            .to_dtype::<TestDtype>();
+        let g = (r * w).sum().backward();
+        assert_close_to_literal!(g.get(&inp), [[1.0, 2.0, 3.0], [4.0, 5.0, 6.0]]);
+        assert_close_to_literal!(g.get(&src), [[3.0, 1.0, 3.0, 2.0], [6.0, 4.0, 6.0, 5.0]]);
+    }
+
+    #[test]
+    fn test_index_add_usize_dim() {
+        let dev: TestDevice = Default::default();
+        let inp: Tensor<Rank2<3, 2>, TestDtype, _> = dev.zeros();
+        let src: Tensor<(usize, Const<2>), TestDtype, _> = dev.ones_like(&(5, Const));
+        let idx: Tensor<(usize,), usize, _> = dev.tensor_from_vec(vec![1, 1, 0, 1, 0], (5,));
+        let r = inp.index_add::<Axis<0>>(idx, src);
+        assert_close_to_literal!(r, [[2.0; 2], [3.0; 2], [0.0; 2]]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_scatter_index_out_of_bounds() {
+        let dev: TestDevice = Default::default();
+        let inp: Tensor<Rank1<3>, TestDtype, _> = dev.zeros();
+        let src: Tensor<Rank1<1>, TestDtype, _> = dev.ones();
+        let _ = inp.scatter::<Axis<0>>(dev.tensor([3]), src);
+    }
+}
@@ -0,0 +1,138 @@
+#include "cuda_utils.cuh"
+
+// The index into the contiguous output of the i'th element of src, where `info` holds
+// the dims & strides of src, the strides of idx, the strides of the output and the
+// size of the output along the axis.
+__device__ size_t scattered_index(
+    const size_t i,
+    const size_t num_dims,
+    const size_t ax,
+    const size_t *info,
+    const size_t *idx,
+    size_t *src_i
+) {
+    const size_t *dims = info;
+    const size_t *src_strides = info + num_dims;
+    const size_t *idx_strides = info + 2 * num_dims;
+    const size_t *out_strides = info + 3 * num_dims;
+    const size_t out_size = info[4 * num_dims];
+
+    size_t idx_i = 0;
+    size_t out_i = 0;
+    *src_i = 0;
+    unsigned int rem = i;
+    for (int d = num_dims - 1; d >= 0; d--) {
+        size_t dim_i = rem % dims[d];
+        *src_i += dim_i * src_strides[d];
+        idx_i += dim_i * idx_strides[d];
+        if (d != ax) {
+            out_i += dim_i * out_strides[d];
+        }
+        rem /= dims[d];
+    }
+    assert(idx[idx_i] < out_size);
+    return out_i + idx[idx_i] * out_strides[ax];
+}
+
+template<typename T>
+__device__ void scatter_fwd(
+    const size_t numel,
+    const size_t num_dims,
+    const size_t ax,
+    const bool add,
+    const size_t *info,
+    const size_t *idx,
+    const T *src,
+    T *out
+) {
+    for (unsigned int i = blockIdx.x * blockDim.x + threadIdx.x; i < numel; i += blockDim.x * gridDim.x) {
+        size_t src_i;
+        size_t out_i = scattered_index(i, num_dims, ax, info, idx, &src_i);
+        if (add) {
+            atomicAdd(out + out_i, src[src_i]);
+        } else {
+            out[out_i] = src[src_i];
+        }
+    }
+}
+
+// Adds grad_out to grad_src, and zeroes the positions of grad_out that were overwritten
+// if `zero`. These are separate launches, as the same position may be scattered to twice.
+template<typename T>
+__device__ void scatter_bwd_src(
+    const size_t numel,
+    const size_t num_dims,
+    const size_t ax,
+    const bool zero,
+    const size_t *info,
+    const size_t *idx,
+    T *grad_src,
+    T *grad_out
+) {
+    for (unsigned int i = blockIdx.x * blockDim.x + threadIdx.x; i < numel; i += blockDim.x * gridDim.x) {
+        size_t src_i;
+        size_t out_i = scattered_index(i, num_dims, ax, info, idx, &src_i);
+        if (zero) {
+            grad_out[out_i] = T(0.0);
+        } else {
+            atomicAdd(grad_src + src_i, grad_out[out_i]);
+        }
+    }
+}
+
+// Copies a strided tensor into a contiguous one if `fwd`, otherwise adds the contiguous
+// tensor to the strided one.
+template<typename T>
+__device__ void scatter_copy(
+    const size_t numel,
+    const size_t num_dims,
+    const bool fwd,
+    const size_t *dims,
+    const size_t *strides,
+    T *strided,
+    T *contiguous
+) {
+    for (unsigned int i = blockIdx.x * blockDim.x + threadIdx.x; i < numel; i += blockDim.x * gridDim.x) {
+        size_t strided_i = get_strided_index(i, num_dims, dims, strides);
+        if (fwd) {
+            contiguous[i] = strided[strided_i];
+        } else {
+            atomicAdd(strided + strided_i, contiguous[i]);
+        }
+    }
+}
+
+#define SCATTER(TY, FWD, BWD_SRC, COPY) \
+extern "C" __global__ void FWD( \
+    const size_t numel, \
+    const size_t num_dims, \
+    const size_t ax, \
+    const bool add, \
+    const size_t *info, \
+    const size_t *idx, \
+    const TY *src, \
+    TY *out \
+) { scatter_fwd(numel, num_dims, ax, add, info, idx, src, out); } \
+extern "C" __global__ void BWD_SRC( \
+    const size_t numel, \
+    const size_t num_dims, \
+    const size_t ax, \
+    const bool zero, \
+    const size_t *info, \
+    const size_t *idx, \
+    TY *grad_src, \
+    TY *grad_out \
+) { scatter_bwd_src(numel, num_dims, ax, zero, info, idx, grad_src, grad_out); } \
+extern "C" __global__ void COPY( \
+    const size_t numel, \
+    const size_t num_dims, \
+    const bool fwd, \
+    const size_t *dims, \
+    const size_t *strides, \
+    TY *strided, \
+    TY *contiguous \
+) { scatter_copy(numel, num_dims, fwd, dims, strides, strided, contiguous); }
+
+SCATTER(__half, scatter_fwd_f16, scatter_bwd_src_f16, scatter_copy_f16);
+SCATTER(float, scatter_fwd_f32, scatter_bwd_src_f32, scatter_copy_f32);
+SCATTER(double, scatter_fwd_f64, scatter_bwd_src_f64, scatter_copy_f64);
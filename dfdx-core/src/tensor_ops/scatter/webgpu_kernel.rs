@@ -0,0 +1,31 @@
+use crate::prelude::{Dtype, Webgpu};
+
+impl<E: Dtype> super::ScatterKernel<E> for Webgpu {
+    fn forward<Dst: crate::prelude::Shape, Src: crate::prelude::Shape<Concrete = Dst::Concrete>>(
+        &self,
+        ax: usize,
+        add: bool,
+        inp: &crate::prelude::Tensor<Dst, E, Self>,
+        idx: &crate::prelude::Tensor<Src, usize, Self>,
+        src: &crate::prelude::Tensor<Src, E, Self>,
+    ) -> Result<crate::prelude::Tensor<Dst, E, Self>, crate::prelude::Error> {
+        todo!()
+    }
+
+    fn backward<
+        Dst: crate::prelude::Shape,
+        Src: crate::prelude::Shape<Concrete = Dst::Concrete>,
+    >(
+        &self,
+        ax: usize,
+        add: bool,
+        inp: &crate::tensor::GhostTensor<Dst, E, Self>,
+        grad_inp: &mut <Self as crate::tensor::Storage<E>>::Vec,
+        idx: &crate::prelude::Tensor<Src, usize, Self>,
+        src: &crate::tensor::GhostTensor<Src, E, Self>,
+        grad_src: &mut <Self as crate::tensor::Storage<E>>::Vec,
+        grad_out: &<Self as crate::tensor::Storage<E>>::Vec,
+    ) -> Result<(), crate::prelude::Error> {
+        todo!()
+    }
+}
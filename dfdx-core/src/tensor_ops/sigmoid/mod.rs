@@ -7,12 +7,25 @@ mod cuda_kernel;
 mod webgpu_kernel;
 
 use super::ops::{try_unary_op, UnaryKernel};
+use super::utilities::higher_order::UnaryDerivative;
 use crate::{shapes::*, tensor::*};
 
 #[repr(C)]
 #[derive(Debug, Default, Copy, Clone)]
 pub struct SigmoidKernelOp;
 
+impl<E> UnaryDerivative<E> for SigmoidKernelOp {
+    fn df<D: Storage<E>>(
+        &self,
+        ops: &mut dyn HigherOrderOps<E, D>,
+        _x: HigherOrderVar,
+        y: HigherOrderVar,
+    ) -> Result<Option<HigherOrderVar>, Error> {
+        let y2 = ops.square(y)?;
+        ops.sub(y, y2).map(Some)
+    }
+}
+
 /// [Sigmoid](https://en.wikipedia.org/wiki/Sigmoid_function). `1 / (1 + exp(-t))`.
 ///
 /// The derivative is `sigmoid(t) * (1.0 - sigmoid(t))`.
@@ -0,0 +1,95 @@
+use crate::{
+    shapes::{Dtype, Shape},
+    tensor::{cpu::NdIndex, *},
+};
+
+use std::cmp::Ordering;
+
+/// Total order where NaN is greater than all other values, and equal to other NaNs.
+#[allow(clippy::eq_op)]
+fn nan_last_cmp<E: Dtype>(a: &E, b: &E) -> Ordering {
+    a.partial_cmp(b).unwrap_or_else(|| (a != a).cmp(&(b != b)))
+}
+
+impl<E: Dtype> super::SortKernel<E> for Cpu {
+    fn argsort<Src: Shape, Dst: Shape, T>(
+        &self,
+        ax: usize,
+        descending: bool,
+        inp: &Tensor<Src, E, Self, T>,
+        dst: Dst,
+    ) -> Result<Tensor<Dst, usize, Self>, Error> {
+        let mut out = self.try_zeros_like(&dst)?;
+        let size = inp.shape.concrete()[ax];
+        let k = dst.concrete()[ax];
+        let out_strides = out.strides;
+        let out_buf = std::sync::Arc::make_mut(&mut out.data);
+        let mut row = Vec::with_capacity(size);
+        let mut idx = NdIndex::new(inp.shape, inp.strides);
+        while let Some((inp_i, index)) = idx.next_with_idx() {
+            // each row along the axis is sorted when reaching its first element
+            if index[ax] != 0 {
+                continue;
+            }
+            let values: Vec<E> = (0..size)
+                .map(|j| inp.data[inp_i + j * inp.strides[ax]])
+                .collect();
+            row.clear();
+            row.extend(0..size);
+            if descending {
+                row.sort_by(|&a, &b| nan_last_cmp(&values[b], &values[a]));
+            } else {
+                row.sort_by(|&a, &b| nan_last_cmp(&values[a], &values[b]));
+            }
+            let out_i: usize = index.into_iter().zip(out_strides).map(|(i, s)| i * s).sum();
+            for (j, &r) in row.iter().take(k).enumerate() {
+                out_buf[out_i + j * out_strides[ax]] = r;
+            }
+        }
+        Ok(out)
+    }
+
+    fn take_along_fwd<Src: Shape, Dst: Shape>(
+        &self,
+        ax: usize,
+        inp: &Tensor<Src, E, Self>,
+        idx: &Tensor<Dst, usize, Self>,
+    ) -> Result<Tensor<Dst, E, Self>, Error> {
+        let mut out = self.try_zeros_like(&idx.shape)?;
+        let mut out_idx = NdIndex::new(idx.shape, idx.shape.strides());
+        let mut idx_idx = NdIndex::new(idx.shape, idx.strides);
+        let out_buf = std::sync::Arc::make_mut(&mut out.data);
+        while let Some((out_i, index)) = out_idx.next_with_idx() {
+            let j = idx.data[idx_idx.next().unwrap()];
+            let inp_i: usize = index
+                .into_iter()
+                .enumerate()
+                .map(|(d, i)| if d == ax { j } else { i } * inp.strides[d])
+                .sum();
+            out_buf[out_i] = inp.data[inp_i];
+        }
+        Ok(out)
+    }
+
+    fn take_along_bwd<Src: Shape, Dst: Shape>(
+        &self,
+        ax: usize,
+        inp: &GhostTensor<Src, E, Self>,
+        grad_inp: &mut <Self as Storage<E>>::Vec,
+        idx: &Tensor<Dst, usize, Self>,
+        grad_out: &<Self as Storage<E>>::Vec,
+    ) -> Result<(), Error> {
+        let mut out_idx = NdIndex::new(idx.shape, idx.shape.strides());
+        let mut idx_idx = NdIndex::new(idx.shape, idx.strides);
+        while let Some((out_i, index)) = out_idx.next_with_idx() {
+            let j = idx.data[idx_idx.next().unwrap()];
+            let inp_i: usize = index
+                .into_iter()
+                .enumerate()
+                .map(|(d, i)| if d == ax { j } else { i } * inp.strides[d])
+                .sum();
+            grad_inp[inp_i] += grad_out[out_i];
+        }
+        Ok(())
+    }
+}
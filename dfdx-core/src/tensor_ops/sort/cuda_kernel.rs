@@ -0,0 +1,148 @@
+use crate::{
+    dtypes::*,
+    shapes::Shape,
+    tensor::{launch_cfg, Cuda, Error, GhostTensor, Storage, Tensor},
+};
+
+use cudarc::driver::{LaunchAsync, LaunchConfig};
+
+const PTX_SRC: &str = include_str!(concat!(env!("OUT_DIR"), "/sort.ptx"));
+
+trait HasCudaKernel<E> {
+    const FNS: &'static [&'static str];
+}
+#[cfg(feature = "f16")]
+impl HasCudaKernel<f16> for Cuda {
+    const FNS: &'static [&'static str] =
+        &["argsort_f16", "take_along_fwd_f16", "take_along_bwd_f16"];
+}
+#[cfg(feature = "f16")]
+impl HasCudaKernel<AMP<f16>> for Cuda {
+    const FNS: &'static [&'static str] =
+        &["argsort_f16", "take_along_fwd_f16", "take_along_bwd_f16"];
+}
+impl HasCudaKernel<f32> for Cuda {
+    const FNS: &'static [&'static str] =
+        &["argsort_f32", "take_along_fwd_f32", "take_along_bwd_f32"];
+}
+impl HasCudaKernel<f64> for Cuda {
+    const FNS: &'static [&'static str] =
+        &["argsort_f64", "take_along_fwd_f64", "take_along_bwd_f64"];
+}
+
+impl<E: Dtype> super::SortKernel<E> for Cuda
+where
+    Self: HasCudaKernel<E>,
+{
+    fn argsort<Src: Shape, Dst: Shape, T>(
+        &self,
+        ax: usize,
+        descending: bool,
+        inp: &Tensor<Src, E, Self, T>,
+        dst: Dst,
+    ) -> Result<Tensor<Dst, usize, Self>, Error> {
+        if !self.dev.has_func(Self::FNS[0], Self::FNS[0]) {
+            self.dev.load_ptx(PTX_SRC.into(), Self::FNS[0], Self::FNS)?;
+        }
+
+        let numel = inp.shape.num_elements();
+        let strides = dst.strides();
+
+        let mut out = unsafe { self.alloc_empty::<usize>(dst.num_elements()) }?;
+        if numel == 0 {
+            return Ok(self.build_tensor(dst, strides, out));
+        }
+        let size = inp.shape.concrete()[ax];
+        let num_rows = numel / size;
+        let padded = size.next_power_of_two();
+        let mut buf = unsafe { self.alloc_empty::<usize>(num_rows * padded) }?;
+        let dims = self.dev.htod_copy(inp.shape.concrete().into())?;
+        let inp_strides = self.dev.htod_copy(inp.strides.into())?;
+        let out_strides = self.dev.htod_copy(strides.into())?;
+
+        let fwd = self.dev.get_func(Self::FNS[0], Self::FNS[0]).unwrap();
+        // one block per row, with up to one thread per element of the row
+        let cfg = LaunchConfig {
+            grid_dim: (num_rows.min(65535) as u32, 1, 1),
+            block_dim: (padded.clamp(32, 1024) as u32, 1, 1),
+            shared_mem_bytes: 0,
+        };
+        let params = (
+            numel,
+            Src::NUM_DIMS,
+            ax,
+            dst.concrete()[ax],
+            descending,
+            &dims,
+            &inp_strides,
+            &out_strides,
+            inp.data.as_ref(),
+            &mut out,
+            &mut buf,
+        );
+        unsafe { fwd.launch(cfg, params) }?;
+        Ok(self.build_tensor(dst, strides, out))
+    }
+
+    fn take_along_fwd<Src: Shape, Dst: Shape>(
+        &self,
+        ax: usize,
+        inp: &Tensor<Src, E, Self>,
+        idx: &Tensor<Dst, usize, Self>,
+    ) -> Result<Tensor<Dst, E, Self>, Error> {
+        if !self.dev.has_func(Self::FNS[0], Self::FNS[1]) {
+            self.dev.load_ptx(PTX_SRC.into(), Self::FNS[0], Self::FNS)?;
+        }
+
+        let numel = idx.shape.num_elements();
+        let strides = idx.shape.strides();
+        assert_eq!(idx.strides, strides);
+
+        let mut out = unsafe { self.alloc_empty::<E>(numel) }?;
+        let dims = self.dev.htod_copy(idx.shape.concrete().into())?;
+        let inp_strides = self.dev.htod_copy(inp.strides.into())?;
+
+        let fwd = self.dev.get_func(Self::FNS[0], Self::FNS[1]).unwrap();
+        let cfg = launch_cfg::<128>(numel as u32);
+        let params = (
+            numel,
+            Src::NUM_DIMS,
+            ax,
+            &dims,
+            &inp_strides,
+            idx.data.as_ref(),
+            inp.data.as_ref(),
+            &mut out,
+        );
+        unsafe { fwd.launch(cfg, params) }?;
+        Ok(self.build_tensor(idx.shape, strides, out))
+    }
+
+    fn take_along_bwd<Src: Shape, Dst: Shape>(
+        &self,
+        ax: usize,
+        inp: &GhostTensor<Src, E, Self>,
+        grad_inp: &mut <Self as Storage<E>>::Vec,
+        idx: &Tensor<Dst, usize, Self>,
+        grad_out: &<Self as Storage<E>>::Vec,
+    ) -> Result<(), Error> {
+        let numel = idx.shape.num_elements();
+        let dims = self.dev.htod_copy(idx.shape.concrete().into())?;
+        let inp_strides = self.dev.htod_copy(inp.strides.into())?;
+
+        let bwd = self.dev.get_func(Self::FNS[0], Self::FNS[2]).unwrap();
+        let cfg = launch_cfg::<128>(numel as u32);
+        let params = (
+            numel,
+            Src::NUM_DIMS,
+            ax,
+            &dims,
+            &inp_strides,
+            idx.data.as_ref(),
+            grad_inp,
+            grad_out,
+        );
+        unsafe { bwd.launch(cfg, params) }?;
+        Ok(())
+    }
+}
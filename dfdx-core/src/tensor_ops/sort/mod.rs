@@ -0,0 +1,349 @@
+#![allow(clippy::type_complexity)]
+
+mod cpu_kernel;
+
+#[cfg(feature = "cuda")]
+mod cuda_kernel;
+
+#[cfg(feature = "webgpu")]
+mod webgpu_kernel;
+
+use crate::{shapes::*, tensor::*};
+
+pub trait SortKernel<E: Dtype>: Storage<E> + Storage<usize> {
+    /// The indices along `ax` that sort `inp`, keeping only the first `dst.concrete()[ax]`.
+    fn argsort<Src: Shape, Dst: Shape, T>(
+        &self,
+        ax: usize,
+        descending: bool,
+        inp: &Tensor<Src, E, Self, T>,
+        dst: Dst,
+    ) -> Result<Tensor<Dst, usize, Self>, Error>;
+
+    /// Takes the values of `inp` at `idx` along `ax`, where all other dimensions of `idx` are
+    /// the same as `inp`.
+    fn take_along_fwd<Src: Shape, Dst: Shape>(
+        &self,
+        ax: usize,
+        inp: &Tensor<Src, E, Self>,
+        idx: &Tensor<Dst, usize, Self>,
+    ) -> Result<Tensor<Dst, E, Self>, Error>;
+
+    fn take_along_bwd<Src: Shape, Dst: Shape>(
+        &self,
+        ax: usize,
+        inp: &GhostTensor<Src, E, Self>,
+        grad_inp: &mut <Self as Storage<E>>::Vec,
+        idx: &Tensor<Dst, usize, Self>,
+        grad_out: &<Self as Storage<E>>::Vec,
+    ) -> Result<(), Error>;
+}
+
+/// Sorts values along a single axis, which is always stable: equal values keep their
+/// order. NaN is greater than any other value, so NaNs come last in ascending order and
+/// first in descending order.
+///
+/// Gradients flow through the sorted values back to the positions they were taken from.
+///
+/// ```rust
+/// # use dfdx_core::prelude::*;
+/// # let dev: Cpu = Default::default();
+/// let t: Tensor<Rank2<2, 3>, f32, _> = dev.tensor([[2.0, 3.0, 1.0], [-1.0, -1.0, 0.0]]);
+///
+/// let r = t.clone().sort::<Axis<1>>(false);
+/// assert_eq!(r.array(), [[1.0, 2.0, 3.0], [-1.0, -1.0, 0.0]]);
+///
+/// let r = t.argsort::<Axis<1>>(true);
+/// assert_eq!(r.array(), [[1, 0, 2], [2, 0, 1]]);
+/// ```
+///
+/// The `k` largest values and their indices, which can be [Const] or [usize]:
+/// ```rust
+/// # use dfdx_core::prelude::*;
+/// # let dev: Cpu = Default::default();
+/// # let t: Tensor<Rank2<2, 3>, f32, _> = dev.tensor([[2.0, 3.0, 1.0], [-1.0, -1.0, 0.0]]);
+/// let (values, indices) = t.clone().topk::<Axis<1>, _>(Const::<2>);
+/// assert_eq!(values.array(), [[3.0, 2.0], [0.0, -1.0]]);
+/// assert_eq!(indices.array(), [[1, 0], [2, 0]]);
+///
+/// let (values, indices) = t.topk::<Axis<0>, _>(1);
+/// let _: Tensor<(usize, Const<3>), f32, _> = values;
+/// ```
+pub trait SortAlong<E, D: Storage<E> + Storage<usize>>: Sized + HasShape {
+    /// Sorts values along `Ax` in ascending or descending order.
+    /// **Pytorch equivalent**: `t.sort(dim=Ax, descending=descending, stable=True).values`
+    fn sort<Ax: Axes<Array = [isize; 1]>>(self, descending: bool) -> Self
+    where
+        Self::Shape: HasAxes<Ax>,
+    {
+        self.try_sort::<Ax>(descending).unwrap()
+    }
+
+    /// Fallible version of [SortAlong::sort]
+    fn try_sort<Ax: Axes<Array = [isize; 1]>>(self, descending: bool) -> Result<Self, Error>
+    where
+        Self::Shape: HasAxes<Ax>;
+
+    /// The indices along `Ax` that sort the values in ascending or descending order.
+    /// **Pytorch equivalent**: `t.argsort(dim=Ax, descending=descending, stable=True)`
+    fn argsort<Ax: Axes<Array = [isize; 1]>>(
+        &self,
+        descending: bool,
+    ) -> Tensor<Self::Shape, usize, D>
+    where
+        Self::Shape: HasAxes<Ax>,
+    {
+        self.try_argsort::<Ax>(descending).unwrap()
+    }
+
+    /// Fallible version of [SortAlong::argsort]
+    fn try_argsort<Ax: Axes<Array = [isize; 1]>>(
+        &self,
+        descending: bool,
+    ) -> Result<Tensor<Self::Shape, usize, D>, Error>
+    where
+        Self::Shape: HasAxes<Ax>;
+
+    /// The `k` largest values along `Ax` in descending order, and their indices along `Ax`.
+    /// **Pytorch equivalent**: `t.topk(k, dim=Ax)`
+    ///
+    /// Panics if `k` is larger than the size of `Ax`.
+    fn topk<Ax: Axes<Array = [isize; 1]>, K: Dim>(
+        self,
+        k: K,
+    ) -> (
+        Self::WithShape<<Self::Shape as ReplaceDimAlong<Ax, K>>::Output>,
+        Tensor<<Self::Shape as ReplaceDimAlong<Ax, K>>::Output, usize, D>,
+    )
+    where
+        Self::Shape: ReplaceDimAlong<Ax, K>,
+    {
+        self.try_topk::<Ax, K>(k).unwrap()
+    }
+
+    /// Fallible version of [SortAlong::topk], which returns [Error::TopKTooLarge] if `k` is
+    /// larger than the size of `Ax`.
+    fn try_topk<Ax: Axes<Array = [isize; 1]>, K: Dim>(
+        self,
+        k: K,
+    ) -> Result<
+        (
+            Self::WithShape<<Self::Shape as ReplaceDimAlong<Ax, K>>::Output>,
+            Tensor<<Self::Shape as ReplaceDimAlong<Ax, K>>::Output, usize, D>,
+        ),
+        Error,
+    >
+    where
+        Self::Shape: ReplaceDimAlong<Ax, K>;
+}
+
+impl<S: Shape, E: Dtype, D: SortKernel<E>, T: Tape<E, D>> SortAlong<E, D> for Tensor<S, E, D, T> {
+    fn try_sort<Ax: Axes<Array = [isize; 1]>>(self, descending: bool) -> Result<Self, Error>
+    where
+        S: HasAxes<Ax>,
+    {
+        let ax = Ax::as_array()[0] as usize;
+        let idx = self.device.argsort(ax, descending, &self, self.shape)?;
+        try_take_along(self, ax, idx)
+    }
+
+    fn try_argsort<Ax: Axes<Array = [isize; 1]>>(
+        &self,
+        descending: bool,
+    ) -> Result<Tensor<S, usize, D>, Error>
+    where
+        S: HasAxes<Ax>,
+    {
+        let ax = Ax::as_array()[0] as usize;
+        self.device.argsort(ax, descending, self, self.shape)
+    }
+
+    fn try_topk<Ax: Axes<Array = [isize; 1]>, K: Dim>(
+        self,
+        k: K,
+    ) -> Result<(Tensor<S::Output, E, D, T>, Tensor<S::Output, usize, D>), Error>
+    where
+        S: ReplaceDimAlong<Ax, K>,
+    {
+        let ax = Ax::as_array()[0] as usize;
+        let size = self.shape.concrete()[ax];
+        if k.size() > size {
+            return Err(Error::TopKTooLarge { k: k.size(), size });
+        }
+        let dst = self.shape.replace_along(k);
+        let idx = self.device.argsort(ax, true, &self, dst)?;
+        let values = try_take_along(self, ax, idx.clone())?;
+        Ok((values, idx))
+    }
+}
+
+/// The values of `inp` at `idx` along `ax`, scattering gradients back to those positions.
+fn try_take_along<Src: Shape, Dst: Shape, E: Dtype, D: SortKernel<E>, T: Tape<E, D>>(
+    inp: Tensor<Src, E, D, T>,
+    ax: usize,
+    idx: Tensor<Dst, usize, D>,
+) -> Result<Tensor<Dst, E, D, T>, Error> {
+    let (inp, mut tape) = inp.split_tape();
+    let out = inp.device.take_along_fwd(ax, &inp, &idx)?;
+    let inp_ghost = inp.ghost();
+    let out_ghost = out.ghost();
+    tape.try_add_op_info(&out, || OpInfo::new("TakeAlong", &out).input(&inp))?;
+    tape.add_retainable_backward_op(move |grads| {
+        grads.try_alloc_for(&inp_ghost)?;
+        grads.try_alloc_for(&out_ghost)?;
+        let (grad_inp, grad_out) = grads.mut_and_ref(&inp_ghost, &out_ghost);
+        inp_ghost
+            .dev
+            .take_along_bwd(ax, &inp_ghost, grad_inp, &idx, grad_out)
+    });
+    Ok(out.put_tape(tape))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{tensor_ops::*, tests::*};
+
+    #[test]
+    fn test_sort_1d() {
+        let dev: TestDevice = Default::default();
+        let t = dev
+            .tensor([0.5, -1.0, 2.0, 0.0, -1.0])
+            .to_dtype::<TestDtype>();
+        let r = t.leaky_trace().sort::<Axis<0>>(false);
+        assert_close_to_literal!(r, [-1.0, -1.0, 0.0, 0.5, 2.0]);
+        assert_eq!(t.argsort::<Axis<0>>(false).array(), [1, 4, 3, 0, 2]);
+        assert_eq!(t.argsort::<Axis<0>>(true).array(), [2, 0, 3, 1, 4]);
+
+        let w = dev
+            .tensor([1.0, 2.0, 3.0, 4.0, 5.0])
+            .to_dtype::<TestDtype>();
+        let g = (r * w).sum().backward();
+        assert_close_to_literal!(g.get(&t), [4.0, 1.0, 5.0, 3.0, 2.0]);
+    }
+
+    #[test]
+    fn test_sort_3d_each_axis() {
+        let dev: TestDevice = Default::default();
+        let t: Tensor<Rank3<2, 3, 4>, TestDtype, _> = dev.sample_normal();
+        let t_array = t.array();
+
+        let r = t.clone().sort::<Axis<0>>(true).array();
+        let idx = t.argsort::<Axis<0>>(true).array();
+        for j in 0..3 {
+            for k in 0..4 {
+                assert!(r[0][j][k] >= r[1][j][k]);
+                for i in 0..2 {
+                    assert_eq!(r[i][j][k], t_array[idx[i][j][k]][j][k]);
+                }
+            }
+        }
+
+        let r = t.clone().sort::<Axis<1>>(false).array();
+        let idx = t.argsort::<Axis<1>>(false).array();
+        for i in 0..2 {
+            for k in 0..4 {
+                for j in 0..3 {
+                    assert_eq!(r[i][j][k], t_array[i][idx[i][j][k]][k]);
+                    if j > 0 {
+                        assert!(r[i][j - 1][k] <= r[i][j][k]);
+                    }
+                }
+            }
+        }
+
+        let r = t.clone().sort::<Axis<2>>(false).array();
+        let idx = t.argsort::<Axis<2>>(false).array();
+        for i in 0..2 {
+            for j in 0..3 {
+                for k in 0..4 {
+                    assert_eq!(r[i][j][k], t_array[i][j][idx[i][j][k]]);
+                    if k > 0 {
+                        assert!(r[i][j][k - 1] <= r[i][j][k]);
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_sort_nans() {
+        let dev: TestDevice = Default::default();
+        let t = dev
+            .tensor([f64::NAN, 1.0, f64::NEG_INFINITY, f64::NAN, 0.0])
+            .to_dtype::<TestDtype>();
+        assert_eq!(t.argsort::<Axis<0>>(false).array(), [2, 4, 1, 0, 3]);
+        assert_eq!(t.argsort::<Axis<0>>(true).array(), [0, 3, 1, 4, 2]);
+        let (_, idx) = t.topk::<Axis<0>, _>(Const::<3>);
+        assert_eq!(idx.array(), [0, 3, 1]);
+    }
+
+    #[test]
+    fn test_topk_2d() {
+        let dev: TestDevice = Default::default();
+        let t = dev
+            .tensor([[0.1, 0.9, 0.4, 0.9], [-0.5, -0.2, -0.3, 0.0]])
+            .to_dtype::<TestDtype>();
+        let (r, idx) = t.leaky_trace().topk::<Axis<1>, _>(Const::<2>);
+        assert_close_to_literal!(r, [[0.9, 0.9], [0.0, -0.2]]);
+        assert_eq!(idx.array(), [[1, 3], [3, 1]]);
+        let g = r.exp().sum().backward();
+        assert_close_to_literal!(
+            g.get(&t),
+            [[0.0, 2.4596031, 0.0, 2.4596031], [0.0, 0.8187308, 0.0, 1.0]]
+        );
+
+        let (r, idx) = t.topk::<Axis<0>, _>(1);
+        assert_eq!(r.shape(), &(1, Const::<4>));
+        assert_close_to_literal!(r.realize::<Rank2<1, 4>>(), [[0.1, 0.9, 0.4, 0.9]]);
+        assert_eq!(idx.as_vec(), [0, 0, 0, 0]);
+    }
+
+    #[test]
+    fn test_try_topk_too_large() {
+        let dev: TestDevice = Default::default();
+        let t: Tensor<Rank2<2, 3>, TestDtype, _> = dev.sample_normal();
+        assert!(matches!(
+            t.try_topk::<Axis<1>, _>(4),
+            Err(Error::TopKTooLarge { k: 4, size: 3 })
+        ));
+    }
+
+    #[test]
+    fn test_topk_broadcasted() {
+        let dev: TestDevice = Default::default();
+        let t = dev.tensor([3.0, 1.0, 2.0]).to_dtype::<TestDtype>();
+        let b = t.leaky_trace().broadcast::<Rank2<2, 3>, _>();
+        let (r, idx) = b.topk::<Axis<1>, _>(Const::<2>);
+        assert_close_to_literal!(r, [[3.0, 2.0]; 2]);
+        assert_eq!(idx.array(), [[0, 2]; 2]);
+        let g = r.sum().backward();
+        assert_close_to_literal!(g.get(&t), [2.0, 0.0, 2.0]);
+
+        // the broadcasted axis is sorted too, keeping the order of equal values
+        let b = t.broadcast::<Rank2<3, 3>, Axis<0>>();
+        assert_eq!(b.argsort::<Axis<0>>(true).array(), [[0; 3], [1; 3], [2; 3]]);
+    }
+
+    #[test]
+    fn test_topk_permuted() {
+        let dev: TestDevice = Default::default();
+        let t = dev
+            .tensor([[1.0, 4.0], [3.0, 2.0], [0.0, 5.0]])
+            .to_dtype::<TestDtype>();
+        let p = t.leaky_trace().permute::<Rank2<2, 3>, _>();
+        let (r, idx) = p.topk::<Axis<1>, _>(Const::<1>);
+        assert_close_to_literal!(r, [[3.0], [5.0]]);
+        assert_eq!(idx.array(), [[1], [2]]);
+        let g = r.sum().backward();
+        assert_close_to_literal!(g.get(&t), [[0.0, 0.0], [1.0, 0.0], [0.0, 1.0]]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_topk_too_large() {
+        let dev: TestDevice = Default::default();
+        let t: Tensor<Rank1<3>, TestDtype, _> = dev.zeros();
+        let _ = t.topk::<Axis<0>, _>(4);
+    }
+}
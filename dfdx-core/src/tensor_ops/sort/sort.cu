@@ -0,0 +1,188 @@
+#include "cuda_utils.cuh"
+
+// NaN is greater than all other values, and equal to other NaNs.
+template<typename T>
+__device__ bool nan_last_gt(T a, T b) {
+    return (isnang(a) && !isnang(b)) || a > b;
+}
+
+// Whether element a of a row comes before element b in the sorted row. Ties are
+// broken by index, so the sort is stable. Indices past the end of the row are the
+// padding of the bitonic sort, and come after all elements of the row.
+template<typename T>
+__device__ bool comes_before(
+    const T *row,
+    const size_t stride,
+    const size_t size,
+    const bool descending,
+    const size_t a,
+    const size_t b
+) {
+    if (a >= size || b >= size) {
+        return a < b;
+    }
+    const T x = row[a * stride];
+    const T y = row[b * stride];
+    if (descending ? nan_last_gt(x, y) : nan_last_gt(y, x)) {
+        return true;
+    }
+    if (descending ? nan_last_gt(y, x) : nan_last_gt(x, y)) {
+        return false;
+    }
+    return a < b;
+}
+
+// One block per row along the axis, which bitonic sorts the indices of the row in
+// buf (padded to a power of two per row), and writes the first k of them to out.
+template<typename T>
+__device__ void argsort(
+    const size_t numel,
+    const size_t num_dims,
+    const size_t ax,
+    const size_t k,
+    const bool descending,
+    const size_t *dims,
+    const size_t *inp_strides,
+    const size_t *out_strides,
+    const T *inp,
+    size_t *out,
+    size_t *buf
+) {
+    const size_t size = dims[ax];
+    const size_t padded = next_power_of_two(size);
+    const size_t num_rows = numel / size;
+    for (size_t row = blockIdx.x; row < num_rows; row += gridDim.x) {
+        // the strided index of the start of the row in inp & out
+        size_t inp_i = 0;
+        size_t out_i = 0;
+        size_t idx = row;
+        for (int d = num_dims - 1; d >= 0; d--) {
+            if (d == ax) {
+                continue;
+            }
+            size_t dim_i = idx % dims[d];
+            inp_i += dim_i * inp_strides[d];
+            out_i += dim_i * out_strides[d];
+            idx /= dims[d];
+        }
+
+        size_t *row_buf = buf + row * padded;
+        for (size_t j = threadIdx.x; j < padded; j += blockDim.x) {
+            row_buf[j] = j;
+        }
+        __syncthreads();
+
+        for (size_t len = 2; len <= padded; len <<= 1) {
+            for (size_t incr = len >> 1; incr > 0; incr >>= 1) {
+                for (size_t j = threadIdx.x; j < padded; j += blockDim.x) {
+                    const size_t l = j ^ incr;
+                    if (l > j) {
+                        // sub-sequences alternate between ascending and descending order
+                        const bool ascending = (j & len) == 0;
+                        const size_t a = row_buf[j];
+                        const size_t b = row_buf[l];
+                        if (comes_before(inp + inp_i, inp_strides[ax], size, descending, b, a) == ascending) {
+                            row_buf[j] = b;
+                            row_buf[l] = a;
+                        }
+                    }
+                }
+                __syncthreads();
+            }
+        }
+
+        for (size_t j = threadIdx.x; j < k; j += blockDim.x) {
+            out[out_i + j * out_strides[ax]] = row_buf[j];
+        }
+    }
+}
+
+// One thread per output element, where out & idx are contiguous with dims `dims`.
+__device__ size_t take_along_index(
+    const size_t i,
+    const size_t num_dims,
+    const size_t ax,
+    const size_t *dims,
+    const size_t *inp_strides,
+    const size_t *idx
+) {
+    size_t inp_i = 0;
+    unsigned int rem = i;
+    for (int d = num_dims - 1; d >= 0; d--) {
+        size_t dim_i = rem % dims[d];
+        inp_i += (d == ax ? idx[i] : dim_i) * inp_strides[d];
+        rem /= dims[d];
+    }
+    return inp_i;
+}
+
+template<typename T>
+__device__ void take_along_fwd(
+    const size_t numel,
+    const size_t num_dims,
+    const size_t ax,
+    const size_t *dims,
+    const size_t *inp_strides,
+    const size_t *idx,
+    const T *inp,
+    T *out
+) {
+    for (unsigned int i = blockIdx.x * blockDim.x + threadIdx.x; i < numel; i += blockDim.x * gridDim.x) {
+        out[i] = inp[take_along_index(i, num_dims, ax, dims, inp_strides, idx)];
+    }
+}
+
+template<typename T>
+__device__ void take_along_bwd(
+    const size_t numel,
+    const size_t num_dims,
+    const size_t ax,
+    const size_t *dims,
+    const size_t *inp_strides,
+    const size_t *idx,
+    T *grad_inp,
+    const T *grad_out
+) {
+    for (unsigned int i = blockIdx.x * blockDim.x + threadIdx.x; i < numel; i += blockDim.x * gridDim.x) {
+        atomicAdd(grad_inp + take_along_index(i, num_dims, ax, dims, inp_strides, idx), grad_out[i]);
+    }
+}
+
+#define SORT(TY, ARGSORT, FWD, BWD) \
+extern "C" __global__ void ARGSORT( \
+    const size_t numel, \
+    const size_t num_dims, \
+    const size_t ax, \
+    const size_t k, \
+    const bool descending, \
+    const size_t *dims, \
+    const size_t *inp_strides, \
+    const size_t *out_strides, \
+    const TY *inp, \
+    size_t *out, \
+    size_t *buf \
+) { argsort(numel, num_dims, ax, k, descending, dims, inp_strides, out_strides, inp, out, buf); } \
+extern "C" __global__ void FWD( \
+    const size_t numel, \
+    const size_t num_dims, \
+    const size_t ax, \
+    const size_t *dims, \
+    const size_t *inp_strides, \
+    const size_t *idx, \
+    const TY *inp, \
+    TY *out \
+) { take_along_fwd(numel, num_dims, ax, dims, inp_strides, idx, inp, out); } \
+extern "C" __global__ void BWD( \
+    const size_t numel, \
+    const size_t num_dims, \
+    const size_t ax, \
+    const size_t *dims, \
+    const size_t *inp_strides, \
+    const size_t *idx, \
+    TY *grad_inp, \
+    const TY *grad_out \
+) { take_along_bwd(numel, num_dims, ax, dims, inp_strides, idx, grad_inp, grad_out); }
+
+SORT(__half, argsort_f16, take_along_fwd_f16, take_along_bwd_f16);
+SORT(float, argsort_f32, take_along_fwd_f32, take_along_bwd_f32);
+SORT(double, argsort_f64, take_along_fwd_f64, take_along_bwd_f64);
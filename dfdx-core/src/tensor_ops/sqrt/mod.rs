@@ -7,12 +7,25 @@ mod cuda_kernel;
 mod webgpu_kernel;
 
 use super::ops::{try_unary_op, UnaryKernel};
+use super::utilities::higher_order::UnaryDerivative;
 use crate::{shapes::*, tensor::*};
 
 #[repr(C)]
 #[derive(Debug, Default, Copy, Clone)]
 pub struct SqrtKernelOp;
 
+impl<E> UnaryDerivative<E> for SqrtKernelOp {
+    fn df<D: Storage<E>>(
+        &self,
+        ops: &mut dyn HigherOrderOps<E, D>,
+        _x: HigherOrderVar,
+        y: HigherOrderVar,
+    ) -> Result<Option<HigherOrderVar>, Error> {
+        let df = ops.recip(y)?;
+        ops.scale(df, 0.5).map(Some)
+    }
+}
+
 /// `√t` or `t^0.5`
 ///
 /// The derivative is `0.5 / (t ^ 0.5)`.
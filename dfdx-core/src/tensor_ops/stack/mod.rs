@@ -152,7 +152,12 @@ where
 
     let inp_ghosts: Vec<_> = tensors.iter().map(|t| t.ghost()).collect();
     let out_ghost = out.ghost();
-    tape.add_backward_op(move |grads| {
+    tape.try_add_op_info(&out, || {
+        tensors
+            .iter()
+            .fold(OpInfo::new("Stack", &out), |info, t| info.input(t))
+    })?;
+    tape.add_retainable_backward_op(move |grads| {
         for t in inp_ghosts.iter() {
             grads.try_alloc_for(t)?;
         }
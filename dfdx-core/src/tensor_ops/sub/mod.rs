@@ -7,18 +7,41 @@ mod cuda_kernel;
 mod webgpu_kernel;
 
 use super::ops::*;
+use super::utilities::higher_order::{BinaryDerivative, UnaryDerivative};
 use crate::{shapes::*, tensor::*};
 
 #[repr(C)]
 #[derive(Debug, Default, Clone, Copy)]
 pub struct BinarySubKernelOp;
 
+impl<E> BinaryDerivative<E> for BinarySubKernelOp {
+    fn df<D: Storage<E>>(
+        &self,
+        _ops: &mut dyn HigherOrderOps<E, D>,
+        _x: HigherOrderVar,
+        _y: HigherOrderVar,
+    ) -> Result<Option<(HigherOrderVar, HigherOrderVar)>, Error> {
+        Ok(None)
+    }
+}
+
 #[repr(C)]
 #[derive(Debug, Clone, Copy)]
 pub struct ScalarSubKernelOp<E> {
     scalar: E,
 }
 
+impl<E> UnaryDerivative<E> for ScalarSubKernelOp<E> {
+    fn df<D: Storage<E>>(
+        &self,
+        _ops: &mut dyn HigherOrderOps<E, D>,
+        _x: HigherOrderVar,
+        _y: HigherOrderVar,
+    ) -> Result<Option<HigherOrderVar>, Error> {
+        Ok(None)
+    }
+}
+
 /// Element wise and scalar subtraction.
 ///
 /// Example:
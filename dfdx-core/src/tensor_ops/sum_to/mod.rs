@@ -6,6 +6,7 @@ mod cuda_kernel;
 #[cfg(feature = "webgpu")]
 mod webgpu_kernel;
 
+use super::utilities::higher_order::sum_backward;
 use crate::{shapes::*, tensor::*};
 
 pub trait SumKernel<E: Dtype>: Storage<E> {
@@ -70,12 +71,19 @@ impl<S: Shape, E: Dtype, D: SumKernel<E>, T: Tape<E, D>> SumTo for Tensor<S, E,
         let out = inp.device.forward(dst, &inp)?;
         let inp_ghost = inp.ghost();
         let out_ghost = out.ghost();
-        tape.add_backward_op(move |grads| {
+        let higher_order = tape.is_higher_order().then(|| (inp.ghost(), out.ghost()));
+        tape.try_add_op_info(&out, || OpInfo::new("Sum", &out).input(&inp))?;
+        tape.add_retainable_backward_op(move |grads| {
             grads.try_alloc_for(&inp_ghost)?;
             grads.try_alloc_for(&out_ghost)?;
             let (grad_inp, grad_out) = grads.mut_and_ref(&inp_ghost, &out_ghost);
             inp.device.backward(dst, &inp_ghost, grad_inp, grad_out)
         });
+        if let Some((inp_ghost, out_ghost)) = higher_order {
+            tape.add_higher_order_op(move |ops| {
+                sum_backward::<S, Dst, Ax, E, D>(ops, &inp_ghost, &out_ghost)
+            });
+        }
         Ok(out.put_tape(tape))
     }
 }
@@ -7,12 +7,26 @@ mod cuda_kernel;
 mod webgpu_kernel;
 
 use super::ops::{try_unary_op, UnaryKernel};
+use super::utilities::higher_order::UnaryDerivative;
 use crate::{shapes::*, tensor::*};
 
 #[repr(C)]
 #[derive(Debug, Default, Copy, Clone)]
 pub struct TanhKernelOp;
 
+impl<E> UnaryDerivative<E> for TanhKernelOp {
+    fn df<D: Storage<E>>(
+        &self,
+        ops: &mut dyn HigherOrderOps<E, D>,
+        _x: HigherOrderVar,
+        y: HigherOrderVar,
+    ) -> Result<Option<HigherOrderVar>, Error> {
+        let y2 = ops.square(y)?;
+        let df = ops.scale(y2, -1.0)?;
+        ops.add_scalar(df, 1.0).map(Some)
+    }
+}
+
 /// [Hyperbolic Tangent (Tanh)](https://en.wikipedia.org/wiki/Hyperbolic_functions).
 ///
 /// The derivative is `1.0 - square(tanh(t))`.
@@ -5,7 +5,7 @@ mod cuda_kernel;
 
 use crate::{
     shapes::*,
-    tensor::{Error, PutTape, SplitTape, Storage, Tape, Tensor, ZerosTensor},
+    tensor::{Error, OpInfo, PutTape, SplitTape, Storage, Tape, Tensor, ZerosTensor},
 };
 
 #[repr(C)]
@@ -187,7 +187,8 @@ impl<
         let inp_ghost = inp.ghost();
         let out_ghost = out.ghost();
         let out_clone = out.clone();
-        tape.add_backward_op(move |grads| {
+        tape.try_add_op_info(&out, || OpInfo::new("Upscale2D", &out).input(&inp))?;
+        tape.add_retainable_backward_op(move |grads| {
             grads.try_alloc_for(&inp_ghost)?;
             grads.try_alloc_for(&out_ghost)?;
             let (grad_inp, grad_out) = grads.mut_and_ref(&inp_ghost, &out_ghost);
@@ -233,7 +234,8 @@ impl<
         let inp_ghost = inp.ghost();
         let out_ghost = out.ghost();
         let out_clone = out.clone();
-        tape.add_backward_op(move |grads| {
+        tape.try_add_op_info(&out, || OpInfo::new("Upscale2D", &out).input(&inp))?;
+        tape.add_retainable_backward_op(move |grads| {
             grads.try_alloc_for(&inp_ghost)?;
             grads.try_alloc_for(&out_ghost)?;
             let (grad_inp, grad_out) = grads.mut_and_ref(&inp_ghost, &out_ghost);
@@ -11,6 +11,39 @@ pub trait Backward<E, D: Storage<E>>: Sized {
     }
     /// Fallible version of [Backward::backward]
     fn try_backward(self) -> Result<Gradients<E, D>, Error>;
+
+    /// Runs backprop without consuming the tape, so that backprop can be run again through
+    /// the same operations. This is what PyTorch calls `retain_graph=True`.
+    ///
+    /// The returned gradients only contain the gradients from this call, so backprop of
+    /// multiple losses that share a tape gives separate gradients for each loss.
+    ///
+    /// Returns [Error::NotRetainable] if the tape contains an operation that can only run
+    /// once, like [crate::tensor_ops::custom_op()] and [crate::tensor_ops::try_checkpoint()].
+    /// Nothing is run in that case. Since the check covers the whole tape, this also applies
+    /// when such an operation is part of a trunk shared by multiple heads: only the last
+    /// head can run backprop, with [Backward::backward].
+    ///
+    /// Example, two heads on top of a shared trunk:
+    /// ```rust
+    /// # use dfdx_core::prelude::*;
+    /// # let dev: Cpu = Default::default();
+    /// let x: Tensor<Rank1<3>, f32, _> = dev.tensor([1.0, 2.0, 3.0]);
+    /// let (trunk, tape) = x.leaky_trace().square().split_tape();
+    /// // share the tape between the heads
+    /// let trunk = trunk.put_tape(std::sync::Arc::new(std::sync::Mutex::new(tape)));
+    /// let mut loss_a = trunk.clone().sum();
+    /// let loss_b = trunk.mean();
+    /// let grads_a = loss_a.backward_retain_graph();
+    /// let grads_b = loss_b.backward();
+    /// assert_eq!(grads_a.get(&x).array(), [2.0, 4.0, 6.0]);
+    /// assert_eq!(grads_b.get(&x).array(), [2.0 / 3.0, 4.0 / 3.0, 2.0]);
+    /// ```
+    fn backward_retain_graph(&mut self) -> Gradients<E, D> {
+        self.try_backward_retain_graph().unwrap()
+    }
+    /// Fallible version of [Backward::backward_retain_graph]
+    fn try_backward_retain_graph(&mut self) -> Result<Gradients<E, D>, Error>;
 }
 
 impl<E: 'static + Clone, D: OneFillStorage<E>> Backward<E, D>
@@ -27,6 +60,17 @@ impl<E: 'static + Clone, D: OneFillStorage<E>> Backward<E, D>
         grads.drop_non_leafs();
         Ok(grads)
     }
+
+    fn try_backward_retain_graph(&mut self) -> Result<Gradients<E, D>, Error> {
+        let t_ghost = self.ghost();
+        let device = self.device.clone();
+        let mut grads = self.tape.execute_retained(|grads| {
+            grads.try_alloc_for(&t_ghost)?;
+            device.try_fill_with_ones(grads.get_mut(&t_ghost))
+        })?;
+        grads.drop_non_leafs();
+        Ok(grads)
+    }
 }
 
 #[cfg(feature = "std")]
@@ -45,4 +89,15 @@ impl<E: 'static + Clone, D: OneFillStorage<E>> Backward<E, D>
         grads.drop_non_leafs();
         Ok(grads)
     }
+
+    fn try_backward_retain_graph(&mut self) -> Result<Gradients<E, D>, Error> {
+        let t_ghost = self.ghost();
+        let device = self.device.clone();
+        let mut grads = self.tape.lock().unwrap().execute_retained(|grads| {
+            grads.try_alloc_for(&t_ghost)?;
+            device.try_fill_with_ones(grads.get_mut(&t_ghost))
+        })?;
+        grads.drop_non_leafs();
+        Ok(grads)
+    }
 }
@@ -1,13 +1,13 @@
 use super::super::ops::{BinaryKernel, UnaryKernel};
 use crate::{
     dtypes::*,
-    tensor::{CopySlice, RandomU64, Storage},
+    tensor::{CopySlice, RngState, Storage},
 };
 
 /// A [Storage] that requires all the tensor ops implementations
 pub trait Device<E: Dtype>:
     Storage<E>
-    + RandomU64
+    + RngState
     + CopySlice<E>
     + crate::tensor::TensorFromVec<E>
     + crate::tensor::TensorFromVec<usize>
@@ -25,6 +25,15 @@ pub trait Device<E: Dtype>:
     + super::super::adam::AdamKernel<E>
     + super::super::sgd::SgdKernel<E>
     + super::super::rmsprop::RMSpropKernel<E>
+    + super::super::adagrad::AdaGradKernel<E>
+    + super::super::adadelta::AdadeltaKernel<E>
+    + super::super::adamax::AdamaxKernel<E>
+    + super::super::nadam::NAdamKernel<E>
+    + super::super::radam::RAdamKernel<E>
+    + super::super::lamb::LambKernel<E>
+    + super::super::lars::LarsKernel<E>
+    + super::super::lion::LionKernel<E>
+    + super::super::adafactor::AdafactorKernel<E>
 
     // allocation
     + crate::tensor::ZerosTensor<E>
@@ -37,20 +46,32 @@ pub trait Device<E: Dtype>:
     + super::super::sum_to::SumKernel<E>
     + super::super::max_to::MaxReduceKernel<E>
     + super::super::min_to::MinReduceKernel<E>
+    + super::super::arg_reduce_to::ArgReduceKernel<super::super::arg_reduce_to::ArgMaxKernelOp, E>
+    + super::super::arg_reduce_to::ArgReduceKernel<super::super::arg_reduce_to::ArgMinKernelOp, E>
     + super::super::reshape_to::ReshapeKernel<E>
 
+    // cumulative scans
+    + super::super::cumulative::CumKernel<super::super::cumulative::CumSumKernelOp, E>
+    + super::super::cumulative::CumKernel<super::super::cumulative::CumProdKernelOp, E>
+    + super::super::cumulative::CumKernel<super::super::cumulative::CumMaxKernelOp, E>
+    + super::super::cumulative::CumKernel<super::super::cumulative::CumMinKernelOp, E>
+    + super::super::cumulative::CumKernel<super::super::cumulative::LogCumSumExpKernelOp, E>
+
     // indexing
     + super::super::select_and_gather::ReplaceDimKernel<E>
     + super::super::select_and_gather::RemoveDimKernel<E>
+    + super::super::scatter::ScatterKernel<E>
     + super::super::choose::ChooseKernel<E>
     + super::super::slice::SliceKernel<E>
     + super::super::roll::RollKernel<E>
+    + super::super::sort::SortKernel<E>
 
     // matmuls
     + super::super::matmul::MatMatKernel<E>
     + super::super::matmul::MatMatBrKernel<E>
     + super::super::matmul::MatMatBatch3Kernel<E>
     + super::super::matmul::MatMatBatch4Kernel<E>
+    + super::super::einsum::EinsumKernel<E>
 
     // scalar arithmetic
     + UnaryKernel<super::super::add::ScalarAddKernelOp<E>, E>
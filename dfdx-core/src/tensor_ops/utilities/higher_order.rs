@@ -0,0 +1,657 @@
+use super::reduction_utils::index_for_reductions;
+use crate::{
+    shapes::{Axes, Dtype, HasAxes, HasShape, Shape},
+    tensor::{cpu::NdIndex, *},
+};
+
+use std::vec::Vec;
+
+#[cfg(feature = "std")]
+use super::Device;
+#[cfg(feature = "std")]
+use crate::tensor_ops::{axpy::AxpyKernel, select_and_gather::ReplaceDimKernel, *};
+#[cfg(feature = "std")]
+use std::{
+    collections::BTreeMap,
+    sync::{Arc, Mutex},
+};
+
+/// The derivative of a unary operation in terms of [HigherOrderOps].
+pub(crate) trait UnaryDerivative<E> {
+    /// Computes `df(x)` given the input `x` and output `y` of the operation.
+    ///
+    /// Returns `None` if the derivative is piecewise constant, in which case it is
+    /// computed with the first order backward kernel and treated as a constant.
+    fn df<D: Storage<E>>(
+        &self,
+        ops: &mut dyn HigherOrderOps<E, D>,
+        x: HigherOrderVar,
+        y: HigherOrderVar,
+    ) -> Result<Option<HigherOrderVar>, Error>;
+}
+
+/// The partial derivatives of a binary operation in terms of [HigherOrderOps].
+pub(crate) trait BinaryDerivative<E> {
+    /// Computes `(df/dx, df/dy)` given the (logical, contiguous) inputs `x` and `y`.
+    ///
+    /// Returns `None` if the derivatives are piecewise constant, in which case they are
+    /// computed with the first order backward kernel and treated as constants.
+    #[allow(clippy::type_complexity)]
+    fn df<D: Storage<E>>(
+        &self,
+        ops: &mut dyn HigherOrderOps<E, D>,
+        x: HigherOrderVar,
+        y: HigherOrderVar,
+    ) -> Result<Option<(HigherOrderVar, HigherOrderVar)>, Error>;
+}
+
+/// A 1d tensor with the same id & physical data as `t`.
+pub(crate) fn flat<S: Shape, E, D: Storage<E>, T>(
+    t: &Tensor<S, E, D, T>,
+) -> Tensor<(usize,), E, D> {
+    Tensor {
+        id: t.id,
+        data: t.data.clone(),
+        shape: (t.device.len(&t.data),),
+        strides: [1],
+        device: t.device.clone(),
+        tape: NoneTape,
+    }
+}
+
+/// A new 1d tensor containing `data`.
+pub(crate) fn flat_from_vec<E, D: Storage<E>>(device: &D, data: D::Vec) -> Tensor<(usize,), E, D> {
+    Tensor {
+        id: unique_id(),
+        shape: (device.len(&data),),
+        data: std::sync::Arc::new(data),
+        strides: [1],
+        device: device.clone(),
+        tape: NoneTape,
+    }
+}
+
+/// Views a 1d tensor as a contiguous tensor of `shape`.
+pub(crate) fn unflatten<S: Shape, E, D: Storage<E>>(
+    t: Tensor<(usize,), E, D>,
+    shape: S,
+) -> Tensor<S, E, D> {
+    assert_eq!(t.shape.0, shape.num_elements());
+    Tensor {
+        id: t.id,
+        data: t.data,
+        shape,
+        strides: shape.strides(),
+        device: t.device,
+        tape: NoneTape,
+    }
+}
+
+/// The physical index of each logical element, or `None` if the
+/// physical and logical layouts are the same.
+pub(crate) fn physical_indices<S: Shape>(shape: S, strides: S::Concrete) -> Option<Vec<usize>> {
+    if strides == shape.strides() {
+        return None;
+    }
+    let mut index = NdIndex::new(shape, strides);
+    let mut indices = Vec::with_capacity(shape.num_elements());
+    while let Some(i) = index.next() {
+        indices.push(i);
+    }
+    Some(indices)
+}
+
+/// The elements of `t` in logical order.
+pub(crate) fn logical_value<S: Shape, E, D: Storage<E>>(
+    ops: &mut dyn HigherOrderOps<E, D>,
+    t: &Tensor<S, E, D>,
+) -> Result<HigherOrderVar, Error> {
+    let value = ops.value(flat(t));
+    match physical_indices(t.shape, t.strides) {
+        Some(idx) => ops.gather(value, idx),
+        None => Ok(value),
+    }
+}
+
+/// The gradient of `t` in logical order, if there is one.
+pub(crate) fn logical_grad<S: Shape, E, D: Storage<E>>(
+    ops: &mut dyn HigherOrderOps<E, D>,
+    t: &impl Tensorlike<S, E, D>,
+) -> Result<Option<HigherOrderVar>, Error> {
+    let Some(grad) = ops.grad(t.id(), t.len()) else {
+        return Ok(None);
+    };
+    match physical_indices(*t.shape(), t.strides()) {
+        Some(idx) => ops.gather(grad, idx).map(Some),
+        None => Ok(Some(grad)),
+    }
+}
+
+/// Accumulates a gradient in logical order into the physical gradient of `t`.
+pub(crate) fn accumulate_logical<S: Shape, E, D: Storage<E>>(
+    ops: &mut dyn HigherOrderOps<E, D>,
+    t: &impl Tensorlike<S, E, D>,
+    grad: HigherOrderVar,
+) -> Result<(), Error> {
+    let grad = match physical_indices(*t.shape(), t.strides()) {
+        Some(idx) => ops.scatter_add(grad, idx, t.len())?,
+        None => grad,
+    };
+    ops.accumulate_grad(t.id(), grad)
+}
+
+/// For each element being reduced over, its physical index in the input and
+/// the logical index of the output element it is reduced into.
+fn reduction_indices<S: Shape + HasAxes<Ax>, Ax: Axes>(
+    shape: S,
+    strides: S::Concrete,
+) -> (Vec<usize>, Vec<usize>) {
+    let numel = shape.num_elements();
+    let mut src = Vec::with_capacity(numel);
+    let mut dst = Vec::with_capacity(numel);
+    if numel > 0 {
+        let num_reduced = HasAxes::<Ax>::size(&shape);
+        let mut index = index_for_reductions::<S, Ax>(shape, strides);
+        while let Some(i) = index.next() {
+            dst.push(src.len() / num_reduced);
+            src.push(i);
+        }
+    }
+    (src, dst)
+}
+
+/// Higher order backward of summing `inp` along `Ax` into `out`.
+pub(crate) fn sum_backward<Src: Shape + HasAxes<Ax>, Dst: Shape, Ax: Axes, E, D: Storage<E>>(
+    ops: &mut dyn HigherOrderOps<E, D>,
+    inp: &impl Tensorlike<Src, E, D>,
+    out: &impl Tensorlike<Dst, E, D>,
+) -> Result<(), Error> {
+    let Some(grad_out) = logical_grad(ops, out)? else {
+        return Ok(());
+    };
+    let (src, dst) = reduction_indices::<Src, Ax>(*inp.shape(), inp.strides());
+    let grad_inp = ops.gather(grad_out, dst)?;
+    let grad_inp = ops.scatter_add(grad_inp, src, inp.len())?;
+    ops.accumulate_grad(inp.id(), grad_inp)
+}
+
+/// Higher order backward of reducing `inp` along `Ax` into `out` with a max or min.
+/// The gradient flows into every element that is equal to the reduced value.
+pub(crate) fn extremum_backward<
+    Src: Shape + HasAxes<Ax>,
+    Dst: Shape,
+    Ax: Axes,
+    E,
+    D: Storage<E>,
+>(
+    ops: &mut dyn HigherOrderOps<E, D>,
+    inp: &Tensor<Src, E, D>,
+    out: &Tensor<Dst, E, D>,
+) -> Result<(), Error> {
+    let Some(grad_out) = logical_grad(ops, out)? else {
+        return Ok(());
+    };
+    let (src, dst) = reduction_indices::<Src, Ax>(inp.shape, inp.strides);
+    let x = ops.value(flat(inp));
+    let x = ops.gather(x, src.clone())?;
+    let y = logical_value(ops, out)?;
+    let y = ops.gather(y, dst.clone())?;
+    let mask = ops.eq(x, y)?;
+    let grad_inp = ops.gather(grad_out, dst)?;
+    let grad_inp = ops.mul(grad_inp, mask)?;
+    let grad_inp = ops.scatter_add(grad_inp, src, inp.len())?;
+    ops.accumulate_grad(inp.id, grad_inp)
+}
+
+/// Runs backprop on a tape with higher order gradients enabled
+/// (see [Tensor::higher_order()]).
+///
+/// Instead of plain [Gradients], this returns [TracedGradients], whose
+/// gradients are tensors that are themselves being traced. Calling
+/// `.backward()` on a function of them gives the gradients of that function with
+/// respect to the original tensors. This is useful for things like gradient penalties,
+/// meta learning, or hessian vector products.
+///
+/// ```rust
+/// # use dfdx_core::prelude::*;
+/// # let dev: Cpu = Default::default();
+/// let x: Tensor<Rank1<2>, f32, _> = dev.tensor([0.0, 1.0]);
+/// let w: Tensor<Rank1<2>, f32, _> = dev.tensor([2.0, 3.0]);
+/// let y = (x.leaky_trace().higher_order() * w.clone()).sin().sum();
+/// let g = y.traced_backward();
+/// // penalize the norm of the gradient with respect to x
+/// let penalty = g.get(&x).square().sum();
+/// let g2 = penalty.backward();
+/// let _ = g2.get(&w);
+/// ```
+///
+/// Every operation that the tape recorded must support higher order
+/// gradients, otherwise [Error::NoHigherOrderGradient] is returned.
+#[cfg(feature = "std")]
+pub trait TracedBackward<E, D: Storage<E>>: Sized {
+    /// Runs backprop, recording the backward pass onto a new tape.
+    fn traced_backward(self) -> TracedGradients<E, D> {
+        self.try_traced_backward().unwrap()
+    }
+    /// Fallible version of [TracedBackward::traced_backward]
+    fn try_traced_backward(self) -> Result<TracedGradients<E, D>, Error>;
+}
+
+#[cfg(feature = "std")]
+type SharedTape<E, D> = Arc<Mutex<OwnedTape<E, D>>>;
+#[cfg(feature = "std")]
+type TracedFlat<E, D> = Tensor<(usize,), E, D, SharedTape<E, D>>;
+
+/// Gradients computed by [TracedBackward]. Each gradient is a tensor that records
+/// operations onto a shared tape, so it can be differentiated again.
+#[cfg(feature = "std")]
+pub struct TracedGradients<E, D: Storage<E>> {
+    tape: SharedTape<E, D>,
+    device: D,
+    grads: BTreeMap<UniqueId, TracedFlat<E, D>>,
+    vars: Vec<TracedFlat<E, D>>,
+    /// If set, missing gradients are created as new zero valued leaf tensors when requested.
+    leaves: Option<Vec<TracedFlat<E, D>>>,
+    leaf_error: Option<Error>,
+}
+
+#[cfg(feature = "std")]
+impl<E, D: Storage<E>> std::fmt::Debug for TracedGradients<E, D> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TracedGradients")
+            .field("num_gradients", &self.grads.len())
+            .finish()
+    }
+}
+
+#[cfg(feature = "std")]
+impl<E: Dtype, D: Storage<E>> TracedGradients<E, D> {
+    /// Returns the gradient for `t` as a traced tensor.
+    ///
+    /// # Panics
+    /// If no gradient was computed for `t`.
+    pub fn get<S: Shape>(&self, t: &impl Tensorlike<S, E, D>) -> Tensor<S, E, D, SharedTape<E, D>> {
+        self.get_checked(t).unwrap()
+    }
+
+    /// Returns the gradient for `t` as a traced tensor, if it exists.
+    pub fn get_checked<S: Shape>(
+        &self,
+        t: &impl Tensorlike<S, E, D>,
+    ) -> Option<Tensor<S, E, D, SharedTape<E, D>>> {
+        self.grads.get(&t.id()).map(|grad| Tensor {
+            id: grad.id,
+            data: grad.data.clone(),
+            shape: *t.shape(),
+            strides: t.strides(),
+            device: grad.device.clone(),
+            tape: self.tape.clone(),
+        })
+    }
+
+    fn push(&mut self, t: TracedFlat<E, D>) -> HigherOrderVar {
+        self.vars.push(t);
+        HigherOrderVar(self.vars.len() - 1)
+    }
+
+    fn var(&self, a: HigherOrderVar) -> TracedFlat<E, D> {
+        self.vars[a.0].clone()
+    }
+}
+
+#[cfg(feature = "std")]
+impl<E: Dtype, D: Device<E>> TracedBackward<E, D>
+    for Tensor<crate::shapes::Rank0, E, D, OwnedTape<E, D>>
+{
+    fn try_traced_backward(self) -> Result<TracedGradients<E, D>, Error> {
+        let (t, mut tape) = self.split_tape();
+        let mut operations = std::mem::take(&mut tape.operations);
+        let mut higher_order: BTreeMap<_, _> = std::mem::take(&mut tape.higher_order_operations)
+            .into_iter()
+            .collect();
+        operations.sort_by_key(|(k, _)| *k);
+        operations.dedup_by_key(|(k, _)| *k);
+
+        // The first order backward operations are moved onto the shared tape, so that
+        // they run when the traced gradients are backpropped through.
+        let gradients = std::mem::replace(&mut tape.gradients, Gradients::leaky());
+        let mut traced = TracedGradients {
+            tape: Arc::new(Mutex::new(OwnedTape::from(gradients))),
+            device: t.device.clone(),
+            grads: Default::default(),
+            vars: Vec::new(),
+            leaves: None,
+            leaf_error: None,
+        };
+        let seed = traced.ones(<D as Storage<E>>::len(&t.device, &t.data))?;
+        traced
+            .grads
+            .insert(t.id, seed.put_tape(traced.tape.clone()));
+
+        for (time, operation) in operations.into_iter().rev() {
+            let higher_order_op = higher_order
+                .remove(&time)
+                .ok_or(Error::NoHigherOrderGradient)?;
+            (higher_order_op)(&mut traced)?;
+            traced.vars.clear();
+            traced
+                .tape
+                .lock()
+                .unwrap()
+                .operations
+                .push((time, operation));
+        }
+        Ok(traced)
+    }
+}
+
+/// Computes the tangents of the outputs of a single operation from the tangents of its
+/// inputs (keyed by id, in physical order), using its differentiable backward pass.
+///
+/// The backward pass is linear in the gradient of the output `u`, so the gradient of
+/// `sum(backward(u) * tangents)` with respect to `u` is the jacobian vector product.
+#[cfg(feature = "std")]
+#[allow(clippy::type_complexity)]
+pub(crate) fn push_forward<E: Dtype, D: Device<E>>(
+    device: &D,
+    tangents: &BTreeMap<UniqueId, Tensor<(usize,), E, D>>,
+    operation: impl FnOnce(&mut dyn HigherOrderOps<E, D>) -> Result<(), Error>,
+) -> Result<Vec<(UniqueId, Tensor<(usize,), E, D>)>, Error> {
+    let mut traced = TracedGradients {
+        tape: Default::default(),
+        device: device.clone(),
+        grads: Default::default(),
+        vars: Vec::new(),
+        leaves: Some(Vec::new()),
+        leaf_error: None,
+    };
+    operation(&mut traced)?;
+    if let Some(e) = traced.leaf_error {
+        return Err(e);
+    }
+    let leaves = traced.leaves.unwrap_or_default();
+
+    let mut total: Option<Tensor<crate::shapes::Rank0, E, D, SharedTape<E, D>>> = None;
+    for (id, grad) in traced.grads.iter() {
+        let Some(tangent) = tangents.get(id) else {
+            continue;
+        };
+        let term = grad.clone().try_mul(tangent.clone())?.try_sum()?;
+        total = Some(match total {
+            Some(total) => total.try_add(term)?,
+            None => term,
+        });
+    }
+    let Some(total) = total else {
+        return Ok(Vec::new());
+    };
+
+    let grads = total.try_backward()?;
+    let mut outputs = Vec::with_capacity(leaves.len());
+    for (id, leaf) in traced.grads.into_iter() {
+        if leaves.iter().any(|l| l.id == leaf.id) {
+            if let Some(tangent) = grads.get_ref_checked(&leaf) {
+                outputs.push((id, flat_from_vec(device, tangent.clone())));
+            }
+        }
+    }
+    Ok(outputs)
+}
+
+#[cfg(feature = "std")]
+impl<E: Dtype, D: Device<E>> HigherOrderOps<E, D> for TracedGradients<E, D> {
+    fn grad(&mut self, id: UniqueId, len: usize) -> Option<HigherOrderVar> {
+        if let (None, Some(leaves)) = (self.grads.get(&id), self.leaves.as_mut()) {
+            let leaf = match self.device.try_zeros_like(&(len,)) {
+                Ok(leaf) => leaf.put_tape(self.tape.clone()),
+                Err(e) => {
+                    self.leaf_error = Some(e);
+                    return None;
+                }
+            };
+            leaves.push(leaf.clone());
+            self.grads.insert(id, leaf);
+        }
+        let grad = self.grads.get(&id)?.clone();
+        Some(self.push(grad))
+    }
+
+    fn accumulate_grad(&mut self, id: UniqueId, grad: HigherOrderVar) -> Result<(), Error> {
+        let grad = self.var(grad);
+        let grad = match self.grads.remove(&id) {
+            Some(prev) => prev.try_add(grad)?,
+            None => grad,
+        };
+        self.grads.insert(id, grad);
+        Ok(())
+    }
+
+    fn value(&mut self, t: Tensor<(usize,), E, D>) -> HigherOrderVar {
+        let t = t.put_tape(self.tape.clone());
+        self.push(t)
+    }
+
+    fn detach(&mut self, a: HigherOrderVar) -> Tensor<(usize,), E, D> {
+        self.var(a).split_tape().0
+    }
+
+    fn ones(&mut self, len: usize) -> Result<Tensor<(usize,), E, D>, Error> {
+        self.device.try_ones_like(&(len,))
+    }
+
+    fn add(&mut self, a: HigherOrderVar, b: HigherOrderVar) -> Result<HigherOrderVar, Error> {
+        let c = self.var(a).try_add(self.var(b))?;
+        Ok(self.push(c))
+    }
+
+    fn sub(&mut self, a: HigherOrderVar, b: HigherOrderVar) -> Result<HigherOrderVar, Error> {
+        let c = self.var(a).try_sub(self.var(b))?;
+        Ok(self.push(c))
+    }
+
+    fn mul(&mut self, a: HigherOrderVar, b: HigherOrderVar) -> Result<HigherOrderVar, Error> {
+        let c = self.var(a).try_mul(self.var(b))?;
+        Ok(self.push(c))
+    }
+
+    fn div(&mut self, a: HigherOrderVar, b: HigherOrderVar) -> Result<HigherOrderVar, Error> {
+        let c = self.var(a).try_div(self.var(b))?;
+        Ok(self.push(c))
+    }
+
+    fn scale(&mut self, a: HigherOrderVar, scalar: f64) -> Result<HigherOrderVar, Error> {
+        let c = self.var(a).try_mul(scalar)?;
+        Ok(self.push(c))
+    }
+
+    fn add_scalar(&mut self, a: HigherOrderVar, scalar: f64) -> Result<HigherOrderVar, Error> {
+        let c = self.var(a).try_add(scalar)?;
+        Ok(self.push(c))
+    }
+
+    fn square(&mut self, a: HigherOrderVar) -> Result<HigherOrderVar, Error> {
+        let c = self.var(a).try_square()?;
+        Ok(self.push(c))
+    }
+
+    fn powf(&mut self, a: HigherOrderVar, p: f64) -> Result<HigherOrderVar, Error> {
+        let c = self.var(a).try_powf(p)?;
+        Ok(self.push(c))
+    }
+
+    fn powi(&mut self, a: HigherOrderVar, p: i32) -> Result<HigherOrderVar, Error> {
+        let c = self.var(a).try_powi(p)?;
+        Ok(self.push(c))
+    }
+
+    fn recip(&mut self, a: HigherOrderVar) -> Result<HigherOrderVar, Error> {
+        let c = self.var(a).try_recip()?;
+        Ok(self.push(c))
+    }
+
+    fn sin(&mut self, a: HigherOrderVar) -> Result<HigherOrderVar, Error> {
+        let c = self.var(a).try_sin()?;
+        Ok(self.push(c))
+    }
+
+    fn cos(&mut self, a: HigherOrderVar) -> Result<HigherOrderVar, Error> {
+        let c = self.var(a).try_cos()?;
+        Ok(self.push(c))
+    }
+
+    fn sigmoid(&mut self, a: HigherOrderVar) -> Result<HigherOrderVar, Error> {
+        let c = self.var(a).try_sigmoid()?;
+        Ok(self.push(c))
+    }
+
+    fn eq(&mut self, a: HigherOrderVar, b: HigherOrderVar) -> Result<HigherOrderVar, Error> {
+        let a = self.detach(a);
+        let b = self.detach(b);
+        let ones = self.device.try_ones_like(a.shape())?;
+        let zeros = self.device.try_zeros_like(a.shape())?;
+        let mask = a.try_eq(&b)?.try_choose(ones, zeros)?;
+        Ok(self.value(mask))
+    }
+
+    fn gather(&mut self, a: HigherOrderVar, idx: Vec<usize>) -> Result<HigherOrderVar, Error> {
+        let n = idx.len();
+        let idx = self.device.try_tensor_from_vec(idx, (n,))?;
+        let c = self.var(a).try_gather(idx)?;
+        Ok(self.push(c))
+    }
+
+    fn scatter_add(
+        &mut self,
+        a: HigherOrderVar,
+        idx: Vec<usize>,
+        len: usize,
+    ) -> Result<HigherOrderVar, Error> {
+        let (src, mut tape) = self.var(a).split_tape();
+        let n = idx.len();
+        let idx = self.device.try_tensor_from_vec(idx, (n,))?;
+
+        // scatter adding is the backward pass of gathering
+        let out: Tensor<(usize,), E, D> = self.device.try_zeros_like(&(len,))?;
+        let mut data = Storage::<E>::try_alloc_len(&self.device, len)?;
+        ReplaceDimKernel::backward(&self.device, &out, &mut data, &idx, &src, src.data.as_ref())?;
+        let out = Tensor {
+            data: Arc::new(data),
+            ..out
+        };
+
+        let src_ghost = src.ghost();
+        let out_ghost = out.ghost();
+        let dev = self.device.clone();
+        tape.add_retainable_backward_op(move |grads| {
+            grads.try_alloc_for(&src_ghost)?;
+            grads.try_alloc_for(&out_ghost)?;
+            let (grad_src, grad_out) = grads.mut_and_ref(&src_ghost, &out_ghost);
+            let grad_out = flat_from_vec(&dev, grad_out.clone());
+            let gathered: Tensor<(usize,), E, D> =
+                ReplaceDimKernel::forward(&dev, &grad_out, &idx)?;
+            AxpyKernel::forward(&dev, grad_src, E::ONE, gathered.data.as_ref(), E::ONE)
+        });
+        Ok(self.push(out.put_tape(tape)))
+    }
+
+    fn transpose(
+        &mut self,
+        a: HigherOrderVar,
+        batch: usize,
+        rows: usize,
+        cols: usize,
+    ) -> Result<HigherOrderVar, Error> {
+        let c = self
+            .var(a)
+            .try_reshape_like(&(batch, rows, cols))?
+            .try_permute::<_, crate::shapes::Axes3<0, 2, 1>>()?
+            .try_reshape_like(&(batch * rows * cols,))?;
+        Ok(self.push(c))
+    }
+
+    fn matmul(
+        &mut self,
+        lhs: HigherOrderVar,
+        rhs: HigherOrderVar,
+        batch: usize,
+        m: usize,
+        k: usize,
+        n: usize,
+    ) -> Result<HigherOrderVar, Error> {
+        let lhs = self.var(lhs).try_reshape_like(&(batch, m, k))?;
+        let rhs = self.var(rhs).try_reshape_like(&(batch, k, n))?;
+        let c = lhs.try_matmul(rhs)?.try_reshape_like(&(batch * m * n,))?;
+        Ok(self.push(c))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{shapes::*, tests::*};
+
+    #[test]
+    fn test_second_derivative_unary() {
+        let dev: TestDevice = Default::default();
+        let x: Tensor<Rank1<3>, TestDtype, _> = dev.tensor([0.5, -1.0, 2.0]);
+        let t = x.leaky_trace().higher_order();
+        let y = (t.with_empty_tape().powi(3) + t.with_empty_tape().sin() + t.exp()).sum();
+        let g = y.traced_backward();
+        let g2 = g.get(&x).sum().backward();
+        assert_close_to_literal!(g2.get(&x), [4.1692957, -4.7906494, 18.479759]);
+    }
+
+    #[test]
+    fn test_gradient_penalty() {
+        let dev: TestDevice = Default::default();
+        let x: Tensor<Rank1<2>, TestDtype, _> = dev.tensor([0.5, -1.0]);
+        let w: Tensor<Rank1<2>, TestDtype, _> = dev.tensor([1.5, 2.0]);
+        let y = (x.leaky_trace().higher_order() * w.clone()).sin().sum();
+        let g = y.traced_backward();
+        let penalty = g.get(&x).square().sum();
+        let g2 = penalty.backward();
+        assert_close_to_literal!(g2.get(&x), [-3.3665456, -6.05442]);
+        assert_close_to_literal!(g2.get(&w), [0.48392394, 3.7199227]);
+    }
+
+    #[test]
+    fn test_higher_order_matmul_broadcast() {
+        let dev: TestDevice = Default::default();
+        let x: Tensor<Rank2<2, 3>, TestDtype, _> = dev.tensor([[0.1, -0.2, 0.3], [0.4, 0.5, -0.6]]);
+        let w: Tensor<Rank2<3, 2>, TestDtype, _> =
+            dev.tensor([[0.7, -0.8], [0.9, 0.1], [-0.2, 0.3]]);
+        let b: Tensor<Rank1<2>, TestDtype, _> = dev.tensor([0.05, -0.1]);
+        let y = x.leaky_trace().higher_order().matmul(w.clone()) + b.broadcast::<_, Axis<0>>();
+        let g = y.tanh().sum().traced_backward();
+        let g2 = g.get(&w).square().sum().backward();
+        assert_close_to_literal!(
+            g2.get(&x),
+            [
+                [1.3872912, 0.44849104, -0.30324678],
+                [0.3399116, 0.18792867, -0.034649625],
+            ]
+        );
+    }
+
+    #[test]
+    fn test_higher_order_max() {
+        let dev: TestDevice = Default::default();
+        let x: Tensor<Rank1<3>, TestDtype, _> = dev.tensor([1.0, -3.0, 2.0]);
+        let y = x.leaky_trace().higher_order().square().max();
+        let g = y.traced_backward();
+        assert_close_to_literal!(g.get(&x), [0.0, -6.0, 0.0]);
+        let g2 = g.get(&x).sum().backward();
+        assert_close_to_literal!(g2.get(&x), [0.0, 2.0, 0.0]);
+    }
+
+    #[test]
+    fn test_no_higher_order_gradient() {
+        let dev: TestDevice = Default::default();
+        let x: Tensor<Rank1<3>, TestDtype, _> = dev.tensor([1.0, -3.0, 2.0]);
+        let y = x.leaky_trace().higher_order().fast_gelu().sum();
+        assert!(matches!(
+            y.try_traced_backward(),
+            Err(Error::NoHigherOrderGradient)
+        ));
+    }
+}
@@ -0,0 +1,308 @@
+//! Forward mode automatic differentiation with [JvpTape].
+
+use super::{higher_order::push_forward, Device};
+use crate::{
+    shapes::{Dtype, HasShape, Shape},
+    tensor::*,
+    tensor_ops::ReshapeTo,
+};
+
+use std::collections::BTreeMap;
+
+/// A [Tape] for forward mode automatic differentiation, which computes jacobian
+/// vector products (JVPs).
+///
+/// Each tensor carrying this tape has a tangent, which is the directional derivative of
+/// the tensor in the direction of the tangents that the inputs were seeded with. Tangents
+/// are computed as each operation runs, so there is no separate backward pass. A tape
+/// only holds the tangent of the tensor it is attached to, so the tangents of intermediate
+/// values are freed along with the values themselves.
+///
+/// Seed tangents with [Tensor::with_tangent()], and read them with [Tensor::split_tangent()]:
+/// ```rust
+/// # use dfdx_core::prelude::*;
+/// # let dev: Cpu = Default::default();
+/// let x: Tensor<Rank1<3>, f32, _> = dev.tensor([0.0, 1.0, 2.0]);
+/// let v: Tensor<Rank1<3>, f32, _> = dev.tensor([1.0, 1.0, 0.0]);
+/// let y = x.with_tangent(v).square();
+/// let (y, dy) = y.split_tangent();
+/// assert_eq!(y.array(), [0.0, 1.0, 4.0]);
+/// assert_eq!(dy.array(), [0.0, 2.0, 0.0]);
+/// ```
+///
+/// Tangents are computed from the differentiable backward pass of each operation (see
+/// [crate::tensor::HigherOrderOps]), so the same operations that support higher order
+/// gradients are supported. Using any other operation results in
+/// [Error::NoHigherOrderGradient] when the tangent is read. Computing the tangent of an
+/// operation runs a small reverse pass through its backward pass, so each operation costs
+/// a few times more than it would without a tangent.
+pub struct JvpTape<E, D: Storage<E>> {
+    device: Option<D>,
+    /// The tangent of the tensor this tape belongs to, in physical order, keyed by the
+    /// tensor's id. After merging tapes, this holds the tangents of each input until the
+    /// next operation replaces them with the tangent of its output.
+    tangents: BTreeMap<UniqueId, Tensor<(usize,), E, D>>,
+    /// Whether the most recent operation did not record a differentiable backward pass.
+    unsupported: bool,
+    error: Option<Error>,
+}
+
+impl<E, D: Storage<E>> Default for JvpTape<E, D> {
+    fn default() -> Self {
+        Self {
+            device: None,
+            tangents: Default::default(),
+            unsupported: false,
+            error: None,
+        }
+    }
+}
+
+impl<E, D: Storage<E>> std::fmt::Debug for JvpTape<E, D> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("JvpTape")
+            .field("num_tangents", &self.tangents.len())
+            .field("error", &self.error)
+            .finish()
+    }
+}
+
+impl<E: Dtype, D: Device<E>> Tape<E, D> for JvpTape<E, D> {
+    const OWNS_TAPE: bool = true;
+    fn add_backward_op<F>(&mut self, _: F)
+    where
+        F: 'static + FnOnce(&mut Gradients<E, D>) -> Result<(), Error>,
+    {
+        if self.unsupported && self.error.is_none() {
+            self.error = Some(Error::NoHigherOrderGradient);
+        }
+        self.unsupported = true;
+    }
+
+    fn empty_like(&self) -> Self {
+        Self {
+            device: self.device.clone(),
+            tangents: self.tangents.clone(),
+            unsupported: false,
+            error: None,
+        }
+    }
+
+    fn is_higher_order(&self) -> bool {
+        true
+    }
+
+    fn add_higher_order_op<F>(&mut self, operation: F)
+    where
+        F: 'static + FnOnce(&mut dyn HigherOrderOps<E, D>) -> Result<(), Error>,
+    {
+        self.unsupported = false;
+        if self.error.is_some() {
+            return;
+        }
+        if let Some(device) = self.device.as_ref() {
+            match push_forward(device, &self.tangents, operation) {
+                Ok(tangents) => self.tangents = tangents.into_iter().collect(),
+                Err(e) => self.error = Some(e),
+            }
+        }
+    }
+}
+
+impl<E, D: Storage<E>> Merge<NoneTape> for JvpTape<E, D> {
+    fn merge(self, _: NoneTape) -> Self {
+        self
+    }
+}
+
+impl<E, D: Storage<E>> Merge<JvpTape<E, D>> for JvpTape<E, D> {
+    fn merge(mut self, other: Self) -> Self {
+        self.device = self.device.or(other.device);
+        self.tangents.extend(other.tangents);
+        self.unsupported |= other.unsupported;
+        self.error = self.error.or(other.error);
+        self
+    }
+}
+
+impl<S: Shape, E: Dtype, D: Device<E>> Tensor<S, E, D, NoneTape> {
+    /// Starts forward mode differentiation, where `tangent` is the direction to
+    /// differentiate in. See [JvpTape].
+    pub fn with_tangent(self, tangent: Tensor<S, E, D>) -> Tensor<S, E, D, JvpTape<E, D>> {
+        self.try_with_tangent(tangent).unwrap()
+    }
+
+    /// Fallible version of [Tensor::with_tangent]
+    pub fn try_with_tangent(
+        self,
+        tangent: Tensor<S, E, D>,
+    ) -> Result<Tensor<S, E, D, JvpTape<E, D>>, Error> {
+        if self.shape() != tangent.shape() {
+            return Err(Error::ShapeMismatch {
+                expected: self.shape().concrete().into(),
+                found: tangent.shape().concrete().into(),
+            });
+        }
+        let primal = self.try_contiguous()?;
+        let tangent = tangent.try_contiguous()?;
+        let mut tape = JvpTape {
+            device: Some(primal.device.clone()),
+            ..Default::default()
+        };
+        tape.tangents.insert(
+            primal.id,
+            Tensor {
+                id: tangent.id,
+                shape: (tangent.shape.num_elements(),),
+                strides: [1],
+                data: tangent.data,
+                device: tangent.device,
+                tape: NoneTape,
+            },
+        );
+        Ok(primal.put_tape(tape))
+    }
+}
+
+impl<S: Shape, E: Dtype, D: Device<E>> Tensor<S, E, D, JvpTape<E, D>> {
+    /// Returns the tensor without its tape, and its tangent. The tangent is zero
+    /// if the tensor does not depend on any tensor with a tangent.
+    pub fn split_tangent(self) -> (Tensor<S, E, D>, Tensor<S, E, D>) {
+        self.try_split_tangent().unwrap()
+    }
+
+    /// Fallible version of [Tensor::split_tangent]
+    #[allow(clippy::type_complexity)]
+    pub fn try_split_tangent(self) -> Result<(Tensor<S, E, D>, Tensor<S, E, D>), Error> {
+        let (primal, tape) = self.split_tape();
+        if let Some(e) = tape.error {
+            return Err(e);
+        }
+        if tape.unsupported {
+            return Err(Error::NoHigherOrderGradient);
+        }
+        let tangent = match tape.tangents.get(&primal.id) {
+            Some(tangent) => Tensor {
+                id: unique_id(),
+                data: tangent.data.clone(),
+                shape: primal.shape,
+                strides: primal.strides,
+                device: primal.device.clone(),
+                tape: NoneTape,
+            },
+            None => primal.device.try_zeros_like(&primal.shape)?,
+        };
+        Ok((primal, tangent))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{shapes::*, tensor_ops::*, tests::*};
+
+    #[test]
+    fn test_jvp_unary_chain() {
+        let dev: TestDevice = Default::default();
+        let x: Tensor<Rank1<3>, TestDtype, _> = dev.tensor([0.5, -1.0, 2.0]);
+        let v: Tensor<Rank1<3>, TestDtype, _> = dev.tensor([1.0, 2.0, -1.0]);
+        let (y, dy) = x.clone().with_tangent(v).sin().exp().split_tangent();
+        assert_close_to_tensor!(y, x.clone().sin().exp());
+        // d/dx exp(sin(x)) = exp(sin(x)) * cos(x)
+        let expected = x.clone().sin().exp() * x.cos() * dev.tensor([1.0, 2.0, -1.0]);
+        assert_close_to_tensor!(dy, expected);
+    }
+
+    #[test]
+    fn test_jvp_tangent_shape_mismatch() {
+        let dev: TestDevice = Default::default();
+        let x: Tensor<(usize,), TestDtype, _> = dev.tensor_from_vec(std::vec![1.0, 2.0], (2,));
+        let v: Tensor<(usize,), TestDtype, _> = dev.tensor_from_vec(std::vec![1.0; 3], (3,));
+        assert!(matches!(
+            x.try_with_tangent(v),
+            Err(Error::ShapeMismatch { .. })
+        ));
+    }
+
+    #[test]
+    fn test_jvp_matches_reverse_mode() {
+        let dev: TestDevice = Default::default();
+        let x: Tensor<Rank2<2, 3>, TestDtype, _> = dev.sample_normal();
+        let w: Tensor<Rank2<3, 4>, TestDtype, _> = dev.sample_normal();
+        let b: Tensor<Rank1<4>, TestDtype, _> = dev.sample_normal();
+
+        // the tangent of a scalar output in direction v is <grad, v>
+        let v: Tensor<Rank2<2, 3>, TestDtype, _> = dev.sample_normal();
+        let f = |x: Tensor<Rank2<2, 3>, TestDtype, TestDevice, JvpTape<TestDtype, TestDevice>>| {
+            (x.matmul(w.clone()) + b.clone().broadcast::<_, Axis<0>>())
+                .tanh()
+                .square()
+                .mean::<Rank0, _>()
+        };
+        let (_, dy) = f(x.clone().with_tangent(v.clone())).split_tangent();
+
+        let y = (x.leaky_trace().matmul(w.clone()) + b.clone().broadcast::<_, Axis<0>>())
+            .tanh()
+            .square()
+            .mean::<Rank0, _>();
+        let g = y.backward();
+        let expected = (g.get(&x) * v).sum::<Rank0, _>();
+        assert_close_to_tensor!(dy, expected);
+    }
+
+    #[test]
+    fn test_jvp_two_inputs() {
+        let dev: TestDevice = Default::default();
+        let a: Tensor<Rank1<2>, TestDtype, _> = dev.tensor([1.0, 2.0]);
+        let b: Tensor<Rank1<2>, TestDtype, _> = dev.tensor([3.0, -4.0]);
+        let da: Tensor<Rank1<2>, TestDtype, _> = dev.tensor([1.0, 0.0]);
+        let db: Tensor<Rank1<2>, TestDtype, _> = dev.tensor([0.5, 1.0]);
+        let y = a.with_tangent(da) * b.with_tangent(db);
+        let (y, dy) = y.split_tangent();
+        assert_close_to_literal!(y, [3.0, -8.0]);
+        // da * b + a * db
+        assert_close_to_literal!(dy, [3.5, 2.0]);
+    }
+
+    #[test]
+    fn test_jvp_only_keeps_own_tangent() {
+        let dev: TestDevice = Default::default();
+        let a: Tensor<Rank1<2>, TestDtype, _> = dev.tensor([1.0, 2.0]);
+        let b: Tensor<Rank1<2>, TestDtype, _> = dev.tensor([3.0, -4.0]);
+        let y = a.with_tangent(dev.ones()).exp() * b.with_tangent(dev.ones()).sin();
+        let y = y.square().sum::<Rank0, _>();
+        assert_eq!(y.tape.tangents.len(), 1);
+        assert!(y.tape.tangents.contains_key(&y.id));
+    }
+
+    #[test]
+    fn test_jvp_no_tangent_is_zero() {
+        let dev: TestDevice = Default::default();
+        let x: Tensor<Rank1<2>, TestDtype, _> = dev.tensor([1.0, 2.0]);
+        let (_, dx) = x.with_tangent(dev.zeros()).split_tangent();
+        assert_close_to_literal!(dx, [0.0; 2]);
+    }
+
+    #[test]
+    fn test_jvp_unsupported_op() {
+        let dev: TestDevice = Default::default();
+        let x: Tensor<Rank1<3>, TestDtype, _> = dev.tensor([1.0, -3.0, 2.0]);
+        let y = x.clone().with_tangent(dev.ones()).fast_gelu();
+        assert!(matches!(
+            y.try_split_tangent(),
+            Err(Error::NoHigherOrderGradient)
+        ));
+
+        // operations without a differentiable backward pass at all
+        let y = custom_op(
+            x.with_tangent(dev.ones()),
+            |x| x.clone().try_exp(),
+            |_, y, g| y.clone().try_mul(g),
+        )
+        .sin();
+        assert!(matches!(
+            y.try_split_tangent(),
+            Err(Error::NoHigherOrderGradient)
+        ));
+    }
+}
@@ -3,6 +3,9 @@ pub(crate) mod cpu_kernels;
 #[cfg(feature = "cuda")]
 pub(crate) mod cuda_kernels;
 mod device;
+pub(crate) mod higher_order;
+#[cfg(feature = "std")]
+mod jvp;
 pub(crate) mod ops;
 pub(crate) mod reduction_utils;
 #[cfg(feature = "webgpu")]
@@ -10,3 +13,7 @@ pub(crate) mod webgpu_kernels;
 
 pub use backward::Backward;
 pub use device::Device;
+#[cfg(feature = "std")]
+pub use higher_order::{TracedBackward, TracedGradients};
+#[cfg(feature = "std")]
+pub use jvp::JvpTape;
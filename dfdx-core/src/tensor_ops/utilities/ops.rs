@@ -1,3 +1,4 @@
+use super::higher_order::*;
 use crate::{
     shapes::{Dtype, HasShape, Shape},
     tensor::*,
@@ -42,7 +43,7 @@ pub trait BinaryKernel<Op, E: Dtype>: Storage<E> {
 }
 
 pub(crate) fn try_unary_op<
-    Op: 'static + Clone,
+    Op: 'static + Clone + UnaryDerivative<E>,
     S: Shape,
     E: Dtype,
     D: UnaryKernel<Op, E>,
@@ -54,42 +55,106 @@ pub(crate) fn try_unary_op<
     let (inp, mut tape) = inp.split_tape();
     let inp_ghost = inp.ghost();
     let dev = inp.device.clone();
-    if !T::OWNS_TAPE || D::BACKWARD_WITHOUT_DATA {
+    if tape.is_higher_order() {
+        let out = inp.device.forward(op.clone(), Cow::Borrowed(&inp))?;
+        let out_ghost = out.ghost();
+        tape.try_add_op_info(&out, || {
+            OpInfo::new(op_name::<Op>(), &out).input(&inp_ghost)
+        })?;
+        let (op_clone, inp_clone, out_clone) = (op.clone(), inp.clone(), out.clone());
+        tape.add_retainable_backward_op(move |grads| {
+            grads.try_alloc_for(&inp_ghost)?;
+            grads.try_alloc_for(&out_ghost)?;
+            let (grad_inp, grad_out) = grads.mut_and_ref(&inp_ghost, &out_ghost);
+            unary_backward(op_clone.clone(), &inp_clone, grad_inp, &out_clone, grad_out)
+        });
+        let out_clone = out.clone();
+        tape.add_higher_order_op(move |ops| {
+            let Some(grad_out) = ops.grad(out_clone.id, dev.len(&out_clone.data)) else {
+                return Ok(());
+            };
+            let x = ops.value(flat(&inp));
+            let y = ops.value(flat(&out_clone));
+            let df = match op.df(ops, x, y)? {
+                Some(df) => df,
+                None => {
+                    // piecewise constant derivatives are computed by the backward kernel
+                    let ones = ops.ones(dev.len(&out_clone.data))?;
+                    let mut df = dev.try_alloc_len(dev.len(&inp.data))?;
+                    unary_backward(op, &inp, &mut df, &out_clone, ones.data.as_ref())?;
+                    ops.value(flat_from_vec(&dev, df))
+                }
+            };
+            let grad_inp = ops.mul(grad_out, df)?;
+            ops.accumulate_grad(inp.id, grad_inp)
+        });
+        Ok(out.put_tape(tape))
+    } else if !T::OWNS_TAPE || D::BACKWARD_WITHOUT_DATA {
         let out = inp_ghost.dev.forward(op.clone(), Cow::Owned(inp))?;
         let out_ghost = out.ghost();
-        tape.add_backward_op(move |grads| {
+        tape.try_add_op_info(&out, || {
+            OpInfo::new(op_name::<Op>(), &out).input(&inp_ghost)
+        })?;
+        tape.add_retainable_backward_op(move |grads| {
             grads.try_alloc_for(&inp_ghost)?;
             grads.try_alloc_for(&out_ghost)?;
             let (grad_inp, grad_out) = grads.mut_and_ref(&inp_ghost, &out_ghost);
-            dev.backward(op, &inp_ghost, grad_inp, &out_ghost, grad_out)
+            dev.backward(op.clone(), &inp_ghost, grad_inp, &out_ghost, grad_out)
         });
         Ok(out.put_tape(tape))
     } else if D::BACKWARD_WITHOUT_INP {
         let out = inp_ghost.dev.forward(op.clone(), Cow::Owned(inp))?;
         let out_ghost = out.ghost();
+        tape.try_add_op_info(&out, || {
+            OpInfo::new(op_name::<Op>(), &out).input(&inp_ghost)
+        })?;
         let out_clone = out.clone();
-        tape.add_backward_op(move |grads| {
+        tape.add_retainable_backward_op(move |grads| {
             grads.try_alloc_for(&inp_ghost)?;
             grads.try_alloc_for(&out_ghost)?;
             let (grad_inp, grad_out) = grads.mut_and_ref(&inp_ghost, &out_ghost);
-            dev.backward(op, &inp_ghost, grad_inp, &out_clone, grad_out)
+            dev.backward(op.clone(), &inp_ghost, grad_inp, &out_clone, grad_out)
         });
         Ok(out.put_tape(tape))
     } else {
         let out = inp.device.forward(op.clone(), Cow::Borrowed(&inp))?;
         let out_ghost = out.ghost();
-        tape.add_backward_op(move |grads| {
+        tape.try_add_op_info(&out, || {
+            OpInfo::new(op_name::<Op>(), &out).input(&inp_ghost)
+        })?;
+        tape.add_retainable_backward_op(move |grads| {
             grads.try_alloc_for(&inp_ghost)?;
             grads.try_alloc_for(&out_ghost)?;
             let (grad_inp, grad_out) = grads.mut_and_ref(&inp_ghost, &out_ghost);
-            dev.backward(op, &inp, grad_inp, &out_ghost, grad_out)
+            dev.backward(op.clone(), &inp, grad_inp, &out_ghost, grad_out)
         });
         Ok(out.put_tape(tape))
     }
 }
 
+/// Runs the backward kernel of a unary op given both the input and output,
+/// passing along only what the kernel expects.
+fn unary_backward<Op, S: Shape, E: Dtype, D: UnaryKernel<Op, E>>(
+    op: Op,
+    inp: &Tensor<S, E, D>,
+    grad_inp: &mut D::Vec,
+    out: &Tensor<S, E, D>,
+    grad_out: &D::Vec,
+) -> Result<(), Error> {
+    if D::BACKWARD_WITHOUT_DATA {
+        inp.device
+            .backward(op, &inp.ghost(), grad_inp, &out.ghost(), grad_out)
+    } else if D::BACKWARD_WITHOUT_INP {
+        inp.device
+            .backward(op, &inp.ghost(), grad_inp, out, grad_out)
+    } else {
+        inp.device
+            .backward(op, inp, grad_inp, &out.ghost(), grad_out)
+    }
+}
+
 pub(crate) fn try_binary_op<
-    Op: 'static + Copy,
+    Op: 'static + Copy + BinaryDerivative<E>,
     S: Shape,
     E: Dtype,
     D: BinaryKernel<Op, E>,
@@ -106,12 +171,65 @@ pub(crate) fn try_binary_op<
     let lhs_ghost = lhs.ghost();
     let rhs_ghost = rhs.ghost();
     let mut tape = ltape.merge(rtape);
-    if !LhsTape::OWNS_TAPE || D::BACKWARD_WITHOUT_DATA {
+    if tape.is_higher_order() {
+        let out = lhs
+            .device
+            .forward(op, Cow::Borrowed(&lhs), Cow::Borrowed(&rhs))?;
+        let out_ghost = out.ghost();
+        tape.try_add_op_info(&out, || {
+            OpInfo::new(op_name::<Op>(), &out)
+                .input(&lhs_ghost)
+                .input(&rhs_ghost)
+        })?;
+        let (lhs_clone, rhs_clone) = (lhs.clone(), rhs.clone());
+        tape.add_retainable_backward_op(move |grads| {
+            grads.try_alloc_for(&lhs_ghost)?;
+            grads.try_alloc_for(&rhs_ghost)?;
+            grads.try_alloc_for(&out_ghost)?;
+            let (grad_lhs, grad_rhs, grad_out) =
+                grads.muts_and_ref(&lhs_ghost, &rhs_ghost, &out_ghost);
+            binary_backward(op, &lhs_clone, grad_lhs, &rhs_clone, grad_rhs, grad_out)
+        });
+        let out_ghost = out.ghost();
+        tape.add_higher_order_op(move |ops| {
+            let Some(grad_out) = logical_grad(ops, &out_ghost)? else {
+                return Ok(());
+            };
+            let x = logical_value(ops, &lhs)?;
+            let y = logical_value(ops, &rhs)?;
+            let (dfdx, dfdy) = match op.df(ops, x, y)? {
+                Some(dfs) => dfs,
+                None => {
+                    let shape = *lhs.shape();
+                    let x = unflatten(ops.detach(x), shape);
+                    let y = unflatten(ops.detach(y), shape);
+                    let ones = ops.ones(shape.num_elements())?;
+                    let mut dfdx = x.device.try_alloc_len(shape.num_elements())?;
+                    let mut dfdy = x.device.try_alloc_len(shape.num_elements())?;
+                    binary_backward(op, &x, &mut dfdx, &y, &mut dfdy, ones.data.as_ref())?;
+                    (
+                        ops.value(flat_from_vec(&x.device, dfdx)),
+                        ops.value(flat_from_vec(&x.device, dfdy)),
+                    )
+                }
+            };
+            let grad_lhs = ops.mul(grad_out, dfdx)?;
+            accumulate_logical(ops, &lhs, grad_lhs)?;
+            let grad_rhs = ops.mul(grad_out, dfdy)?;
+            accumulate_logical(ops, &rhs, grad_rhs)
+        });
+        Ok(out.put_tape(tape))
+    } else if !LhsTape::OWNS_TAPE || D::BACKWARD_WITHOUT_DATA {
         let out = lhs_ghost
             .dev
             .forward(op, Cow::Owned(lhs), Cow::Owned(rhs))?;
         let out_ghost = out.ghost();
-        tape.add_backward_op(move |grads| {
+        tape.try_add_op_info(&out, || {
+            OpInfo::new(op_name::<Op>(), &out)
+                .input(&lhs_ghost)
+                .input(&rhs_ghost)
+        })?;
+        tape.add_retainable_backward_op(move |grads| {
             grads.try_alloc_for(&lhs_ghost)?;
             grads.try_alloc_for(&rhs_ghost)?;
             grads.try_alloc_for(&out_ghost)?;
@@ -127,7 +245,12 @@ pub(crate) fn try_binary_op<
             .device
             .forward(op, Cow::Borrowed(&lhs), Cow::Borrowed(&rhs))?;
         let out_ghost = out.ghost();
-        tape.add_backward_op(move |grads| {
+        tape.try_add_op_info(&out, || {
+            OpInfo::new(op_name::<Op>(), &out)
+                .input(&lhs_ghost)
+                .input(&rhs_ghost)
+        })?;
+        tape.add_retainable_backward_op(move |grads| {
             grads.try_alloc_for(&lhs_ghost)?;
             grads.try_alloc_for(&rhs_ghost)?;
             grads.try_alloc_for(&out_ghost)?;
@@ -139,3 +262,30 @@ pub(crate) fn try_binary_op<
         Ok(out.put_tape(tape))
     }
 }
+
+/// The name of a kernel op, e.g. `Exp` for `ExpKernelOp`.
+pub(crate) fn op_name<Op>() -> &'static str {
+    let name = std::any::type_name::<Op>();
+    let name = name.split('<').next().unwrap_or(name);
+    let name = name.rsplit("::").next().unwrap_or(name);
+    name.trim_end_matches("KernelOp")
+}
+
+/// Runs the backward kernel of a binary op given both inputs, passing along
+/// only what the kernel expects.
+fn binary_backward<Op, S: Shape, E: Dtype, D: BinaryKernel<Op, E>>(
+    op: Op,
+    lhs: &Tensor<S, E, D>,
+    grad_lhs: &mut D::Vec,
+    rhs: &Tensor<S, E, D>,
+    grad_rhs: &mut D::Vec,
+    grad_out: &D::Vec,
+) -> Result<(), Error> {
+    if D::BACKWARD_WITHOUT_DATA {
+        lhs.device
+            .backward(op, &lhs.ghost(), grad_lhs, &rhs.ghost(), grad_rhs, grad_out)
+    } else {
+        lhs.device
+            .backward(op, lhs, grad_lhs, rhs, grad_rhs, grad_out)
+    }
+}
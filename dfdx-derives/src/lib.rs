@@ -8,18 +8,35 @@ macro_rules! has_attr {
     };
 }
 
+/// Whether a field is marked with `#[param(frozen)]` or `#[module(frozen)]`.
+macro_rules! is_frozen {
+    ($F:expr) => {
+        $F.attrs.iter().any(|a| {
+            (a.path().is_ident("param") || a.path().is_ident("module"))
+                && a.parse_args::<syn::Ident>()
+                    .map_or(false, |i| i == "frozen")
+        })
+    };
+}
+
 /// Allows you to implement [dfdx::nn_traits::Module], while automatically implementing the following:
 /// 1. [dfdx::nn_traits::BuildOnDevice]
 /// 2. [dfdx::nn_traits::ResetParams]
 /// 3. [dfdx::nn_traits::UpdateParams]
 /// 4. [dfdx::nn_traits::ZeroGrads]
-/// 5. [dfdx::nn_traits::SaveSafeTensors]
-/// 6. [dfdx::nn_traits::LoadSafeTensors]
+/// 5. [dfdx::nn_traits::WithGrads]
+/// 6. [dfdx::nn_traits::SaveSafeTensors]
+/// 7. [dfdx::nn_traits::LoadSafeTensors]
 ///
 /// If your struct contains sub module configs, then you must add the `#[module]` attribute to those items. Any field that is marked with `#[module]` will be expected to implement [dfdx::nn_traits::BuildOnDevice].
 ///
 /// You can control the name of the built struct with the `#[built(<type name>)]` attribute on the struct.
 ///
+/// Sub modules marked with `#[module(frozen)]` are not updated by optimizers, and don't have
+/// gradients allocated by [dfdx::nn_traits::ZeroGrads]. The same goes for parameters marked with
+/// `#[param(frozen)]` when deriving those traits directly. See [dfdx::nn::Frozen] to freeze
+/// modules at runtime instead.
+///
 /// # Using CustomModule on unit structs
 ///
 /// Here we have a unit struct that just calls a method on Tensor in the forward:
@@ -102,7 +119,7 @@ pub fn custom_module(input: proc_macro::TokenStream) -> proc_macro::TokenStream
                             let name = &f.ident;
                             let ty = &f.ty;
                             let vis = &f.vis;
-                            if has_attr!(f, "module") {
+                            if let Some(module_attr) = f.attrs.iter().find(|a| a.path().is_ident("module")) {
                                 has_fields_to_build = true;
                                 where_clause
                                     .predicates
@@ -112,7 +129,7 @@ pub fn custom_module(input: proc_macro::TokenStream) -> proc_macro::TokenStream
                                 } else {
                                     quote!()
                                 };
-                                quote_spanned!(f.span()=> #[module] #safetensors_serialize_attr #vis #name: <#ty as ::dfdx::nn_traits::BuildOnDevice<Elem, Dev>>::Built,)
+                                quote_spanned!(f.span()=> #module_attr #safetensors_serialize_attr #vis #name: <#ty as ::dfdx::nn_traits::BuildOnDevice<Elem, Dev>>::Built,)
                             } else {
                                 quote_spanned!(f.span()=> #vis #name: #ty,)
                             }
@@ -123,7 +140,7 @@ pub fn custom_module(input: proc_macro::TokenStream) -> proc_macro::TokenStream
                         let fields = fields.unnamed.iter().map(|f| {
                             let ty = &f.ty;
                             let vis = &f.vis;
-                            if has_attr!(f, "module") {
+                            if let Some(module_attr) = f.attrs.iter().find(|a| a.path().is_ident("module")) {
                                 has_fields_to_build = true;
                                 where_clause
                                     .predicates
@@ -133,7 +150,7 @@ pub fn custom_module(input: proc_macro::TokenStream) -> proc_macro::TokenStream
                                 } else {
                                     quote!()
                                 };
-                                quote_spanned!(f.span()=> #[module] #safetensors_serialize_attr #vis <#ty as ::dfdx::nn_traits::BuildOnDevice<Elem, Dev>>::Built,)
+                                quote_spanned!(f.span()=> #module_attr #safetensors_serialize_attr #vis <#ty as ::dfdx::nn_traits::BuildOnDevice<Elem, Dev>>::Built,)
                             } else {
                                 quote_spanned!(f.span()=> #vis #ty,)
                             }
@@ -175,11 +192,11 @@ pub fn custom_module(input: proc_macro::TokenStream) -> proc_macro::TokenStream
                 quote!()
             };
             quote! {
-                #[derive(Clone, Debug, ::dfdx::ResetParams, ::dfdx::UpdateParams, ::dfdx::ZeroGrads, #safetensors_derive)]
+                #[derive(Clone, Debug, ::dfdx::ResetParams, ::dfdx::UpdateParams, ::dfdx::ZeroGrads, ::dfdx::WithGrads, #safetensors_derive)]
                 pub struct #built_name #built_impl #built_where #fields
             }
         } else {
-            // there are no fields to build - we still have to derive ResetParams/UpdateParams/ZeroGrads, but since
+            // there are no fields to build - we still have to derive ResetParams/UpdateParams/ZeroGrads/WithGrads, but since
             // there aren't any fields, they will just be passthrough impls
             let mut build_generics = built_generics.clone();
             if !has_fields_to_build {
@@ -235,6 +252,8 @@ pub fn custom_module(input: proc_macro::TokenStream) -> proc_macro::TokenStream
                     ) -> Result<(), ::dfdx::tensor::Error> {
                         Ok(())
                     }
+
+                    fn visit_params<_F: FnMut(&str, &[usize], &::dfdx::tensor::Tensor<(usize,), Elem, Dev>)>(&self, location: &str, f: &mut _F) {}
                 }
 
                 impl #build_impl ::dfdx::nn_traits::ZeroGrads<Elem, Dev> for #builder_name #built_ty #built_where {
@@ -242,6 +261,28 @@ pub fn custom_module(input: proc_macro::TokenStream) -> proc_macro::TokenStream
                         Ok(())
                     }
                 }
+
+                impl #build_impl ::dfdx::nn_traits::WithGrads<Elem, Dev> for #builder_name #built_ty #built_where {
+                    fn try_grads_view<_F>(&self, grads: &mut ::dfdx::tensor::Gradients<Elem, Dev>, f: &mut _F) -> Result<(), ::dfdx::tensor::Error>
+                    where
+                        _F: FnMut(&::dfdx::tensor::Tensor<(usize,), Elem, Dev>) -> Result<(), ::dfdx::tensor::Error>,
+                    {
+                        Ok(())
+                    }
+
+                    fn try_grads_map<_F>(&self, grads: &mut ::dfdx::tensor::Gradients<Elem, Dev>, f: &mut _F) -> Result<(), ::dfdx::tensor::Error>
+                    where
+                        _F: FnMut(::dfdx::tensor::Tensor<(usize,), Elem, Dev>) -> Result<::dfdx::tensor::Tensor<(usize,), Elem, Dev>, ::dfdx::tensor::Error>,
+                    {
+                        Ok(())
+                    }
+
+                    fn register_grad_hooks<_F>(&self, grads: &mut ::dfdx::tensor::Gradients<Elem, Dev>, hook: &_F)
+                    where
+                        _F: 'static + Clone + Send + Sync + Fn(::dfdx::tensor::UniqueId, ::dfdx::tensor::Tensor<(usize,), Elem, Dev>) -> Result<::dfdx::tensor::Tensor<(usize,), Elem, Dev>, ::dfdx::tensor::Error>,
+                    {
+                    }
+                }
             }
         };
         (built_name, def)
@@ -431,7 +472,7 @@ pub fn sequential(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
         };
 
         quote! {
-            #[derive(Clone, Debug, ::dfdx::ResetParams, ::dfdx::UpdateParams, ::dfdx::ZeroGrads, #safetensors_derive)]
+            #[derive(Clone, Debug, ::dfdx::ResetParams, ::dfdx::UpdateParams, ::dfdx::ZeroGrads, ::dfdx::WithGrads, #safetensors_derive)]
             pub struct #built_name #built_impl #built_where {
                 #fields
             }
@@ -688,13 +729,15 @@ pub fn update_params(input: proc_macro::TokenStream) -> proc_macro::TokenStream
     }
 
     let where_clause = input.generics.make_where_clause();
-    let updates = match &input.data {
+    let (updates, visits) = match &input.data {
         Data::Struct(ref obj) => match obj.fields {
             Fields::Named(ref fields) => {
                 let updates = fields.named.iter().map(|f| {
                     let name = &f.ident;
                     let ty = &f.ty;
-                    if has_attr!(f, "module") {
+                    if is_frozen!(f) {
+                        Default::default()
+                    } else if has_attr!(f, "module") {
                         where_clause
                             .predicates
                             .push(parse_quote!(#ty: ::dfdx::nn_traits::UpdateParams<Elem, Dev>));
@@ -705,13 +748,28 @@ pub fn update_params(input: proc_macro::TokenStream) -> proc_macro::TokenStream
                         Default::default()
                     }
                 });
-                quote! { #(#updates)* }
+                let visits = fields.named.iter().map(|f| {
+                    let name = &f.ident;
+                    let name_str = name.as_ref().map(|n| n.to_string());
+                    if is_frozen!(f) {
+                        Default::default()
+                    } else if has_attr!(f, "module") {
+                        quote_spanned!(f.span()=>self.#name.visit_params(&format!("{location}{}.", #name_str), f);)
+                    } else if has_attr!(f, "param") {
+                        quote_spanned!(f.span()=>self.#name.visit_params(&format!("{location}{}", #name_str), f);)
+                    } else {
+                        Default::default()
+                    }
+                });
+                (quote! { #(#updates)* }, quote! { #(#visits)* })
             }
             Fields::Unnamed(ref fields) => {
                 let updates = fields.unnamed.iter().enumerate().map(|(i, f)| {
                     let index = Index::from(i);
                     let ty = &f.ty;
-                    if has_attr!(f, "module") {
+                    if is_frozen!(f) {
+                        Default::default()
+                    } else if has_attr!(f, "module") {
                         where_clause
                             .predicates
                             .push(parse_quote!(#ty: ::dfdx::nn_traits::UpdateParams<Elem, Dev>));
@@ -722,7 +780,19 @@ pub fn update_params(input: proc_macro::TokenStream) -> proc_macro::TokenStream
                         Default::default()
                     }
                 });
-                quote! { #(#updates)* }
+                let visits = fields.unnamed.iter().enumerate().map(|(i, f)| {
+                    let index = Index::from(i);
+                    if is_frozen!(f) {
+                        Default::default()
+                    } else if has_attr!(f, "module") {
+                        quote_spanned!(f.span()=>self.#index.visit_params(&format!("{location}{}.", #i), f);)
+                    } else if has_attr!(f, "param") {
+                        quote_spanned!(f.span()=>self.#index.visit_params(&format!("{location}{}", #i), f);)
+                    } else {
+                        Default::default()
+                    }
+                });
+                (quote! { #(#updates)* }, quote! { #(#visits)* })
             }
             Fields::Unit => Default::default(),
         },
@@ -744,6 +814,10 @@ pub fn update_params(input: proc_macro::TokenStream) -> proc_macro::TokenStream
                 #updates
                 Ok(())
             }
+
+            fn visit_params<_F: FnMut(&str, &[usize], &::dfdx::tensor::Tensor<(usize,), Elem, Dev>)>(&self, location: &str, f: &mut _F) {
+                #visits
+            }
         }
     })
 }
@@ -778,7 +852,9 @@ pub fn zero_grads(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
                 let zero_grads = fields.named.iter().map(|f| {
                     let name = &f.ident;
                     let ty = &f.ty;
-                    if has_attr!(f, "module")
+                    if is_frozen!(f) {
+                        Default::default()
+                    } else if has_attr!(f, "module")
                     {
                         where_clause
                             .predicates
@@ -797,7 +873,9 @@ pub fn zero_grads(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
                 let zero_grads = fields.unnamed.iter().enumerate().map(|(i, f)| {
                     let index = Index::from(i);
                     let ty = &f.ty;
-                    if has_attr!(f, "module")
+                    if is_frozen!(f) {
+                        Default::default()
+                    } else if has_attr!(f, "module")
                     {
                         where_clause
                             .predicates
@@ -831,6 +909,128 @@ pub fn zero_grads(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
     })
 }
 
+#[proc_macro_derive(WithGrads, attributes(param, module))]
+pub fn with_grads(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let mut input = parse_macro_input!(input as DeriveInput);
+
+    let name = input.ident;
+
+    let mut custom_generics = input.generics.clone();
+    if !custom_generics.params.iter().any(
+        |param| matches!(param, syn::GenericParam::Type(type_param) if type_param.ident == "Elem"),
+    ) {
+        custom_generics
+            .params
+            .push(parse_quote!(Elem: ::dfdx::prelude::Dtype));
+    }
+
+    if !custom_generics.params.iter().any(
+        |param| matches!(param, syn::GenericParam::Type(type_param) if type_param.ident == "Dev"),
+    ) {
+        custom_generics
+            .params
+            .push(parse_quote!(Dev: ::dfdx::prelude::Device<Elem>));
+    }
+
+    let where_clause = input.generics.make_where_clause();
+    let (views, maps, hooks) = match &input.data {
+        Data::Struct(ref obj) => match obj.fields {
+            Fields::Named(ref fields) => {
+                let mut views = Vec::new();
+                let mut maps = Vec::new();
+                let mut hooks = Vec::new();
+                for f in fields.named.iter() {
+                    let name = &f.ident;
+                    let ty = &f.ty;
+                    if is_frozen!(f) {
+                        continue;
+                    }
+                    if has_attr!(f, "module") {
+                        where_clause
+                            .predicates
+                            .push(parse_quote!(#ty: ::dfdx::nn_traits::WithGrads<Elem, Dev>));
+                    }
+                    if has_attr!(f, "module") || has_attr!(f, "param") {
+                        views.push(quote_spanned!(f.span()=>self.#name.try_grads_view(grads, f)?;));
+                        maps.push(quote_spanned!(f.span()=>self.#name.try_grads_map(grads, f)?;));
+                        hooks.push(
+                            quote_spanned!(f.span()=>self.#name.register_grad_hooks(grads, hook);),
+                        );
+                    }
+                }
+                (
+                    quote! { #(#views)* },
+                    quote! { #(#maps)* },
+                    quote! { #(#hooks)* },
+                )
+            }
+            Fields::Unnamed(ref fields) => {
+                let mut views = Vec::new();
+                let mut maps = Vec::new();
+                let mut hooks = Vec::new();
+                for (i, f) in fields.unnamed.iter().enumerate() {
+                    let index = Index::from(i);
+                    let ty = &f.ty;
+                    if is_frozen!(f) {
+                        continue;
+                    }
+                    if has_attr!(f, "module") {
+                        where_clause
+                            .predicates
+                            .push(parse_quote!(#ty: ::dfdx::nn_traits::WithGrads<Elem, Dev>));
+                    }
+                    if has_attr!(f, "module") || has_attr!(f, "param") {
+                        views
+                            .push(quote_spanned!(f.span()=>self.#index.try_grads_view(grads, f)?;));
+                        maps.push(quote_spanned!(f.span()=>self.#index.try_grads_map(grads, f)?;));
+                        hooks.push(
+                            quote_spanned!(f.span()=>self.#index.register_grad_hooks(grads, hook);),
+                        );
+                    }
+                }
+                (
+                    quote! { #(#views)* },
+                    quote! { #(#maps)* },
+                    quote! { #(#hooks)* },
+                )
+            }
+            Fields::Unit => Default::default(),
+        },
+        Data::Enum(_) => unimplemented!("WithGrads not implemented for enums."),
+        Data::Union(_) => unimplemented!("WithGrads not implemented for unions."),
+    };
+
+    let (impl_generics, _, _) = custom_generics.split_for_impl();
+    let (_, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    proc_macro::TokenStream::from(quote! {
+        impl #impl_generics ::dfdx::nn_traits::WithGrads<Elem, Dev> for #name #ty_generics #where_clause {
+            fn try_grads_view<_F>(&self, grads: &mut ::dfdx::prelude::Gradients<Elem, Dev>, f: &mut _F) -> Result<(), ::dfdx::tensor::Error>
+            where
+                _F: FnMut(&::dfdx::prelude::Tensor<(usize,), Elem, Dev>) -> Result<(), ::dfdx::tensor::Error>,
+            {
+                #views
+                Ok(())
+            }
+
+            fn try_grads_map<_F>(&self, grads: &mut ::dfdx::prelude::Gradients<Elem, Dev>, f: &mut _F) -> Result<(), ::dfdx::tensor::Error>
+            where
+                _F: FnMut(::dfdx::prelude::Tensor<(usize,), Elem, Dev>) -> Result<::dfdx::prelude::Tensor<(usize,), Elem, Dev>, ::dfdx::tensor::Error>,
+            {
+                #maps
+                Ok(())
+            }
+
+            fn register_grad_hooks<_F>(&self, grads: &mut ::dfdx::prelude::Gradients<Elem, Dev>, hook: &_F)
+            where
+                _F: 'static + Clone + Send + Sync + Fn(::dfdx::tensor::UniqueId, ::dfdx::prelude::Tensor<(usize,), Elem, Dev>) -> Result<::dfdx::prelude::Tensor<(usize,), Elem, Dev>, ::dfdx::tensor::Error>,
+            {
+                #hooks
+            }
+        }
+    })
+}
+
 #[proc_macro_derive(SaveSafeTensors, attributes(serialize))]
 pub fn save_safetensors(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
     let mut input = parse_macro_input!(input as DeriveInput);
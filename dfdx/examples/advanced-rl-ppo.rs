@@ -7,6 +7,7 @@ use dfdx::prelude::*;
 const BATCH: usize = 64;
 const STATE: usize = 4;
 const ACTION: usize = 2;
+const GAMMA: f32 = 0.99;
 
 type PolicyNetwork = (
     (LinearConstConfig<STATE, 32>, ReLU),
@@ -38,7 +39,19 @@ fn main() {
         i += 1;
         i % ACTION
     }));
-    let advantage = dev.sample_normal::<Rank1<BATCH>>();
+    let reward = dev.sample_normal::<Rank1<BATCH>>();
+
+    // discounted returns R_t = sum_{k >= t} gamma^(k - t) * r_k, computed on the device
+    // as gamma^-t times a reversed cumulative sum of gamma^k * r_k
+    let mut t = 0;
+    let discount: Tensor<Rank1<BATCH>, f32, _> = dev.tensor([(); BATCH].map(|_| {
+        t += 1;
+        GAMMA.powi(t - 1)
+    }));
+    let discounted = reward * discount.clone();
+    let total = discounted.clone().sum::<Rank0, _>().broadcast();
+    let returns = (total - discounted.clone().cumsum::<Axis<0>>() + discounted) / discount;
+    let advantage = returns.clone() - returns.mean().broadcast();
 
     // run through training data
     for epoch in 0..10 {
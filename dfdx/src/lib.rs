@@ -262,7 +262,7 @@ pub use dfdx_core::*;
 #[cfg(feature = "safetensors")]
 pub use safetensors;
 
-pub use dfdx_derives::{CustomModule, ResetParams, Sequential, UpdateParams, ZeroGrads};
+pub use dfdx_derives::{CustomModule, ResetParams, Sequential, UpdateParams, WithGrads, ZeroGrads};
 #[cfg(feature = "safetensors")]
 pub use dfdx_derives::{LoadSafeTensors, SaveSafeTensors};
 
@@ -421,6 +421,18 @@ pub(crate) mod tests {
         }
     }
 
+    macro_rules! assert_close_to_tensor {
+        ($Lhs:expr, $Rhs:expr) => {
+            let lhs = $Lhs.array();
+            let tol = AssertClose::get_default_tol(&lhs);
+            let far_pair = AssertClose::get_far_pair(&lhs, &$Rhs.array(), tol);
+            if let Some((l, r)) = far_pair {
+                panic!("lhs != rhs | {l} != {r}");
+            }
+        };
+    }
+    pub(crate) use assert_close_to_tensor;
+
     macro_rules! assert_close_to_literal {
         ($Lhs:expr, $Rhs:expr) => {{
             let lhs = $Lhs.array();
@@ -0,0 +1,192 @@
+use crate::prelude::*;
+
+/// Gradient checkpointing around `T`: the intermediate values of `T` are not kept
+/// during the forward pass, and are instead recomputed during the backward pass.
+///
+/// Uses [crate::tensor_ops::try_checkpoint()]. This reduces the memory needed for training
+/// deep models, at the cost of running the forward pass of `T` twice. Dropout and
+/// other random ops inside `T` draw the same random numbers both times.
+///
+/// If the input is not traced, this is the same as just calling `T`.
+///
+/// # Generics
+/// - `T`: The underlying module to checkpoint.
+///
+/// # Examples
+/// ```rust
+/// # use dfdx::prelude::*;
+/// # use dfdx::*;
+/// # let dev: Cpu = Default::default();
+/// type Model = Checkpoint<(LinearConstConfig<5, 10>, Tanh, LinearConstConfig<10, 5>)>;
+/// let model = dev.build_module::<f32>(Model::default());
+/// let x: Tensor<Rank1<5>, f32, _> = dev.sample_normal();
+/// let grads = model.alloc_grads();
+/// let y = model.forward(x.trace(grads));
+/// let grads = y.square().mean().backward();
+/// ```
+#[derive(Default, Clone, Debug, ResetParams, ZeroGrads, UpdateParams, WithGrads)]
+#[cfg_attr(feature = "safetensors", derive(SaveSafeTensors, LoadSafeTensors))]
+#[repr(transparent)]
+pub struct Checkpoint<T>(
+    #[module]
+    #[cfg_attr(feature = "safetensors", serialize)]
+    pub T,
+);
+
+impl<E: Dtype, D: Device<E>, T: BuildOnDevice<E, D>> BuildOnDevice<E, D> for Checkpoint<T> {
+    type Built = Checkpoint<T::Built>;
+    fn try_build_on_device(&self, device: &D) -> Result<Self::Built, crate::tensor::Error> {
+        let t = self.0.try_build_on_device(device)?;
+        Ok(Checkpoint(t))
+    }
+}
+
+impl<S: Shape, E: Dtype, D: Device<E>, T: Module<Tensor<S, E, D>>> Module<Tensor<S, E, D>>
+    for Checkpoint<T>
+{
+    type Output = T::Output;
+    fn try_forward(&self, x: Tensor<S, E, D>) -> Result<Self::Output, Error> {
+        self.0.try_forward(x)
+    }
+    fn try_forward_mut(&mut self, x: Tensor<S, E, D>) -> Result<Self::Output, Error> {
+        self.0.try_forward_mut(x)
+    }
+}
+
+impl<S: Shape, Dst: Shape, E: Dtype, D: Device<E>, T> Module<Tensor<S, E, D, OwnedTape<E, D>>>
+    for Checkpoint<T>
+where
+    T: 'static
+        + Clone
+        + Module<Tensor<S, E, D, OwnedTape<E, D>>, Output = Tensor<Dst, E, D, OwnedTape<E, D>>>,
+{
+    type Output = Tensor<Dst, E, D, OwnedTape<E, D>>;
+    fn try_forward(&self, x: Tensor<S, E, D, OwnedTape<E, D>>) -> Result<Self::Output, Error> {
+        let module = self.0.clone();
+        try_checkpoint(x, |x| self.0.try_forward(x), move |x| module.try_forward(x))
+    }
+    fn try_forward_mut(
+        &mut self,
+        x: Tensor<S, E, D, OwnedTape<E, D>>,
+    ) -> Result<Self::Output, Error> {
+        // recompute with the module as it was before this forward pass
+        let mut module = self.0.clone();
+        try_checkpoint(
+            x,
+            |x| self.0.try_forward_mut(x),
+            move |x| module.try_forward_mut(x),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::*;
+
+    #[test]
+    fn test_checkpoint_same_as_unwrapped() {
+        let dev: TestDevice = Default::default();
+
+        type Model = (LinearConstConfig<3, 5>, Tanh, LinearConstConfig<5, 2>);
+        let model = dev.build_module::<TestDtype>(Model::default());
+        let checkpointed = Checkpoint(model.clone());
+
+        let x: Tensor<Rank2<4, 3>, TestDtype, _> = dev.sample_normal();
+
+        let y1 = model.forward(x.leaky_trace());
+        let y2 = checkpointed.forward(x.leaky_trace());
+        assert_close_to_tensor!(y1, y2);
+
+        let g1 = y1.square().mean().backward();
+        let g2 = y2.square().mean().backward();
+        assert_close_to_tensor!(g1.get(&x), g2.get(&x));
+        assert_close_to_tensor!(g1.get(&model.0.weight), g2.get(&checkpointed.0 .0.weight));
+        assert_close_to_tensor!(g1.get(&model.0.bias), g2.get(&checkpointed.0 .0.bias));
+        assert_close_to_tensor!(g1.get(&model.2.weight), g2.get(&checkpointed.0 .2.weight));
+        assert_close_to_tensor!(g1.get(&model.2.bias), g2.get(&checkpointed.0 .2.bias));
+    }
+
+    #[test]
+    fn test_checkpoint_with_dropout() {
+        let dev: TestDevice = Default::default();
+
+        type Model = Checkpoint<(LinearConstConfig<8, 8>, Dropout)>;
+        let mut model = dev.build_module::<TestDtype>(Model::default());
+
+        let x: Tensor<Rank2<2, 8>, TestDtype, _> = dev.ones();
+        let grads = model.alloc_grads();
+        let y = model.forward_mut(x.trace(grads));
+        let y_array = y.array();
+        let g = y.sum().backward();
+
+        // the gradient of the bias is only non-zero where the recomputed dropout
+        // mask keeps the same values as the original one
+        let mut expected = [0.0; 8];
+        for row in y_array.iter() {
+            for (e, &v) in expected.iter_mut().zip(row.iter()) {
+                if v != 0.0 {
+                    *e += 2.0;
+                }
+            }
+        }
+        assert_close_to_literal!(g.get(&model.0 .0.bias), expected);
+    }
+
+    #[derive(Default, Clone, Sequential)]
+    #[built(Mlp)]
+    struct MlpConfig {
+        pub linear1: LinearConstConfig<3, 5>,
+        pub act1: Tanh,
+        pub block: (LinearConstConfig<5, 5>, Tanh),
+        pub linear2: LinearConstConfig<5, 2>,
+    }
+
+    #[derive(Default, Clone, Sequential)]
+    #[built(CheckpointedMlp)]
+    struct CheckpointedMlpConfig {
+        pub linear1: LinearConstConfig<3, 5>,
+        pub act1: Tanh,
+        pub block: Checkpoint<(LinearConstConfig<5, 5>, Tanh)>,
+        pub linear2: LinearConstConfig<5, 2>,
+    }
+
+    #[test]
+    fn test_checkpoint_in_derived_module() {
+        let dev: TestDevice = Default::default();
+        let model = dev.build_module::<TestDtype>(MlpConfig::default());
+        // building works with a checkpointed field, the parameters are copied over to
+        // compare with the model without checkpointing
+        let checkpointed = dev.build_module::<TestDtype>(CheckpointedMlpConfig::default());
+        let checkpointed = CheckpointedMlp {
+            linear1: model.linear1.clone(),
+            block: Checkpoint(model.block.clone()),
+            linear2: model.linear2.clone(),
+            ..checkpointed
+        };
+
+        let x: Tensor<Rank2<4, 3>, TestDtype, _> = dev.sample_normal();
+        let y1 = model.forward(x.trace(model.alloc_grads()));
+        let y2 = checkpointed.forward(x.trace(checkpointed.alloc_grads()));
+        assert_close_to_tensor!(y1, y2);
+
+        let g1 = y1.square().mean().backward();
+        let g2 = y2.square().mean().backward();
+        assert_close_to_tensor!(
+            g1.get(&model.linear1.weight),
+            g2.get(&checkpointed.linear1.weight)
+        );
+        assert_close_to_tensor!(
+            g1.get(&model.block.0.weight),
+            g2.get(&checkpointed.block.0 .0.weight)
+        );
+        assert_close_to_tensor!(
+            g1.get(&model.block.0.bias),
+            g2.get(&checkpointed.block.0 .0.bias)
+        );
+        assert_close_to_tensor!(
+            g1.get(&model.linear2.weight),
+            g2.get(&checkpointed.linear2.weight)
+        );
+    }
+}
@@ -0,0 +1,278 @@
+use crate::prelude::*;
+
+/// Freezes the parameters of `T`, so that optimizers don't update them. [ZeroGrads] doesn't
+/// allocate gradients for them either, so they are never reported in
+/// [crate::tensor::Error::UnusedTensors].
+///
+/// Gradients still flow through `T` to its input. Set [Frozen::frozen] to `false` to train `T`
+/// again, e.g. to fine tune the whole model after training only the head. Parameters are
+/// saved & loaded as if `T` was not wrapped.
+///
+/// To always freeze a sub module of a custom module, mark it with `#[module(frozen)]` instead.
+///
+/// # Generics
+/// - `T`: The underlying module to freeze.
+///
+/// # Examples
+/// ```rust
+/// # use dfdx::prelude::*;
+/// # let dev: Cpu = Default::default();
+/// type Model = (Frozen<LinearConstConfig<5, 5>>, Tanh, LinearConstConfig<5, 2>);
+/// let mut model = dev.build_module::<f32>(Model::default());
+/// let mut opt = Sgd::new(&model, Default::default());
+///
+/// let x: Tensor<Rank1<5>, f32, _> = dev.sample_normal();
+/// let y = model.forward(x.trace(model.alloc_grads()));
+/// let grads = y.square().mean().backward();
+/// // only the last linear layer is updated
+/// opt.update(&mut model, &grads).unwrap();
+///
+/// // train everything from now on
+/// model.0.frozen = false;
+/// ```
+#[derive(Clone, Debug)]
+pub struct Frozen<T> {
+    pub module: T,
+    pub frozen: bool,
+}
+
+impl<T> Frozen<T> {
+    /// Wraps `module`, starting out frozen.
+    pub fn new(module: T) -> Self {
+        Self {
+            module,
+            frozen: true,
+        }
+    }
+}
+
+impl<T: Default> Default for Frozen<T> {
+    fn default() -> Self {
+        Self::new(Default::default())
+    }
+}
+
+impl<E: Dtype, D: Device<E>, T: BuildOnDevice<E, D>> BuildOnDevice<E, D> for Frozen<T> {
+    type Built = Frozen<T::Built>;
+    fn try_build_on_device(&self, device: &D) -> Result<Self::Built, crate::tensor::Error> {
+        Ok(Frozen {
+            module: self.module.try_build_on_device(device)?,
+            frozen: self.frozen,
+        })
+    }
+}
+
+impl<E: Dtype, D: Device<E>, T: ResetParams<E, D>> ResetParams<E, D> for Frozen<T> {
+    fn try_reset_params(&mut self) -> Result<(), crate::tensor::Error> {
+        self.module.try_reset_params()
+    }
+}
+
+impl<E: Dtype, D: Device<E>, T: UpdateParams<E, D>> UpdateParams<E, D> for Frozen<T> {
+    fn try_update_params<M, Optim: Optimizer<M, E, D>>(
+        &mut self,
+        optimizer: &mut Optim,
+        gradients: &Gradients<E, D>,
+        missing_tensors: &mut Vec<UniqueId>,
+    ) -> Result<(), crate::tensor::Error> {
+        if self.frozen {
+            return Ok(());
+        }
+        self.module
+            .try_update_params(optimizer, gradients, missing_tensors)
+    }
+
+    fn visit_params<F: FnMut(&str, &[usize], &Tensor<(usize,), E, D>)>(
+        &self,
+        location: &str,
+        f: &mut F,
+    ) {
+        if !self.frozen {
+            self.module.visit_params(location, f)
+        }
+    }
+}
+
+impl<E: Dtype, D: Device<E>, T: ZeroGrads<E, D>> ZeroGrads<E, D> for Frozen<T> {
+    fn try_zero_grads(&self, grads: &mut Gradients<E, D>) -> Result<(), crate::tensor::Error> {
+        if self.frozen {
+            return Ok(());
+        }
+        self.module.try_zero_grads(grads)
+    }
+}
+
+impl<E: Dtype, D: Device<E>, T: WithGrads<E, D>> WithGrads<E, D> for Frozen<T> {
+    fn try_grads_view<F>(&self, grads: &mut Gradients<E, D>, f: &mut F) -> Result<(), Error>
+    where
+        F: FnMut(&Tensor<(usize,), E, D>) -> Result<(), Error>,
+    {
+        if self.frozen {
+            return Ok(());
+        }
+        self.module.try_grads_view(grads, f)
+    }
+
+    fn try_grads_map<F>(&self, grads: &mut Gradients<E, D>, f: &mut F) -> Result<(), Error>
+    where
+        F: FnMut(Tensor<(usize,), E, D>) -> Result<Tensor<(usize,), E, D>, Error>,
+    {
+        if self.frozen {
+            return Ok(());
+        }
+        self.module.try_grads_map(grads, f)
+    }
+
+    fn register_grad_hooks<F>(&self, grads: &mut Gradients<E, D>, hook: &F)
+    where
+        F: 'static
+            + Clone
+            + Send
+            + Sync
+            + Fn(UniqueId, Tensor<(usize,), E, D>) -> Result<Tensor<(usize,), E, D>, Error>,
+    {
+        if !self.frozen {
+            self.module.register_grad_hooks(grads, hook)
+        }
+    }
+}
+
+#[cfg(feature = "safetensors")]
+impl<T: SaveSafeTensors> SaveSafeTensors for Frozen<T> {
+    fn write_safetensors(
+        &self,
+        location: &str,
+        tensors: &mut Vec<(String, ::safetensors::Dtype, Vec<usize>, Vec<u8>)>,
+    ) {
+        self.module.write_safetensors(location, tensors)
+    }
+}
+
+#[cfg(feature = "safetensors")]
+impl<T: LoadSafeTensors> LoadSafeTensors for Frozen<T> {
+    fn read_safetensors(
+        &mut self,
+        location: &str,
+        tensors: &::safetensors::SafeTensors,
+    ) -> Result<(), ::safetensors::SafeTensorError> {
+        self.module.read_safetensors(location, tensors)
+    }
+}
+
+impl<X, T: Module<X>> Module<X> for Frozen<T> {
+    type Output = T::Output;
+    fn try_forward(&self, x: X) -> Result<Self::Output, Error> {
+        self.module.try_forward(x)
+    }
+    fn try_forward_mut(&mut self, x: X) -> Result<Self::Output, Error> {
+        self.module.try_forward_mut(x)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::*;
+
+    #[test]
+    fn test_frozen_is_not_updated() {
+        let dev: TestDevice = Default::default();
+        type Model = (Frozen<LinearConstConfig<3, 3>>, LinearConstConfig<3, 2>);
+        let mut model = dev.build_module::<TestDtype>(Model::default());
+        let mut opt = Sgd::new(&model, Default::default());
+        let x: Tensor<Rank2<4, 3>, TestDtype, _> = dev.sample_normal();
+
+        let before = model.clone();
+        let grads = model.alloc_grads();
+        let grads = model.forward(x.trace(grads)).square().mean().backward();
+        assert!(grads.get_ref_checked(&model.0.module.weight).is_none());
+        opt.update(&mut model, &grads).unwrap();
+        assert_eq!(
+            model.0.module.weight.array(),
+            before.0.module.weight.array()
+        );
+        assert_eq!(model.0.module.bias.array(), before.0.module.bias.array());
+        assert_ne!(model.1.weight.array(), before.1.weight.array());
+
+        model.0.frozen = false;
+        let grads = model.alloc_grads();
+        let grads = model.forward(x.trace(grads)).square().mean().backward();
+        opt.update(&mut model, &grads).unwrap();
+        assert_ne!(
+            model.0.module.weight.array(),
+            before.0.module.weight.array()
+        );
+    }
+
+    #[test]
+    fn test_frozen_without_grads_is_not_missing() {
+        let dev: TestDevice = Default::default();
+        type Model = (Frozen<LinearConstConfig<3, 3>>, LinearConstConfig<3, 2>);
+        let mut model = dev.build_module::<TestDtype>(Model::default());
+        let mut opt = Sgd::new(&model, Default::default());
+        let x: Tensor<Rank1<3>, TestDtype, _> = dev.sample_normal();
+
+        // the frozen module isn't traced at all
+        let h = model.0.forward(x.clone());
+        let grads = model.1.forward(h.leaky_trace()).sum().backward();
+        opt.update(&mut model, &grads).unwrap();
+
+        model.0.frozen = false;
+        let h = model.0.forward(x);
+        let grads = model.1.forward(h.leaky_trace()).sum().backward();
+        assert!(matches!(
+            opt.update(&mut model, &grads),
+            Err(Error::UnusedTensors(_))
+        ));
+    }
+
+    #[test]
+    fn test_derive_frozen_attributes() {
+        #[derive(Clone, Debug, UpdateParams, ZeroGrads, WithGrads)]
+        struct Model<Elem: Dtype, Dev: Device<Elem>> {
+            #[param(frozen)]
+            a: Tensor<Rank1<2>, Elem, Dev>,
+            #[param]
+            b: Tensor<Rank1<2>, Elem, Dev>,
+            #[module(frozen)]
+            c: Linear<Const<2>, Const<2>, Elem, Dev>,
+        }
+
+        let dev: TestDevice = Default::default();
+        let mut model = Model {
+            a: dev.ones(),
+            b: dev.ones(),
+            c: dev.build_module::<TestDtype>(LinearConstConfig::<2, 2>::default()),
+        };
+        let mut opt = Sgd::new(&model, Default::default());
+        let grads = model.alloc_grads();
+        assert!(grads.get_ref_checked(&model.a).is_none());
+        assert!(grads.get_ref_checked(&model.c.weight).is_none());
+
+        let y = model.b.clone().trace(grads) * model.a.clone();
+        let grads = model.c.forward(y).sum().backward();
+        let c_weight = model.c.weight.array();
+        opt.update(&mut model, &grads).unwrap();
+        assert_eq!(model.a.array(), [1.0; 2]);
+        assert_ne!(model.b.array(), [1.0; 2]);
+        assert_eq!(model.c.weight.array(), c_weight);
+    }
+
+    #[test]
+    fn test_custom_module_frozen_sub_module() {
+        #[derive(Clone, Debug, Default, CustomModule)]
+        #[built(FineTune)]
+        struct FineTuneConfig {
+            #[module(frozen)]
+            backbone: LinearConstConfig<2, 2>,
+            #[module]
+            head: LinearConstConfig<2, 2>,
+        }
+
+        let dev: TestDevice = Default::default();
+        let model = dev.build_module::<TestDtype>(FineTuneConfig::default());
+        let grads = model.alloc_grads();
+        assert!(grads.get_ref_checked(&model.backbone.weight).is_none());
+        assert!(grads.get_ref_checked(&model.head.weight).is_some());
+    }
+}
@@ -4,6 +4,7 @@ mod batch_norm1d;
 mod batch_norm2d;
 mod bias1d;
 mod bias2d;
+mod checkpoint;
 #[cfg(feature = "nightly")]
 mod conv1d;
 #[cfg(feature = "nightly")]
@@ -16,6 +17,7 @@ mod embedding;
 mod exp;
 #[cfg(feature = "nightly")]
 mod flatten2d;
+mod frozen;
 mod gelu;
 mod generalized_add;
 mod generalized_mul;
@@ -57,6 +59,7 @@ pub use batch_norm1d::{BatchNorm1D, BatchNorm1DConfig, BatchNorm1DConstConfig};
 pub use batch_norm2d::{BatchNorm2D, BatchNorm2DConfig, BatchNorm2DConstConfig};
 pub use bias1d::{Bias1D, Bias1DConfig, Bias1DConstConfig};
 pub use bias2d::{Bias2D, Bias2DConfig, Bias2DConstConfig};
+pub use checkpoint::Checkpoint;
 #[cfg(feature = "nightly")]
 pub use conv1d::{Conv1D, Conv1DConfig, Conv1DConstConfig};
 #[cfg(feature = "nightly")]
@@ -69,6 +72,7 @@ pub use embedding::{Embedding, EmbeddingConfig, EmbeddingConstConfig};
 pub use exp::Exp;
 #[cfg(feature = "nightly")]
 pub use flatten2d::Flatten2D;
+pub use frozen::Frozen;
 pub use gelu::{AccurateGeLU, FastGeLU};
 pub use generalized_add::GeneralizedAdd;
 pub use generalized_mul::GeneralizedMul;
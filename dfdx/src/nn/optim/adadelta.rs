@@ -0,0 +1,245 @@
+use std::marker::PhantomData;
+
+use super::param_groups::ParamGroups;
+use crate::{
+    nn::UpdateParams,
+    shapes::{Dtype, Shape},
+    tensor::{Error, Gradients, Storage, Tensor, Tensorlike, UniqueId},
+    tensor_ops::{AdadeltaConfig, Device},
+};
+
+/// An implementation of the Adadelta optimizer from
+/// [ADADELTA: An Adaptive Learning Rate Method](https://arxiv.org/abs/1212.5701)
+///
+/// # Example Usage
+/// ```rust
+/// # use dfdx::prelude::*;
+/// # type Model = Tensor<Rank0, f32, Cpu>;
+/// # let dev: Cpu = Default::default();
+/// # let model: Model = dev.zeros();
+/// let mut opt: Adadelta<Model, f32, Cpu> = optim::Adadelta::new(&model, AdadeltaConfig {
+///     lr: 1.0,
+///     rho: 0.95,
+///     eps: 1e-6,
+///     weight_decay: None,
+/// });
+/// ```
+///
+/// See module level documentation at [crate::nn::optim] for examples of how to actually use an optimizer.
+#[derive(Debug, Clone)]
+pub struct Adadelta<M, E: Dtype, D: Storage<E>> {
+    /// Hyperparameter configuration
+    pub cfg: AdadeltaConfig,
+
+    square_avg: Gradients<E, D>,
+    acc_delta: Gradients<E, D>,
+
+    param_groups: ParamGroups<AdadeltaConfig>,
+
+    marker: PhantomData<*const M>,
+}
+
+impl<M, E: Dtype, D: Storage<E>> Adadelta<M, E, D> {
+    /// Constructs using hyperparameters from `cfg`.
+    pub fn new(_model: &M, cfg: AdadeltaConfig) -> Self {
+        Self {
+            cfg,
+            square_avg: Gradients::leaky(),
+            acc_delta: Gradients::leaky(),
+            param_groups: Default::default(),
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<M, E: Dtype, D: Device<E>> Adadelta<M, E, D> {
+    /// Uses `cfg` instead of [Adadelta::cfg] for the parameters of `model` that `select` returns
+    /// `true` for, given their path & shape. See [Adam::add_param_group()] for details.
+    ///
+    /// [Adam::add_param_group()]: crate::nn::optim::Adam::add_param_group()
+    pub fn add_param_group<F>(&mut self, model: &M, select: F, cfg: AdadeltaConfig)
+    where
+        M: UpdateParams<E, D>,
+        F: FnMut(&str, &[usize]) -> bool,
+    {
+        self.param_groups
+            .add(model, select, cfg, self.cfg.lr, cfg.lr);
+    }
+}
+
+impl<M, E: Dtype, D: Storage<E>> super::LearningRate for Adadelta<M, E, D> {
+    fn lr(&self) -> f64 {
+        self.cfg.lr
+    }
+    fn set_lr(&mut self, lr: f64) {
+        self.cfg.lr = lr;
+        self.param_groups.set_lr(lr, |cfg| &mut cfg.lr);
+    }
+}
+
+impl<M, E: Dtype, D: Device<E>> crate::nn::Optimizer<M, E, D> for Adadelta<M, E, D> {
+    fn update_tensor<S: Shape>(
+        &mut self,
+        t: &mut Tensor<S, E, D>,
+        gradients: &Gradients<E, D>,
+        missing_params: &mut Vec<UniqueId>,
+    ) -> Result<(), Error> {
+        let g = gradients.get_ref_checked(t);
+        match g {
+            None => missing_params.push(t.id()),
+            Some(g) => {
+                let sa = self.square_avg.get_or_alloc_mut(t)?;
+                let ad = self.acc_delta.get_or_alloc_mut(t)?;
+                let cfg = self.param_groups.cfg(&t.id(), &self.cfg);
+                cfg.try_update(t, sa, ad, g)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(feature = "safetensors")]
+impl<M: UpdateParams<E, D>, E: Dtype, D: Device<E>> super::OptimizerSafeTensors<M, E, D>
+    for Adadelta<M, E, D>
+{
+    fn write_safetensors(
+        &self,
+        model: &M,
+        location: &str,
+        tensors: &mut Vec<(String, safetensors::Dtype, Vec<usize>, Vec<u8>)>,
+    ) {
+        super::state::write_state(
+            model,
+            &self.square_avg,
+            &format!("{location}square_avg."),
+            tensors,
+        );
+        super::state::write_state(
+            model,
+            &self.acc_delta,
+            &format!("{location}acc_delta."),
+            tensors,
+        );
+    }
+
+    fn read_safetensors(
+        &mut self,
+        model: &M,
+        location: &str,
+        tensors: &safetensors::SafeTensors,
+    ) -> Result<(), safetensors::SafeTensorError> {
+        super::state::read_state(
+            model,
+            &mut self.square_avg,
+            &format!("{location}square_avg."),
+            tensors,
+        )?;
+        super::state::read_state(
+            model,
+            &mut self.acc_delta,
+            &format!("{location}acc_delta."),
+            tensors,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{nn::optim::*, shapes::*, tensor::*, tensor_ops::*, tests::*};
+
+    fn test_matches_expected<const N: usize>(cfg: AdadeltaConfig, expected: [[f64; 5]; N]) {
+        let dev: TestDevice = Default::default();
+        let mut t = dev
+            .tensor([-0.5, -0.25, 0.1, 0.6, 1.0])
+            .to_dtype::<TestDtype>();
+        let mut opt = Adadelta::new(&t, cfg);
+        for e in expected.iter() {
+            let gradients = t.leaky_trace().exp().square().mean().backward();
+            opt.update(&mut t, &gradients).expect("");
+            assert_close_to_literal!(t, e);
+        }
+    }
+
+    #[test]
+    fn test_adadelta_default() {
+        let cfg = AdadeltaConfig::default();
+        #[rustfmt::skip]
+        const EXPECTED: [[f64; 5]; 5] = [
+            [-0.50316155, -0.25316201, 0.096837789, 0.59683773, 0.99683772],
+            [-0.50639547, -0.25639642, 0.093603167, 0.59360305, 0.99360304],
+            [-0.50967224, -0.25967368, 0.090325684, 0.59032551, 0.99032548],
+            [-0.5129772, -0.26297915, 0.087019998, 0.58701976, 0.98701973],
+            [-0.51630164, -0.26630409, 0.08369483, 0.58369453, 0.98369449],
+        ];
+        test_matches_expected(cfg, EXPECTED);
+    }
+    #[test]
+    fn test_adadelta_custom() {
+        let cfg = AdadeltaConfig {
+            lr: 0.5,
+            rho: 0.5,
+            eps: 1e-2,
+            weight_decay: None,
+        };
+        #[rustfmt::skip]
+        const EXPECTED: [[f64; 5]; 5] = [
+            [-0.55098284, -0.31108962, 0.032077693, 0.52968686, 0.92937013],
+            [-0.6035919, -0.37600318, -0.041702451, 0.45269254, 0.85194551],
+            [-0.65528456, -0.44097695, -0.11681376, 0.37377188, 0.77250905],
+            [-0.70488054, -0.50421136, -0.19116871, 0.2950406, 0.69317592],
+            [-0.75181847, -0.56476622, -0.26374947, 0.21742331, 0.61485037],
+        ];
+        test_matches_expected(cfg, EXPECTED);
+    }
+    #[test]
+    fn test_adadelta_l2_weight_decay() {
+        let cfg = AdadeltaConfig {
+            weight_decay: Some(WeightDecay::L2(0.5)),
+            ..Default::default()
+        };
+        #[rustfmt::skip]
+        const EXPECTED: [[f64; 5]; 5] = [
+            [-0.49683922, -0.25316114, 0.096837777, 0.59683773, 0.99683772],
+            [-0.49363466, -0.25636287, 0.093606798, 0.59360275, 0.99360233],
+            [-0.49042754, -0.25956297, 0.09033808, 0.59032451, 0.9903231],
+            [-0.48723892, -0.26274014, 0.08704709, 0.58701762, 0.98701455],
+            [-0.48408147, -0.26588177, 0.083743071, 0.58369079, 0.98368532],
+        ];
+        test_matches_expected(cfg, EXPECTED);
+    }
+    #[test]
+    fn test_adadelta_decoupled_weight_decay() {
+        // pytorch's Adadelta has no decoupled weight decay, so the expected values are computed
+        // here instead: the AdamW style decay `p -= lr * wd * p` next to the plain update.
+        let (lr, wd) = (1.0, 0.5);
+        let mut p: [f64; 5] = [-0.5, -0.25, 0.1, 0.6, 1.0];
+        let (mut square_avg, mut acc_delta) = ([0.0; 5], [0.0; 5]);
+        let expected: [[f64; 5]; 5] = std::array::from_fn(|_| {
+            for (p, (v, a)) in p
+                .iter_mut()
+                .zip(square_avg.iter_mut().zip(acc_delta.iter_mut()))
+            {
+                let g = 2.0 * (2.0 * *p).exp() / 5.0;
+                *v = 0.9 * *v + 0.1 * g * g;
+                let delta = (*a + 1e-6).sqrt() / (*v + 1e-6).sqrt() * g;
+                *a = 0.9 * *a + 0.1 * delta * delta;
+                *p -= lr * wd * *p + lr * delta;
+            }
+            p
+        });
+        let cfg = AdadeltaConfig {
+            weight_decay: Some(WeightDecay::Decoupled(wd)),
+            ..Default::default()
+        };
+        test_matches_expected(cfg, expected);
+    }
+
+    #[test]
+    fn test_unused_tensors() {
+        let dev: TestDevice = Default::default();
+        let mut t: Tensor<Rank1<5>, TestDtype, _> = dev.sample_normal();
+        let mut opt = Adadelta::new(&t, Default::default());
+        opt.update(&mut t, &Gradients::leaky()).expect_err("");
+    }
+}
@@ -0,0 +1,286 @@
+use std::collections::{hash_map::Entry, HashMap};
+use std::marker::PhantomData;
+
+use super::param_groups::ParamGroups;
+use crate::{
+    nn::UpdateParams,
+    shapes::{Dtype, HasShape, Shape},
+    tensor::{Error, Gradients, Storage, Tensor, Tensorlike, UniqueId},
+    tensor_ops::{AdafactorConfig, Device},
+};
+
+/// An implementation of the Adafactor optimizer from
+/// [Adafactor: Adaptive Learning Rates with Sublinear Memory Cost](https://arxiv.org/abs/1804.04861)
+///
+/// The running average of squared gradients of rank 2 parameters (e.g. [Linear::weight]) is factored into
+/// the averages of each row and each column, so it needs `rows + cols` instead of `rows * cols` elements.
+/// There is no running average of the gradients. The step size is relative to the root mean square of
+/// each parameter, see [AdafactorConfig::lr].
+///
+/// [Linear::weight]: crate::nn::Linear::weight
+///
+/// # Example Usage
+/// ```rust
+/// # use dfdx::prelude::*;
+/// # type Model = Tensor<Rank0, f32, Cpu>;
+/// # let dev: Cpu = Default::default();
+/// # let model: Model = dev.zeros();
+/// let mut opt: Adafactor<Model, f32, Cpu> = optim::Adafactor::new(&model, AdafactorConfig {
+///     lr: 1e-2,
+///     beta2_decay: -0.8,
+///     eps: [1e-30, 1e-3],
+///     clip_threshold: 1.0,
+///     weight_decay: Some(WeightDecay::Decoupled(1e-2)),
+/// });
+/// ```
+///
+/// See module level documentation at [crate::nn::optim] for examples of how to actually use an optimizer.
+#[derive(Debug, Clone)]
+pub struct Adafactor<M, E: Dtype, D: Storage<E>> {
+    /// Hyperparameter configuration
+    pub cfg: AdafactorConfig,
+
+    t: i32,
+
+    /// Running averages of the squared gradients. They are factored into the averages of
+    /// each row and column for rank 2 parameters.
+    exp_avg_sq: HashMap<UniqueId, Tensor<(usize,), E, D>>,
+
+    param_groups: ParamGroups<AdafactorConfig>,
+
+    marker: PhantomData<*const M>,
+}
+
+impl<M, E: Dtype, D: Storage<E>> Adafactor<M, E, D> {
+    /// Constructs using hyperparameters from `cfg`.
+    pub fn new(_model: &M, cfg: AdafactorConfig) -> Self {
+        Self {
+            cfg,
+            t: 0,
+            exp_avg_sq: HashMap::new(),
+            param_groups: Default::default(),
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<M, E: Dtype, D: Device<E>> Adafactor<M, E, D> {
+    /// Uses `cfg` instead of [Adafactor::cfg] for the parameters of `model` that `select` returns
+    /// `true` for, given their path & shape. See [Adam::add_param_group()] for details.
+    ///
+    /// [Adam::add_param_group()]: crate::nn::optim::Adam::add_param_group()
+    pub fn add_param_group<F>(&mut self, model: &M, select: F, cfg: AdafactorConfig)
+    where
+        M: UpdateParams<E, D>,
+        F: FnMut(&str, &[usize]) -> bool,
+    {
+        self.param_groups
+            .add(model, select, cfg, self.cfg.lr, cfg.lr);
+    }
+}
+
+impl<M, E: Dtype, D: Storage<E>> super::LearningRate for Adafactor<M, E, D> {
+    fn lr(&self) -> f64 {
+        self.cfg.lr
+    }
+    fn set_lr(&mut self, lr: f64) {
+        self.cfg.lr = lr;
+        self.param_groups.set_lr(lr, |cfg| &mut cfg.lr);
+    }
+}
+
+impl<M, E: Dtype, D: Device<E>> crate::nn::Optimizer<M, E, D> for Adafactor<M, E, D> {
+    fn update_tensor<S: Shape>(
+        &mut self,
+        t: &mut Tensor<S, E, D>,
+        gradients: &Gradients<E, D>,
+        missing_params: &mut Vec<UniqueId>,
+    ) -> Result<(), Error> {
+        let g = gradients.get_ref_checked(t);
+        match g {
+            None => missing_params.push(t.id()),
+            Some(g) => {
+                let len = AdafactorConfig::exp_avg_sq_len(t.shape().concrete().as_ref());
+                let exp_avg_sq = match self.exp_avg_sq.entry(t.id()) {
+                    Entry::Occupied(entry) => entry.into_mut(),
+                    Entry::Vacant(entry) => entry.insert(t.device().try_zeros_like(&(len,))?),
+                };
+                let cfg = self.param_groups.cfg(&t.id(), &self.cfg);
+                cfg.try_update(self.t, t, exp_avg_sq, g)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn update(&mut self, module: &mut M, gradients: &Gradients<E, D>) -> Result<(), Error>
+    where
+        M: crate::nn::UpdateParams<E, D>,
+    {
+        self.t = self.t.checked_add(1).unwrap();
+
+        // NOTE: the rest of this is identical to default implementation of update.
+        let mut missing_tensors = Vec::new();
+        module.try_update_params(self, gradients, &mut missing_tensors)?;
+        if missing_tensors.is_empty() {
+            Ok(())
+        } else {
+            Err(Error::UnusedTensors(missing_tensors))
+        }
+    }
+}
+
+#[cfg(feature = "safetensors")]
+impl<M: UpdateParams<E, D>, E: Dtype, D: Device<E>> super::OptimizerSafeTensors<M, E, D>
+    for Adafactor<M, E, D>
+{
+    fn write_safetensors(
+        &self,
+        model: &M,
+        location: &str,
+        tensors: &mut Vec<(String, safetensors::Dtype, Vec<usize>, Vec<u8>)>,
+    ) {
+        crate::nn::SaveSafeTensors::write_safetensors(&self.t, &format!("{location}t"), tensors);
+        super::state::write_flat_state(
+            model,
+            &self.exp_avg_sq,
+            &format!("{location}exp_avg_sq."),
+            tensors,
+        );
+    }
+
+    fn read_safetensors(
+        &mut self,
+        model: &M,
+        location: &str,
+        tensors: &safetensors::SafeTensors,
+    ) -> Result<(), safetensors::SafeTensorError> {
+        crate::nn::LoadSafeTensors::read_safetensors(
+            &mut self.t,
+            &format!("{location}t"),
+            tensors,
+        )?;
+        super::state::read_flat_state(
+            model,
+            &mut self.exp_avg_sq,
+            &format!("{location}exp_avg_sq."),
+            tensors,
+            AdafactorConfig::exp_avg_sq_len,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{nn::optim::*, shapes::*, tensor::*, tensor_ops::*, tests::*};
+
+    fn test_matches_expected<const N: usize>(cfg: AdafactorConfig, expected: [[f64; 5]; N]) {
+        let dev: TestDevice = Default::default();
+        let mut t = dev
+            .tensor([-0.5, -0.25, 0.1, 0.6, 1.0])
+            .to_dtype::<TestDtype>();
+        let mut opt = Adafactor::new(&t, cfg);
+        for e in expected.iter() {
+            let gradients = t.leaky_trace().exp().square().mean().backward();
+            opt.update(&mut t, &gradients).expect("");
+            assert_close_to_literal!(t, e);
+        }
+    }
+
+    #[test]
+    fn test_adafactor_default() {
+        let cfg = AdafactorConfig::default();
+        #[rustfmt::skip]
+        const EXPECTED: [[f64; 5]; 5] = [
+            [-0.50580086, -0.25580086, 0.094199138, 0.59419914, 0.99419914],
+            [-0.51155431, -0.26155431, 0.088445689, 0.58844569, 0.98844569],
+            [-0.51726271, -0.26726271, 0.082737287, 0.58273729, 0.98273729],
+            [-0.52292804, -0.27292804, 0.077071961, 0.57707196, 0.97707196],
+            [-0.52855202, -0.27855202, 0.07144798, 0.57144798, 0.97144798],
+        ];
+        test_matches_expected(cfg, EXPECTED);
+    }
+    #[test]
+    fn test_adafactor_custom() {
+        let cfg = AdafactorConfig {
+            lr: 1.0,
+            beta2_decay: -0.5,
+            eps: [1e-30, 1e-2],
+            clip_threshold: 0.5,
+            weight_decay: None,
+        };
+        #[rustfmt::skip]
+        const EXPECTED: [[f64; 5]; 5] = [
+            [-0.7900431, -0.5400431, -0.1900431, 0.3099569, 0.7099569],
+            [-0.98702299, -0.73702299, -0.38702299, 0.11297701, 0.51297701],
+            [-1.1669819, -0.91698194, -0.56698194, -0.066981936, 0.33301806],
+            [-1.3486258, -1.0986258, -0.74862578, -0.24862578, 0.15137422],
+            [-1.5402227, -1.2902227, -0.94022274, -0.44022274, -0.04022274],
+        ];
+        test_matches_expected(cfg, EXPECTED);
+    }
+    #[test]
+    fn test_adafactor_l2_weight_decay() {
+        let cfg = AdafactorConfig {
+            weight_decay: Some(WeightDecay::L2(0.5)),
+            ..Default::default()
+        };
+        #[rustfmt::skip]
+        const EXPECTED: [[f64; 5]; 5] = [
+            [-0.49419914, -0.25580086, 0.094199138, 0.59419914, 0.99419914],
+            [-0.48855161, -0.26143893, 0.088476524, 0.58846483, 0.98846362],
+            [-0.48305389, -0.26691937, 0.082828888, 0.58279435, 0.98279075],
+            [-0.47770392, -0.27224567, 0.07725353, 0.57718541, 0.97717828],
+            [-0.47250053, -0.27742033, 0.071748105, 0.57163597, 0.97162417],
+        ];
+        test_matches_expected(cfg, EXPECTED);
+    }
+    #[test]
+    fn test_adafactor_decoupled_weight_decay() {
+        let cfg = AdafactorConfig {
+            weight_decay: Some(WeightDecay::Decoupled(0.5)),
+            ..Default::default()
+        };
+        #[rustfmt::skip]
+        const EXPECTED: [[f64; 5]; 5] = [
+            [-0.50330086, -0.25455086, 0.093699138, 0.59119914, 0.98919914],
+            [-0.50652127, -0.25900886, 0.087508523, 0.5825334, 0.97855333],
+            [-0.50966425, -0.26337753, 0.081423929, 0.57399754, 0.96805652],
+            [-0.51273249, -0.26765996, 0.075441668, 0.56558705, 0.95770351],
+            [-0.51572848, -0.27185901, 0.069558406, 0.55729787, 0.94748969],
+        ];
+        test_matches_expected(cfg, EXPECTED);
+    }
+
+    #[test]
+    fn test_adafactor_factored() {
+        let dev: TestDevice = Default::default();
+        let mut t = dev
+            .tensor([[-0.5, -0.25, 0.1], [0.6, 1.0, 0.3]])
+            .to_dtype::<TestDtype>();
+        let mut opt = Adafactor::new(&t, Default::default());
+        #[rustfmt::skip]
+        const EXPECTED: [[[f64; 3]; 2]; 5] = [
+            [[-0.50230067, -0.25170906, 0.088368085], [0.59646786, 0.99645815, 0.29704808]],
+            [[-0.50462557, -0.25343819, 0.076803484], [0.59292998, 0.99291057, 0.29408086]],
+            [[-0.50697472, -0.25518746, 0.065304783], [0.58938676, 0.98935765, 0.29109866]],
+            [[-0.50934818, -0.25695699, 0.053870736], [0.58583835, 0.98579955, 0.28810168]],
+            [[-0.51174603, -0.25874688, 0.042500191], [0.58228478, 0.98223629, 0.28509001]],
+        ];
+        for e in EXPECTED.iter() {
+            let gradients = t.leaky_trace().exp().square().mean().backward();
+            opt.update(&mut t, &gradients).expect("");
+            assert_close_to_literal!(t, e);
+        }
+        // only the averages of the 2 rows & 3 columns are kept
+        assert_eq!(opt.exp_avg_sq[&t.id()].shape(), &(5,));
+    }
+
+    #[test]
+    fn test_unused_tensors() {
+        let dev: TestDevice = Default::default();
+        let mut t: Tensor<Rank1<5>, TestDtype, _> = dev.sample_normal();
+        let mut opt = Adafactor::new(&t, Default::default());
+        opt.update(&mut t, &Gradients::leaky()).expect_err("");
+    }
+}
@@ -0,0 +1,245 @@
+use std::marker::PhantomData;
+
+use super::param_groups::ParamGroups;
+use crate::{
+    nn::{UpdateParams, WithGrads},
+    shapes::{Dtype, Shape},
+    tensor::{Error, Gradients, Storage, Tensor, Tensorlike, UniqueId},
+    tensor_ops::{AdaGradConfig, Device, TryAdd},
+};
+
+/// An implementation of the AdaGrad optimizer from
+/// [Adaptive Subgradient Methods for Online Learning and Stochastic Optimization](https://jmlr.org/papers/v12/duchi11a.html)
+///
+/// # Example Usage
+/// ```rust
+/// # use dfdx::prelude::*;
+/// # type Model = Tensor<Rank0, f32, Cpu>;
+/// # let dev: Cpu = Default::default();
+/// # let model: Model = dev.zeros();
+/// let mut opt: AdaGrad<Model, f32, Cpu> = optim::AdaGrad::new(&model, AdaGradConfig {
+///     lr: 1e-2,
+///     lr_decay: 1e-4,
+///     initial_accumulator_value: 0.1,
+///     eps: 1e-10,
+///     weight_decay: Some(WeightDecay::L2(1e-2)),
+/// });
+/// ```
+///
+/// See module level documentation at [crate::nn::optim] for examples of how to actually use an optimizer.
+#[derive(Debug, Clone)]
+pub struct AdaGrad<M, E: Dtype, D: Storage<E>> {
+    /// Hyperparameter configuration
+    pub cfg: AdaGradConfig,
+
+    t: i32,
+    sum: Gradients<E, D>,
+
+    param_groups: ParamGroups<AdaGradConfig>,
+
+    marker: PhantomData<*const M>,
+}
+
+impl<M, E: Dtype, D: Storage<E>> AdaGrad<M, E, D> {
+    /// Constructs using hyperparameters from `cfg`.
+    pub fn new(_model: &M, cfg: AdaGradConfig) -> Self {
+        Self {
+            cfg,
+            t: 0,
+            sum: Gradients::leaky(),
+            param_groups: Default::default(),
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<M, E: Dtype, D: Device<E>> AdaGrad<M, E, D> {
+    /// Uses `cfg` instead of [AdaGrad::cfg] for the parameters of `model` that `select` returns
+    /// `true` for, given their path & shape. See [Adam::add_param_group()] for details.
+    ///
+    /// [Adam::add_param_group()]: crate::nn::optim::Adam::add_param_group()
+    pub fn add_param_group<F>(&mut self, model: &M, select: F, cfg: AdaGradConfig)
+    where
+        M: UpdateParams<E, D>,
+        F: FnMut(&str, &[usize]) -> bool,
+    {
+        self.param_groups
+            .add(model, select, cfg, self.cfg.lr, cfg.lr);
+    }
+}
+
+impl<M, E: Dtype, D: Storage<E>> super::LearningRate for AdaGrad<M, E, D> {
+    fn lr(&self) -> f64 {
+        self.cfg.lr
+    }
+    fn set_lr(&mut self, lr: f64) {
+        self.cfg.lr = lr;
+        self.param_groups.set_lr(lr, |cfg| &mut cfg.lr);
+    }
+}
+
+impl<M, E: Dtype, D: Device<E>> crate::nn::Optimizer<M, E, D> for AdaGrad<M, E, D> {
+    fn update_tensor<S: Shape>(
+        &mut self,
+        t: &mut Tensor<S, E, D>,
+        gradients: &Gradients<E, D>,
+        missing_params: &mut Vec<UniqueId>,
+    ) -> Result<(), Error> {
+        let g = gradients.get_ref_checked(t);
+        match g {
+            None => missing_params.push(t.id()),
+            Some(g) => {
+                let cfg = self.param_groups.cfg(&t.id(), &self.cfg);
+                if self.sum.get_ref_checked(t).is_none() {
+                    self.sum.get_or_alloc_mut(t)?;
+                    let init = cfg.initial_accumulator_value;
+                    t.try_grads_map(&mut self.sum, &mut |sum| sum.try_add(init))?;
+                }
+                let sum = self.sum.get_or_alloc_mut(t)?;
+                cfg.try_update(self.t, t, sum, g)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn update(&mut self, module: &mut M, gradients: &Gradients<E, D>) -> Result<(), Error>
+    where
+        M: crate::nn::UpdateParams<E, D>,
+    {
+        self.t = self.t.checked_add(1).unwrap();
+
+        // NOTE: the rest of this is identical to default implementation of update.
+        let mut missing_tensors = Vec::new();
+        module.try_update_params(self, gradients, &mut missing_tensors)?;
+        if missing_tensors.is_empty() {
+            Ok(())
+        } else {
+            Err(Error::UnusedTensors(missing_tensors))
+        }
+    }
+}
+
+#[cfg(feature = "safetensors")]
+impl<M: UpdateParams<E, D>, E: Dtype, D: Device<E>> super::OptimizerSafeTensors<M, E, D>
+    for AdaGrad<M, E, D>
+{
+    fn write_safetensors(
+        &self,
+        model: &M,
+        location: &str,
+        tensors: &mut Vec<(String, safetensors::Dtype, Vec<usize>, Vec<u8>)>,
+    ) {
+        crate::nn::SaveSafeTensors::write_safetensors(&self.t, &format!("{location}t"), tensors);
+        super::state::write_state(model, &self.sum, &format!("{location}sum."), tensors);
+    }
+
+    fn read_safetensors(
+        &mut self,
+        model: &M,
+        location: &str,
+        tensors: &safetensors::SafeTensors,
+    ) -> Result<(), safetensors::SafeTensorError> {
+        crate::nn::LoadSafeTensors::read_safetensors(
+            &mut self.t,
+            &format!("{location}t"),
+            tensors,
+        )?;
+        super::state::read_state(model, &mut self.sum, &format!("{location}sum."), tensors)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{nn::optim::*, shapes::*, tensor::*, tensor_ops::*, tests::*};
+
+    fn test_matches_expected<const N: usize>(cfg: AdaGradConfig, expected: [[f64; 5]; N]) {
+        let dev: TestDevice = Default::default();
+        let mut t = dev
+            .tensor([-0.5, -0.25, 0.1, 0.6, 1.0])
+            .to_dtype::<TestDtype>();
+        let mut opt = AdaGrad::new(&t, cfg);
+        for e in expected.iter() {
+            let gradients = t.leaky_trace().exp().square().mean().backward();
+            opt.update(&mut t, &gradients).expect("");
+            assert_close_to_literal!(t, e);
+        }
+    }
+
+    #[test]
+    fn test_adagrad_default() {
+        let cfg = AdaGradConfig::default();
+        #[rustfmt::skip]
+        const EXPECTED: [[f64; 5]; 5] = [
+            [-0.51, -0.26, 0.09, 0.59, 0.99],
+            [-0.51700001, -0.26700001, 0.08299999, 0.58299999, 0.98299999],
+            [-0.52268076, -0.27268076, 0.077319237, 0.57731924, 0.97731924],
+            [-0.5275778, -0.2775778, 0.072422204, 0.5724222, 0.9724222],
+            [-0.53194113, -0.28194113, 0.068058872, 0.56805887, 0.96805887],
+        ];
+        test_matches_expected(cfg, EXPECTED);
+    }
+    #[test]
+    fn test_adagrad_lr_decay() {
+        let cfg = AdaGradConfig {
+            lr: 1e-1,
+            lr_decay: 0.5,
+            initial_accumulator_value: 0.1,
+            ..Default::default()
+        };
+        #[rustfmt::skip]
+        const EXPECTED: [[f64; 5]; 5] = [
+            [-0.54218936, -0.31087017, 0.016050822, 0.50271981, 0.9005675],
+            [-0.56629123, -0.34249819, -0.022534565, 0.46104787, 0.85844994],
+            [-0.58257753, -0.36283926, -0.046148426, 0.43612124, 0.83334053],
+            [-0.59460536, -0.37739257, -0.062577907, 0.41897995, 0.81610197],
+            [-0.60399415, -0.38849927, -0.074889738, 0.40622708, 0.80328954],
+        ];
+        test_matches_expected(cfg, EXPECTED);
+    }
+    #[test]
+    fn test_adagrad_l2_weight_decay() {
+        let cfg = AdaGradConfig {
+            weight_decay: Some(WeightDecay::L2(0.5)),
+            ..Default::default()
+        };
+        #[rustfmt::skip]
+        const EXPECTED: [[f64; 5]; 5] = [
+            [-0.49, -0.26, 0.09, 0.59, 0.99],
+            [-0.48321956, -0.26675714, 0.08302726, 0.58299789, 0.98299483],
+            [-0.47783401, -0.27211488, 0.077382691, 0.57731453, 0.97730739],
+            [-0.47327369, -0.27664655, 0.07252642, 0.57241472, 0.97240298],
+            [-0.46927504, -0.28061694, 0.068206732, 0.56804857, 0.96803187],
+        ];
+        test_matches_expected(cfg, EXPECTED);
+    }
+    #[test]
+    fn test_adagrad_decoupled_weight_decay() {
+        // pytorch's AdaGrad has no decoupled weight decay, so the expected values are computed
+        // here instead: the AdamW style decay `p -= lr * wd * p` next to the plain update.
+        let (lr, wd) = (1e-2, 0.5);
+        let mut p: [f64; 5] = [-0.5, -0.25, 0.1, 0.6, 1.0];
+        let mut sum = [0.0; 5];
+        let expected: [[f64; 5]; 5] = std::array::from_fn(|_| {
+            for (p, sum) in p.iter_mut().zip(sum.iter_mut()) {
+                let g = 2.0 * (2.0 * *p).exp() / 5.0;
+                *sum += g * g;
+                *p -= lr * wd * *p + lr * g / (sum.sqrt() + 1e-10);
+            }
+            p
+        });
+        let cfg = AdaGradConfig {
+            weight_decay: Some(WeightDecay::Decoupled(wd)),
+            ..Default::default()
+        };
+        test_matches_expected(cfg, expected);
+    }
+
+    #[test]
+    fn test_unused_tensors() {
+        let dev: TestDevice = Default::default();
+        let mut t: Tensor<Rank1<5>, TestDtype, _> = dev.sample_normal();
+        let mut opt = AdaGrad::new(&t, Default::default());
+        opt.update(&mut t, &Gradients::leaky()).expect_err("");
+    }
+}
@@ -1,6 +1,8 @@
 use std::marker::PhantomData;
 
+use super::param_groups::ParamGroups;
 use crate::{
+    nn::UpdateParams,
     shapes::{Dtype, Shape},
     tensor::{Error, Gradients, Storage, Tensor, Tensorlike, UniqueId},
     tensor_ops::{AdamConfig, Device},
@@ -33,6 +35,8 @@ pub struct Adam<M, E: Dtype, D: Storage<E>> {
     moment1: Gradients<E, D>,
     moment2: Gradients<E, D>,
 
+    param_groups: ParamGroups<AdamConfig>,
+
     marker: PhantomData<*const M>,
 }
 
@@ -44,11 +48,64 @@ impl<M, E: Dtype, D: Storage<E>> Adam<M, E, D> {
             t: 0,
             moment1: Gradients::leaky(),
             moment2: Gradients::leaky(),
+            param_groups: Default::default(),
             marker: PhantomData,
         }
     }
 }
 
+impl<M, E: Dtype, D: Device<E>> Adam<M, E, D> {
+    /// Adds a parameter group: uses `cfg` instead of [Adam::cfg] for the parameters of `model`
+    /// that `select` returns `true` for, given their path & shape. Use [with_path()] to select
+    /// parameters by their path, see [UpdateParams::visit_params()] for how paths are built.
+    ///
+    /// If a parameter is selected by multiple groups, the group added last is used.
+    /// When the learning rate is changed with [LearningRate::set_lr()] (e.g. by a scheduler),
+    /// the learning rate of each group is changed by the same factor, unless the learning rate
+    /// of the optimizer was 0 when the group was added.
+    ///
+    /// ```rust
+    /// # use dfdx::prelude::*;
+    /// # let dev: Cpu = Default::default();
+    /// type Model = (LinearConstConfig<5, 5>, LayerNorm1DConstConfig<5>, LinearConstConfig<5, 2>);
+    /// let model = dev.build_module::<f32>(Model::default());
+    /// let cfg = AdamConfig {
+    ///     weight_decay: Some(WeightDecay::Decoupled(1e-2)),
+    ///     ..Default::default()
+    /// };
+    /// let mut opt = Adam::new(&model, cfg);
+    /// // the first layer is pretrained
+    /// opt.add_param_group(&model, with_path("0"), AdamConfig { lr: cfg.lr * 0.1, ..cfg });
+    /// // no weight decay for biases & layer norm
+    /// opt.add_param_group(
+    ///     &model,
+    ///     |path, shape| shape.len() == 1 && !path.starts_with("0."),
+    ///     AdamConfig { weight_decay: None, ..cfg },
+    /// );
+    /// ```
+    ///
+    /// [with_path()]: crate::nn::optim::with_path()
+    /// [LearningRate::set_lr()]: crate::nn::optim::LearningRate::set_lr()
+    pub fn add_param_group<F>(&mut self, model: &M, select: F, cfg: AdamConfig)
+    where
+        M: UpdateParams<E, D>,
+        F: FnMut(&str, &[usize]) -> bool,
+    {
+        self.param_groups
+            .add(model, select, cfg, self.cfg.lr, cfg.lr);
+    }
+}
+
+impl<M, E: Dtype, D: Storage<E>> super::LearningRate for Adam<M, E, D> {
+    fn lr(&self) -> f64 {
+        self.cfg.lr
+    }
+    fn set_lr(&mut self, lr: f64) {
+        self.cfg.lr = lr;
+        self.param_groups.set_lr(lr, |cfg| &mut cfg.lr);
+    }
+}
+
 impl<M, E: Dtype, D: Device<E>> crate::nn::Optimizer<M, E, D> for Adam<M, E, D> {
     fn update_tensor<S: Shape>(
         &mut self,
@@ -62,7 +119,8 @@ impl<M, E: Dtype, D: Device<E>> crate::nn::Optimizer<M, E, D> for Adam<M, E, D>
             Some(g) => {
                 let m_t = self.moment1.get_or_alloc_mut(t)?;
                 let v_t = self.moment2.get_or_alloc_mut(t)?;
-                self.cfg.try_update(self.t, t, m_t, v_t, g)?;
+                let cfg = self.param_groups.cfg(&t.id(), &self.cfg);
+                cfg.try_update(self.t, t, m_t, v_t, g)?;
             }
         }
         Ok(())
@@ -85,6 +143,57 @@ impl<M, E: Dtype, D: Device<E>> crate::nn::Optimizer<M, E, D> for Adam<M, E, D>
     }
 }
 
+#[cfg(feature = "safetensors")]
+impl<M: UpdateParams<E, D>, E: Dtype, D: Device<E>> super::OptimizerSafeTensors<M, E, D>
+    for Adam<M, E, D>
+{
+    fn write_safetensors(
+        &self,
+        model: &M,
+        location: &str,
+        tensors: &mut Vec<(String, safetensors::Dtype, Vec<usize>, Vec<u8>)>,
+    ) {
+        crate::nn::SaveSafeTensors::write_safetensors(&self.t, &format!("{location}t"), tensors);
+        super::state::write_state(
+            model,
+            &self.moment1,
+            &format!("{location}moment1."),
+            tensors,
+        );
+        super::state::write_state(
+            model,
+            &self.moment2,
+            &format!("{location}moment2."),
+            tensors,
+        );
+    }
+
+    fn read_safetensors(
+        &mut self,
+        model: &M,
+        location: &str,
+        tensors: &safetensors::SafeTensors,
+    ) -> Result<(), safetensors::SafeTensorError> {
+        crate::nn::LoadSafeTensors::read_safetensors(
+            &mut self.t,
+            &format!("{location}t"),
+            tensors,
+        )?;
+        super::state::read_state(
+            model,
+            &mut self.moment1,
+            &format!("{location}moment1."),
+            tensors,
+        )?;
+        super::state::read_state(
+            model,
+            &mut self.moment2,
+            &format!("{location}moment2."),
+            tensors,
+        )
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
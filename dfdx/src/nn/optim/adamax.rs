@@ -0,0 +1,265 @@
+use std::marker::PhantomData;
+
+use super::param_groups::ParamGroups;
+use crate::{
+    nn::UpdateParams,
+    shapes::{Dtype, Shape},
+    tensor::{Error, Gradients, Storage, Tensor, Tensorlike, UniqueId},
+    tensor_ops::{AdamaxConfig, Device},
+};
+
+/// An implementation of the Adamax optimizer, the infinity norm variant of Adam from
+/// [Adam: A Method for Stochastic Optimization](https://arxiv.org/abs/1412.6980)
+///
+/// # Example Usage
+/// ```rust
+/// # use dfdx::prelude::*;
+/// # type Model = Tensor<Rank0, f32, Cpu>;
+/// # let dev: Cpu = Default::default();
+/// # let model: Model = dev.zeros();
+/// let mut opt: Adamax<Model, f32, Cpu> = optim::Adamax::new(&model, AdamaxConfig {
+///     lr: 2e-3,
+///     betas: [0.9, 0.999],
+///     eps: 1e-8,
+///     weight_decay: Some(WeightDecay::Decoupled(1e-2)),
+/// });
+/// ```
+///
+/// See module level documentation at [crate::nn::optim] for examples of how to actually use an optimizer.
+#[derive(Debug, Clone)]
+pub struct Adamax<M, E: Dtype, D: Storage<E>> {
+    /// Hyperparameter configuration
+    pub cfg: AdamaxConfig,
+
+    t: i32,
+    exp_avg: Gradients<E, D>,
+    exp_inf: Gradients<E, D>,
+
+    param_groups: ParamGroups<AdamaxConfig>,
+
+    marker: PhantomData<*const M>,
+}
+
+impl<M, E: Dtype, D: Storage<E>> Adamax<M, E, D> {
+    /// Constructs using hyperparameters from `cfg`.
+    pub fn new(_model: &M, cfg: AdamaxConfig) -> Self {
+        Self {
+            cfg,
+            t: 0,
+            exp_avg: Gradients::leaky(),
+            exp_inf: Gradients::leaky(),
+            param_groups: Default::default(),
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<M, E: Dtype, D: Device<E>> Adamax<M, E, D> {
+    /// Uses `cfg` instead of [Adamax::cfg] for the parameters of `model` that `select` returns
+    /// `true` for, given their path & shape. See [Adam::add_param_group()] for details.
+    ///
+    /// [Adam::add_param_group()]: crate::nn::optim::Adam::add_param_group()
+    pub fn add_param_group<F>(&mut self, model: &M, select: F, cfg: AdamaxConfig)
+    where
+        M: UpdateParams<E, D>,
+        F: FnMut(&str, &[usize]) -> bool,
+    {
+        self.param_groups
+            .add(model, select, cfg, self.cfg.lr, cfg.lr);
+    }
+}
+
+impl<M, E: Dtype, D: Storage<E>> super::LearningRate for Adamax<M, E, D> {
+    fn lr(&self) -> f64 {
+        self.cfg.lr
+    }
+    fn set_lr(&mut self, lr: f64) {
+        self.cfg.lr = lr;
+        self.param_groups.set_lr(lr, |cfg| &mut cfg.lr);
+    }
+}
+
+impl<M, E: Dtype, D: Device<E>> crate::nn::Optimizer<M, E, D> for Adamax<M, E, D> {
+    fn update_tensor<S: Shape>(
+        &mut self,
+        t: &mut Tensor<S, E, D>,
+        gradients: &Gradients<E, D>,
+        missing_params: &mut Vec<UniqueId>,
+    ) -> Result<(), Error> {
+        let g = gradients.get_ref_checked(t);
+        match g {
+            None => missing_params.push(t.id()),
+            Some(g) => {
+                let m = self.exp_avg.get_or_alloc_mut(t)?;
+                let u = self.exp_inf.get_or_alloc_mut(t)?;
+                let cfg = self.param_groups.cfg(&t.id(), &self.cfg);
+                cfg.try_update(self.t, t, m, u, g)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn update(&mut self, module: &mut M, gradients: &Gradients<E, D>) -> Result<(), Error>
+    where
+        M: crate::nn::UpdateParams<E, D>,
+    {
+        self.t = self.t.checked_add(1).unwrap();
+
+        // NOTE: the rest of this is identical to default implementation of update.
+        let mut missing_tensors = Vec::new();
+        module.try_update_params(self, gradients, &mut missing_tensors)?;
+        if missing_tensors.is_empty() {
+            Ok(())
+        } else {
+            Err(Error::UnusedTensors(missing_tensors))
+        }
+    }
+}
+
+#[cfg(feature = "safetensors")]
+impl<M: UpdateParams<E, D>, E: Dtype, D: Device<E>> super::OptimizerSafeTensors<M, E, D>
+    for Adamax<M, E, D>
+{
+    fn write_safetensors(
+        &self,
+        model: &M,
+        location: &str,
+        tensors: &mut Vec<(String, safetensors::Dtype, Vec<usize>, Vec<u8>)>,
+    ) {
+        crate::nn::SaveSafeTensors::write_safetensors(&self.t, &format!("{location}t"), tensors);
+        super::state::write_state(
+            model,
+            &self.exp_avg,
+            &format!("{location}exp_avg."),
+            tensors,
+        );
+        super::state::write_state(
+            model,
+            &self.exp_inf,
+            &format!("{location}exp_inf."),
+            tensors,
+        );
+    }
+
+    fn read_safetensors(
+        &mut self,
+        model: &M,
+        location: &str,
+        tensors: &safetensors::SafeTensors,
+    ) -> Result<(), safetensors::SafeTensorError> {
+        crate::nn::LoadSafeTensors::read_safetensors(
+            &mut self.t,
+            &format!("{location}t"),
+            tensors,
+        )?;
+        super::state::read_state(
+            model,
+            &mut self.exp_avg,
+            &format!("{location}exp_avg."),
+            tensors,
+        )?;
+        super::state::read_state(
+            model,
+            &mut self.exp_inf,
+            &format!("{location}exp_inf."),
+            tensors,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{nn::optim::*, shapes::*, tensor::*, tensor_ops::*, tests::*};
+
+    fn test_matches_expected<const N: usize>(cfg: AdamaxConfig, expected: [[f64; 5]; N]) {
+        let dev: TestDevice = Default::default();
+        let mut t = dev
+            .tensor([-0.5, -0.25, 0.1, 0.6, 1.0])
+            .to_dtype::<TestDtype>();
+        let mut opt = Adamax::new(&t, cfg);
+        for e in expected.iter() {
+            let gradients = t.leaky_trace().exp().square().mean().backward();
+            opt.update(&mut t, &gradients).expect("");
+            assert_close_to_literal!(t, e);
+        }
+    }
+
+    #[test]
+    fn test_adamax_default() {
+        let cfg = AdamaxConfig::default();
+        #[rustfmt::skip]
+        const EXPECTED: [[f64; 5]; 5] = [
+            [-0.502, -0.252, 0.098, 0.598, 0.998],
+            [-0.5039978, -0.2539978, 0.096002204, 0.5960022, 0.9960022],
+            [-0.50599326, -0.25599326, 0.094006744, 0.59400674, 0.99400674],
+            [-0.50798625, -0.25798625, 0.092013749, 0.59201375, 0.99201375],
+            [-0.50997665, -0.25997665, 0.090023348, 0.59002335, 0.99002335],
+        ];
+        test_matches_expected(cfg, EXPECTED);
+    }
+    #[test]
+    fn test_adamax_custom() {
+        let cfg = AdamaxConfig {
+            lr: 1e-2,
+            betas: [0.5, 0.25],
+            eps: 1e-8,
+            weight_decay: None,
+        };
+        #[rustfmt::skip]
+        const EXPECTED: [[f64; 5]; 5] = [
+            [-0.51, -0.26, 0.09, 0.59, 0.99],
+            [-0.52006734, -0.27006734, 0.079932663, 0.57993266, 0.97993266],
+            [-0.53018395, -0.28018395, 0.069816051, 0.56981605, 0.96981605],
+            [-0.54033486, -0.29033486, 0.059665136, 0.55966514, 0.95966514],
+            [-0.55050862, -0.30050862, 0.049491377, 0.54949138, 0.94949138],
+        ];
+        test_matches_expected(cfg, EXPECTED);
+    }
+    #[test]
+    fn test_adamax_l2_weight_decay() {
+        let cfg = AdamaxConfig {
+            weight_decay: Some(WeightDecay::L2(0.5)),
+            ..Default::default()
+        };
+        #[rustfmt::skip]
+        const EXPECTED: [[f64; 5]; 5] = [
+            [-0.498, -0.252, 0.098, 0.598, 0.998],
+            [-0.49601429, -0.25398437, 0.09600377, 0.59600208, 0.9960019],
+            [-0.49404336, -0.2559526, 0.094011494, 0.59400636, 0.99400582],
+            [-0.49208773, -0.25790421, 0.092023352, 0.59201297, 0.99201189],
+            [-0.49014788, -0.25983874, 0.090039524, 0.59002205, 0.99002023],
+        ];
+        test_matches_expected(cfg, EXPECTED);
+    }
+    #[test]
+    fn test_adamax_decoupled_weight_decay() {
+        // pytorch's Adamax has no decoupled weight decay, so the expected values are computed
+        // here instead: the AdamW style decay `p -= lr * wd * p` next to the plain update.
+        let (lr, wd) = (2e-3, 0.5);
+        let mut p: [f64; 5] = [-0.5, -0.25, 0.1, 0.6, 1.0];
+        let (mut exp_avg, mut exp_inf) = ([0.0; 5], [0.0; 5]);
+        let expected: [[f64; 5]; 5] = std::array::from_fn(|i| {
+            for (p, (m, u)) in p.iter_mut().zip(exp_avg.iter_mut().zip(exp_inf.iter_mut())) {
+                let g = 2.0 * (2.0 * *p).exp() / 5.0;
+                *m = 0.9 * *m + 0.1 * g;
+                *u = (0.999 * *u).max(g.abs() + 1e-8);
+                *p -= lr * wd * *p + lr / (1.0 - 0.9f64.powi(i as i32 + 1)) * *m / *u;
+            }
+            p
+        });
+        let cfg = AdamaxConfig {
+            weight_decay: Some(WeightDecay::Decoupled(wd)),
+            ..Default::default()
+        };
+        test_matches_expected(cfg, expected);
+    }
+
+    #[test]
+    fn test_unused_tensors() {
+        let dev: TestDevice = Default::default();
+        let mut t: Tensor<Rank1<5>, TestDtype, _> = dev.sample_normal();
+        let mut opt = Adamax::new(&t, Default::default());
+        opt.update(&mut t, &Gradients::leaky()).expect_err("");
+    }
+}
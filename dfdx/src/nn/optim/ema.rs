@@ -0,0 +1,399 @@
+use std::collections::HashMap;
+
+use crate::{
+    nn::{Optimizer, UpdateParams},
+    shapes::{Dtype, HasShape, Shape},
+    tensor::{Error, Gradients, Tensor, Tensorlike, UniqueId},
+    tensor_ops::{Device, ReshapeTo},
+};
+
+/// Configuration of [Ema].
+///
+/// Warming up the decay, so the average doesn't hold on to the initial weights for too long:
+/// ```rust
+/// # use dfdx::prelude::*;
+/// EmaConfig {
+///     decay: 0.9999,
+///     warmup: Some(EmaWarmup::Power { gamma: 1.0, power: 0.75 }),
+/// };
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct EmaConfig {
+    /// The weight of the current average in each update. Defaults to `0.999`.
+    pub decay: f64,
+
+    /// Optional warmup of the decay. Defaults to `None`.
+    pub warmup: Option<EmaWarmup>,
+}
+
+impl Default for EmaConfig {
+    fn default() -> Self {
+        Self {
+            decay: 0.999,
+            warmup: None,
+        }
+    }
+}
+
+/// Warmup of [EmaConfig::decay], which lowers the decay of the first updates.
+/// `n` is the number of updates done before.
+#[derive(Debug, Clone, Copy)]
+pub enum EmaWarmup {
+    /// `min(decay, (1 + n) / (10 + n))`
+    NumUpdates,
+
+    /// `min(decay, 1 - (1 + n / gamma) ^ -power)`, where `gamma = 1` and `power = 2/3` or `3/4`
+    /// are typical values.
+    Power { gamma: f64, power: f64 },
+}
+
+/// An exponential moving average (also called Polyak averaging) of the parameters of a model,
+/// which is updated after each optimizer step with
+/// `average = decay * average + (1 - decay) * param`.
+///
+/// ```rust
+/// # use dfdx::prelude::*;
+/// # let dev: Cpu = Default::default();
+/// let mut model = dev.build_module::<f32>(LinearConstConfig::<5, 2>::default());
+/// let mut opt = Sgd::new(&model, Default::default());
+/// let mut ema = Ema::new(&model, Default::default());
+/// # let x: Tensor<Rank1<5>, f32, _> = dev.zeros();
+/// # let grads = model.forward(x.leaky_trace()).sum().backward();
+/// // -- snip loss computation --
+///
+/// opt.update(&mut model, &grads).unwrap();
+/// ema.update(&model);
+///
+/// // evaluate with the averaged parameters, then swap the trained ones back in
+/// ema.swap(&mut model);
+/// # let _ = model.forward(x.clone());
+/// // -- snip evaluation --
+/// ema.swap(&mut model);
+/// ```
+///
+/// The averaged copy is also available as [Ema::model].
+#[derive(Debug, Clone)]
+pub struct Ema<M> {
+    /// Hyperparameter configuration
+    pub cfg: EmaConfig,
+
+    /// The model with the averaged parameters. Its other tensors, such as the running
+    /// statistics of batch norms, are the ones of the model [Ema::new()] was called with.
+    pub model: M,
+
+    num_updates: usize,
+}
+
+impl<M: Clone> Ema<M> {
+    /// Starts the average at the current parameters of `model`.
+    pub fn new(model: &M, cfg: EmaConfig) -> Self {
+        Self {
+            cfg,
+            model: model.clone(),
+            num_updates: 0,
+        }
+    }
+}
+
+impl<M> Ema<M> {
+    /// The decay of the next update, including the warmup.
+    pub fn decay(&self) -> f64 {
+        let n = self.num_updates as f64;
+        let warmup = match self.cfg.warmup {
+            None => return self.cfg.decay,
+            Some(EmaWarmup::NumUpdates) => (1.0 + n) / (10.0 + n),
+            Some(EmaWarmup::Power { gamma, power }) => 1.0 - (1.0 + n / gamma).powf(-power),
+        };
+        self.cfg.decay.min(warmup)
+    }
+
+    /// Moves the average towards the parameters of `model`, see [Ema::try_update()].
+    pub fn update<E: Dtype, D: Device<E>>(&mut self, model: &M)
+    where
+        M: UpdateParams<E, D>,
+    {
+        self.try_update(model).unwrap()
+    }
+
+    /// Moves the average towards the parameters of `model`. `model` must be the module
+    /// [Ema::new()] was called with, as parameters are matched by their id.
+    ///
+    /// Returns [Error::UnusedTensors] with the averaged parameters that `model` doesn't have.
+    pub fn try_update<E: Dtype, D: Device<E>>(&mut self, model: &M) -> Result<(), Error>
+    where
+        M: UpdateParams<E, D>,
+    {
+        let mut step = EmaStep {
+            decay: self.decay(),
+            params: flat_params(model),
+        };
+        let mut missing_params = Vec::new();
+        self.model.try_update_params::<M, _>(
+            &mut step,
+            &Gradients::leaky(),
+            &mut missing_params,
+        )?;
+        if !missing_params.is_empty() {
+            return Err(Error::UnusedTensors(missing_params));
+        }
+        self.num_updates += 1;
+        Ok(())
+    }
+
+    /// Exchanges the parameters of `model` with the averaged ones, see [Ema::try_swap()].
+    pub fn swap<E: Dtype, D: Device<E>>(&mut self, model: &mut M)
+    where
+        M: UpdateParams<E, D>,
+    {
+        self.try_swap(model).unwrap()
+    }
+
+    /// Exchanges the parameters of `model` with the averaged ones, without copying them.
+    /// Swapping again puts the original parameters back. The ids of the parameters don't
+    /// change, so the state of optimizers stays valid.
+    ///
+    /// Returns [Error::UnusedTensors] with the parameters that only one of `model` and
+    /// [Ema::model] has, in which case nothing is swapped.
+    pub fn try_swap<E: Dtype, D: Device<E>>(&mut self, model: &mut M) -> Result<(), Error>
+    where
+        M: UpdateParams<E, D>,
+    {
+        let averaged = flat_params(&self.model);
+        let current = flat_params(model);
+        let mut missing_params: Vec<UniqueId> = averaged
+            .keys()
+            .filter(|id| !current.contains_key(id))
+            .chain(current.keys().filter(|id| !averaged.contains_key(id)))
+            .copied()
+            .collect();
+        if !missing_params.is_empty() {
+            return Err(Error::UnusedTensors(missing_params));
+        }
+        model.try_update_params::<M, _>(
+            &mut Assign(averaged),
+            &Gradients::leaky(),
+            &mut missing_params,
+        )?;
+        self.model.try_update_params::<M, _>(
+            &mut Assign(current),
+            &Gradients::leaky(),
+            &mut missing_params,
+        )?;
+        if missing_params.is_empty() {
+            Ok(())
+        } else {
+            Err(Error::UnusedTensors(missing_params))
+        }
+    }
+}
+
+/// The parameters of `model` by their id, flattened.
+fn flat_params<E: Dtype, D: Device<E>, M: UpdateParams<E, D>>(
+    model: &M,
+) -> HashMap<UniqueId, Tensor<(usize,), E, D>> {
+    let mut params = HashMap::new();
+    model.visit_params("", &mut |_, _, param| {
+        params.insert(param.id(), param.clone());
+    });
+    params
+}
+
+/// Updates each tensor towards the parameter with the same id.
+struct EmaStep<E: Dtype, D: Device<E>> {
+    decay: f64,
+    params: HashMap<UniqueId, Tensor<(usize,), E, D>>,
+}
+
+impl<M, E: Dtype, D: Device<E>> Optimizer<M, E, D> for EmaStep<E, D> {
+    fn update_tensor<S: Shape>(
+        &mut self,
+        t: &mut Tensor<S, E, D>,
+        _gradients: &Gradients<E, D>,
+        missing_params: &mut Vec<UniqueId>,
+    ) -> Result<(), Error> {
+        match self.params.remove(&t.id()) {
+            None => missing_params.push(t.id()),
+            Some(param) => {
+                let param = param.try_reshape_like(t.shape())?;
+                t.try_axpy(self.decay, &param, 1.0 - self.decay)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Replaces each tensor with the parameter with the same id. Reshaping the flattened
+/// parameter back keeps its id and shares its data.
+struct Assign<E: Dtype, D: Device<E>>(HashMap<UniqueId, Tensor<(usize,), E, D>>);
+
+impl<M, E: Dtype, D: Device<E>> Optimizer<M, E, D> for Assign<E, D> {
+    fn update_tensor<S: Shape>(
+        &mut self,
+        t: &mut Tensor<S, E, D>,
+        _gradients: &Gradients<E, D>,
+        missing_params: &mut Vec<UniqueId>,
+    ) -> Result<(), Error> {
+        match self.0.remove(&t.id()) {
+            None => missing_params.push(t.id()),
+            Some(param) => *t = param.try_reshape_like(t.shape())?,
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{prelude::*, tests::*};
+
+    #[test]
+    fn test_ema_update() {
+        let dev: TestDevice = Default::default();
+        let mut model = dev.build_module::<TestDtype>(<Bias1DConstConfig<3>>::default());
+        model.bias = dev.tensor([1.0, 2.0, 3.0]).to_dtype::<TestDtype>();
+        let mut ema = Ema::new(
+            &model,
+            EmaConfig {
+                decay: 0.5,
+                warmup: None,
+            },
+        );
+
+        // parameters are matched by id, so they have to be changed in place
+        let update = dev.tensor([3.0, 2.0, 1.0]).to_dtype::<TestDtype>();
+        model.bias.copy_from(&update.as_vec());
+        ema.update(&model);
+        assert_close_to_literal!(ema.model.bias, [2.0, 2.0, 2.0]);
+        ema.update(&model);
+        assert_close_to_literal!(ema.model.bias, [2.5, 2.0, 1.5]);
+        // the model is untouched
+        assert_close_to_literal!(model.bias, [3.0, 2.0, 1.0]);
+    }
+
+    #[test]
+    fn test_ema_after_optimizer_step() {
+        let dev: TestDevice = Default::default();
+        let mut model = dev.build_module::<TestDtype>(<Bias1DConstConfig<2>>::default());
+        let mut opt = Sgd::new(
+            &model,
+            SgdConfig {
+                lr: 1.0,
+                momentum: None,
+                weight_decay: None,
+            },
+        );
+        let mut ema = Ema::new(
+            &model,
+            EmaConfig {
+                decay: 0.9,
+                warmup: None,
+            },
+        );
+
+        let x: Tensor<Rank1<2>, TestDtype, _> = dev.zeros();
+        for _ in 0..2 {
+            let grads = model.forward(x.leaky_trace()).sum().backward();
+            opt.update(&mut model, &grads).unwrap();
+            ema.update(&model);
+        }
+        assert_close_to_literal!(model.bias, [-2.0; 2]);
+        // 0.9 * (0.9 * 0 + 0.1 * -1) + 0.1 * -2
+        assert_close_to_literal!(ema.model.bias, [-0.29; 2]);
+    }
+
+    #[test]
+    fn test_ema_warmup() {
+        let dev: TestDevice = Default::default();
+        let model = dev.build_module::<TestDtype>(<Bias1DConstConfig<2>>::default());
+        let mut ema = Ema::new(
+            &model,
+            EmaConfig {
+                decay: 0.3,
+                warmup: Some(EmaWarmup::NumUpdates),
+            },
+        );
+        let assert_close = |a: &[f64], b: &[f64]| {
+            for (a, b) in a.iter().zip(b) {
+                assert!((a - b).abs() < 1e-12, "{a} != {b}");
+            }
+        };
+        let mut decays = Vec::new();
+        for _ in 0..4 {
+            decays.push(ema.decay());
+            ema.update(&model);
+        }
+        assert_close(&decays, &[0.1, 2.0 / 11.0, 0.25, 0.3]);
+
+        let mut ema = Ema::new(
+            &model,
+            EmaConfig {
+                decay: 0.9,
+                warmup: Some(EmaWarmup::Power {
+                    gamma: 1.0,
+                    power: 1.0,
+                }),
+            },
+        );
+        let mut decays = Vec::new();
+        for _ in 0..3 {
+            decays.push(ema.decay());
+            ema.update(&model);
+        }
+        assert_close(&decays, &[0.0, 0.5, 2.0 / 3.0]);
+    }
+
+    #[test]
+    fn test_ema_swap() {
+        let dev: TestDevice = Default::default();
+        type Model = (LinearConstConfig<2, 3>, BatchNorm1DConstConfig<3>);
+        let mut model = dev.build_module::<TestDtype>(Model::default());
+        let mut ema = Ema::new(
+            &model,
+            EmaConfig {
+                decay: 0.0,
+                warmup: None,
+            },
+        );
+        let original = model.clone();
+        model.0.weight.axpy(1.0, &dev.ones(), 1.0);
+        model.1.running_mean = dev.ones();
+        let trained = model.clone();
+        let ids = (model.0.weight.id(), model.0.bias.id());
+        ema.update(&model);
+        ema.update(&original);
+
+        ema.swap(&mut model);
+        assert_eq!(model.0.weight.array(), original.0.weight.array());
+        assert_eq!(ema.model.0.weight.array(), trained.0.weight.array());
+        assert_eq!((model.0.weight.id(), model.0.bias.id()), ids);
+        // only parameters are swapped
+        assert_close_to_literal!(model.1.running_mean, [1.0; 3]);
+
+        ema.swap(&mut model);
+        assert_eq!(model.0.weight.array(), trained.0.weight.array());
+        assert_eq!(ema.model.0.weight.array(), original.0.weight.array());
+    }
+
+    #[test]
+    fn test_ema_other_model() {
+        let dev: TestDevice = Default::default();
+        let mut model = dev.build_module::<TestDtype>(<Bias1DConstConfig<2>>::default());
+        let mut ema = Ema::new(&model, Default::default());
+
+        // a model built separately has parameters with other ids
+        let mut other = dev.build_module::<TestDtype>(<Bias1DConstConfig<2>>::default());
+        other.bias = dev.ones();
+        assert!(matches!(
+            ema.try_update(&other),
+            Err(Error::UnusedTensors(ids)) if ids == [model.bias.id()]
+        ));
+        assert!(matches!(
+            ema.try_swap(&mut other),
+            Err(Error::UnusedTensors(_))
+        ));
+        assert_close_to_literal!(other.bias, [1.0; 2]);
+        assert_close_to_literal!(ema.model.bias, [0.0; 2]);
+
+        ema.try_swap(&mut model).unwrap();
+    }
+}
@@ -0,0 +1,264 @@
+use std::marker::PhantomData;
+
+use super::param_groups::ParamGroups;
+use crate::{
+    nn::UpdateParams,
+    shapes::{Dtype, Shape},
+    tensor::{Error, Gradients, Storage, Tensor, Tensorlike, UniqueId},
+    tensor_ops::{Device, LambConfig},
+};
+
+/// An implementation of the LAMB optimizer from
+/// [Large Batch Optimization for Deep Learning: Training BERT in 76 minutes](https://arxiv.org/abs/1904.00962)
+///
+/// The Adam update of each tensor is scaled by the trust ratio `|param| / |update|` of the
+/// whole tensor, optionally clipped by [LambConfig::max_trust_ratio].
+///
+/// # Example Usage
+/// ```rust
+/// # use dfdx::prelude::*;
+/// # type Model = Tensor<Rank0, f32, Cpu>;
+/// # let dev: Cpu = Default::default();
+/// # let model: Model = dev.zeros();
+/// let mut opt: Lamb<Model, f32, Cpu> = optim::Lamb::new(&model, LambConfig {
+///     lr: 1e-2,
+///     betas: [0.9, 0.999],
+///     eps: 1e-6,
+///     weight_decay: Some(WeightDecay::Decoupled(1e-2)),
+///     max_trust_ratio: Some(10.0),
+/// });
+/// ```
+///
+/// See module level documentation at [crate::nn::optim] for examples of how to actually use an optimizer.
+#[derive(Debug, Clone)]
+pub struct Lamb<M, E: Dtype, D: Storage<E>> {
+    /// Hyperparameter configuration
+    pub cfg: LambConfig,
+
+    t: i32,
+    moment1: Gradients<E, D>,
+    moment2: Gradients<E, D>,
+
+    param_groups: ParamGroups<LambConfig>,
+
+    marker: PhantomData<*const M>,
+}
+
+impl<M, E: Dtype, D: Storage<E>> Lamb<M, E, D> {
+    /// Constructs using hyperparameters from `cfg`.
+    pub fn new(_model: &M, cfg: LambConfig) -> Self {
+        Self {
+            cfg,
+            t: 0,
+            moment1: Gradients::leaky(),
+            moment2: Gradients::leaky(),
+            param_groups: Default::default(),
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<M, E: Dtype, D: Device<E>> Lamb<M, E, D> {
+    /// Uses `cfg` instead of [Lamb::cfg] for the parameters of `model` that `select` returns
+    /// `true` for, given their path & shape. See [Adam::add_param_group()] for details.
+    ///
+    /// [Adam::add_param_group()]: crate::nn::optim::Adam::add_param_group()
+    pub fn add_param_group<F>(&mut self, model: &M, select: F, cfg: LambConfig)
+    where
+        M: UpdateParams<E, D>,
+        F: FnMut(&str, &[usize]) -> bool,
+    {
+        self.param_groups
+            .add(model, select, cfg, self.cfg.lr, cfg.lr);
+    }
+}
+
+impl<M, E: Dtype, D: Storage<E>> super::LearningRate for Lamb<M, E, D> {
+    fn lr(&self) -> f64 {
+        self.cfg.lr
+    }
+    fn set_lr(&mut self, lr: f64) {
+        self.cfg.lr = lr;
+        self.param_groups.set_lr(lr, |cfg| &mut cfg.lr);
+    }
+}
+
+impl<M, E: Dtype, D: Device<E>> crate::nn::Optimizer<M, E, D> for Lamb<M, E, D> {
+    fn update_tensor<S: Shape>(
+        &mut self,
+        t: &mut Tensor<S, E, D>,
+        gradients: &Gradients<E, D>,
+        missing_params: &mut Vec<UniqueId>,
+    ) -> Result<(), Error> {
+        let g = gradients.get_ref_checked(t);
+        match g {
+            None => missing_params.push(t.id()),
+            Some(g) => {
+                let m_t = self.moment1.get_or_alloc_mut(t)?;
+                let v_t = self.moment2.get_or_alloc_mut(t)?;
+                let cfg = self.param_groups.cfg(&t.id(), &self.cfg);
+                cfg.try_update(self.t, t, m_t, v_t, g)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn update(&mut self, module: &mut M, gradients: &Gradients<E, D>) -> Result<(), Error>
+    where
+        M: crate::nn::UpdateParams<E, D>,
+    {
+        self.t = self.t.checked_add(1).unwrap();
+
+        // NOTE: the rest of this is identical to default implementation of update.
+        let mut missing_tensors = Vec::new();
+        module.try_update_params(self, gradients, &mut missing_tensors)?;
+        if missing_tensors.is_empty() {
+            Ok(())
+        } else {
+            Err(Error::UnusedTensors(missing_tensors))
+        }
+    }
+}
+
+#[cfg(feature = "safetensors")]
+impl<M: UpdateParams<E, D>, E: Dtype, D: Device<E>> super::OptimizerSafeTensors<M, E, D>
+    for Lamb<M, E, D>
+{
+    fn write_safetensors(
+        &self,
+        model: &M,
+        location: &str,
+        tensors: &mut Vec<(String, safetensors::Dtype, Vec<usize>, Vec<u8>)>,
+    ) {
+        crate::nn::SaveSafeTensors::write_safetensors(&self.t, &format!("{location}t"), tensors);
+        super::state::write_state(
+            model,
+            &self.moment1,
+            &format!("{location}moment1."),
+            tensors,
+        );
+        super::state::write_state(
+            model,
+            &self.moment2,
+            &format!("{location}moment2."),
+            tensors,
+        );
+    }
+
+    fn read_safetensors(
+        &mut self,
+        model: &M,
+        location: &str,
+        tensors: &safetensors::SafeTensors,
+    ) -> Result<(), safetensors::SafeTensorError> {
+        crate::nn::LoadSafeTensors::read_safetensors(
+            &mut self.t,
+            &format!("{location}t"),
+            tensors,
+        )?;
+        super::state::read_state(
+            model,
+            &mut self.moment1,
+            &format!("{location}moment1."),
+            tensors,
+        )?;
+        super::state::read_state(
+            model,
+            &mut self.moment2,
+            &format!("{location}moment2."),
+            tensors,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{nn::optim::*, shapes::*, tensor::*, tensor_ops::*, tests::*};
+
+    fn test_matches_expected<const N: usize>(cfg: LambConfig, expected: [[f64; 5]; N]) {
+        let dev: TestDevice = Default::default();
+        let mut t = dev
+            .tensor([-0.5, -0.25, 0.1, 0.6, 1.0])
+            .to_dtype::<TestDtype>();
+        let mut opt = Lamb::new(&t, cfg);
+        for e in expected.iter() {
+            let gradients = t.leaky_trace().exp().square().mean().backward();
+            opt.update(&mut t, &gradients).expect("");
+            assert_close_to_literal!(t, e);
+        }
+    }
+
+    #[test]
+    fn test_lamb_default() {
+        let cfg = LambConfig::default();
+        #[rustfmt::skip]
+        const EXPECTED: [[f64; 5]; 5] = [
+            [-0.50058008, -0.25058009, 0.099419913, 0.59941991, 0.99941991],
+            [-0.50115998, -0.25115998, 0.098840016, 0.59884001, 0.99884001],
+            [-0.50173968, -0.25173969, 0.098260309, 0.59826031, 0.99826031],
+            [-0.5023192, -0.25231921, 0.09768079, 0.59768079, 0.99768079],
+            [-0.50289853, -0.25289854, 0.097101458, 0.59710145, 0.99710145],
+        ];
+        test_matches_expected(cfg, EXPECTED);
+    }
+    #[test]
+    fn test_lamb_custom() {
+        let cfg = LambConfig {
+            lr: 1e-2,
+            betas: [0.5, 0.25],
+            eps: 1e-6,
+            weight_decay: None,
+            max_trust_ratio: Some(0.5),
+        };
+        #[rustfmt::skip]
+        const EXPECTED: [[f64; 5]; 5] = [
+            [-0.50499997, -0.25499998, 0.09500001, 0.595, 0.995],
+            [-0.51000658, -0.2600066, 0.089993374, 0.58999336, 0.98999336],
+            [-0.51502084, -0.26502088, 0.084979084, 0.58497906, 0.98497906],
+            [-0.5200417, -0.27004176, 0.079958202, 0.57995818, 0.97995817],
+            [-0.5250674, -0.27506747, 0.074932474, 0.57493244, 0.97493243],
+        ];
+        test_matches_expected(cfg, EXPECTED);
+    }
+    #[test]
+    fn test_lamb_l2_weight_decay() {
+        let cfg = LambConfig {
+            weight_decay: Some(WeightDecay::L2(0.5)),
+            ..Default::default()
+        };
+        #[rustfmt::skip]
+        const EXPECTED: [[f64; 5]; 5] = [
+            [-0.49941992, -0.25058008, 0.099419912, 0.59941991, 0.99941991],
+            [-0.49884025, -0.25115974, 0.098840198, 0.59884019, 0.99884019],
+            [-0.49826103, -0.25173895, 0.098260847, 0.59826082, 0.99826082],
+            [-0.49768226, -0.2523177, 0.097681846, 0.59768178, 0.99768177],
+            [-0.49710396, -0.25289594, 0.097103184, 0.59710306, 0.99710305],
+        ];
+        test_matches_expected(cfg, EXPECTED);
+    }
+    #[test]
+    fn test_lamb_decoupled_weight_decay() {
+        let cfg = LambConfig {
+            weight_decay: Some(WeightDecay::Decoupled(0.5)),
+            ..Default::default()
+        };
+        #[rustfmt::skip]
+        const EXPECTED: [[f64; 5]; 5] = [
+            [-0.50038543, -0.25044967, 0.099460395, 0.59933192, 0.99922913],
+            [-0.50077065, -0.25089909, 0.098921085, 0.5986642, 0.99845869],
+            [-0.50115566, -0.25134827, 0.09838207, 0.59799685, 0.99768867],
+            [-0.50154045, -0.2517972, 0.097843349, 0.59732986, 0.99691906],
+            [-0.50192504, -0.25224589, 0.097304921, 0.59666323, 0.99614988],
+        ];
+        test_matches_expected(cfg, EXPECTED);
+    }
+
+    #[test]
+    fn test_unused_tensors() {
+        let dev: TestDevice = Default::default();
+        let mut t: Tensor<Rank1<5>, TestDtype, _> = dev.sample_normal();
+        let mut opt = Lamb::new(&t, Default::default());
+        opt.update(&mut t, &Gradients::leaky()).expect_err("");
+    }
+}
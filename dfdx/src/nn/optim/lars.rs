@@ -0,0 +1,233 @@
+use std::marker::PhantomData;
+
+use super::param_groups::ParamGroups;
+use crate::{
+    nn::UpdateParams,
+    shapes::{Dtype, Shape},
+    tensor::{Error, Gradients, Storage, Tensor, Tensorlike, UniqueId},
+    tensor_ops::{Device, LarsConfig},
+};
+
+/// An implementation of the LARS optimizer from
+/// [Large Batch Training of Convolutional Networks](https://arxiv.org/abs/1708.03888)
+///
+/// The gradient of each tensor is scaled by the trust ratio of the whole tensor before
+/// the momentum update, optionally clipped by [LarsConfig::max_trust_ratio].
+///
+/// # Example Usage
+/// ```rust
+/// # use dfdx::prelude::*;
+/// # type Model = Tensor<Rank0, f32, Cpu>;
+/// # let dev: Cpu = Default::default();
+/// # let model: Model = dev.zeros();
+/// let mut opt: Lars<Model, f32, Cpu> = optim::Lars::new(&model, LarsConfig {
+///     lr: 1e-1,
+///     momentum: Some(Momentum::Nesterov(0.9)),
+///     trust_coefficient: 1e-3,
+///     eps: 1e-8,
+///     weight_decay: Some(WeightDecay::L2(1e-4)),
+///     max_trust_ratio: Some(1.0),
+/// });
+/// ```
+///
+/// See module level documentation at [crate::nn::optim] for examples of how to actually use an optimizer.
+#[derive(Debug, Clone)]
+pub struct Lars<M, E: Dtype, D: Storage<E>> {
+    /// Hyperparameter configuration
+    pub cfg: LarsConfig,
+
+    momentum: Gradients<E, D>,
+
+    param_groups: ParamGroups<LarsConfig>,
+
+    marker: PhantomData<*const M>,
+}
+
+impl<M, E: Dtype, D: Storage<E>> Lars<M, E, D> {
+    /// Constructs using hyperparameters from `cfg`.
+    pub fn new(_model: &M, cfg: LarsConfig) -> Self {
+        Self {
+            cfg,
+            momentum: Gradients::leaky(),
+            param_groups: Default::default(),
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<M, E: Dtype, D: Device<E>> Lars<M, E, D> {
+    /// Uses `cfg` instead of [Lars::cfg] for the parameters of `model` that `select` returns
+    /// `true` for, given their path & shape. See [Adam::add_param_group()] for details.
+    ///
+    /// [Adam::add_param_group()]: crate::nn::optim::Adam::add_param_group()
+    pub fn add_param_group<F>(&mut self, model: &M, select: F, cfg: LarsConfig)
+    where
+        M: UpdateParams<E, D>,
+        F: FnMut(&str, &[usize]) -> bool,
+    {
+        self.param_groups
+            .add(model, select, cfg, self.cfg.lr, cfg.lr);
+    }
+}
+
+impl<M, E: Dtype, D: Storage<E>> super::LearningRate for Lars<M, E, D> {
+    fn lr(&self) -> f64 {
+        self.cfg.lr
+    }
+    fn set_lr(&mut self, lr: f64) {
+        self.cfg.lr = lr;
+        self.param_groups.set_lr(lr, |cfg| &mut cfg.lr);
+    }
+}
+
+impl<M, E: Dtype, D: Device<E>> crate::nn::Optimizer<M, E, D> for Lars<M, E, D> {
+    fn update_tensor<S: Shape>(
+        &mut self,
+        t: &mut Tensor<S, E, D>,
+        gradients: &Gradients<E, D>,
+        missing_params: &mut Vec<UniqueId>,
+    ) -> Result<(), Error> {
+        let g = gradients.get_ref_checked(t);
+        match g {
+            None => missing_params.push(t.id()),
+            Some(g) => {
+                let v = self.momentum.get_or_alloc_mut(t)?;
+                let cfg = self.param_groups.cfg(&t.id(), &self.cfg);
+                cfg.try_update(t, v, g)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(feature = "safetensors")]
+impl<M: UpdateParams<E, D>, E: Dtype, D: Device<E>> super::OptimizerSafeTensors<M, E, D>
+    for Lars<M, E, D>
+{
+    fn write_safetensors(
+        &self,
+        model: &M,
+        location: &str,
+        tensors: &mut Vec<(String, safetensors::Dtype, Vec<usize>, Vec<u8>)>,
+    ) {
+        super::state::write_state(
+            model,
+            &self.momentum,
+            &format!("{location}momentum."),
+            tensors,
+        );
+    }
+
+    fn read_safetensors(
+        &mut self,
+        model: &M,
+        location: &str,
+        tensors: &safetensors::SafeTensors,
+    ) -> Result<(), safetensors::SafeTensorError> {
+        super::state::read_state(
+            model,
+            &mut self.momentum,
+            &format!("{location}momentum."),
+            tensors,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{nn::optim::*, shapes::*, tensor::*, tensor_ops::*, tests::*};
+
+    fn test_matches_expected<const N: usize>(cfg: LarsConfig, expected: [[f64; 5]; N]) {
+        let dev: TestDevice = Default::default();
+        let mut t = dev
+            .tensor([-0.5, -0.25, 0.1, 0.6, 1.0])
+            .to_dtype::<TestDtype>();
+        let mut opt = Lars::new(&t, cfg);
+        for e in expected.iter() {
+            let gradients = t.leaky_trace().exp().square().mean().backward();
+            opt.update(&mut t, &gradients).expect("");
+            assert_close_to_literal!(t, e);
+        }
+    }
+
+    #[test]
+    fn test_lars_default() {
+        let cfg = LarsConfig::default();
+        #[rustfmt::skip]
+        const EXPECTED: [[f64; 5]; 5] = [
+            [-0.50000058, -0.25000096, 0.099998073, 0.59999476, 0.99998834],
+            [-0.50000168, -0.25000277, 0.099994413, 0.59998481, 0.9999662],
+            [-0.50000326, -0.25000537, 0.099989191, 0.59997062, 0.99993461],
+            [-0.50000525, -0.25000866, 0.099982565, 0.59995261, 0.99989453],
+            [-0.50000763, -0.25001258, 0.099974675, 0.59993116, 0.9998468],
+        ];
+        test_matches_expected(cfg, EXPECTED);
+    }
+    #[test]
+    fn test_lars_nesterov_max_trust_ratio() {
+        let cfg = LarsConfig {
+            lr: 1.0,
+            momentum: Some(Momentum::Nesterov(0.5)),
+            trust_coefficient: 0.02,
+            eps: 1e-8,
+            weight_decay: None,
+            max_trust_ratio: Some(0.01),
+        };
+        #[rustfmt::skip]
+        const EXPECTED: [[f64; 5]; 5] = [
+            [-0.50174092, -0.25287029, 0.094219948, 0.58428819, 0.96503272],
+            [-0.50382881, -0.25630595, 0.087335936, 0.56589203, 0.92541787],
+            [-0.50613524, -0.26009232, 0.07979459, 0.5461383, 0.88441306],
+            [-0.50859519, -0.26411997, 0.071826851, 0.52572251, 0.84362999],
+            [-0.51117441, -0.26833058, 0.063558087, 0.50502262, 0.8038375],
+        ];
+        test_matches_expected(cfg, EXPECTED);
+    }
+    #[test]
+    fn test_lars_l2_weight_decay() {
+        let cfg = LarsConfig {
+            lr: 1.0,
+            momentum: None,
+            trust_coefficient: 0.02,
+            weight_decay: Some(WeightDecay::L2(0.5)),
+            ..Default::default()
+        };
+        #[rustfmt::skip]
+        const EXPECTED: [[f64; 5]; 5] = [
+            [-0.49932242, -0.25077485, 0.096451888, 0.58927421, 0.9772339],
+            [-0.49863561, -0.25155926, 0.092871584, 0.57857021, 0.9549956],
+            [-0.49794013, -0.25235257, 0.08926254, 0.56789773, 0.93327147],
+            [-0.49723654, -0.25315407, 0.08562812, 0.55726578, 0.91204803],
+            [-0.49652541, -0.25396312, 0.081971588, 0.54668271, 0.89131201],
+        ];
+        test_matches_expected(cfg, EXPECTED);
+    }
+    #[test]
+    fn test_lars_decoupled_weight_decay() {
+        let cfg = LarsConfig {
+            lr: 1.0,
+            momentum: None,
+            trust_coefficient: 0.02,
+            weight_decay: Some(WeightDecay::Decoupled(0.5)),
+            ..Default::default()
+        };
+        #[rustfmt::skip]
+        const EXPECTED: [[f64; 5]; 5] = [
+            [-0.25116061, -0.12691353, 0.046146632, 0.28952546, 0.47668848],
+            [-0.12776096, -0.066252561, 0.019121253, 0.1383326, 0.22899475],
+            [-0.065704131, -0.035188655, 0.0071142555, 0.066061252, 0.11077504],
+            [-0.033964606, -0.018776882, 0.0022701675, 0.031582635, 0.05380407],
+            [-0.017585582, -0.010010327, 0.00048646186, 0.015103534, 0.026182995],
+        ];
+        test_matches_expected(cfg, EXPECTED);
+    }
+
+    #[test]
+    fn test_unused_tensors() {
+        let dev: TestDevice = Default::default();
+        let mut t: Tensor<Rank1<5>, TestDtype, _> = dev.sample_normal();
+        let mut opt = Lars::new(&t, Default::default());
+        opt.update(&mut t, &Gradients::leaky()).expect_err("");
+    }
+}
@@ -0,0 +1,696 @@
+use std::collections::VecDeque;
+use std::marker::PhantomData;
+
+use crate::{
+    nn::{Optimizer, UpdateParams, WithGrads},
+    shapes::{Dtype, HasShape, Rank0, Shape},
+    tensor::{Error, Gradients, Storage, Tensor, Tensorlike, UniqueId},
+    tensor_ops::{Device, MaxTo, SumTo, TryMul},
+};
+
+/// Configuration of hyperparameters for [LBfgs].
+///
+/// Using a strong Wolfe line search instead of a fixed step size:
+/// ```rust
+/// # use dfdx::prelude::*;
+/// LBfgsConfig {
+///     line_search: Some(LineSearch::StrongWolfe { c1: 1e-4, c2: 0.9 }),
+///     ..Default::default()
+/// };
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct LBfgsConfig {
+    /// Learning rate, which is the step size when there is no line search, and the initial
+    /// step size of the line search otherwise. Defaults to `1.0`.
+    pub lr: f64,
+
+    /// Maximum number of iterations per [LBfgs::step()]. Defaults to `20`.
+    pub max_iter: usize,
+
+    /// Maximum number of evaluations of the closure per [LBfgs::step()].
+    /// Defaults to `None`, which is `max_iter * 5 / 4`.
+    pub max_eval: Option<usize>,
+
+    /// Stops once the largest absolute value of the gradients is at most this. Defaults to `1e-7`.
+    pub tolerance_grad: f64,
+
+    /// Stops once a step, or the change of the loss, is smaller than this. Defaults to `1e-9`.
+    pub tolerance_change: f64,
+
+    /// The number of past parameter & gradient differences used to approximate the
+    /// inverse Hessian. Defaults to `100`.
+    pub history_size: usize,
+
+    /// Optional line search. Defaults to `None`.
+    pub line_search: Option<LineSearch>,
+}
+
+impl Default for LBfgsConfig {
+    fn default() -> Self {
+        Self {
+            lr: 1.0,
+            max_iter: 20,
+            max_eval: None,
+            tolerance_grad: 1e-7,
+            tolerance_change: 1e-9,
+            history_size: 100,
+            line_search: None,
+        }
+    }
+}
+
+/// Line searches for the step size of [LBfgs].
+#[derive(Debug, Clone, Copy)]
+pub enum LineSearch {
+    /// Finds a step size that satisfies the strong Wolfe conditions, with the sufficient
+    /// decrease coefficient `c1` and the curvature coefficient `c2`. Commonly `c1 = 1e-4`
+    /// and `c2 = 0.9`.
+    StrongWolfe { c1: f64, c2: f64 },
+}
+
+/// The maximum number of evaluations of a single line search.
+const MAX_LINE_SEARCH: usize = 25;
+
+/// An implementation of the L-BFGS optimizer from
+/// [On the limited memory BFGS method for large scale optimization](https://doi.org/10.1007/BF01589116),
+/// with the strong Wolfe line search of Nocedal & Wright's Numerical Optimization.
+///
+/// L-BFGS has to evaluate the loss multiple times per step, so it doesn't implement
+/// [Optimizer]. Instead [LBfgs::step()] takes a closure that recomputes the loss and
+/// gradients of the model:
+///
+/// ```rust
+/// # use dfdx::prelude::*;
+/// # let dev: Cpu = Default::default();
+/// let mut model = dev.build_module::<f32>(LinearConstConfig::<5, 2>::default());
+/// let mut opt = LBfgs::new(&model, LBfgsConfig {
+///     line_search: Some(LineSearch::StrongWolfe { c1: 1e-4, c2: 0.9 }),
+///     ..Default::default()
+/// });
+/// let x: Tensor<Rank2<8, 5>, f32, _> = dev.sample_normal();
+/// let y: Tensor<Rank2<8, 2>, f32, _> = dev.sample_normal();
+/// let loss = opt
+///     .step(&mut model, |model| {
+///         let loss = mse_loss(model.forward(x.leaky_trace()), y.clone());
+///         Ok((loss.array(), loss.backward()))
+///     })
+///     .unwrap();
+/// ```
+///
+/// Parameters without a gradient are treated as if their gradient was zero.
+#[derive(Debug, Clone)]
+pub struct LBfgs<M, E: Dtype, D: Storage<E>> {
+    /// Hyperparameter configuration
+    pub cfg: LBfgsConfig,
+
+    /// The number of iterations over all steps.
+    n_iter: usize,
+    direction: Option<Gradients<E, D>>,
+    step_size: f64,
+    prev_grad: Option<Gradients<E, D>>,
+    history: VecDeque<Correction<E, D>>,
+    /// The scale of the initial inverse Hessian approximation.
+    h_diag: f64,
+
+    marker: PhantomData<*const M>,
+}
+
+/// A pair of parameter & gradient differences of an iteration.
+#[derive(Debug, Clone)]
+struct Correction<E, D: Storage<E>> {
+    s: Gradients<E, D>,
+    y: Gradients<E, D>,
+    /// `1 / y.dot(s)`
+    rho: f64,
+}
+
+impl<M, E: Dtype, D: Storage<E>> LBfgs<M, E, D> {
+    /// Constructs using hyperparameters from `cfg`.
+    pub fn new(_model: &M, cfg: LBfgsConfig) -> Self {
+        Self {
+            cfg,
+            n_iter: 0,
+            direction: None,
+            step_size: 0.0,
+            prev_grad: None,
+            history: VecDeque::new(),
+            h_diag: 1.0,
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<M, E: Dtype, D: Storage<E>> super::LearningRate for LBfgs<M, E, D> {
+    fn lr(&self) -> f64 {
+        self.cfg.lr
+    }
+    fn set_lr(&mut self, lr: f64) {
+        self.cfg.lr = lr;
+    }
+}
+
+impl<M: UpdateParams<E, D>, E: Dtype, D: Device<E>> LBfgs<M, E, D> {
+    /// Runs up to [LBfgsConfig::max_iter] iterations, using `closure` to compute the loss and
+    /// the gradients of `model` with its current parameters. Returns the loss before the step.
+    pub fn step<F>(&mut self, model: &mut M, mut closure: F) -> Result<E, Error>
+    where
+        F: FnMut(&mut M) -> Result<(E, Gradients<E, D>), Error>,
+    {
+        let cfg = self.cfg;
+        let max_eval = cfg.max_eval.unwrap_or(cfg.max_iter * 5 / 4);
+        let params = Params::of(model);
+
+        let (orig_loss, grads) = closure(model)?;
+        let mut loss = orig_loss.to_f64().unwrap();
+        let mut grad = params.grads(grads)?;
+        let mut num_evals = 1;
+        if params.max_abs(&grad)? <= cfg.tolerance_grad {
+            return Ok(orig_loss);
+        }
+
+        let mut n_iter = 0;
+        while n_iter < cfg.max_iter {
+            n_iter += 1;
+            self.n_iter += 1;
+
+            let direction = match (self.direction.take(), self.prev_grad.take()) {
+                (Some(prev_direction), Some(prev_grad)) => {
+                    self.update_history(&params, &grad, prev_direction, prev_grad)?;
+                    self.two_loop_recursion(&params, &grad)?
+                }
+                _ => {
+                    self.history.clear();
+                    self.h_diag = 1.0;
+                    let mut direction = grad.clone();
+                    params.scale(&mut direction, -1.0)?;
+                    direction
+                }
+            };
+            let prev_loss = loss;
+            self.prev_grad = Some(grad.clone());
+
+            // the first step is scaled down for large gradients, as there is no curvature information yet
+            self.step_size = match self.n_iter {
+                1 => cfg.lr * f64::min(1.0, 1.0 / params.abs_sum(&grad)?),
+                _ => cfg.lr,
+            };
+            let gtd = params.dot(&grad, &direction)?;
+            if gtd > -cfg.tolerance_change {
+                // not a descent direction
+                self.direction = Some(direction);
+                break;
+            }
+
+            let mut line = Line {
+                model: &mut *model,
+                params: &params,
+                closure: &mut closure,
+                direction: &direction,
+                offset: 0.0,
+            };
+            match cfg.line_search {
+                Some(LineSearch::StrongWolfe { c1, c2 }) => {
+                    let start = Point {
+                        t: 0.0,
+                        f: loss,
+                        g: grad,
+                        gtd,
+                    };
+                    let (point, evals) =
+                        strong_wolfe(&mut line, start, self.step_size, c1, c2, &cfg)?;
+                    line.move_to(point.t)?;
+                    (self.step_size, loss, grad) = (point.t, point.f, point.g);
+                    num_evals += evals;
+                }
+                None => {
+                    line.move_to(self.step_size)?;
+                    if n_iter == cfg.max_iter {
+                        self.direction = Some(direction);
+                        break;
+                    }
+                    let point = line.eval(self.step_size)?;
+                    (loss, grad) = (point.f, point.g);
+                    num_evals += 1;
+                }
+            }
+
+            let step_max = params.max_abs(&direction)? * self.step_size.abs();
+            self.direction = Some(direction);
+            if n_iter == cfg.max_iter
+                || num_evals >= max_eval
+                || params.max_abs(&grad)? <= cfg.tolerance_grad
+                || step_max <= cfg.tolerance_change
+                || (loss - prev_loss).abs() < cfg.tolerance_change
+            {
+                break;
+            }
+        }
+
+        Ok(orig_loss)
+    }
+
+    /// Adds the differences to the previous iteration to the history, if they keep the
+    /// inverse Hessian approximation positive definite.
+    fn update_history(
+        &mut self,
+        params: &Params<E, D>,
+        grad: &Gradients<E, D>,
+        prev_direction: Gradients<E, D>,
+        prev_grad: Gradients<E, D>,
+    ) -> Result<(), Error> {
+        let mut y = grad.clone();
+        params.add(&mut y, -1.0, &prev_grad)?;
+        let mut s = prev_direction;
+        params.scale(&mut s, self.step_size)?;
+        let ys = params.dot(&y, &s)?;
+        if ys > 1e-10 {
+            if self.history.len() == self.cfg.history_size {
+                self.history.pop_front();
+            }
+            self.h_diag = ys / params.dot(&y, &y)?;
+            self.history.push_back(Correction {
+                s,
+                y,
+                rho: 1.0 / ys,
+            });
+        }
+        Ok(())
+    }
+
+    /// The direction of the next step: the negative gradient multiplied with the
+    /// approximation of the inverse Hessian.
+    fn two_loop_recursion(
+        &self,
+        params: &Params<E, D>,
+        grad: &Gradients<E, D>,
+    ) -> Result<Gradients<E, D>, Error> {
+        let mut q = grad.clone();
+        params.scale(&mut q, -1.0)?;
+        let mut alphas = Vec::with_capacity(self.history.len());
+        for c in self.history.iter().rev() {
+            let alpha = params.dot(&c.s, &q)? * c.rho;
+            params.add(&mut q, -alpha, &c.y)?;
+            alphas.push(alpha);
+        }
+
+        let mut r = q;
+        params.scale(&mut r, self.h_diag)?;
+        for (c, alpha) in self.history.iter().zip(alphas.into_iter().rev()) {
+            let beta = params.dot(&c.y, &r)? * c.rho;
+            params.add(&mut r, alpha - beta, &c.s)?;
+        }
+        Ok(r)
+    }
+}
+
+/// The flattened parameters of a model. Vectors with a value for each element of the
+/// parameters (e.g. the gradients and the search direction) are stored in a [Gradients].
+struct Params<E: Dtype, D: Storage<E>>(Vec<Tensor<(usize,), E, D>>);
+
+impl<E: Dtype, D: Device<E>> Params<E, D> {
+    fn of<M: UpdateParams<E, D>>(model: &M) -> Self {
+        let mut params = Vec::new();
+        model.visit_params("", &mut |_, _, param| params.push(param.clone()));
+        Self(params)
+    }
+
+    /// Drops the gradients of non parameters, and uses zeros for missing ones.
+    fn grads(&self, mut grads: Gradients<E, D>) -> Result<Gradients<E, D>, Error> {
+        let ids: Vec<UniqueId> = self.0.iter().map(|p| p.id()).collect();
+        grads.retain_leafs(&ids);
+        for p in self.0.iter() {
+            grads.get_or_alloc_mut(p)?;
+        }
+        Ok(grads)
+    }
+
+    fn dot(&self, a: &Gradients<E, D>, b: &Gradients<E, D>) -> Result<f64, Error> {
+        let mut total = 0.0;
+        for p in self.0.iter() {
+            let ab = a.get(p).try_mul(b.get(p))?.try_sum::<Rank0, _>()?;
+            total += ab.as_vec()[0].to_f64().unwrap();
+        }
+        Ok(total)
+    }
+
+    fn max_abs(&self, a: &Gradients<E, D>) -> Result<f64, Error> {
+        let mut max: f64 = 0.0;
+        for p in self.0.iter().filter(|p| p.shape().0 > 0) {
+            let p_max = a.get(p).try_abs()?.try_max::<Rank0, _>()?;
+            max = max.max(p_max.as_vec()[0].to_f64().unwrap());
+        }
+        Ok(max)
+    }
+
+    fn abs_sum(&self, a: &Gradients<E, D>) -> Result<f64, Error> {
+        let mut total = 0.0;
+        for p in self.0.iter() {
+            let p_sum = a.get(p).try_abs()?.try_sum::<Rank0, _>()?;
+            total += p_sum.as_vec()[0].to_f64().unwrap();
+        }
+        Ok(total)
+    }
+
+    /// `a *= scale`
+    fn scale(&self, a: &mut Gradients<E, D>, scale: f64) -> Result<(), Error> {
+        for p in self.0.iter() {
+            p.try_grads_map(a, &mut |x| x.try_mul(scale))?;
+        }
+        Ok(())
+    }
+
+    /// `a += scale * b`
+    fn add(&self, a: &mut Gradients<E, D>, scale: f64, b: &Gradients<E, D>) -> Result<(), Error> {
+        for p in self.0.iter() {
+            p.try_grads_map(a, &mut |mut x| {
+                x.try_axpy(1.0, &b.get(p), scale)?;
+                Ok(x)
+            })?;
+        }
+        Ok(())
+    }
+}
+
+/// Adds `scale` times the direction in the [Gradients] to each parameter.
+struct AddScaled(f64);
+
+impl<M, E: Dtype, D: Device<E>> Optimizer<M, E, D> for AddScaled {
+    fn update_tensor<S: Shape>(
+        &mut self,
+        t: &mut Tensor<S, E, D>,
+        direction: &Gradients<E, D>,
+        missing_params: &mut Vec<UniqueId>,
+    ) -> Result<(), Error> {
+        match direction.get_ref_checked(t) {
+            None => missing_params.push(t.id()),
+            Some(_) => {
+                let d = direction.get(t);
+                t.try_axpy(1.0, &d, self.0)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The loss & gradients at a step size `t` along the search direction.
+struct Point<E, D: Storage<E>> {
+    t: f64,
+    f: f64,
+    g: Gradients<E, D>,
+    /// The directional derivative, `g.dot(direction)`
+    gtd: f64,
+}
+
+/// Evaluates the model along the search direction from its parameters at the start of the
+/// iteration, which are `offset` steps back.
+struct Line<'a, M, E: Dtype, D: Storage<E>, F> {
+    model: &'a mut M,
+    params: &'a Params<E, D>,
+    closure: &'a mut F,
+    direction: &'a Gradients<E, D>,
+    offset: f64,
+}
+
+impl<'a, M, E, D, F> Line<'a, M, E, D, F>
+where
+    M: UpdateParams<E, D>,
+    E: Dtype,
+    D: Device<E>,
+    F: FnMut(&mut M) -> Result<(E, Gradients<E, D>), Error>,
+{
+    fn move_to(&mut self, t: f64) -> Result<(), Error> {
+        let mut missing_params = Vec::new();
+        self.model.try_update_params::<M, _>(
+            &mut AddScaled(t - self.offset),
+            self.direction,
+            &mut missing_params,
+        )?;
+        self.offset = t;
+        Ok(())
+    }
+
+    fn eval(&mut self, t: f64) -> Result<Point<E, D>, Error> {
+        self.move_to(t)?;
+        let (f, g) = (self.closure)(self.model)?;
+        let g = self.params.grads(g)?;
+        let gtd = self.params.dot(&g, self.direction)?;
+        Ok(Point {
+            t,
+            f: f.to_f64().unwrap(),
+            g,
+            gtd,
+        })
+    }
+}
+
+/// Algorithms 3.5 & 3.6 of Numerical Optimization: brackets a step size that satisfies
+/// the strong Wolfe conditions, and then zooms into the bracket. Returns the best point
+/// found and the number of evaluations.
+fn strong_wolfe<M, E, D, F>(
+    line: &mut Line<M, E, D, F>,
+    start: Point<E, D>,
+    t: f64,
+    c1: f64,
+    c2: f64,
+    cfg: &LBfgsConfig,
+) -> Result<(Point<E, D>, usize), Error>
+where
+    M: UpdateParams<E, D>,
+    E: Dtype,
+    D: Device<E>,
+    F: FnMut(&mut M) -> Result<(E, Gradients<E, D>), Error>,
+{
+    let (f, gtd) = (start.f, start.gtd);
+    let sufficient_decrease = |p: &Point<E, D>| p.f <= f + c1 * p.t * gtd;
+    let curvature = |p: &Point<E, D>| p.gtd.abs() <= -c2 * gtd;
+    let d_norm = line.params.max_abs(line.direction)?;
+
+    let mut new = line.eval(t)?;
+    let mut evals = 1;
+    let mut prev = Point {
+        t: 0.0,
+        f,
+        g: start.g.clone(),
+        gtd,
+    };
+    let mut done = false;
+    let mut bracket = loop {
+        if evals > MAX_LINE_SEARCH {
+            break vec![start, new];
+        }
+        if !sufficient_decrease(&new) || (evals > 2 && new.f >= prev.f) {
+            break vec![prev, new];
+        }
+        if curvature(&new) {
+            done = true;
+            break vec![new];
+        }
+        if new.gtd >= 0.0 {
+            break vec![prev, new];
+        }
+
+        // extrapolate
+        let min_step = new.t + 0.01 * (new.t - prev.t);
+        let max_step = new.t * 10.0;
+        let t = cubic_interpolate(&prev, &new, Some([min_step, max_step]));
+        prev = new;
+        new = line.eval(t)?;
+        evals += 1;
+    };
+
+    let low_high = |bracket: &[Point<E, D>]| match bracket[0].f <= bracket[bracket.len() - 1].f {
+        true => (0, 1),
+        false => (1, 0),
+    };
+    let (mut low, mut high) = low_high(&bracket);
+    let mut insufficient_progress = false;
+    while !done && evals <= MAX_LINE_SEARCH {
+        let (min_t, max_t) = match bracket[0].t <= bracket[1].t {
+            true => (bracket[0].t, bracket[1].t),
+            false => (bracket[1].t, bracket[0].t),
+        };
+        if (max_t - min_t) * d_norm < cfg.tolerance_change {
+            break;
+        }
+
+        let mut t = cubic_interpolate(&bracket[0], &bracket[1], None);
+        // don't get too close to the ends of the bracket, unless that was tried already
+        let eps = 0.1 * (max_t - min_t);
+        if f64::min(max_t - t, t - min_t) < eps {
+            if insufficient_progress || t >= max_t || t <= min_t {
+                t = match (t - max_t).abs() < (t - min_t).abs() {
+                    true => max_t - eps,
+                    false => min_t + eps,
+                };
+                insufficient_progress = false;
+            } else {
+                insufficient_progress = true;
+            }
+        } else {
+            insufficient_progress = false;
+        }
+
+        let new = line.eval(t)?;
+        evals += 1;
+        if !sufficient_decrease(&new) || new.f >= bracket[low].f {
+            bracket[high] = new;
+            (low, high) = low_high(&bracket);
+        } else {
+            if curvature(&new) {
+                done = true;
+            } else if new.gtd * (bracket[high].t - bracket[low].t) >= 0.0 {
+                bracket.swap(low, high);
+            }
+            bracket[low] = new;
+        }
+    }
+
+    Ok((bracket.swap_remove(low), evals))
+}
+
+/// The minimum of the cubic interpolation of the loss between `a` and `b`, clamped to
+/// `bounds` (which default to the interval between `a` and `b`).
+fn cubic_interpolate<E, D: Storage<E>>(
+    a: &Point<E, D>,
+    b: &Point<E, D>,
+    bounds: Option<[f64; 2]>,
+) -> f64 {
+    let [min_bound, max_bound] = bounds.unwrap_or(match a.t <= b.t {
+        true => [a.t, b.t],
+        false => [b.t, a.t],
+    });
+    let d1 = a.gtd + b.gtd - 3.0 * (a.f - b.f) / (a.t - b.t);
+    let d2_square = d1 * d1 - a.gtd * b.gtd;
+    if d2_square >= 0.0 {
+        let d2 = d2_square.sqrt();
+        let min_pos = match a.t <= b.t {
+            true => b.t - (b.t - a.t) * ((b.gtd + d2 - d1) / (b.gtd - a.gtd + 2.0 * d2)),
+            false => a.t - (a.t - b.t) * ((a.gtd + d2 - d1) / (a.gtd - b.gtd + 2.0 * d2)),
+        };
+        min_pos.max(min_bound).min(max_bound)
+    } else {
+        (min_bound + max_bound) / 2.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{prelude::*, tests::*};
+    use num_traits::ToPrimitive;
+
+    fn assert_near<const N: usize>(t: [TestDtype; N], expected: [f64; N], tol: f64) {
+        for (a, b) in t.into_iter().zip(expected) {
+            assert!(
+                (a.to_f64().unwrap() - b).abs() < tol,
+                "{t:?} != {expected:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_lbfgs_first_iteration() {
+        let dev: TestDevice = Default::default();
+        let mut t = dev
+            .tensor([-0.5, -0.25, 0.1, 0.6, 1.0])
+            .to_dtype::<TestDtype>();
+        let cfg = LBfgsConfig {
+            max_iter: 1,
+            ..Default::default()
+        };
+        let mut opt = LBfgs::new(&t, cfg);
+        let loss = opt
+            .step(&mut t, |t| {
+                let loss = t.leaky_trace().square().sum();
+                Ok((loss.array(), loss.backward()))
+            })
+            .unwrap();
+        assert!((loss.to_f64().unwrap() - 1.6825).abs() < 1e-5);
+        // the first step is the gradient scaled by 1 / |grad|_1 = 1 / 4.9
+        assert_close_to_literal!(
+            t,
+            [-0.29591837, -0.14795918, 0.05918367, 0.35510204, 0.59183673]
+        );
+    }
+
+    #[test]
+    fn test_lbfgs_quadratic() {
+        let dev: TestDevice = Default::default();
+        let mut t: Tensor<Rank1<4>, TestDtype, _> = dev.zeros();
+        let target = dev.tensor([1.0, -2.0, 3.0, 0.5]).to_dtype::<TestDtype>();
+        let scale = dev.tensor([1.0, 10.0, 0.1, 4.0]).to_dtype::<TestDtype>();
+        let mut opt = LBfgs::new(&t, Default::default());
+        opt.step(&mut t, |t| {
+            let loss = ((t.leaky_trace() - target.clone()).square() * scale.clone()).sum();
+            Ok((loss.array(), loss.backward()))
+        })
+        .unwrap();
+        assert_near(t.array(), [1.0, -2.0, 3.0, 0.5], 1e-4);
+    }
+
+    #[test]
+    fn test_lbfgs_strong_wolfe_rosenbrock() {
+        let dev: TestDevice = Default::default();
+        let mut t = dev.tensor([-1.5, 2.0]).to_dtype::<TestDtype>();
+        let cfg = LBfgsConfig {
+            max_iter: 100,
+            line_search: Some(LineSearch::StrongWolfe { c1: 1e-4, c2: 0.9 }),
+            ..Default::default()
+        };
+        let mut opt = LBfgs::new(&t, cfg);
+        let e_x = dev.tensor([1.0, 0.0]).to_dtype::<TestDtype>();
+        let e_y = dev.tensor([0.0, 1.0]).to_dtype::<TestDtype>();
+        let rosenbrock = |t: &mut Tensor<Rank1<2>, TestDtype, TestDevice>| {
+            // (1 - x)^2 + 100 * (y - x^2)^2
+            let a = ((t.leaky_trace().negate() + 1.0) * e_x.clone()).square();
+            let b = (t.leaky_trace() * e_y.clone() - t.leaky_trace().square() * e_x.clone())
+                .sum::<Rank0, _>();
+            let loss = a.sum() + b.square() * 100.0;
+            Ok((loss.array(), loss.backward()))
+        };
+        let loss = opt.step(&mut t, rosenbrock).unwrap();
+        assert!((loss.to_f64().unwrap() - 12.5).abs() < 1e-4);
+        assert_near(t.array(), [1.0, 1.0], 1e-3);
+        assert!(opt.history.len() <= opt.cfg.history_size);
+    }
+
+    #[test]
+    fn test_lbfgs_history_size() {
+        let dev: TestDevice = Default::default();
+        let mut t: Tensor<Rank1<8>, TestDtype, _> = dev.zeros();
+        let scale = dev
+            .tensor([1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0])
+            .to_dtype::<TestDtype>();
+        let cfg = LBfgsConfig {
+            max_iter: 5,
+            history_size: 2,
+            ..Default::default()
+        };
+        let mut opt = LBfgs::new(&t, cfg);
+        for _ in 0..3 {
+            opt.step(&mut t, |t| {
+                let loss = ((t.leaky_trace() - 1.0).square() * scale.clone()).sum();
+                Ok((loss.array(), loss.backward()))
+            })
+            .unwrap();
+            assert!(opt.history.len() <= 2);
+        }
+        assert_eq!(opt.history.len(), 2);
+    }
+
+    #[test]
+    fn test_lbfgs_unused_params() {
+        let dev: TestDevice = Default::default();
+        type Model = (Bias1DConstConfig<2>, Bias1DConstConfig<2>);
+        let mut model = dev.build_module::<TestDtype>(Model::default());
+        model.1.bias = dev.ones();
+        let mut opt = LBfgs::new(&model, Default::default());
+        opt.step(&mut model, |model| {
+            let loss = (model.0.bias.leaky_trace() - 2.0).square().sum();
+            Ok((loss.array(), loss.backward()))
+        })
+        .unwrap();
+        assert_near(model.0.bias.array(), [2.0; 2], 1e-4);
+        assert_close_to_literal!(model.1.bias, [1.0; 2]);
+    }
+}
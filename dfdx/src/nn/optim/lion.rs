@@ -0,0 +1,223 @@
+use std::marker::PhantomData;
+
+use super::param_groups::ParamGroups;
+use crate::{
+    nn::UpdateParams,
+    shapes::{Dtype, Shape},
+    tensor::{Error, Gradients, Storage, Tensor, Tensorlike, UniqueId},
+    tensor_ops::{Device, LionConfig},
+};
+
+/// An implementation of the Lion optimizer from
+/// [Symbolic Discovery of Optimization Algorithms](https://arxiv.org/abs/2302.06675)
+///
+/// Lion only keeps a running average of the gradients, and updates each parameter by the
+/// sign of an interpolation between it and the gradient, so it needs half the memory of [Adam].
+///
+/// [Adam]: crate::nn::optim::Adam
+///
+/// # Example Usage
+/// ```rust
+/// # use dfdx::prelude::*;
+/// # type Model = Tensor<Rank0, f32, Cpu>;
+/// # let dev: Cpu = Default::default();
+/// # let model: Model = dev.zeros();
+/// let mut opt: Lion<Model, f32, Cpu> = optim::Lion::new(&model, LionConfig {
+///     lr: 3e-5,
+///     betas: [0.9, 0.99],
+///     weight_decay: Some(WeightDecay::Decoupled(1.0)),
+/// });
+/// ```
+///
+/// See module level documentation at [crate::nn::optim] for examples of how to actually use an optimizer.
+#[derive(Debug, Clone)]
+pub struct Lion<M, E: Dtype, D: Storage<E>> {
+    /// Hyperparameter configuration
+    pub cfg: LionConfig,
+
+    momentum: Gradients<E, D>,
+
+    param_groups: ParamGroups<LionConfig>,
+
+    marker: PhantomData<*const M>,
+}
+
+impl<M, E: Dtype, D: Storage<E>> Lion<M, E, D> {
+    /// Constructs using hyperparameters from `cfg`.
+    pub fn new(_model: &M, cfg: LionConfig) -> Self {
+        Self {
+            cfg,
+            momentum: Gradients::leaky(),
+            param_groups: Default::default(),
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<M, E: Dtype, D: Device<E>> Lion<M, E, D> {
+    /// Uses `cfg` instead of [Lion::cfg] for the parameters of `model` that `select` returns
+    /// `true` for, given their path & shape. See [Adam::add_param_group()] for details.
+    ///
+    /// [Adam::add_param_group()]: crate::nn::optim::Adam::add_param_group()
+    pub fn add_param_group<F>(&mut self, model: &M, select: F, cfg: LionConfig)
+    where
+        M: UpdateParams<E, D>,
+        F: FnMut(&str, &[usize]) -> bool,
+    {
+        self.param_groups
+            .add(model, select, cfg, self.cfg.lr, cfg.lr);
+    }
+}
+
+impl<M, E: Dtype, D: Storage<E>> super::LearningRate for Lion<M, E, D> {
+    fn lr(&self) -> f64 {
+        self.cfg.lr
+    }
+    fn set_lr(&mut self, lr: f64) {
+        self.cfg.lr = lr;
+        self.param_groups.set_lr(lr, |cfg| &mut cfg.lr);
+    }
+}
+
+impl<M, E: Dtype, D: Device<E>> crate::nn::Optimizer<M, E, D> for Lion<M, E, D> {
+    fn update_tensor<S: Shape>(
+        &mut self,
+        t: &mut Tensor<S, E, D>,
+        gradients: &Gradients<E, D>,
+        missing_params: &mut Vec<UniqueId>,
+    ) -> Result<(), Error> {
+        let g = gradients.get_ref_checked(t);
+        match g {
+            None => missing_params.push(t.id()),
+            Some(g) => {
+                let m = self.momentum.get_or_alloc_mut(t)?;
+                let cfg = self.param_groups.cfg(&t.id(), &self.cfg);
+                cfg.try_update(t, m, g)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(feature = "safetensors")]
+impl<M: UpdateParams<E, D>, E: Dtype, D: Device<E>> super::OptimizerSafeTensors<M, E, D>
+    for Lion<M, E, D>
+{
+    fn write_safetensors(
+        &self,
+        model: &M,
+        location: &str,
+        tensors: &mut Vec<(String, safetensors::Dtype, Vec<usize>, Vec<u8>)>,
+    ) {
+        super::state::write_state(
+            model,
+            &self.momentum,
+            &format!("{location}momentum."),
+            tensors,
+        );
+    }
+
+    fn read_safetensors(
+        &mut self,
+        model: &M,
+        location: &str,
+        tensors: &safetensors::SafeTensors,
+    ) -> Result<(), safetensors::SafeTensorError> {
+        super::state::read_state(
+            model,
+            &mut self.momentum,
+            &format!("{location}momentum."),
+            tensors,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{nn::optim::*, shapes::*, tensor::*, tensor_ops::*, tests::*};
+
+    fn test_matches_expected<const N: usize>(cfg: LionConfig, expected: [[f64; 5]; N]) {
+        let dev: TestDevice = Default::default();
+        let mut t = dev
+            .tensor([-0.5, -0.25, 0.1, 0.6, 1.0])
+            .to_dtype::<TestDtype>();
+        let mut opt = Lion::new(&t, cfg);
+        for e in expected.iter() {
+            let gradients = t.leaky_trace().exp().square().mean().backward();
+            opt.update(&mut t, &gradients).expect("");
+            assert_close_to_literal!(t, e);
+        }
+    }
+
+    #[test]
+    fn test_lion_default() {
+        let cfg = LionConfig::default();
+        #[rustfmt::skip]
+        const EXPECTED: [[f64; 5]; 5] = [
+            [-0.5001, -0.2501, 0.0999, 0.5999, 0.9999],
+            [-0.5002, -0.2502, 0.0998, 0.5998, 0.9998],
+            [-0.5003, -0.2503, 0.0997, 0.5997, 0.9997],
+            [-0.5004, -0.2504, 0.0996, 0.5996, 0.9996],
+            [-0.5005, -0.2505, 0.0995, 0.5995, 0.9995],
+        ];
+        test_matches_expected(cfg, EXPECTED);
+    }
+    #[test]
+    fn test_lion_custom() {
+        let cfg = LionConfig {
+            lr: 1e-2,
+            betas: [0.5, 0.25],
+            weight_decay: None,
+        };
+        #[rustfmt::skip]
+        const EXPECTED: [[f64; 5]; 5] = [
+            [-0.51, -0.26, 0.09, 0.59, 0.99],
+            [-0.52, -0.27, 0.08, 0.58, 0.98],
+            [-0.53, -0.28, 0.07, 0.57, 0.97],
+            [-0.54, -0.29, 0.06, 0.56, 0.96],
+            [-0.55, -0.3, 0.05, 0.55, 0.95],
+        ];
+        test_matches_expected(cfg, EXPECTED);
+    }
+    #[test]
+    fn test_lion_l2_weight_decay() {
+        let cfg = LionConfig {
+            weight_decay: Some(WeightDecay::L2(0.5)),
+            ..Default::default()
+        };
+        #[rustfmt::skip]
+        const EXPECTED: [[f64; 5]; 5] = [
+            [-0.4999, -0.2501, 0.0999, 0.5999, 0.9999],
+            [-0.4998, -0.2502, 0.0998, 0.5998, 0.9998],
+            [-0.4997, -0.2503, 0.0997, 0.5997, 0.9997],
+            [-0.4996, -0.2504, 0.0996, 0.5996, 0.9996],
+            [-0.4995, -0.2505, 0.0995, 0.5995, 0.9995],
+        ];
+        test_matches_expected(cfg, EXPECTED);
+    }
+    #[test]
+    fn test_lion_decoupled_weight_decay() {
+        let cfg = LionConfig {
+            weight_decay: Some(WeightDecay::Decoupled(0.5)),
+            ..Default::default()
+        };
+        #[rustfmt::skip]
+        const EXPECTED: [[f64; 5]; 5] = [
+            [-0.500075, -0.2500875, 0.099895, 0.59987, 0.99985],
+            [-0.50015, -0.250175, 0.099790005, 0.59974001, 0.99970001],
+            [-0.50022499, -0.25026249, 0.099685016, 0.59961002, 0.99955002],
+            [-0.50029998, -0.25034997, 0.099580031, 0.59948004, 0.99940004],
+            [-0.50037496, -0.25043746, 0.099475052, 0.59935006, 0.99925007],
+        ];
+        test_matches_expected(cfg, EXPECTED);
+    }
+
+    #[test]
+    fn test_unused_tensors() {
+        let dev: TestDevice = Default::default();
+        let mut t: Tensor<Rank1<5>, TestDtype, _> = dev.sample_normal();
+        let mut opt = Lion::new(&t, Default::default());
+        opt.update(&mut t, &Gradients::leaky()).expect_err("");
+    }
+}
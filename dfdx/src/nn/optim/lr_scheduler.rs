@@ -0,0 +1,812 @@
+#[cfg(feature = "safetensors")]
+use crate::{
+    nn_traits::{LoadSafeTensors, SaveSafeTensors},
+    safetensors::{Dtype, SafeTensorError, SafeTensors},
+};
+
+/// An optimizer with a learning rate that can be changed between updates, e.g. by a [LrScheduler].
+pub trait LearningRate {
+    /// The learning rate used by the next update.
+    fn lr(&self) -> f64;
+
+    /// Sets the learning rate used by the next update.
+    fn set_lr(&mut self, lr: f64);
+}
+
+/// Computes the learning rate of an optimizer for every step of training. What a step
+/// is (a batch or an epoch) is up to the training loop.
+///
+/// Schedulers don't hold on to the optimizer, instead call [LrScheduler::apply()]
+/// before updating, and [LrScheduler::step()] after:
+///
+/// ```rust
+/// # use dfdx::prelude::*;
+/// # let dev: Cpu = Default::default();
+/// # type Model = Tensor<Rank0, f32, Cpu>;
+/// # let model: Model = dev.zeros();
+/// let mut opt: Sgd<Model, f32, Cpu> = Sgd::new(&model, Default::default());
+/// let mut sched = StepLr::new(&opt, StepLrConfig { step_size: 30, gamma: 0.1 });
+/// for epoch in 0..100 {
+///     sched.apply(&mut opt);
+///     // -- snip training for an epoch --
+///     sched.step();
+/// }
+/// assert!((sched.lr() - 1e-5).abs() < 1e-12);
+/// ```
+///
+/// The state of a scheduler can be saved and loaded with `SaveSafeTensors`/`LoadSafeTensors`
+/// when the `safetensors` feature is enabled.
+pub trait LrScheduler {
+    /// The learning rate for the current step.
+    fn lr(&self) -> f64;
+
+    /// Moves on to the next step.
+    fn step(&mut self);
+
+    /// Sets the learning rate of `opt` to [LrScheduler::lr()].
+    fn apply<O: LearningRate>(&self, opt: &mut O) {
+        opt.set_lr(self.lr());
+    }
+}
+
+/// A learning rate that never changes. Useful as the scheduler after a [LinearWarmup].
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(
+    feature = "safetensors",
+    derive(crate::SaveSafeTensors, crate::LoadSafeTensors)
+)]
+pub struct ConstantLr {
+    #[cfg_attr(feature = "safetensors", serialize)]
+    base_lr: f64,
+}
+
+impl ConstantLr {
+    /// Keeps the current learning rate of `opt`.
+    pub fn new<O: LearningRate>(opt: &O) -> Self {
+        Self { base_lr: opt.lr() }
+    }
+}
+
+impl LrScheduler for ConstantLr {
+    fn lr(&self) -> f64 {
+        self.base_lr
+    }
+    fn step(&mut self) {}
+}
+
+/// Configuration of [StepLr].
+#[derive(Debug, Clone, Copy)]
+pub struct StepLrConfig {
+    /// Number of steps between decays.
+    pub step_size: usize,
+    /// Factor the learning rate is multiplied by every [StepLrConfig::step_size] steps.
+    pub gamma: f64,
+}
+
+/// Multiplies the learning rate by [StepLrConfig::gamma] every [StepLrConfig::step_size] steps.
+///
+/// **Pytorch equivalent**: `torch.optim.lr_scheduler.StepLR`.
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(
+    feature = "safetensors",
+    derive(crate::SaveSafeTensors, crate::LoadSafeTensors)
+)]
+pub struct StepLr {
+    pub cfg: StepLrConfig,
+    #[cfg_attr(feature = "safetensors", serialize)]
+    base_lr: f64,
+    #[cfg_attr(feature = "safetensors", serialize)]
+    step: usize,
+}
+
+impl StepLr {
+    /// Starts decaying from the current learning rate of `opt`.
+    ///
+    /// Panics if `step_size` is 0.
+    pub fn new<O: LearningRate>(opt: &O, cfg: StepLrConfig) -> Self {
+        assert!(cfg.step_size > 0, "step_size must be positive");
+        Self {
+            cfg,
+            base_lr: opt.lr(),
+            step: 0,
+        }
+    }
+}
+
+impl LrScheduler for StepLr {
+    fn lr(&self) -> f64 {
+        self.base_lr * self.cfg.gamma.powf((self.step / self.cfg.step_size) as f64)
+    }
+    fn step(&mut self) {
+        self.step += 1;
+    }
+}
+
+/// Configuration of [ExponentialLr].
+#[derive(Debug, Clone, Copy)]
+pub struct ExponentialLrConfig {
+    /// Factor the learning rate is multiplied by every step.
+    pub gamma: f64,
+}
+
+/// Multiplies the learning rate by [ExponentialLrConfig::gamma] every step.
+///
+/// **Pytorch equivalent**: `torch.optim.lr_scheduler.ExponentialLR`.
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(
+    feature = "safetensors",
+    derive(crate::SaveSafeTensors, crate::LoadSafeTensors)
+)]
+pub struct ExponentialLr {
+    pub cfg: ExponentialLrConfig,
+    #[cfg_attr(feature = "safetensors", serialize)]
+    base_lr: f64,
+    #[cfg_attr(feature = "safetensors", serialize)]
+    step: usize,
+}
+
+impl ExponentialLr {
+    /// Starts decaying from the current learning rate of `opt`.
+    pub fn new<O: LearningRate>(opt: &O, cfg: ExponentialLrConfig) -> Self {
+        Self {
+            cfg,
+            base_lr: opt.lr(),
+            step: 0,
+        }
+    }
+}
+
+impl LrScheduler for ExponentialLr {
+    fn lr(&self) -> f64 {
+        self.base_lr * self.cfg.gamma.powf(self.step as f64)
+    }
+    fn step(&mut self) {
+        self.step += 1;
+    }
+}
+
+/// Configuration of [CosineAnnealingWarmRestarts].
+#[derive(Debug, Clone, Copy)]
+pub struct CosineAnnealingWarmRestartsConfig {
+    /// Number of steps until the first restart.
+    pub t_0: usize,
+    /// Factor the number of steps between restarts grows by after each restart.
+    pub t_mult: usize,
+    /// The learning rate at the end of each cycle.
+    pub eta_min: f64,
+}
+
+/// Anneals the learning rate from its initial value to [CosineAnnealingWarmRestartsConfig::eta_min]
+/// along a cosine curve, and then restarts from the initial value. Described in
+/// [SGDR: Stochastic Gradient Descent with Warm Restarts](https://arxiv.org/abs/1608.03983).
+///
+/// With `t_mult` set to 1 every cycle is `t_0` steps long. Set `t_0` to the total number of
+/// steps for plain cosine annealing.
+///
+/// **Pytorch equivalent**: `torch.optim.lr_scheduler.CosineAnnealingWarmRestarts`.
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(
+    feature = "safetensors",
+    derive(crate::SaveSafeTensors, crate::LoadSafeTensors)
+)]
+pub struct CosineAnnealingWarmRestarts {
+    pub cfg: CosineAnnealingWarmRestartsConfig,
+    #[cfg_attr(feature = "safetensors", serialize)]
+    base_lr: f64,
+    #[cfg_attr(feature = "safetensors", serialize)]
+    step: usize,
+}
+
+impl CosineAnnealingWarmRestarts {
+    /// Anneals from the current learning rate of `opt`.
+    ///
+    /// Panics if `t_0` is 0 or `t_mult` is less than 1.
+    pub fn new<O: LearningRate>(opt: &O, cfg: CosineAnnealingWarmRestartsConfig) -> Self {
+        assert!(cfg.t_0 > 0, "t_0 must be positive");
+        assert!(cfg.t_mult >= 1, "t_mult must be at least 1");
+        Self {
+            cfg,
+            base_lr: opt.lr(),
+            step: 0,
+        }
+    }
+}
+
+impl LrScheduler for CosineAnnealingWarmRestarts {
+    fn lr(&self) -> f64 {
+        let (t_cur, t_i) = if self.cfg.t_mult == 1 {
+            (self.step % self.cfg.t_0, self.cfg.t_0)
+        } else {
+            let (mut t_cur, mut t_i) = (self.step, self.cfg.t_0);
+            while t_cur >= t_i {
+                t_cur -= t_i;
+                t_i *= self.cfg.t_mult;
+            }
+            (t_cur, t_i)
+        };
+        let cos = (core::f64::consts::PI * t_cur as f64 / t_i as f64).cos();
+        self.cfg.eta_min + (self.base_lr - self.cfg.eta_min) * (1.0 + cos) / 2.0
+    }
+    fn step(&mut self) {
+        self.step += 1;
+    }
+}
+
+/// Configuration of [LinearWarmup].
+#[derive(Debug, Clone, Copy)]
+pub struct LinearWarmupConfig {
+    /// Number of steps to warm up for.
+    pub warmup_steps: usize,
+    /// The learning rate at the first step, as a fraction of the scheduled learning rate.
+    pub start_factor: f64,
+}
+
+/// Linearly increases the learning rate from `start_factor` times the learning rate of
+/// [LinearWarmup::scheduler] to the full learning rate over [LinearWarmupConfig::warmup_steps].
+/// After that, [LinearWarmup::scheduler] takes over, starting from its first step.
+///
+/// ```rust
+/// # use dfdx::prelude::*;
+/// # let dev: Cpu = Default::default();
+/// # type Model = Tensor<Rank0, f32, Cpu>;
+/// # let model: Model = dev.zeros();
+/// let opt: Adam<Model, f32, Cpu> = Adam::new(&model, Default::default());
+/// // warm up for 100 steps, then anneal for 1000 steps
+/// let cosine = CosineAnnealingWarmRestarts::new(&opt, CosineAnnealingWarmRestartsConfig {
+///     t_0: 1000,
+///     t_mult: 1,
+///     eta_min: 0.0,
+/// });
+/// let sched = LinearWarmup::new(cosine, LinearWarmupConfig {
+///     warmup_steps: 100,
+///     start_factor: 0.0,
+/// });
+/// ```
+///
+/// Use [ConstantLr] as the scheduler to keep the learning rate after warming up.
+#[derive(Debug, Clone, Copy)]
+pub struct LinearWarmup<S> {
+    pub cfg: LinearWarmupConfig,
+    pub scheduler: S,
+    step: usize,
+}
+
+impl<S: LrScheduler> LinearWarmup<S> {
+    pub fn new(scheduler: S, cfg: LinearWarmupConfig) -> Self {
+        Self {
+            cfg,
+            scheduler,
+            step: 0,
+        }
+    }
+}
+
+impl<S: LrScheduler> LrScheduler for LinearWarmup<S> {
+    fn lr(&self) -> f64 {
+        let lr = self.scheduler.lr();
+        if self.step < self.cfg.warmup_steps {
+            let pct = self.step as f64 / self.cfg.warmup_steps as f64;
+            lr * (self.cfg.start_factor + (1.0 - self.cfg.start_factor) * pct)
+        } else {
+            lr
+        }
+    }
+    fn step(&mut self) {
+        if self.step < self.cfg.warmup_steps {
+            self.step += 1;
+        } else {
+            self.scheduler.step();
+        }
+    }
+}
+
+#[cfg(feature = "safetensors")]
+impl<S: SaveSafeTensors> SaveSafeTensors for LinearWarmup<S> {
+    fn write_safetensors(
+        &self,
+        location: &str,
+        tensors: &mut Vec<(String, Dtype, Vec<usize>, Vec<u8>)>,
+    ) {
+        self.step
+            .write_safetensors(&format!("{location}step"), tensors);
+        self.scheduler
+            .write_safetensors(&format!("{location}scheduler."), tensors);
+    }
+}
+
+#[cfg(feature = "safetensors")]
+impl<S: LoadSafeTensors> LoadSafeTensors for LinearWarmup<S> {
+    fn read_safetensors(
+        &mut self,
+        location: &str,
+        tensors: &SafeTensors,
+    ) -> Result<(), SafeTensorError> {
+        self.step
+            .read_safetensors(&format!("{location}step"), tensors)?;
+        self.scheduler
+            .read_safetensors(&format!("{location}scheduler."), tensors)
+    }
+}
+
+/// Configuration of [OneCycleLr].
+#[derive(Debug, Clone, Copy)]
+pub struct OneCycleLrConfig {
+    /// The highest learning rate in the cycle.
+    pub max_lr: f64,
+    /// Number of steps in the cycle.
+    pub total_steps: usize,
+    /// Fraction of the cycle spent increasing the learning rate. Pytorch defaults to `0.3`.
+    pub pct_start: f64,
+    /// The initial learning rate is `max_lr / div_factor`. Pytorch defaults to `25.0`.
+    pub div_factor: f64,
+    /// The final learning rate is `max_lr / div_factor / final_div_factor`.
+    /// Pytorch defaults to `1e4`.
+    pub final_div_factor: f64,
+}
+
+/// The 1cycle policy from [Super-Convergence: Very Fast Training of Neural Networks
+/// Using Large Learning Rates](https://arxiv.org/abs/1708.07120): anneals the learning
+/// rate up to [OneCycleLrConfig::max_lr], and then down to far below the initial learning
+/// rate, both along a cosine curve.
+///
+/// The learning rate stays at its final value after [OneCycleLrConfig::total_steps].
+/// Unlike pytorch, momentum is not cycled.
+///
+/// **Pytorch equivalent**: `torch.optim.lr_scheduler.OneCycleLR` with `anneal_strategy="cos"`
+/// and `cycle_momentum=False`.
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(
+    feature = "safetensors",
+    derive(crate::SaveSafeTensors, crate::LoadSafeTensors)
+)]
+pub struct OneCycleLr {
+    pub cfg: OneCycleLrConfig,
+    #[cfg_attr(feature = "safetensors", serialize)]
+    step: usize,
+}
+
+impl OneCycleLr {
+    /// The learning rate of the optimizer is ignored, the cycle is defined by `cfg` alone.
+    ///
+    /// Panics if `pct_start` is not in `[0, 1]`, or if the warmup is shorter than one step
+    /// (i.e. `pct_start * total_steps <= 1`).
+    pub fn new(cfg: OneCycleLrConfig) -> Self {
+        assert!(cfg.total_steps > 1, "total_steps must be at least 2");
+        assert!(
+            (0.0..=1.0).contains(&cfg.pct_start),
+            "pct_start must be in [0, 1]"
+        );
+        assert!(
+            cfg.pct_start * cfg.total_steps as f64 - 1.0 > 0.0,
+            "pct_start * total_steps must be greater than 1"
+        );
+        Self { cfg, step: 0 }
+    }
+}
+
+impl LrScheduler for OneCycleLr {
+    fn lr(&self) -> f64 {
+        let cfg = &self.cfg;
+        let initial_lr = cfg.max_lr / cfg.div_factor;
+        let min_lr = initial_lr / cfg.final_div_factor;
+        let end_warmup = cfg.pct_start * cfg.total_steps as f64 - 1.0;
+        let end = (cfg.total_steps - 1) as f64;
+
+        let step = (self.step as f64).min(end);
+        let (start_lr, end_lr, pct) = if step <= end_warmup {
+            (initial_lr, cfg.max_lr, step / end_warmup)
+        } else {
+            let pct = (step - end_warmup) / (end - end_warmup);
+            (cfg.max_lr, min_lr, pct)
+        };
+        let cos = (core::f64::consts::PI * pct).cos();
+        end_lr + (start_lr - end_lr) * (1.0 + cos) / 2.0
+    }
+    fn step(&mut self) {
+        self.step += 1;
+    }
+}
+
+/// Whether lower or higher values of the metric passed to [ReduceLrOnPlateau::step()] are better.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PlateauMode {
+    /// E.g. for a loss.
+    Min,
+    /// E.g. for an accuracy.
+    Max,
+}
+
+/// Configuration of [ReduceLrOnPlateau].
+#[derive(Debug, Clone, Copy)]
+pub struct ReduceLrOnPlateauConfig {
+    /// Defaults to [PlateauMode::Min].
+    pub mode: PlateauMode,
+    /// Factor the learning rate is multiplied by when reduced. Defaults to `0.1`.
+    pub factor: f64,
+    /// Number of steps without improvement to wait for before reducing. Defaults to `10`.
+    pub patience: usize,
+    /// Relative amount the metric must improve by to count as an improvement. Defaults to `1e-4`.
+    pub threshold: f64,
+    /// Number of steps to wait for after reducing, before counting steps without
+    /// improvement again. Defaults to `0`.
+    pub cooldown: usize,
+    /// The learning rate is never reduced below this. Defaults to `0.0`.
+    pub min_lr: f64,
+}
+
+impl Default for ReduceLrOnPlateauConfig {
+    fn default() -> Self {
+        Self {
+            mode: PlateauMode::Min,
+            factor: 0.1,
+            patience: 10,
+            threshold: 1e-4,
+            cooldown: 0,
+            min_lr: 0.0,
+        }
+    }
+}
+
+/// Reduces the learning rate when a metric (e.g. the validation loss) stops improving.
+///
+/// This is not a [LrScheduler], because [ReduceLrOnPlateau::step()] takes the metric:
+///
+/// ```rust
+/// # use dfdx::prelude::*;
+/// # let dev: Cpu = Default::default();
+/// # type Model = Tensor<Rank0, f32, Cpu>;
+/// # let model: Model = dev.zeros();
+/// let mut opt: Sgd<Model, f32, Cpu> = Sgd::new(&model, Default::default());
+/// let mut sched = ReduceLrOnPlateau::new(&opt, Default::default());
+/// for epoch in 0..100 {
+///     sched.apply(&mut opt);
+///     // -- snip training for an epoch --
+///     let valid_loss = 1.0;
+///     sched.step(valid_loss);
+/// }
+/// ```
+///
+/// **Pytorch equivalent**: `torch.optim.lr_scheduler.ReduceLROnPlateau` with `threshold_mode="rel"`.
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(
+    feature = "safetensors",
+    derive(crate::SaveSafeTensors, crate::LoadSafeTensors)
+)]
+pub struct ReduceLrOnPlateau {
+    pub cfg: ReduceLrOnPlateauConfig,
+    #[cfg_attr(feature = "safetensors", serialize)]
+    lr: f64,
+    #[cfg_attr(feature = "safetensors", serialize)]
+    best: f64,
+    #[cfg_attr(feature = "safetensors", serialize)]
+    num_bad_steps: usize,
+    #[cfg_attr(feature = "safetensors", serialize)]
+    cooldown_counter: usize,
+}
+
+impl ReduceLrOnPlateau {
+    /// Starts from the current learning rate of `opt`.
+    pub fn new<O: LearningRate>(opt: &O, cfg: ReduceLrOnPlateauConfig) -> Self {
+        let best = match cfg.mode {
+            PlateauMode::Min => f64::INFINITY,
+            PlateauMode::Max => f64::NEG_INFINITY,
+        };
+        Self {
+            cfg,
+            lr: opt.lr(),
+            best,
+            num_bad_steps: 0,
+            cooldown_counter: 0,
+        }
+    }
+
+    /// The current learning rate.
+    pub fn lr(&self) -> f64 {
+        self.lr
+    }
+
+    /// Records the metric for this step, and reduces the learning rate if it
+    /// hasn't improved for more than [ReduceLrOnPlateauConfig::patience] steps.
+    pub fn step(&mut self, metric: f64) {
+        let improved = match self.cfg.mode {
+            PlateauMode::Min => metric < self.best * (1.0 - self.cfg.threshold),
+            PlateauMode::Max => metric > self.best * (1.0 + self.cfg.threshold),
+        };
+        if improved {
+            self.best = metric;
+            self.num_bad_steps = 0;
+        } else {
+            self.num_bad_steps += 1;
+        }
+
+        if self.cooldown_counter > 0 {
+            self.cooldown_counter -= 1;
+            self.num_bad_steps = 0;
+        }
+
+        if self.num_bad_steps > self.cfg.patience {
+            self.lr = (self.lr * self.cfg.factor).max(self.cfg.min_lr);
+            self.cooldown_counter = self.cfg.cooldown;
+            self.num_bad_steps = 0;
+        }
+    }
+
+    /// Sets the learning rate of `opt` to [ReduceLrOnPlateau::lr()].
+    pub fn apply<O: LearningRate>(&self, opt: &mut O) {
+        opt.set_lr(self.lr);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{prelude::*, tests::*};
+
+    fn lrs<S: LrScheduler>(mut sched: S, n: usize) -> Vec<f64> {
+        let mut lrs = Vec::new();
+        for _ in 0..n {
+            lrs.push(sched.lr());
+            sched.step();
+        }
+        lrs
+    }
+
+    fn assert_lrs_eq(a: &[f64], b: &[f64]) {
+        assert_eq!(a.len(), b.len());
+        for (a, b) in a.iter().zip(b.iter()) {
+            assert!((a - b).abs() < 1e-9, "{a:?} != {b:?}");
+        }
+    }
+
+    fn sgd(lr: f64) -> Sgd<(), TestDtype, TestDevice> {
+        Sgd::new(
+            &(),
+            SgdConfig {
+                lr,
+                ..Default::default()
+            },
+        )
+    }
+
+    #[test]
+    fn test_step_lr() {
+        let sched = StepLr::new(
+            &sgd(1.0),
+            StepLrConfig {
+                step_size: 2,
+                gamma: 0.5,
+            },
+        );
+        assert_lrs_eq(&lrs(sched, 6), &[1.0, 1.0, 0.5, 0.5, 0.25, 0.25]);
+    }
+
+    #[test]
+    #[should_panic = "step_size must be positive"]
+    fn test_step_lr_zero_step_size() {
+        StepLr::new(
+            &sgd(1.0),
+            StepLrConfig {
+                step_size: 0,
+                gamma: 0.5,
+            },
+        );
+    }
+
+    #[test]
+    fn test_exponential_lr() {
+        let sched = ExponentialLr::new(&sgd(2.0), ExponentialLrConfig { gamma: 0.5 });
+        assert_lrs_eq(&lrs(sched, 4), &[2.0, 1.0, 0.5, 0.25]);
+    }
+
+    #[test]
+    fn test_cosine_annealing_warm_restarts() {
+        let cfg = CosineAnnealingWarmRestartsConfig {
+            t_0: 2,
+            t_mult: 2,
+            eta_min: 0.0,
+        };
+        let sched = CosineAnnealingWarmRestarts::new(&sgd(1.0), cfg);
+        assert_lrs_eq(
+            &lrs(sched, 7),
+            &[
+                1.0,
+                0.5,
+                1.0,
+                0.8535533905932737,
+                0.5,
+                0.14644660940672627,
+                1.0,
+            ],
+        );
+
+        let cfg = CosineAnnealingWarmRestartsConfig {
+            t_0: 2,
+            t_mult: 1,
+            eta_min: 0.5,
+        };
+        let sched = CosineAnnealingWarmRestarts::new(&sgd(1.0), cfg);
+        assert_lrs_eq(&lrs(sched, 5), &[1.0, 0.75, 1.0, 0.75, 1.0]);
+    }
+
+    #[test]
+    #[should_panic = "t_0 must be positive"]
+    fn test_cosine_annealing_warm_restarts_zero_t_0() {
+        let cfg = CosineAnnealingWarmRestartsConfig {
+            t_0: 0,
+            t_mult: 1,
+            eta_min: 0.0,
+        };
+        CosineAnnealingWarmRestarts::new(&sgd(1.0), cfg);
+    }
+
+    #[test]
+    #[should_panic = "t_mult must be at least 1"]
+    fn test_cosine_annealing_warm_restarts_zero_t_mult() {
+        let cfg = CosineAnnealingWarmRestartsConfig {
+            t_0: 2,
+            t_mult: 0,
+            eta_min: 0.0,
+        };
+        CosineAnnealingWarmRestarts::new(&sgd(1.0), cfg);
+    }
+
+    #[test]
+    fn test_linear_warmup() {
+        let cfg = LinearWarmupConfig {
+            warmup_steps: 4,
+            start_factor: 0.25,
+        };
+        let sched = LinearWarmup::new(ConstantLr::new(&sgd(1.0)), cfg);
+        assert_lrs_eq(&lrs(sched, 6), &[0.25, 0.4375, 0.625, 0.8125, 1.0, 1.0]);
+
+        // the wrapped scheduler starts after warming up
+        let cfg = LinearWarmupConfig {
+            warmup_steps: 2,
+            start_factor: 0.0,
+        };
+        let step = StepLr::new(
+            &sgd(1.0),
+            StepLrConfig {
+                step_size: 1,
+                gamma: 0.5,
+            },
+        );
+        let sched = LinearWarmup::new(step, cfg);
+        assert_lrs_eq(&lrs(sched, 5), &[0.0, 0.5, 1.0, 0.5, 0.25]);
+    }
+
+    #[test]
+    fn test_one_cycle_lr() {
+        let sched = OneCycleLr::new(OneCycleLrConfig {
+            max_lr: 1.0,
+            total_steps: 10,
+            pct_start: 0.3,
+            div_factor: 25.0,
+            final_div_factor: 1e4,
+        });
+        // from torch.optim.lr_scheduler.OneCycleLR
+        assert_lrs_eq(
+            &lrs(sched, 11),
+            &[
+                0.04,
+                0.52,
+                1.0,
+                0.9504846320134737,
+                0.8117456539497631,
+                0.6112620219362893,
+                0.38874197806371075,
+                0.18825834605023697,
+                0.049519367986526286,
+                4e-06,
+                4e-06,
+            ],
+        );
+    }
+
+    #[test]
+    #[should_panic = "pct_start * total_steps must be greater than 1"]
+    fn test_one_cycle_lr_short_warmup() {
+        // a warmup of a single step would divide by zero
+        OneCycleLr::new(OneCycleLrConfig {
+            max_lr: 1.0,
+            total_steps: 10,
+            pct_start: 0.1,
+            div_factor: 25.0,
+            final_div_factor: 1e4,
+        });
+    }
+
+    #[test]
+    #[should_panic = "pct_start must be in [0, 1]"]
+    fn test_one_cycle_lr_pct_start_out_of_range() {
+        OneCycleLr::new(OneCycleLrConfig {
+            max_lr: 1.0,
+            total_steps: 10,
+            pct_start: 1.5,
+            div_factor: 25.0,
+            final_div_factor: 1e4,
+        });
+    }
+
+    #[test]
+    fn test_reduce_lr_on_plateau() {
+        let cfg = ReduceLrOnPlateauConfig {
+            factor: 0.5,
+            patience: 1,
+            cooldown: 1,
+            ..Default::default()
+        };
+        let mut sched = ReduceLrOnPlateau::new(&sgd(1.0), cfg);
+        let mut lrs = Vec::new();
+        for metric in [1.0, 0.9, 0.95, 0.96, 0.97, 0.98, 0.99, 1.0] {
+            sched.step(metric);
+            lrs.push(sched.lr());
+        }
+        assert_lrs_eq(&lrs, &[1.0, 1.0, 1.0, 0.5, 0.5, 0.5, 0.25, 0.25]);
+
+        let cfg = ReduceLrOnPlateauConfig {
+            mode: PlateauMode::Max,
+            patience: 0,
+            min_lr: 0.05,
+            ..Default::default()
+        };
+        let mut sched = ReduceLrOnPlateau::new(&sgd(1.0), cfg);
+        for metric in [0.5, 0.6, 0.6, 0.6, 0.7] {
+            sched.step(metric);
+        }
+        assert_lrs_eq(&[sched.lr()], &[0.05]);
+    }
+
+    #[test]
+    fn test_scheduler_sets_lr() {
+        let dev: TestDevice = Default::default();
+        let mut t: Tensor<Rank1<3>, TestDtype, _> = dev.zeros();
+        let mut opt = Adam::new(&t, Default::default());
+        let mut sched = ExponentialLr::new(&opt, ExponentialLrConfig { gamma: 0.1 });
+        for _ in 0..2 {
+            sched.apply(&mut opt);
+            let grads = t.leaky_trace().sum().backward();
+            opt.update(&mut t, &grads).unwrap();
+            sched.step();
+        }
+        // the first step of adam moves by lr, the second by lr * 0.1
+        assert_close_to_literal!(t, [-1.1e-3; 3]);
+        sched.apply(&mut opt);
+        assert_lrs_eq(&[opt.lr()], &[1e-5]);
+    }
+
+    #[cfg(feature = "safetensors")]
+    #[test]
+    fn test_save_load_scheduler() {
+        let cfg = LinearWarmupConfig {
+            warmup_steps: 2,
+            start_factor: 0.0,
+        };
+        let step = StepLr::new(
+            &sgd(1.0),
+            StepLrConfig {
+                step_size: 1,
+                gamma: 0.5,
+            },
+        );
+        let mut sched = LinearWarmup::new(step, cfg);
+        for _ in 0..3 {
+            sched.step();
+        }
+
+        let file = tempfile::NamedTempFile::new().unwrap();
+        sched.save_safetensors(file.path()).unwrap();
+
+        let step = StepLr::new(
+            &sgd(2.0),
+            StepLrConfig {
+                step_size: 1,
+                gamma: 0.5,
+            },
+        );
+        let mut loaded = LinearWarmup::new(step, cfg);
+        loaded.load_safetensors(file.path()).unwrap();
+        assert_eq!(loaded.lr(), 0.5);
+        assert_lrs_eq(&lrs(loaded, 3), &lrs(sched, 3));
+    }
+}
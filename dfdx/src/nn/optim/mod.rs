@@ -7,6 +7,17 @@
 //! - [Sgd::new()] with [SgdConfig]
 //! - [Adam::new()] with [AdamConfig]
 //! - [RMSprop::new()] with [RMSpropConfig]
+//! - [AdaGrad::new()] with [AdaGradConfig]
+//! - [Adadelta::new()] with [AdadeltaConfig]
+//! - [Adafactor::new()] with [AdafactorConfig]
+//! - [Adamax::new()] with [AdamaxConfig]
+//! - [Lamb::new()] with [LambConfig]
+//! - [Lars::new()] with [LarsConfig]
+//! - [LBfgs::new()] with [LBfgsConfig], which is updated with [LBfgs::step()] instead of
+//!   [crate::nn::Optimizer::update()]
+//! - [Lion::new()] with [LionConfig]
+//! - [NAdam::new()] with [NAdamConfig]
+//! - [RAdam::new()] with [RAdamConfig]
 //!
 //! # Updating network parameters
 //!
@@ -30,14 +41,78 @@
 //! opt.update(&mut model, &grads);
 //! model.zero_grads(&mut grads);
 //! ```
+//!
+//! # Parameter groups
+//!
+//! Different parameters can be optimized with different hyperparameters, e.g. no weight decay
+//! for biases, see [Adam::add_param_group()].
+//!
+//! # Learning rate schedules
+//!
+//! The learning rate of an optimizer can be changed between updates with a [LrScheduler]
+//! (or [ReduceLrOnPlateau]), see [LrScheduler] for how to use one:
+//! - [StepLr] and [ExponentialLr] for decaying the learning rate
+//! - [CosineAnnealingWarmRestarts] for cosine annealing, with or without restarts
+//! - [LinearWarmup] for warming up before another schedule
+//! - [OneCycleLr] for the 1cycle policy
+//! - [ReduceLrOnPlateau] for reducing the learning rate when a metric stops improving
+//!
+//! # Averaging weights
+//!
+//! [Ema] keeps an exponential moving average of the parameters of a model, which is updated
+//! after each optimizer step and can be swapped in for evaluation.
+//!
+//! # Checkpointing
+//!
+//! With the `safetensors` feature, the state of an optimizer can be saved and loaded along with
+//! the model, see `OptimizerSafeTensors`.
 
+mod adadelta;
+mod adafactor;
+mod adagrad;
 mod adam;
+mod adamax;
+mod ema;
+mod lamb;
+mod lars;
+mod lbfgs;
+mod lion;
+mod lr_scheduler;
+mod nadam;
+mod param_groups;
+mod radam;
 mod rmsprop;
 mod sgd;
+#[cfg(feature = "safetensors")]
+mod state;
 
+pub use adadelta::Adadelta;
+pub use adafactor::Adafactor;
+pub use adagrad::AdaGrad;
 pub use adam::Adam;
+pub use adamax::Adamax;
+pub use ema::{Ema, EmaConfig, EmaWarmup};
+pub use lamb::Lamb;
+pub use lars::Lars;
+pub use lbfgs::{LBfgs, LBfgsConfig, LineSearch};
+pub use lion::Lion;
+pub use lr_scheduler::{
+    ConstantLr, CosineAnnealingWarmRestarts, CosineAnnealingWarmRestartsConfig, ExponentialLr,
+    ExponentialLrConfig, LearningRate, LinearWarmup, LinearWarmupConfig, LrScheduler, OneCycleLr,
+    OneCycleLrConfig, PlateauMode, ReduceLrOnPlateau, ReduceLrOnPlateauConfig, StepLr,
+    StepLrConfig,
+};
+pub use nadam::NAdam;
+pub use param_groups::with_path;
+pub use radam::RAdam;
 pub use rmsprop::RMSprop;
 pub use sgd::Sgd;
+#[cfg(feature = "safetensors")]
+pub use state::OptimizerSafeTensors;
 // re-exports
 pub use super::Optimizer;
-pub use crate::tensor_ops::{AdamConfig, Momentum, RMSpropConfig, SgdConfig, WeightDecay};
+pub use crate::tensor_ops::{
+    AdaGradConfig, AdadeltaConfig, AdafactorConfig, AdamConfig, AdamaxConfig, LambConfig,
+    LarsConfig, LionConfig, Momentum, NAdamConfig, RAdamConfig, RMSpropConfig, SgdConfig,
+    WeightDecay,
+};
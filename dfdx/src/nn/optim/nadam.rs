@@ -0,0 +1,278 @@
+use std::collections::HashMap;
+use std::marker::PhantomData;
+
+use super::param_groups::ParamGroups;
+use crate::{
+    nn::UpdateParams,
+    shapes::{Dtype, Shape},
+    tensor::{Error, Gradients, Storage, Tensor, Tensorlike, UniqueId},
+    tensor_ops::{Device, NAdamConfig},
+};
+
+/// An implementation of the NAdam optimizer, Adam with Nesterov momentum, from
+/// [Incorporating Nesterov Momentum into Adam](https://openreview.net/forum?id=OM0jvwB8jIp57ZJjtNEZ)
+///
+/// # Example Usage
+/// ```rust
+/// # use dfdx::prelude::*;
+/// # type Model = Tensor<Rank0, f32, Cpu>;
+/// # let dev: Cpu = Default::default();
+/// # let model: Model = dev.zeros();
+/// let mut opt: NAdam<Model, f32, Cpu> = optim::NAdam::new(&model, NAdamConfig {
+///     lr: 2e-3,
+///     betas: [0.9, 0.999],
+///     eps: 1e-8,
+///     momentum_decay: 4e-3,
+///     weight_decay: Some(WeightDecay::Decoupled(1e-2)),
+/// });
+/// ```
+///
+/// See module level documentation at [crate::nn::optim] for examples of how to actually use an optimizer.
+#[derive(Debug, Clone)]
+pub struct NAdam<M, E: Dtype, D: Storage<E>> {
+    /// Hyperparameter configuration
+    pub cfg: NAdamConfig,
+
+    t: i32,
+    moment1: Gradients<E, D>,
+    moment2: Gradients<E, D>,
+    /// Product of the momentum coefficients of each parameter so far.
+    mu_product: HashMap<UniqueId, f64>,
+
+    param_groups: ParamGroups<NAdamConfig>,
+
+    marker: PhantomData<*const M>,
+}
+
+impl<M, E: Dtype, D: Storage<E>> NAdam<M, E, D> {
+    /// Constructs using hyperparameters from `cfg`.
+    pub fn new(_model: &M, cfg: NAdamConfig) -> Self {
+        Self {
+            cfg,
+            t: 0,
+            moment1: Gradients::leaky(),
+            moment2: Gradients::leaky(),
+            mu_product: HashMap::new(),
+            param_groups: Default::default(),
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<M, E: Dtype, D: Device<E>> NAdam<M, E, D> {
+    /// Uses `cfg` instead of [NAdam::cfg] for the parameters of `model` that `select` returns
+    /// `true` for, given their path & shape. See [Adam::add_param_group()] for details.
+    ///
+    /// [Adam::add_param_group()]: crate::nn::optim::Adam::add_param_group()
+    pub fn add_param_group<F>(&mut self, model: &M, select: F, cfg: NAdamConfig)
+    where
+        M: UpdateParams<E, D>,
+        F: FnMut(&str, &[usize]) -> bool,
+    {
+        self.param_groups
+            .add(model, select, cfg, self.cfg.lr, cfg.lr);
+    }
+}
+
+impl<M, E: Dtype, D: Storage<E>> super::LearningRate for NAdam<M, E, D> {
+    fn lr(&self) -> f64 {
+        self.cfg.lr
+    }
+    fn set_lr(&mut self, lr: f64) {
+        self.cfg.lr = lr;
+        self.param_groups.set_lr(lr, |cfg| &mut cfg.lr);
+    }
+}
+
+impl<M, E: Dtype, D: Device<E>> crate::nn::Optimizer<M, E, D> for NAdam<M, E, D> {
+    fn update_tensor<S: Shape>(
+        &mut self,
+        t: &mut Tensor<S, E, D>,
+        gradients: &Gradients<E, D>,
+        missing_params: &mut Vec<UniqueId>,
+    ) -> Result<(), Error> {
+        let g = gradients.get_ref_checked(t);
+        match g {
+            None => missing_params.push(t.id()),
+            Some(g) => {
+                let m_t = self.moment1.get_or_alloc_mut(t)?;
+                let v_t = self.moment2.get_or_alloc_mut(t)?;
+                let mu_product = self.mu_product.entry(t.id()).or_insert(1.0);
+                let cfg = self.param_groups.cfg(&t.id(), &self.cfg);
+                cfg.try_update(self.t, mu_product, t, m_t, v_t, g)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn update(&mut self, module: &mut M, gradients: &Gradients<E, D>) -> Result<(), Error>
+    where
+        M: crate::nn::UpdateParams<E, D>,
+    {
+        self.t = self.t.checked_add(1).unwrap();
+
+        // NOTE: the rest of this is identical to default implementation of update.
+        let mut missing_tensors = Vec::new();
+        module.try_update_params(self, gradients, &mut missing_tensors)?;
+        if missing_tensors.is_empty() {
+            Ok(())
+        } else {
+            Err(Error::UnusedTensors(missing_tensors))
+        }
+    }
+}
+
+#[cfg(feature = "safetensors")]
+impl<M: UpdateParams<E, D>, E: Dtype, D: Device<E>> super::OptimizerSafeTensors<M, E, D>
+    for NAdam<M, E, D>
+{
+    fn write_safetensors(
+        &self,
+        model: &M,
+        location: &str,
+        tensors: &mut Vec<(String, safetensors::Dtype, Vec<usize>, Vec<u8>)>,
+    ) {
+        crate::nn::SaveSafeTensors::write_safetensors(&self.t, &format!("{location}t"), tensors);
+        super::state::write_state(
+            model,
+            &self.moment1,
+            &format!("{location}moment1."),
+            tensors,
+        );
+        super::state::write_state(
+            model,
+            &self.moment2,
+            &format!("{location}moment2."),
+            tensors,
+        );
+        super::state::write_scalar_state(
+            model,
+            &self.mu_product,
+            &format!("{location}mu_product."),
+            tensors,
+        );
+    }
+
+    fn read_safetensors(
+        &mut self,
+        model: &M,
+        location: &str,
+        tensors: &safetensors::SafeTensors,
+    ) -> Result<(), safetensors::SafeTensorError> {
+        crate::nn::LoadSafeTensors::read_safetensors(
+            &mut self.t,
+            &format!("{location}t"),
+            tensors,
+        )?;
+        super::state::read_state(
+            model,
+            &mut self.moment1,
+            &format!("{location}moment1."),
+            tensors,
+        )?;
+        super::state::read_state(
+            model,
+            &mut self.moment2,
+            &format!("{location}moment2."),
+            tensors,
+        )?;
+        super::state::read_scalar_state(
+            model,
+            &mut self.mu_product,
+            &format!("{location}mu_product."),
+            tensors,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{nn::optim::*, shapes::*, tensor::*, tensor_ops::*, tests::*};
+
+    fn test_matches_expected<const N: usize>(cfg: NAdamConfig, expected: [[f64; 5]; N]) {
+        let dev: TestDevice = Default::default();
+        let mut t = dev
+            .tensor([-0.5, -0.25, 0.1, 0.6, 1.0])
+            .to_dtype::<TestDtype>();
+        let mut opt = NAdam::new(&t, cfg);
+        for e in expected.iter() {
+            let gradients = t.leaky_trace().exp().square().mean().backward();
+            opt.update(&mut t, &gradients).expect("");
+            assert_close_to_literal!(t, e);
+        }
+    }
+
+    #[test]
+    fn test_nadam_default() {
+        let cfg = NAdamConfig::default();
+        #[rustfmt::skip]
+        const EXPECTED: [[f64; 5]; 5] = [
+            [-0.5021129, -0.2521129, 0.097887096, 0.5978871, 0.9978871],
+            [-0.50367734, -0.25367734, 0.096322665, 0.59632266, 0.99632266],
+            [-0.50513747, -0.25513747, 0.094862525, 0.59486252, 0.99486252],
+            [-0.50659392, -0.25659392, 0.093406077, 0.59340608, 0.99340608],
+            [-0.50807876, -0.25807876, 0.091921243, 0.59192124, 0.99192124],
+        ];
+        test_matches_expected(cfg, EXPECTED);
+    }
+    #[test]
+    fn test_nadam_custom() {
+        let cfg = NAdamConfig {
+            lr: 1e-2,
+            betas: [0.5, 0.25],
+            eps: 1e-8,
+            momentum_decay: 0.1,
+            weight_decay: None,
+        };
+        #[rustfmt::skip]
+        const EXPECTED: [[f64; 5]; 5] = [
+            [-0.51134527, -0.26134527, 0.088654728, 0.58865473, 0.98865473],
+            [-0.52122778, -0.27122778, 0.078772219, 0.57877222, 0.97877222],
+            [-0.53101935, -0.28101935, 0.068980649, 0.56898065, 0.96898065],
+            [-0.54087506, -0.29087506, 0.05912494, 0.55912494, 0.95912494],
+            [-0.55078947, -0.30078947, 0.049210529, 0.54921053, 0.94921053],
+        ];
+        test_matches_expected(cfg, EXPECTED);
+    }
+    #[test]
+    fn test_nadam_l2_weight_decay() {
+        let cfg = NAdamConfig {
+            weight_decay: Some(WeightDecay::L2(0.5)),
+            ..Default::default()
+        };
+        #[rustfmt::skip]
+        const EXPECTED: [[f64; 5]; 5] = [
+            [-0.4978871, -0.2521129, 0.097887096, 0.5978871, 0.9978871],
+            [-0.49633121, -0.25366783, 0.096323763, 0.59632257, 0.99632245],
+            [-0.49488367, -0.25511399, 0.094865236, 0.59486231, 0.994862],
+            [-0.49344394, -0.25655191, 0.093410921, 0.59340569, 0.99340515],
+            [-0.49198047, -0.25801312, 0.091928802, 0.59192064, 0.99191979],
+        ];
+        test_matches_expected(cfg, EXPECTED);
+    }
+    #[test]
+    fn test_nadam_decoupled_weight_decay() {
+        let cfg = NAdamConfig {
+            weight_decay: Some(WeightDecay::Decoupled(0.5)),
+            ..Default::default()
+        };
+        #[rustfmt::skip]
+        const EXPECTED: [[f64; 5]; 5] = [
+            [-0.5016129, -0.2518629, 0.097787096, 0.5972871, 0.9968871],
+            [-0.50267642, -0.25317582, 0.096125017, 0.59512621, 0.99432717],
+            [-0.50363511, -0.2543834, 0.094568994, 0.59307242, 0.99187515],
+            [-0.50458969, -0.25558635, 0.09301832, 0.591025, 0.98943034],
+            [-0.50557226, -0.25681677, 0.091440913, 0.58895189, 0.98696067],
+        ];
+        test_matches_expected(cfg, EXPECTED);
+    }
+
+    #[test]
+    fn test_unused_tensors() {
+        let dev: TestDevice = Default::default();
+        let mut t: Tensor<Rank1<5>, TestDtype, _> = dev.sample_normal();
+        let mut opt = NAdam::new(&t, Default::default());
+        opt.update(&mut t, &Gradients::leaky()).expect_err("");
+    }
+}
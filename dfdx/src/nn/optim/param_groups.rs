@@ -0,0 +1,223 @@
+use std::collections::HashMap;
+
+use crate::{
+    nn::UpdateParams,
+    shapes::Dtype,
+    tensor::{Tensorlike, UniqueId},
+    tensor_ops::Device,
+};
+
+/// Selects the parameters at or below `path` in the module tree, for
+/// `add_param_group` of the optimizers. E.g. `"0"` selects all parameters of the
+/// first module of a tuple, and `"0.weight"` only its weight. See [UpdateParams::visit_params()]
+/// for how paths are built.
+pub fn with_path(path: &str) -> impl '_ + FnMut(&str, &[usize]) -> bool {
+    move |p, _| {
+        p.strip_prefix(path)
+            .map_or(false, |rest| rest.is_empty() || rest.starts_with('.'))
+    }
+}
+
+/// Per parameter hyperparameters of an optimizer, see `add_param_group` of the optimizers.
+/// Parameters that are not in any group use the config of the optimizer.
+#[derive(Debug, Clone)]
+pub(super) struct ParamGroups<C> {
+    groups: Vec<ParamGroup<C>>,
+    group_of: HashMap<UniqueId, usize>,
+}
+
+#[derive(Debug, Clone)]
+struct ParamGroup<C> {
+    cfg: C,
+    /// The learning rate of the group relative to the optimizer's learning rate. `None` if
+    /// the optimizer's learning rate was 0 when the group was added, in which case the
+    /// group keeps its own learning rate.
+    lr_ratio: Option<f64>,
+}
+
+impl<C> Default for ParamGroups<C> {
+    fn default() -> Self {
+        Self {
+            groups: Vec::new(),
+            group_of: HashMap::new(),
+        }
+    }
+}
+
+impl<C> ParamGroups<C> {
+    /// Adds a group with `cfg`, and moves the selected parameters of `model` into it.
+    /// `lr` is the learning rate of the optimizer at this point, and `group_lr` the learning
+    /// rate of the group. Either can be 0, e.g. to freeze the parameters of a group.
+    pub(super) fn add<E: Dtype, D: Device<E>, M: UpdateParams<E, D>, F>(
+        &mut self,
+        model: &M,
+        mut select: F,
+        cfg: C,
+        lr: f64,
+        group_lr: f64,
+    ) where
+        F: FnMut(&str, &[usize]) -> bool,
+    {
+        let group = self.groups.len();
+        self.groups.push(ParamGroup {
+            cfg,
+            lr_ratio: (lr != 0.0).then(|| group_lr / lr),
+        });
+        model.visit_params("", &mut |path, shape, param| {
+            if select(path, shape) {
+                self.group_of.insert(param.id(), group);
+            }
+        });
+    }
+
+    /// The config to use for the parameter with `id`.
+    pub(super) fn cfg<'a>(&'a self, id: &UniqueId, default: &'a C) -> &'a C {
+        match self.group_of.get(id) {
+            Some(&group) => &self.groups[group].cfg,
+            None => default,
+        }
+    }
+
+    /// Scales the learning rate of all groups along with the optimizer's learning rate.
+    pub(super) fn set_lr<L: Fn(&mut C) -> &mut f64>(&mut self, lr: f64, lr_of: L) {
+        for group in self.groups.iter_mut() {
+            if let Some(lr_ratio) = group.lr_ratio {
+                *lr_of(&mut group.cfg) = lr * lr_ratio;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{prelude::*, tests::*};
+
+    #[test]
+    fn test_visit_param_paths() {
+        #[allow(clippy::type_complexity)]
+        #[derive(Clone, Debug, UpdateParams)]
+        struct Model<Elem: Dtype, Dev: Device<Elem>> {
+            #[module]
+            layers: (
+                Linear<Const<2>, Const<2>, Elem, Dev>,
+                Frozen<Linear<Const<2>, Const<2>, Elem, Dev>>,
+            ),
+            #[module]
+            blocks: Vec<Bias1D<Const<2>, Elem, Dev>>,
+            #[param]
+            scale: Tensor<Rank1<2>, Elem, Dev>,
+        }
+
+        let dev: TestDevice = Default::default();
+        let linear = dev.build_module::<TestDtype>(LinearConstConfig::<2, 2>::default());
+        let bias = dev.build_module::<TestDtype>(<Bias1DConstConfig<2>>::default());
+        let model = Model {
+            layers: (linear.clone(), Frozen::new(linear)),
+            blocks: std::vec![bias.clone(), bias],
+            scale: dev.ones(),
+        };
+
+        let mut paths = Vec::new();
+        model.visit_params("", &mut |path, shape, param| {
+            paths.push((path.to_string(), shape.to_vec()));
+            if path == "scale" {
+                assert_eq!(param.id(), model.scale.id());
+            }
+        });
+        let expected = [
+            ("layers.0.weight", std::vec![2, 2]),
+            ("layers.0.bias", std::vec![2]),
+            ("blocks.0.bias", std::vec![2]),
+            ("blocks.1.bias", std::vec![2]),
+            ("scale", std::vec![2]),
+        ];
+        assert_eq!(paths, expected.map(|(p, s)| (p.to_string(), s)).to_vec());
+    }
+
+    #[test]
+    fn test_with_path() {
+        let mut select = with_path("0.linear");
+        assert!(select("0.linear", &[]));
+        assert!(select("0.linear.weight", &[]));
+        assert!(!select("0.linear2.weight", &[]));
+        assert!(!select("1.0.linear.weight", &[]));
+    }
+
+    #[test]
+    fn test_sgd_param_groups() {
+        let dev: TestDevice = Default::default();
+        type Model = (
+            Bias1DConstConfig<2>,
+            Bias1DConstConfig<2>,
+            Bias1DConstConfig<2>,
+        );
+        let mut model = dev.build_module::<TestDtype>(Model::default());
+        model.0.bias = dev.ones();
+        model.1.bias = dev.ones();
+        model.2.bias = dev.ones();
+
+        let cfg = SgdConfig {
+            lr: 1.0,
+            momentum: None,
+            weight_decay: Some(WeightDecay::L2(1.0)),
+        };
+        let mut opt = Sgd::new(&model, cfg);
+        opt.add_param_group(&model, with_path("1"), SgdConfig { lr: 0.5, ..cfg });
+        opt.add_param_group(
+            &model,
+            with_path("2"),
+            SgdConfig {
+                weight_decay: None,
+                ..cfg
+            },
+        );
+
+        let x: Tensor<Rank1<2>, TestDtype, _> = dev.zeros();
+        let grads = model.forward(x.leaky_trace()).sum().backward();
+        opt.update(&mut model, &grads).unwrap();
+        // gradient is 1.0, plus 1.0 from weight decay
+        assert_close_to_literal!(model.0.bias, [-1.0; 2]);
+        assert_close_to_literal!(model.1.bias, [0.0; 2]);
+        assert_close_to_literal!(model.2.bias, [0.0; 2]);
+
+        // the learning rate of groups is scaled along with the optimizer's learning rate
+        opt.set_lr(0.1);
+        let grads = model.forward(x.leaky_trace()).sum().backward();
+        opt.update(&mut model, &grads).unwrap();
+        assert_close_to_literal!(model.0.bias, [-1.0; 2]);
+        assert_close_to_literal!(model.1.bias, [-0.05; 2]);
+        assert_close_to_literal!(model.2.bias, [-0.1; 2]);
+    }
+
+    #[test]
+    fn test_param_groups_zero_lr() {
+        let dev: TestDevice = Default::default();
+        type Model = (Bias1DConstConfig<2>, Bias1DConstConfig<2>);
+        let mut model = dev.build_module::<TestDtype>(Model::default());
+        let cfg = SgdConfig {
+            lr: 1.0,
+            momentum: None,
+            weight_decay: None,
+        };
+
+        // a group with a learning rate of 0 is frozen
+        let mut opt = Sgd::new(&model, cfg);
+        opt.add_param_group(&model, with_path("1"), SgdConfig { lr: 0.0, ..cfg });
+        opt.set_lr(0.5);
+        let x: Tensor<Rank1<2>, TestDtype, _> = dev.zeros();
+        let grads = model.forward(x.leaky_trace()).sum().backward();
+        opt.update(&mut model, &grads).unwrap();
+        assert_close_to_literal!(model.0.bias, [-0.5; 2]);
+        assert_close_to_literal!(model.1.bias, [0.0; 2]);
+
+        // groups added while the optimizer's learning rate is 0 keep their learning rate
+        let mut opt = Sgd::new(&model, SgdConfig { lr: 0.0, ..cfg });
+        opt.add_param_group(&model, with_path("1"), cfg);
+        opt.set_lr(0.5);
+        let grads = model.forward(x.leaky_trace()).sum().backward();
+        opt.update(&mut model, &grads).unwrap();
+        assert_close_to_literal!(model.0.bias, [-1.0; 2]);
+        assert_close_to_literal!(model.1.bias, [-1.0; 2]);
+    }
+}
@@ -0,0 +1,276 @@
+use std::marker::PhantomData;
+
+use super::param_groups::ParamGroups;
+use crate::{
+    nn::UpdateParams,
+    shapes::{Dtype, Shape},
+    tensor::{Error, Gradients, Storage, Tensor, Tensorlike, UniqueId},
+    tensor_ops::{Device, RAdamConfig},
+};
+
+/// An implementation of the RAdam optimizer from
+/// [On the Variance of the Adaptive Learning Rate and Beyond](https://arxiv.org/abs/1908.03265)
+///
+/// For the first few steps, while the variance of the adaptive learning rate is intractable,
+/// parameters are updated with the bias corrected first moment only.
+///
+/// # Example Usage
+/// ```rust
+/// # use dfdx::prelude::*;
+/// # type Model = Tensor<Rank0, f32, Cpu>;
+/// # let dev: Cpu = Default::default();
+/// # let model: Model = dev.zeros();
+/// let mut opt: RAdam<Model, f32, Cpu> = optim::RAdam::new(&model, RAdamConfig {
+///     lr: 1e-3,
+///     betas: [0.9, 0.999],
+///     eps: 1e-8,
+///     weight_decay: Some(WeightDecay::Decoupled(1e-2)),
+/// });
+/// ```
+///
+/// See module level documentation at [crate::nn::optim] for examples of how to actually use an optimizer.
+#[derive(Debug, Clone)]
+pub struct RAdam<M, E: Dtype, D: Storage<E>> {
+    /// Hyperparameter configuration
+    pub cfg: RAdamConfig,
+
+    t: i32,
+    moment1: Gradients<E, D>,
+    moment2: Gradients<E, D>,
+
+    param_groups: ParamGroups<RAdamConfig>,
+
+    marker: PhantomData<*const M>,
+}
+
+impl<M, E: Dtype, D: Storage<E>> RAdam<M, E, D> {
+    /// Constructs using hyperparameters from `cfg`.
+    pub fn new(_model: &M, cfg: RAdamConfig) -> Self {
+        Self {
+            cfg,
+            t: 0,
+            moment1: Gradients::leaky(),
+            moment2: Gradients::leaky(),
+            param_groups: Default::default(),
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<M, E: Dtype, D: Device<E>> RAdam<M, E, D> {
+    /// Uses `cfg` instead of [RAdam::cfg] for the parameters of `model` that `select` returns
+    /// `true` for, given their path & shape. See [Adam::add_param_group()] for details.
+    ///
+    /// [Adam::add_param_group()]: crate::nn::optim::Adam::add_param_group()
+    pub fn add_param_group<F>(&mut self, model: &M, select: F, cfg: RAdamConfig)
+    where
+        M: UpdateParams<E, D>,
+        F: FnMut(&str, &[usize]) -> bool,
+    {
+        self.param_groups
+            .add(model, select, cfg, self.cfg.lr, cfg.lr);
+    }
+}
+
+impl<M, E: Dtype, D: Storage<E>> super::LearningRate for RAdam<M, E, D> {
+    fn lr(&self) -> f64 {
+        self.cfg.lr
+    }
+    fn set_lr(&mut self, lr: f64) {
+        self.cfg.lr = lr;
+        self.param_groups.set_lr(lr, |cfg| &mut cfg.lr);
+    }
+}
+
+impl<M, E: Dtype, D: Device<E>> crate::nn::Optimizer<M, E, D> for RAdam<M, E, D> {
+    fn update_tensor<S: Shape>(
+        &mut self,
+        t: &mut Tensor<S, E, D>,
+        gradients: &Gradients<E, D>,
+        missing_params: &mut Vec<UniqueId>,
+    ) -> Result<(), Error> {
+        let g = gradients.get_ref_checked(t);
+        match g {
+            None => missing_params.push(t.id()),
+            Some(g) => {
+                let m_t = self.moment1.get_or_alloc_mut(t)?;
+                let v_t = self.moment2.get_or_alloc_mut(t)?;
+                let cfg = self.param_groups.cfg(&t.id(), &self.cfg);
+                cfg.try_update(self.t, t, m_t, v_t, g)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn update(&mut self, module: &mut M, gradients: &Gradients<E, D>) -> Result<(), Error>
+    where
+        M: crate::nn::UpdateParams<E, D>,
+    {
+        self.t = self.t.checked_add(1).unwrap();
+
+        // NOTE: the rest of this is identical to default implementation of update.
+        let mut missing_tensors = Vec::new();
+        module.try_update_params(self, gradients, &mut missing_tensors)?;
+        if missing_tensors.is_empty() {
+            Ok(())
+        } else {
+            Err(Error::UnusedTensors(missing_tensors))
+        }
+    }
+}
+
+#[cfg(feature = "safetensors")]
+impl<M: UpdateParams<E, D>, E: Dtype, D: Device<E>> super::OptimizerSafeTensors<M, E, D>
+    for RAdam<M, E, D>
+{
+    fn write_safetensors(
+        &self,
+        model: &M,
+        location: &str,
+        tensors: &mut Vec<(String, safetensors::Dtype, Vec<usize>, Vec<u8>)>,
+    ) {
+        crate::nn::SaveSafeTensors::write_safetensors(&self.t, &format!("{location}t"), tensors);
+        super::state::write_state(
+            model,
+            &self.moment1,
+            &format!("{location}moment1."),
+            tensors,
+        );
+        super::state::write_state(
+            model,
+            &self.moment2,
+            &format!("{location}moment2."),
+            tensors,
+        );
+    }
+
+    fn read_safetensors(
+        &mut self,
+        model: &M,
+        location: &str,
+        tensors: &safetensors::SafeTensors,
+    ) -> Result<(), safetensors::SafeTensorError> {
+        crate::nn::LoadSafeTensors::read_safetensors(
+            &mut self.t,
+            &format!("{location}t"),
+            tensors,
+        )?;
+        super::state::read_state(
+            model,
+            &mut self.moment1,
+            &format!("{location}moment1."),
+            tensors,
+        )?;
+        super::state::read_state(
+            model,
+            &mut self.moment2,
+            &format!("{location}moment2."),
+            tensors,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{nn::optim::*, shapes::*, tensor::*, tensor_ops::*, tests::*};
+
+    fn test_matches_expected<const N: usize>(cfg: RAdamConfig, expected: [[f64; 5]; N]) {
+        let dev: TestDevice = Default::default();
+        let mut t = dev
+            .tensor([-0.5, -0.25, 0.1, 0.6, 1.0])
+            .to_dtype::<TestDtype>();
+        let mut opt = RAdam::new(&t, cfg);
+        for e in expected.iter() {
+            let gradients = t.leaky_trace().exp().square().mean().backward();
+            opt.update(&mut t, &gradients).expect("");
+            assert_close_to_literal!(t, e);
+        }
+    }
+
+    #[test]
+    fn test_radam_default() {
+        let cfg = RAdamConfig::default();
+        #[rustfmt::skip]
+        const EXPECTED: [[f64; 5]; 8] = [
+            [-0.50014715, -0.25024261, 0.099511439, 0.59867195, 0.99704438],
+            [-0.50029428, -0.25048516, 0.099023129, 0.59734576, 0.99409792],
+            [-0.50044139, -0.25072765, 0.098535078, 0.59602148, 0.99116088],
+            [-0.50058847, -0.25097007, 0.098047295, 0.59469916, 0.9882335],
+            [-0.50073552, -0.25121242, 0.097559786, 0.59337887, 0.98531601],
+            [-0.50076134, -0.25123824, 0.097533973, 0.59335307, 0.98529024],
+            [-0.50079408, -0.25127097, 0.097501246, 0.59332036, 0.98525757],
+            [-0.50083281, -0.2513097, 0.097462523, 0.59328166, 0.98521893],
+        ];
+        test_matches_expected(cfg, EXPECTED);
+    }
+    #[test]
+    fn test_radam_custom() {
+        let cfg = RAdamConfig {
+            lr: 1e-2,
+            betas: [0.5, 0.9],
+            eps: 1e-8,
+            weight_decay: None,
+        };
+        #[rustfmt::skip]
+        const EXPECTED: [[f64; 5]; 8] = [
+            [-0.50147152, -0.25242612, 0.095114389, 0.58671953, 0.97044378],
+            [-0.50294015, -0.25484442, 0.090260449, 0.57367113, 0.94201855],
+            [-0.5044055, -0.25725382, 0.085442266, 0.56087939, 0.91479628],
+            [-0.50586726, -0.2596535, 0.080662892, 0.54836005, 0.88879901],
+            [-0.50732519, -0.26204288, 0.075924346, 0.53612046, 0.86400678],
+            [-0.5098673, -0.26457874, 0.073404496, 0.53365429, 0.86164046],
+            [-0.51315028, -0.26785342, 0.070151092, 0.53047241, 0.85859225],
+            [-0.51704182, -0.27173575, 0.066292425, 0.52669362, 0.85496411],
+        ];
+        test_matches_expected(cfg, EXPECTED);
+    }
+    #[test]
+    fn test_radam_l2_weight_decay() {
+        let cfg = RAdamConfig {
+            betas: [0.5, 0.9],
+            weight_decay: Some(WeightDecay::L2(0.5)),
+            ..Default::default()
+        };
+        #[rustfmt::skip]
+        const EXPECTED: [[f64; 5]; 8] = [
+            [-0.49989715, -0.25011761, 0.099461439, 0.59837195, 0.99654438],
+            [-0.49979436, -0.25023515, 0.098923408, 0.59674733, 0.99310348],
+            [-0.49969163, -0.25035259, 0.098385982, 0.5951266, 0.98967927],
+            [-0.49958896, -0.25046994, 0.097849221, 0.59351013, 0.98627327],
+            [-0.49948637, -0.25058719, 0.097313168, 0.59189821, 0.9828865],
+            [-0.49923146, -0.25084204, 0.097058478, 0.59164407, 0.98263343],
+            [-0.4989019, -0.2511715, 0.096729091, 0.59131539, 0.98230616],
+            [-0.49851068, -0.25156257, 0.096337781, 0.59092483, 0.98191716],
+        ];
+        test_matches_expected(cfg, EXPECTED);
+    }
+    #[test]
+    fn test_radam_decoupled_weight_decay() {
+        let cfg = RAdamConfig {
+            betas: [0.5, 0.9],
+            weight_decay: Some(WeightDecay::Decoupled(0.5)),
+            ..Default::default()
+        };
+        #[rustfmt::skip]
+        const EXPECTED: [[f64; 5]; 8] = [
+            [-0.49989715, -0.25011761, 0.099461439, 0.59837195, 0.99654438],
+            [-0.49979438, -0.25023513, 0.098923498, 0.5967476, 0.99310405],
+            [-0.49969167, -0.25035254, 0.098386226, 0.59512733, 0.98968083],
+            [-0.49958905, -0.25046985, 0.097849661, 0.59351146, 0.98627608],
+            [-0.4994865, -0.25058704, 0.097313833, 0.59190021, 0.98289073],
+            [-0.499492, -0.25071685, 0.097010353, 0.59135015, 0.98214638],
+            [-0.49957245, -0.25092147, 0.096632274, 0.59072591, 0.98132836],
+            [-0.499715, -0.25118806, 0.096192417, 0.5900402, 0.98044924],
+        ];
+        test_matches_expected(cfg, EXPECTED);
+    }
+
+    #[test]
+    fn test_unused_tensors() {
+        let dev: TestDevice = Default::default();
+        let mut t: Tensor<Rank1<5>, TestDtype, _> = dev.sample_normal();
+        let mut opt = RAdam::new(&t, Default::default());
+        opt.update(&mut t, &Gradients::leaky()).expect_err("");
+    }
+}
@@ -1,6 +1,8 @@
 use std::marker::PhantomData;
 
+use super::param_groups::ParamGroups;
 use crate::{
+    nn::UpdateParams,
     shapes::{Dtype, Shape},
     tensor::{Error, Gradients, Storage, Tensor, Tensorlike, UniqueId},
     tensor_ops::{Device, RMSpropConfig},
@@ -43,6 +45,8 @@ pub struct RMSprop<M, E: Dtype, D: Storage<E>> {
     square_avg: Gradients<E, D>,
     grad_avg: Gradients<E, D>,
 
+    param_groups: ParamGroups<RMSpropConfig>,
+
     marker: PhantomData<*const M>,
 }
 
@@ -55,11 +59,37 @@ impl<M, E: Dtype, D: Storage<E>> RMSprop<M, E, D> {
             momentums: Gradients::leaky(),
             square_avg: Gradients::leaky(),
             grad_avg: Gradients::leaky(),
+            param_groups: Default::default(),
             marker: PhantomData,
         }
     }
 }
 
+impl<M, E: Dtype, D: Device<E>> RMSprop<M, E, D> {
+    /// Uses `cfg` instead of [RMSprop::cfg] for the parameters of `model` that `select` returns
+    /// `true` for, given their path & shape. See [Adam::add_param_group()] for details.
+    ///
+    /// [Adam::add_param_group()]: crate::nn::optim::Adam::add_param_group()
+    pub fn add_param_group<F>(&mut self, model: &M, select: F, cfg: RMSpropConfig)
+    where
+        M: UpdateParams<E, D>,
+        F: FnMut(&str, &[usize]) -> bool,
+    {
+        self.param_groups
+            .add(model, select, cfg, self.cfg.lr, cfg.lr);
+    }
+}
+
+impl<M, E: Dtype, D: Storage<E>> super::LearningRate for RMSprop<M, E, D> {
+    fn lr(&self) -> f64 {
+        self.cfg.lr
+    }
+    fn set_lr(&mut self, lr: f64) {
+        self.cfg.lr = lr;
+        self.param_groups.set_lr(lr, |cfg| &mut cfg.lr);
+    }
+}
+
 impl<M, E: Dtype, D: Device<E>> crate::nn::Optimizer<M, E, D> for RMSprop<M, E, D> {
     fn update_tensor<S: Shape>(
         &mut self,
@@ -79,7 +109,8 @@ impl<M, E: Dtype, D: Device<E>> crate::nn::Optimizer<M, E, D> for RMSprop<M, E,
                     t.device().try_fill_with_ones(sa)?;
                 }
 
-                self.cfg.try_update(t, m, sa, ga, g)?;
+                let cfg = self.param_groups.cfg(&t.id(), &self.cfg);
+                cfg.try_update(t, m, sa, ga, g)?;
             }
         }
         Ok(())
@@ -102,6 +133,73 @@ impl<M, E: Dtype, D: Device<E>> crate::nn::Optimizer<M, E, D> for RMSprop<M, E,
     }
 }
 
+#[cfg(feature = "safetensors")]
+impl<M: UpdateParams<E, D>, E: Dtype, D: Device<E>> super::OptimizerSafeTensors<M, E, D>
+    for RMSprop<M, E, D>
+{
+    fn write_safetensors(
+        &self,
+        model: &M,
+        location: &str,
+        tensors: &mut Vec<(String, safetensors::Dtype, Vec<usize>, Vec<u8>)>,
+    ) {
+        crate::nn::SaveSafeTensors::write_safetensors(
+            &self.step,
+            &format!("{location}step"),
+            tensors,
+        );
+        super::state::write_state(
+            model,
+            &self.momentums,
+            &format!("{location}momentums."),
+            tensors,
+        );
+        super::state::write_state(
+            model,
+            &self.square_avg,
+            &format!("{location}square_avg."),
+            tensors,
+        );
+        super::state::write_state(
+            model,
+            &self.grad_avg,
+            &format!("{location}grad_avg."),
+            tensors,
+        );
+    }
+
+    fn read_safetensors(
+        &mut self,
+        model: &M,
+        location: &str,
+        tensors: &safetensors::SafeTensors,
+    ) -> Result<(), safetensors::SafeTensorError> {
+        crate::nn::LoadSafeTensors::read_safetensors(
+            &mut self.step,
+            &format!("{location}step"),
+            tensors,
+        )?;
+        super::state::read_state(
+            model,
+            &mut self.momentums,
+            &format!("{location}momentums."),
+            tensors,
+        )?;
+        super::state::read_state(
+            model,
+            &mut self.square_avg,
+            &format!("{location}square_avg."),
+            tensors,
+        )?;
+        super::state::read_state(
+            model,
+            &mut self.grad_avg,
+            &format!("{location}grad_avg."),
+            tensors,
+        )
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
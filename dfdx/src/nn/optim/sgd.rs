@@ -1,4 +1,6 @@
+use super::param_groups::ParamGroups;
 use crate::{
+    nn::UpdateParams,
     shapes::{Dtype, Shape},
     tensor::{Gradients, Storage, Tensor, Tensorlike, UniqueId},
     tensor_ops::{Device, SgdConfig},
@@ -32,6 +34,7 @@ use crate::{
 pub struct Sgd<M, E: Dtype, D: Storage<E>> {
     pub cfg: SgdConfig,
     velocity: Gradients<E, D>,
+    param_groups: ParamGroups<SgdConfig>,
     module: std::marker::PhantomData<*const M>,
 }
 
@@ -40,11 +43,37 @@ impl<M, E: Dtype, D: Storage<E>> Sgd<M, E, D> {
         Self {
             cfg,
             velocity: Gradients::leaky(),
+            param_groups: Default::default(),
             module: std::marker::PhantomData,
         }
     }
 }
 
+impl<M, E: Dtype, D: Device<E>> Sgd<M, E, D> {
+    /// Uses `cfg` instead of [Sgd::cfg] for the parameters of `model` that `select` returns
+    /// `true` for, given their path & shape. See [Adam::add_param_group()] for details.
+    ///
+    /// [Adam::add_param_group()]: crate::nn::optim::Adam::add_param_group()
+    pub fn add_param_group<F>(&mut self, model: &M, select: F, cfg: SgdConfig)
+    where
+        M: UpdateParams<E, D>,
+        F: FnMut(&str, &[usize]) -> bool,
+    {
+        self.param_groups
+            .add(model, select, cfg, self.cfg.lr, cfg.lr);
+    }
+}
+
+impl<M, E: Dtype, D: Storage<E>> super::LearningRate for Sgd<M, E, D> {
+    fn lr(&self) -> f64 {
+        self.cfg.lr
+    }
+    fn set_lr(&mut self, lr: f64) {
+        self.cfg.lr = lr;
+        self.param_groups.set_lr(lr, |cfg| &mut cfg.lr);
+    }
+}
+
 impl<M, E: Dtype, D: Device<E>> crate::nn::Optimizer<M, E, D> for Sgd<M, E, D> {
     fn update_tensor<S: Shape>(
         &mut self,
@@ -57,13 +86,47 @@ impl<M, E: Dtype, D: Device<E>> crate::nn::Optimizer<M, E, D> for Sgd<M, E, D> {
             None => missing_params.push(t.id()),
             Some(g) => {
                 let v = self.velocity.get_or_alloc_mut(t)?;
-                self.cfg.try_update(t, v, g)?;
+                let cfg = self.param_groups.cfg(&t.id(), &self.cfg);
+                cfg.try_update(t, v, g)?;
             }
         }
         Ok(())
     }
 }
 
+#[cfg(feature = "safetensors")]
+impl<M: UpdateParams<E, D>, E: Dtype, D: Device<E>> super::OptimizerSafeTensors<M, E, D>
+    for Sgd<M, E, D>
+{
+    fn write_safetensors(
+        &self,
+        model: &M,
+        location: &str,
+        tensors: &mut Vec<(String, safetensors::Dtype, Vec<usize>, Vec<u8>)>,
+    ) {
+        super::state::write_state(
+            model,
+            &self.velocity,
+            &format!("{location}velocity."),
+            tensors,
+        );
+    }
+
+    fn read_safetensors(
+        &mut self,
+        model: &M,
+        location: &str,
+        tensors: &safetensors::SafeTensors,
+    ) -> Result<(), safetensors::SafeTensorError> {
+        super::state::read_state(
+            model,
+            &mut self.velocity,
+            &format!("{location}velocity."),
+            tensors,
+        )
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -0,0 +1,371 @@
+use std::collections::HashMap;
+
+use crate::{
+    dtypes::SafeTensorsDtype,
+    nn::{LoadSafeTensors, SaveSafeTensors, UpdateParams, WithGrads},
+    shapes::Dtype,
+    tensor::{Error, Gradients, Tensor, Tensorlike, UniqueId},
+    tensor_ops::Device,
+};
+use safetensors::{SafeTensorError, SafeTensors};
+
+/// An optimizer whose state (e.g. moments and the step count) can be saved to and loaded
+/// from .safetensors files, so that training can be resumed from a checkpoint.
+///
+/// The state of each parameter is keyed by its path in `model` (see [UpdateParams::visit_params()]),
+/// so it can be loaded for a model with the same structure in another process:
+///
+/// ```rust,no_run
+/// # use dfdx::prelude::*;
+/// # let dev: Cpu = Default::default();
+/// type Model = (LinearConstConfig<5, 5>, ReLU, LinearConstConfig<5, 2>);
+/// let mut model = dev.build_module::<f32>(Model::default());
+/// let mut opt = Adam::new(&model, Default::default());
+/// // -- snip training --
+/// model.save_safetensors("model.safetensors").unwrap();
+/// opt.save_safetensors(&model, "adam.safetensors").unwrap();
+///
+/// // resuming
+/// let mut model = dev.build_module::<f32>(Model::default());
+/// let mut opt = Adam::new(&model, Default::default());
+/// model.load_safetensors("model.safetensors").unwrap();
+/// opt.load_safetensors(&model, "adam.safetensors").unwrap();
+/// ```
+///
+/// Parameters that have no state in the file (e.g. because they didn't have a gradient
+/// yet) keep their current state when loading.
+pub trait OptimizerSafeTensors<M: UpdateParams<E, D>, E: Dtype, D: Device<E>> {
+    fn save_safetensors<P: AsRef<std::path::Path>>(
+        &self,
+        model: &M,
+        path: P,
+    ) -> Result<(), SafeTensorError> {
+        let mut tensors = Vec::new();
+        self.write_safetensors(model, "", &mut tensors);
+        let data = tensors.iter().map(|(k, dtype, shape, data)| {
+            (
+                k.clone(),
+                safetensors::tensor::TensorView::new(*dtype, shape.clone(), data).unwrap(),
+            )
+        });
+
+        safetensors::serialize_to_file(data, &None, path.as_ref())
+    }
+
+    fn write_safetensors(
+        &self,
+        model: &M,
+        location: &str,
+        tensors: &mut Vec<(String, safetensors::Dtype, Vec<usize>, Vec<u8>)>,
+    );
+
+    fn load_safetensors<P: AsRef<std::path::Path>>(
+        &mut self,
+        model: &M,
+        path: P,
+    ) -> Result<(), SafeTensorError> {
+        let buffer = std::fs::read(path)?;
+        let tensors = SafeTensors::deserialize(&buffer)?;
+        self.read_safetensors(model, "", &tensors)
+    }
+
+    fn read_safetensors(
+        &mut self,
+        model: &M,
+        location: &str,
+        tensors: &SafeTensors,
+    ) -> Result<(), SafeTensorError>;
+}
+
+/// Writes the state of each parameter of `model` that has one, in the shape of the parameter.
+pub(super) fn write_state<M: UpdateParams<E, D>, E: Dtype, D: Device<E>>(
+    model: &M,
+    state: &Gradients<E, D>,
+    location: &str,
+    tensors: &mut Vec<(String, safetensors::Dtype, Vec<usize>, Vec<u8>)>,
+) {
+    model.visit_params(location, &mut |path, shape, param| {
+        if state.get_ref_checked(param).is_some() {
+            tensors.push((
+                path.to_string(),
+                <E as SafeTensorsDtype>::DTYPE,
+                shape.to_vec(),
+                state
+                    .get(param)
+                    .as_vec()
+                    .into_iter()
+                    .flat_map(|e| e.to_le_bytes())
+                    .collect(),
+            ));
+        }
+    });
+}
+
+/// Reads the state of each parameter of `model` that has one in `tensors`.
+pub(super) fn read_state<M: UpdateParams<E, D>, E: Dtype, D: Device<E>>(
+    model: &M,
+    state: &mut Gradients<E, D>,
+    location: &str,
+    tensors: &SafeTensors,
+) -> Result<(), SafeTensorError> {
+    let mut params = Vec::new();
+    model.visit_params(location, &mut |path, shape, param| {
+        params.push((path.to_string(), shape.to_vec(), param.clone()))
+    });
+
+    for (path, shape, param) in params {
+        let data: Vec<E> = match read_data(tensors, &path, &shape)? {
+            Some(data) => data,
+            None => continue,
+        };
+        let len = data.len();
+        let loaded: Tensor<(usize,), E, D> = param
+            .device()
+            .try_tensor_from_vec(data, (len,))
+            .map_err(device_error)?;
+        state.get_or_alloc_mut(&param).map_err(device_error)?;
+        param
+            .try_grads_map(state, &mut |_| Ok(loaded.clone()))
+            .map_err(device_error)?;
+    }
+    Ok(())
+}
+
+/// Writes a state of each parameter of `model` that has one, where the state is a
+/// 1d tensor whose length depends on the shape of the parameter.
+pub(super) fn write_flat_state<M: UpdateParams<E, D>, E: Dtype, D: Device<E>>(
+    model: &M,
+    state: &HashMap<UniqueId, Tensor<(usize,), E, D>>,
+    location: &str,
+    tensors: &mut Vec<(String, safetensors::Dtype, Vec<usize>, Vec<u8>)>,
+) {
+    model.visit_params(location, &mut |path, _, param| {
+        if let Some(t) = state.get(&param.id()) {
+            let data = t.as_vec();
+            tensors.push((
+                path.to_string(),
+                <E as SafeTensorsDtype>::DTYPE,
+                std::vec![data.len()],
+                data.into_iter().flat_map(|e| e.to_le_bytes()).collect(),
+            ));
+        }
+    });
+}
+
+/// Reads the flat state of each parameter of `model` that has one in `tensors`.
+/// `len` is the length of the state for the shape of a parameter.
+pub(super) fn read_flat_state<M: UpdateParams<E, D>, E: Dtype, D: Device<E>>(
+    model: &M,
+    state: &mut HashMap<UniqueId, Tensor<(usize,), E, D>>,
+    location: &str,
+    tensors: &SafeTensors,
+    len: impl Fn(&[usize]) -> usize,
+) -> Result<(), SafeTensorError> {
+    let mut params = Vec::new();
+    model.visit_params(location, &mut |path, shape, param| {
+        params.push((path.to_string(), len(shape), param.clone()))
+    });
+
+    for (path, len, param) in params {
+        if let Some(data) = read_data(tensors, &path, &[len])? {
+            let loaded = param
+                .device()
+                .try_tensor_from_vec(data, (len,))
+                .map_err(device_error)?;
+            state.insert(param.id(), loaded);
+        }
+    }
+    Ok(())
+}
+
+/// Reads the tensor at `path`, which has to have `shape`. Returns `None` if there is none.
+fn read_data<E: Dtype>(
+    tensors: &SafeTensors,
+    path: &str,
+    shape: &[usize],
+) -> Result<Option<Vec<E>>, SafeTensorError> {
+    let view = match tensors.tensor(path) {
+        Ok(view) => view,
+        Err(SafeTensorError::TensorNotFound(_)) => return Ok(None),
+        Err(e) => return Err(e),
+    };
+    if view.shape() != shape || view.dtype() != <E as SafeTensorsDtype>::DTYPE {
+        return Err(SafeTensorError::InvalidTensorView(
+            view.dtype(),
+            view.shape().to_vec(),
+            view.data().len(),
+        ));
+    }
+    Ok(Some(
+        view.data()
+            .chunks_exact(std::mem::size_of::<E>())
+            .map(E::from_le_bytes)
+            .collect(),
+    ))
+}
+
+/// Writes a scalar state for each parameter of `model` that has one.
+pub(super) fn write_scalar_state<M: UpdateParams<E, D>, E: Dtype, D: Device<E>>(
+    model: &M,
+    state: &HashMap<UniqueId, f64>,
+    location: &str,
+    tensors: &mut Vec<(String, safetensors::Dtype, Vec<usize>, Vec<u8>)>,
+) {
+    model.visit_params(location, &mut |path, _, param| {
+        if let Some(value) = state.get(&param.id()) {
+            value.write_safetensors(path, tensors);
+        }
+    });
+}
+
+/// Reads the scalar state of each parameter of `model` that has one in `tensors`.
+pub(super) fn read_scalar_state<M: UpdateParams<E, D>, E: Dtype, D: Device<E>>(
+    model: &M,
+    state: &mut HashMap<UniqueId, f64>,
+    location: &str,
+    tensors: &SafeTensors,
+) -> Result<(), SafeTensorError> {
+    let mut result = Ok(());
+    model.visit_params(location, &mut |path, _, param| {
+        let mut value = 0.0;
+        match value.read_safetensors(path, tensors) {
+            Ok(()) => {
+                state.insert(param.id(), value);
+            }
+            Err(SafeTensorError::TensorNotFound(_)) => (),
+            Err(e) => result = Err(e),
+        }
+    });
+    result
+}
+
+fn device_error(e: Error) -> SafeTensorError {
+    SafeTensorError::IoError(std::io::Error::new(
+        std::io::ErrorKind::Other,
+        e.to_string(),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{prelude::*, tests::*};
+
+    #[test]
+    fn test_save_load_adam_state() {
+        let dev: TestDevice = Default::default();
+        type Model = (LinearConstConfig<3, 4>, ReLU, LinearConstConfig<4, 2>);
+        let mut model = dev.build_module::<TestDtype>(Model::default());
+        let mut opt = Adam::new(&model, Default::default());
+        let x: Tensor<Rank2<5, 3>, TestDtype, _> = dev.sample_normal();
+        for _ in 0..2 {
+            let grads = model.forward(x.leaky_trace()).square().mean().backward();
+            opt.update(&mut model, &grads).unwrap();
+        }
+
+        let model_file = tempfile::NamedTempFile::new().unwrap();
+        let opt_file = tempfile::NamedTempFile::new().unwrap();
+        model.save_safetensors(model_file.path()).unwrap();
+        opt.save_safetensors(&model, opt_file.path()).unwrap();
+
+        // a fresh model has new ids, so the state has to be matched up by path
+        let mut loaded_model = dev.build_module::<TestDtype>(Model::default());
+        let mut loaded_opt = Adam::new(&loaded_model, Default::default());
+        loaded_model.load_safetensors(model_file.path()).unwrap();
+        loaded_opt
+            .load_safetensors(&loaded_model, opt_file.path())
+            .unwrap();
+
+        let grads = model.forward(x.leaky_trace()).square().mean().backward();
+        opt.update(&mut model, &grads).unwrap();
+        let grads = loaded_model
+            .forward(x.leaky_trace())
+            .square()
+            .mean()
+            .backward();
+        loaded_opt.update(&mut loaded_model, &grads).unwrap();
+
+        assert_eq!(model.0.weight.array(), loaded_model.0.weight.array());
+        assert_eq!(model.2.bias.array(), loaded_model.2.bias.array());
+    }
+
+    #[test]
+    fn test_save_load_nadam_state() {
+        let dev: TestDevice = Default::default();
+        let mut t: Tensor<Rank1<3>, TestDtype, _> = dev.sample_normal();
+        let mut opt = NAdam::new(&t, Default::default());
+        for _ in 0..3 {
+            let grads = t.leaky_trace().exp().sum().backward();
+            opt.update(&mut t, &grads).unwrap();
+        }
+        let file = tempfile::NamedTempFile::new().unwrap();
+        opt.save_safetensors(&t, file.path()).unwrap();
+
+        let mut loaded_t = dev.tensor(t.array());
+        let mut loaded_opt = NAdam::new(&loaded_t, Default::default());
+        loaded_opt.load_safetensors(&loaded_t, file.path()).unwrap();
+
+        let grads = t.leaky_trace().exp().sum().backward();
+        opt.update(&mut t, &grads).unwrap();
+        let grads = loaded_t.leaky_trace().exp().sum().backward();
+        loaded_opt.update(&mut loaded_t, &grads).unwrap();
+        assert_eq!(t.array(), loaded_t.array());
+    }
+
+    #[test]
+    fn test_save_load_adafactor_state() {
+        let dev: TestDevice = Default::default();
+        type Model = (LinearConstConfig<3, 4>, ReLU, LinearConstConfig<4, 2>);
+        let mut model = dev.build_module::<TestDtype>(Model::default());
+        let mut opt = Adafactor::new(&model, Default::default());
+        let x: Tensor<Rank2<5, 3>, TestDtype, _> = dev.sample_normal();
+        for _ in 0..2 {
+            let grads = model.forward(x.leaky_trace()).square().mean().backward();
+            opt.update(&mut model, &grads).unwrap();
+        }
+        let model_file = tempfile::NamedTempFile::new().unwrap();
+        let opt_file = tempfile::NamedTempFile::new().unwrap();
+        model.save_safetensors(model_file.path()).unwrap();
+        opt.save_safetensors(&model, opt_file.path()).unwrap();
+
+        let mut loaded_model = dev.build_module::<TestDtype>(Model::default());
+        let mut loaded_opt = Adafactor::new(&loaded_model, Default::default());
+        loaded_model.load_safetensors(model_file.path()).unwrap();
+        loaded_opt
+            .load_safetensors(&loaded_model, opt_file.path())
+            .unwrap();
+
+        let grads = model.forward(x.leaky_trace()).square().mean().backward();
+        opt.update(&mut model, &grads).unwrap();
+        let grads = loaded_model
+            .forward(x.leaky_trace())
+            .square()
+            .mean()
+            .backward();
+        loaded_opt.update(&mut loaded_model, &grads).unwrap();
+        assert_eq!(model.0.weight.array(), loaded_model.0.weight.array());
+        assert_eq!(model.2.bias.array(), loaded_model.2.bias.array());
+    }
+
+    #[test]
+    fn test_load_state_with_wrong_shape() {
+        let dev: TestDevice = Default::default();
+        let mut model = dev.build_module::<TestDtype>(LinearConstConfig::<2, 2>::default());
+        let mut opt = Sgd::new(
+            &model,
+            SgdConfig {
+                momentum: Some(Momentum::Classic(0.9)),
+                ..Default::default()
+            },
+        );
+        let x: Tensor<Rank1<2>, TestDtype, _> = dev.sample_normal();
+        let grads = model.forward(x.leaky_trace()).sum().backward();
+        opt.update(&mut model, &grads).unwrap();
+        let file = tempfile::NamedTempFile::new().unwrap();
+        opt.save_safetensors(&model, file.path()).unwrap();
+
+        let other = dev.build_module::<TestDtype>(LinearConstConfig::<3, 2>::default());
+        let mut other_opt = Sgd::new(&other, Default::default());
+        assert!(other_opt.load_safetensors(&other, file.path()).is_err());
+    }
+}